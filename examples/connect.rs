@@ -2,20 +2,16 @@
 
 #[cfg(feature = "std")]
 pub fn main() {
-    use mqtt_tiny::{
-        packets::{ToWriter, TryFromReader},
-        Connack, Connect, Disconnect,
-    };
+    use mqtt_tiny::{client::MqttConnection, Connect};
     use std::{net::TcpStream, thread, time::Duration};
 
     // Connect to a server
-    let mut connection = TcpStream::connect("127.0.0.1:1883").expect("failed to connect to server");
+    let stream = TcpStream::connect("127.0.0.1:1883").expect("failed to connect to server");
 
     // Build CONNECT packet...
-    Connect::new(30, true, b"mqtttinyexamplesconnect").expect("failed to create CONNECT packet")
-        // ...and connect
-        .write(&mut connection).expect("failed to send CONNECT packet");
-    let connack = Connack::try_read(&mut connection).expect("failed to read CONNACK packet");
+    let connect = Connect::new(30, true, b"mqtttinyexamplesconnect").expect("failed to create CONNECT packet");
+    // ...and connect
+    let (connection, connack) = MqttConnection::connect(stream, connect).expect("failed to connect");
     assert_eq!(connack.return_code(), 0, "connection was refused");
 
     // Sleep 10s
@@ -23,7 +19,7 @@ pub fn main() {
     thread::sleep(PAUSE);
 
     // Disconnect
-    Disconnect::new().write(&mut connection).expect("failed to write DISCONNECT packet");
+    connection.disconnect().expect("failed to write DISCONNECT packet");
 }
 
 #[cfg(not(feature = "std"))]
@@ -1,47 +1,100 @@
 // Provides some type aliases that offer reasonable defaults for the underlying container types
+//
+// The container backend is selected by precedence: `alloc` (which is implied by `std`) uses heap-allocated vectors,
+// otherwise `arrayvec` uses fixed-capacity vectors on the stack.
 
 /// The default byte container type used within top-level types
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 #[doc(hidden)]
-pub type Bytes = std::vec::Vec<u8>;
+pub type Bytes = alloc::vec::Vec<u8>;
 /// The default byte container type used within top-level types
 ///
 /// # Note
 /// This default configuration allows for 256 bytes per byte field on the stack.
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 #[doc(hidden)]
 pub type Bytes = arrayvec::ArrayVec<u8, 256>;
 
 /// The default collection type for topic lists used within top-level types
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 #[doc(hidden)]
-pub type Topics = std::vec::Vec<Bytes>;
+pub type Topics = alloc::vec::Vec<Bytes>;
 /// The default collection type for topic lists used within top-level types
 ///
 /// # Note
 /// This default configuration allows for 4 topics per unsubscribe message.
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 #[doc(hidden)]
 pub type Topics = arrayvec::ArrayVec<Bytes, 4>;
 
 /// The default collection type for topic+quality-of-service lists used within top-level types
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 #[doc(hidden)]
-pub type TopicsQos = std::vec::Vec<(Bytes, u8)>;
+pub type TopicsQos = alloc::vec::Vec<(Bytes, u8)>;
 /// The default collection type for topic+quality-of-service lists used within top-level types
 ///
 /// # Note
 /// This default configuration allows for 4 topic+quality-of-service tuples per subscribe message.
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 #[doc(hidden)]
 pub type TopicsQos = arrayvec::ArrayVec<(Bytes, u8), 4>;
 
+/// The default string-backed topic type used within top-level types
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub type Utf8Topic = crate::anystring::Utf8<alloc::string::String>;
+/// The default string-backed topic type used within top-level types
+///
+/// # Note
+/// This default configuration allows for 256 bytes per topic on the stack.
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+#[doc(hidden)]
+pub type Utf8Topic = crate::anystring::Utf8<arrayvec::ArrayString<256>>;
+
+/// The default collection type for string-backed topic lists used within top-level types
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub type Utf8Topics = alloc::vec::Vec<Utf8Topic>;
+/// The default collection type for string-backed topic lists used within top-level types
+///
+/// # Note
+/// This default configuration allows for 4 topics per unsubscribe message.
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+#[doc(hidden)]
+pub type Utf8Topics = arrayvec::ArrayVec<Utf8Topic, 4>;
+
+/// The default collection type for string-backed topic+quality-of-service lists used within top-level types
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub type Utf8TopicsQos = alloc::vec::Vec<(Utf8Topic, u8)>;
+/// The default collection type for string-backed topic+quality-of-service lists used within top-level types
+///
+/// # Note
+/// This default configuration allows for 4 topic+quality-of-service tuples per subscribe message.
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+#[doc(hidden)]
+pub type Utf8TopicsQos = arrayvec::ArrayVec<(Utf8Topic, u8), 4>;
+
 /// A type-erased MQTT packet
 pub type Packet = crate::packets::packet::Packet<Topics, TopicsQos, Bytes>;
+/// A resumable decoder for type-erased MQTT packets
+///
+/// # Note
+/// The decoder buffers the packets using the default byte container type, so with the `arrayvec` backend it can only
+/// hold up to 256 bytes.
+pub type PacketDecoder = crate::packets::stream::PacketDecoder<Packet, Bytes>;
+/// A resumable reader for type-erased MQTT packets from a non-blocking byte reader
+#[cfg(feature = "std")]
+pub type PartialReader = crate::packets::stream::PartialReader<Packet, Bytes>;
+/// An iterator that decodes concatenated type-erased MQTT packets from a byte iterator
+pub type PacketStream<Iter> = crate::packets::stream::PacketStream<Packet, Iter>;
 /// An MQTT [`CONNACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718033)
 pub type Connack = crate::packets::connack::Connack;
 /// An MQTT [`CONNECT` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718033)
 pub type Connect = crate::packets::connect::Connect<Bytes>;
+/// A borrowed view of an MQTT [`CONNECT`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718033)
+pub type ConnectRef<'a> = crate::packets::connect::ConnectRef<'a>;
 /// An MQTT [`DISCONNECT` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718090)
 pub type Disconnect = crate::packets::disconnect::Disconnect;
 /// An MQTT [`PINGREQ` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718081)
@@ -54,15 +107,85 @@ pub type Puback = crate::packets::puback::Puback;
 pub type Pubcomp = crate::packets::pubcomp::Pubcomp;
 /// An MQTT [`PUBLISH` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037)
 pub type Publish = crate::packets::publish::Publish<Bytes>;
+/// A borrowed view of an MQTT [`PUBLISH`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037)
+pub type PublishRef<'a> = crate::packets::publish::PublishRef<'a>;
 /// An MQTT [`PUBREC` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718048)
 pub type Pubrec = crate::packets::pubrec::Pubrec;
 /// An MQTT [`PUBREL` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718053)
 pub type Pubrel = crate::packets::pubrel::Pubrel;
+/// A raw MQTT packet of an arbitrary type that preserves the original body bytes
+pub type Raw = crate::packets::raw::Raw<Bytes>;
 /// An MQTT [`SUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718068)
 pub type Suback = crate::packets::suback::Suback;
 /// An MQTT [`SUBSCRIBE` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718063)
 pub type Subscribe = crate::packets::subscribe::Subscribe<TopicsQos, Bytes>;
+/// An encode-only MQTT [`SUBSCRIBE`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718063) that borrows its topics
+pub type SubscribeRef<'a> = crate::packets::subscribe::SubscribeRef<'a>;
 /// An MQTT [`UNSUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718077)
 pub type Unsuback = crate::packets::unsuback::Unsuback;
 /// An MQTT [`UNSUBSCRIBE` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718072)
 pub type Unsubscribe = crate::packets::unsubscribe::Unsubscribe<Topics, Bytes>;
+
+/// An MQTT [`PUBLISH` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037) with
+/// a string-typed topic
+pub type PublishUtf8 = crate::packets::publish::Publish<Bytes, Utf8Topic>;
+/// An MQTT [`SUBSCRIBE` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718063)
+/// with string-typed topics
+pub type SubscribeUtf8 = crate::packets::subscribe::Subscribe<Utf8TopicsQos, Utf8Topic>;
+/// An MQTT [`UNSUBSCRIBE`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718072) with string-typed topics
+pub type UnsubscribeUtf8 = crate::packets::unsubscribe::Unsubscribe<Utf8Topics, Utf8Topic>;
+
+/// The default collection type for MQTT 5 user property lists used within top-level types
+#[cfg(all(feature = "alloc", feature = "v5"))]
+#[doc(hidden)]
+pub type UserPropertyPairs = alloc::vec::Vec<(Bytes, Bytes)>;
+/// The default collection type for MQTT 5 user property lists used within top-level types
+///
+/// # Note
+/// This default configuration allows for 4 user properties per list.
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec", feature = "v5"))]
+#[doc(hidden)]
+pub type UserPropertyPairs = arrayvec::ArrayVec<(Bytes, Bytes), 4>;
+
+/// A type-erased MQTT 5 packet
+#[cfg(feature = "v5")]
+pub type PacketV5 = crate::packets::v5::packet::PacketV5<Bytes>;
+/// A version-agnostic type-erased MQTT 3.1.1 or MQTT 5 packet
+#[cfg(feature = "v5")]
+pub type AnyPacket = crate::packets::v5::any::AnyPacket<Topics, TopicsQos, Bytes>;
+/// A list of MQTT 5 [user properties](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901068)
+#[cfg(feature = "v5")]
+pub type UserProperties = crate::packets::v5::user_properties::UserProperties<UserPropertyPairs, Bytes>;
+/// An MQTT 5 [`CONNACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901074)
+#[cfg(feature = "v5")]
+pub type ConnackV5 = crate::packets::v5::connack::ConnackV5<Bytes>;
+/// An MQTT 5 [`CONNECT` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901033)
+#[cfg(feature = "v5")]
+pub type ConnectV5 = crate::packets::v5::connect::ConnectV5<Bytes>;
+/// An MQTT 5 [`PUBLISH` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901100)
+/// An MQTT 5 [`PUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901121)
+#[cfg(feature = "v5")]
+pub type PubackV5 = crate::packets::v5::puback::PubackV5<Bytes>;
+/// An MQTT 5 [`PUBCOMP` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901151)
+#[cfg(feature = "v5")]
+pub type PubcompV5 = crate::packets::v5::pubcomp::PubcompV5<Bytes>;
+#[cfg(feature = "v5")]
+pub type PublishV5 = crate::packets::v5::publish::PublishV5<Bytes>;
+/// An MQTT 5 [`PUBREC` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901131)
+#[cfg(feature = "v5")]
+pub type PubrecV5 = crate::packets::v5::pubrec::PubrecV5<Bytes>;
+/// An MQTT 5 [`PUBREL` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901141)
+#[cfg(feature = "v5")]
+pub type PubrelV5 = crate::packets::v5::pubrel::PubrelV5<Bytes>;
+/// An MQTT 5 [`SUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901171)
+#[cfg(feature = "v5")]
+pub type SubackV5 = crate::packets::v5::suback::SubackV5<Bytes>;
+/// An MQTT 5 [`UNSUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901187)
+#[cfg(feature = "v5")]
+pub type UnsubackV5 = crate::packets::v5::unsuback::UnsubackV5<Bytes>;
+/// A fixed-capacity [topic alias](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901113) map
+#[cfg(feature = "v5")]
+pub type TopicAliasMap<const N: usize> = crate::packets::v5::topic_alias::TopicAliasMap<Bytes, N>;
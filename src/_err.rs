@@ -0,0 +1,16 @@
+// Provides internal error macros
+
+/// Creates a [`crate::error::DecoderError`] with the given [`crate::error::Decoding`] variant and static description,
+/// optionally with an expected/found [`crate::error::Detail::Mismatch`] or a violated [`crate::error::Detail::Rule`]
+macro_rules! err {
+    ($variant:ident, $description:expr) => {
+        $crate::error::DecoderError::new($crate::error::Decoding::$variant, $description)
+    };
+    ($variant:ident, $description:expr, expected $expected:expr, found $found:expr) => {
+        err!($variant, $description)
+            .with_detail($crate::error::Detail::Mismatch { expected: $expected as u32, found: $found as u32 })
+    };
+    ($variant:ident, $description:expr, rule $rule:expr) => {
+        err!($variant, $description).with_detail($crate::error::Detail::Rule($rule))
+    };
+}
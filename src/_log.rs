@@ -0,0 +1,25 @@
+// Provides internal logging macros that are compiled out entirely if the `log` feature is disabled
+
+/// Emits a trace-level log message if the `log` feature is enabled
+#[cfg(feature = "log")]
+macro_rules! log_trace {
+    ($($arg:tt)+) => { log::trace!($($arg)+) };
+}
+/// Emits a trace-level log message if the `log` feature is enabled
+#[cfg(not(feature = "log"))]
+macro_rules! log_trace {
+    // Type-check the arguments without evaluating them
+    ($($arg:tt)+) => { if false { let _ = format_args!($($arg)+); } };
+}
+
+/// Emits a debug-level log message if the `log` feature is enabled
+#[cfg(feature = "log")]
+macro_rules! log_debug {
+    ($($arg:tt)+) => { log::debug!($($arg)+) };
+}
+/// Emits a debug-level log message if the `log` feature is enabled
+#[cfg(not(feature = "log"))]
+macro_rules! log_debug {
+    // Type-check the arguments without evaluating them
+    ($($arg:tt)+) => { if false { let _ = format_args!($($arg)+); } };
+}
@@ -0,0 +1,258 @@
+//! A bridge trait to unify required string operations over multiple implementations
+
+use crate::{anyvec::AnyVec, coding::decoder::Utf8Validator};
+use core::iter::FusedIterator;
+
+/// The error if bytes cannot be appended as complete characters
+const INVALID_UTF8: &str = "Invalid UTF-8 string";
+
+/// A bridge trait to unify required string operations over multiple implementations
+pub trait AnyString
+where
+    Self: Default + AsRef<str>,
+{
+    /// Creates a new string by copying the given string
+    fn new(string: &str) -> Result<Self, &'static str> {
+        // Init self, reserve the capacity once and copy the string
+        let mut this = Self::default();
+        this.reserve(string.len())?;
+        this.push_str(string)?;
+        Ok(this)
+    }
+    /// Appends the given string
+    fn push_str(&mut self, string: &str) -> Result<(), &'static str>;
+
+    /// Reserves capacity for at least `additional` more bytes, or fails if the string cannot hold them
+    ///
+    /// # Note
+    /// The default implementation does nothing, so that the capacity is checked lazily upon insertion.
+    fn reserve(&mut self, _additional: usize) -> Result<(), &'static str> {
+        Ok(())
+    }
+    /// The amount of bytes the string can hold without allocating or failing
+    ///
+    /// # Note
+    /// The default implementation returns the current length.
+    fn capacity(&self) -> usize {
+        self.as_ref().len()
+    }
+
+    /// Shortens the string to the given length in bytes; has no effect if the string is already shorter
+    ///
+    /// # Note
+    /// If the length does not lie on a character boundary, the string is shortened to the previous character boundary.
+    fn truncate(&mut self, len: usize);
+}
+// Implement `AnyString` for `String` if `alloc` is enabled
+#[cfg(feature = "alloc")]
+impl AnyString for alloc::string::String {
+    fn push_str(&mut self, string: &str) -> Result<(), &'static str> {
+        // Allocate capacity and append string
+        self.try_reserve(string.len()).map_err(|_| crate::anyvec::ALLOCATION_FAILED)?;
+        self.push_str(string);
+        Ok(())
+    }
+
+    fn reserve(&mut self, additional: usize) -> Result<(), &'static str> {
+        // Allocate capacity
+        self.try_reserve(additional).map_err(|_| crate::anyvec::ALLOCATION_FAILED)
+    }
+
+    fn capacity(&self) -> usize {
+        alloc::string::String::capacity(self)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        let len = floor_char_boundary(self, len);
+        self.truncate(len);
+    }
+}
+// Implement `AnyString` for `ArrayString` if `arrayvec` is enabled
+#[cfg(feature = "arrayvec")]
+impl<const CAP: usize> AnyString for arrayvec::ArrayString<CAP> {
+    fn push_str(&mut self, string: &str) -> Result<(), &'static str> {
+        // Append string
+        self.try_push_str(string).map_err(|_| crate::anyvec::NOT_ENOUGH_MEMORY)
+    }
+
+    fn reserve(&mut self, additional: usize) -> Result<(), &'static str> {
+        // Ensure that the remaining capacity is large enough
+        match self.remaining_capacity() {
+            capacity if capacity >= additional => Ok(()),
+            _ => Err(crate::anyvec::NOT_ENOUGH_MEMORY),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        CAP
+    }
+
+    fn truncate(&mut self, len: usize) {
+        let len = floor_char_boundary(self, len);
+        self.truncate(len);
+    }
+}
+
+/// A string-backed byte field (e.g. a topic) that can be used wherever a byte container is expected
+///
+/// # Note
+/// The byte field is validated as MQTT UTF-8 string when it is modified, i.e. it must be well-formed UTF-8 and must not
+/// contain the null character `U+0000` (see
+/// [MQTT 3.1.1 section 1.5.3](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718016)).
+/// Since the underlying string must remain valid UTF-8, its bytes cannot be mutated in-place.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Utf8<S> {
+    /// The underlying string
+    string: S,
+}
+impl<S> Utf8<S>
+where
+    S: AnyString,
+{
+    /// Creates a new string-backed byte field from the given string
+    ///
+    /// # Note
+    /// Unlike decoding, this does not reject the null character `U+0000`.
+    pub const fn new(string: S) -> Self {
+        Self { string }
+    }
+
+    /// The underlying string
+    pub fn as_str(&self) -> &str {
+        self.string.as_ref()
+    }
+    /// Returns the underlying string
+    pub fn into_inner(self) -> S {
+        self.string
+    }
+}
+impl<S> AsRef<str> for Utf8<S>
+where
+    S: AnyString,
+{
+    fn as_ref(&self) -> &str {
+        self.string.as_ref()
+    }
+}
+impl<S> AsRef<[u8]> for Utf8<S>
+where
+    S: AnyString,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.string.as_ref().as_bytes()
+    }
+}
+impl<S> IntoIterator for Utf8<S>
+where
+    S: AnyString,
+{
+    type Item = u8;
+    type IntoIter = Utf8Iter<S>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Utf8Iter { string: self.string, position: 0 }
+    }
+}
+impl<S> AnyVec<u8> for Utf8<S>
+where
+    S: AnyString,
+{
+    fn extend(&mut self, elements: &[u8]) -> Result<(), &'static str> {
+        self.extend_from_iter(elements.iter().copied())
+    }
+
+    fn extend_from_iter<I>(&mut self, iter: I) -> Result<(), &'static str>
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        // Buffer the bytes of the current character until it is complete
+        let mut validator = Utf8Validator::default();
+        let (mut char_buf, mut char_len) = ([0; 4], 0);
+        for byte in iter {
+            // Validate and buffer the byte
+            validator.push(byte)?;
+            let slot = char_buf.get_mut(char_len).ok_or(INVALID_UTF8)?;
+            (*slot, char_len) = (byte, char_len.saturating_add(1));
+
+            // Append the character once it is complete
+            if validator.is_complete() {
+                let char_bytes = char_buf.get(..char_len).unwrap_or_default();
+                let char_ = core::str::from_utf8(char_bytes).map_err(|_| INVALID_UTF8)?;
+                self.string.push_str(char_)?;
+                char_len = 0;
+            }
+        }
+
+        // Ensure the bytes do not end within a multi-byte sequence
+        validator.finish()
+    }
+
+    fn reserve(&mut self, additional: usize) -> Result<(), &'static str> {
+        self.string.reserve(additional)
+    }
+
+    fn capacity(&self) -> usize {
+        self.string.capacity()
+    }
+
+    fn is_value_error(&self, error: &'static str) -> bool {
+        // Validation errors reject the bytes, all other errors stem from the underlying string
+        Utf8Validator::is_error(error)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.string.truncate(len);
+    }
+
+    fn insert(&mut self, index: usize, element: u8) -> Result<(), &'static str> {
+        // Only ASCII characters can be appended individually
+        let true = index == self.string.as_ref().len() else {
+            return Err("Index is invalid");
+        };
+        self.extend(&[element])
+    }
+}
+
+/// The iterator over the bytes of a [`Utf8`] byte field
+#[derive(Debug, Clone)]
+pub struct Utf8Iter<S> {
+    /// The underlying string
+    string: S,
+    /// The position of the next byte
+    position: usize,
+}
+impl<S> Iterator for Utf8Iter<S>
+where
+    S: AnyString,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = self.string.as_ref().as_bytes().get(self.position).copied()?;
+        self.position = self.position.saturating_add(1);
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.string.as_ref().len().saturating_sub(self.position);
+        (remaining, Some(remaining))
+    }
+}
+impl<S> ExactSizeIterator for Utf8Iter<S>
+where
+    S: AnyString,
+{
+    // No members to implement
+}
+impl<S> FusedIterator for Utf8Iter<S>
+where
+    S: AnyString,
+{
+    // No members to implement
+}
+
+/// Finds the greatest character boundary that is less than or equal to the given length
+#[cfg(any(feature = "alloc", feature = "arrayvec"))]
+fn floor_char_boundary(string: &str, len: usize) -> usize {
+    (0..=len.min(string.len())).rev().find(|len| string.is_char_boundary(*len)).unwrap_or_default()
+}
@@ -1,17 +1,29 @@
 //! A bridge trait to unify required vector operations over multiple implementations
 
+/// The error of heap-allocated vectors if memory cannot be allocated
+#[cfg(feature = "alloc")]
+pub(crate) const ALLOCATION_FAILED: &str = "Failed to alocate memory";
+/// The error of fixed-capacity vectors if the capacity is exhausted
+#[cfg(feature = "arrayvec")]
+pub(crate) const NOT_ENOUGH_MEMORY: &str = "Not enough memory";
+
 /// A bridge trait to unify required vector operations over multiple implementations
+///
+/// # Note
+/// Mutable access to the elements is not required, so that containers with invariants over their elements (e.g.
+/// [`crate::anystring::Utf8`]) can implement this trait.
 pub trait AnyVec<T>
 where
-    Self: Default + AsRef<[T]> + AsMut<[T]> + IntoIterator<Item = T>,
+    Self: Default + AsRef<[T]> + IntoIterator<Item = T>,
 {
     /// Creates a new vector by copying the given elements
     fn new(elements: &[T]) -> Result<Self, &'static str>
     where
         T: Clone,
     {
-        // Init self and copy elements
+        // Init self, reserve the capacity once and copy elements
         let mut this = Self::default();
+        this.reserve(elements.len())?;
         this.extend(elements)?;
         Ok(this)
     }
@@ -19,6 +31,73 @@ where
     fn extend(&mut self, elements: &[T]) -> Result<(), &'static str>
     where
         T: Clone;
+    /// Extends the vector from the given iterator
+    ///
+    /// # Note
+    /// The default implementation pushes the elements one by one. If the vector cannot hold all elements, the elements
+    /// pushed so far are retained.
+    fn extend_from_iter<I>(&mut self, iter: I) -> Result<(), &'static str>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for element in iter {
+            // Push each element
+            self.push(element)?;
+        }
+        Ok(())
+    }
+
+    /// Reserves capacity for at least `additional` more elements, or fails if the vector cannot hold them
+    ///
+    /// # Note
+    /// The default implementation does nothing, so that the capacity is checked lazily upon insertion.
+    fn reserve(&mut self, _additional: usize) -> Result<(), &'static str> {
+        Ok(())
+    }
+    /// The amount of elements the vector can hold without allocating or failing
+    ///
+    /// # Note
+    /// The default implementation returns the current length.
+    fn capacity(&self) -> usize {
+        self.as_ref().len()
+    }
+    /// Whether the given error of this vector rejects an element because of its value (e.g. invalid UTF-8), instead of
+    /// signalling that the vector cannot hold it
+    ///
+    /// # Note
+    /// The default implementation treats all errors as memory errors.
+    fn is_value_error(&self, _error: &'static str) -> bool {
+        false
+    }
+
+    /// Shortens the vector to the given length; has no effect if the vector is already shorter
+    ///
+    /// # Note
+    /// The default implementation rebuilds the vector from the retained elements, which does not retain the allocated
+    /// capacity. If the rebuilt vector cannot hold a retained element (e.g. because an allocation fails), this element
+    /// and all elements after it are lost; this is considered a bug and panics in debug builds. Vectors that can be
+    /// shortened in place should override this.
+    fn truncate(&mut self, len: usize) {
+        // Rebuild the vector if necessary
+        if self.as_ref().len() > len {
+            let elements = core::mem::take(self);
+            for element in elements.into_iter().take(len) {
+                // The retained elements should fit into the vector as they were held before
+                let result = self.push(element);
+                debug_assert!(result.is_ok(), "Failed to retain element when truncating vector");
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    /// Removes all elements from the vector
+    ///
+    /// # Note
+    /// The allocated capacity of the built-in vectors is retained, so the vector can be reused without reallocation.
+    fn clear(&mut self) {
+        self.truncate(0);
+    }
 
     /// Inserts the given element at the given index
     fn insert(&mut self, index: usize, element: T) -> Result<(), &'static str>;
@@ -27,9 +106,9 @@ where
         self.insert(self.as_ref().len(), element)
     }
 }
-// Implement `AnyVec` for `Vec<u8>` if `std` is enabled
-#[cfg(feature = "std")]
-impl<T> AnyVec<T> for std::vec::Vec<T> {
+// Implement `AnyVec` for `Vec<u8>` if `alloc` is enabled
+#[cfg(feature = "alloc")]
+impl<T> AnyVec<T> for alloc::vec::Vec<T> {
     fn insert(&mut self, index: usize, element: T) -> Result<(), &'static str> {
         // Validate index
         let true = index <= self.len() else {
@@ -37,7 +116,7 @@ impl<T> AnyVec<T> for std::vec::Vec<T> {
         };
 
         // Allocate capacity and insert element
-        self.try_reserve(1).map_err(|_| "Failed to alocate memory")?;
+        self.try_reserve(1).map_err(|_| ALLOCATION_FAILED)?;
         self.insert(index, element);
         Ok(())
     }
@@ -47,17 +126,50 @@ impl<T> AnyVec<T> for std::vec::Vec<T> {
         T: Clone,
     {
         // Allocate capacity and extend vector
-        self.try_reserve(elements.len()).map_err(|_| "Failed to alocate memory")?;
+        self.try_reserve(elements.len()).map_err(|_| ALLOCATION_FAILED)?;
         self.extend_from_slice(elements);
         Ok(())
     }
+
+    fn extend_from_iter<I>(&mut self, iter: I) -> Result<(), &'static str>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        // Allocate the expected capacity once and extend vector
+        let iter = iter.into_iter();
+        self.try_reserve(iter.size_hint().0).map_err(|_| ALLOCATION_FAILED)?;
+        for element in iter {
+            // Allocate capacity if the size hint was too small and push element
+            self.try_reserve(1).map_err(|_| ALLOCATION_FAILED)?;
+            self.push(element);
+        }
+        Ok(())
+    }
+
+    fn reserve(&mut self, additional: usize) -> Result<(), &'static str> {
+        // Allocate capacity
+        self.try_reserve(additional).map_err(|_| ALLOCATION_FAILED)
+    }
+
+    fn capacity(&self) -> usize {
+        alloc::vec::Vec::capacity(self)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
 }
-// Implement `AnyVec` for `Vec<u8>` if `std` is enabled
+// Implement `AnyVec` for `ArrayVec` if `arrayvec` is enabled
 #[cfg(feature = "arrayvec")]
 impl<T, const CAP: usize> AnyVec<T> for arrayvec::ArrayVec<T, CAP> {
     fn insert(&mut self, index: usize, element: T) -> Result<(), &'static str> {
+        // Validate index
+        let true = index <= self.len() else {
+            return Err("Index is invalid");
+        };
+
         // Insert element
-        self.try_insert(index, element).map_err(|_| "Index is invalid")
+        self.try_insert(index, element).map_err(|_| NOT_ENOUGH_MEMORY)
     }
 
     fn extend(&mut self, elements: &[T]) -> Result<(), &'static str>
@@ -67,8 +179,177 @@ impl<T, const CAP: usize> AnyVec<T> for arrayvec::ArrayVec<T, CAP> {
         // Extend vector
         for element in elements.iter().cloned() {
             // Push each element
-            self.try_push(element).map_err(|_| "Not enough memory")?;
+            self.try_push(element).map_err(|_| NOT_ENOUGH_MEMORY)?;
+        }
+        Ok(())
+    }
+
+    fn extend_from_iter<I>(&mut self, iter: I) -> Result<(), &'static str>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        // Fail early if the iterator is known to exceed the remaining capacity
+        let iter = iter.into_iter();
+        if iter.size_hint().0 > self.remaining_capacity() {
+            return Err(NOT_ENOUGH_MEMORY);
+        }
+
+        // Extend vector
+        for element in iter {
+            // Push each element
+            self.try_push(element).map_err(|_| NOT_ENOUGH_MEMORY)?;
         }
         Ok(())
     }
+
+    fn reserve(&mut self, additional: usize) -> Result<(), &'static str> {
+        // Ensure that the remaining capacity is large enough
+        match self.remaining_capacity() {
+            capacity if capacity >= additional => Ok(()),
+            _ => Err(NOT_ENOUGH_MEMORY),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        CAP
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+}
+
+/// A byte container that either borrows its bytes or owns them, and switches to owned bytes upon modification
+///
+/// # Note
+/// This allows packets whose fields are mostly borrowed from a parse buffer (see e.g.
+/// [`crate::packets::publish::PublishRef::to_cow`]), but can be rewritten individually without copying other fields.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CowBytes<'a> {
+    /// The underlying bytes
+    bytes: alloc::borrow::Cow<'a, [u8]>,
+}
+#[cfg(feature = "alloc")]
+impl<'a> CowBytes<'a> {
+    /// Creates a new borrowed byte container
+    pub const fn borrowed(bytes: &'a [u8]) -> Self {
+        Self { bytes: alloc::borrow::Cow::Borrowed(bytes) }
+    }
+
+    /// Creates a new owned byte container
+    pub const fn owned(bytes: alloc::vec::Vec<u8>) -> Self {
+        Self { bytes: alloc::borrow::Cow::Owned(bytes) }
+    }
+
+    /// Whether the bytes are borrowed or not
+    pub const fn is_borrowed(&self) -> bool {
+        matches!(self.bytes, alloc::borrow::Cow::Borrowed(_))
+    }
+
+    /// Returns the underlying bytes
+    pub fn into_inner(self) -> alloc::borrow::Cow<'a, [u8]> {
+        self.bytes
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a> From<alloc::borrow::Cow<'a, [u8]>> for CowBytes<'a> {
+    fn from(bytes: alloc::borrow::Cow<'a, [u8]>) -> Self {
+        Self { bytes }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a> From<crate::coding::encoder::BytesRef<'a>> for CowBytes<'a> {
+    fn from(bytes: crate::coding::encoder::BytesRef<'a>) -> Self {
+        Self::borrowed(bytes.into_slice())
+    }
+}
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for CowBytes<'_> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+#[cfg(feature = "alloc")]
+impl AsMut<[u8]> for CowBytes<'_> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        // Switch to owned bytes
+        self.bytes.to_mut()
+    }
+}
+#[cfg(feature = "alloc")]
+impl<'a> IntoIterator for CowBytes<'a> {
+    type Item = u8;
+    type IntoIter = CowBytesIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self.bytes {
+            alloc::borrow::Cow::Borrowed(bytes) => CowBytesIter::Borrowed(bytes.iter().copied()),
+            alloc::borrow::Cow::Owned(bytes) => CowBytesIter::Owned(bytes.into_iter()),
+        }
+    }
+}
+#[cfg(feature = "alloc")]
+impl AnyVec<u8> for CowBytes<'_> {
+    fn insert(&mut self, index: usize, element: u8) -> Result<(), &'static str> {
+        // Switch to owned bytes and insert element
+        AnyVec::insert(self.bytes.to_mut(), index, element)
+    }
+
+    fn extend(&mut self, elements: &[u8]) -> Result<(), &'static str> {
+        // Switch to owned bytes and extend vector
+        AnyVec::extend(self.bytes.to_mut(), elements)
+    }
+
+    fn reserve(&mut self, additional: usize) -> Result<(), &'static str> {
+        // Switch to owned bytes and allocate capacity
+        AnyVec::reserve(self.bytes.to_mut(), additional)
+    }
+
+    fn capacity(&self) -> usize {
+        match &self.bytes {
+            alloc::borrow::Cow::Borrowed(bytes) => bytes.len(),
+            alloc::borrow::Cow::Owned(bytes) => bytes.capacity(),
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        match &mut self.bytes {
+            // Shorten the borrowed slice without copying
+            alloc::borrow::Cow::Borrowed(bytes) => *bytes = bytes.get(..len).unwrap_or(bytes),
+            alloc::borrow::Cow::Owned(bytes) => bytes.truncate(len),
+        }
+    }
+}
+
+/// The iterator over the bytes of a [`CowBytes`] container
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub enum CowBytesIter<'a> {
+    /// An iterator over borrowed bytes
+    Borrowed(crate::coding::encoder::SliceIter<'a>),
+    /// An iterator over owned bytes
+    Owned(alloc::vec::IntoIter<u8>),
+}
+#[cfg(feature = "alloc")]
+impl Iterator for CowBytesIter<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Borrowed(iter) => iter.next(),
+            Self::Owned(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Borrowed(iter) => iter.size_hint(),
+            Self::Owned(iter) => iter.size_hint(),
+        }
+    }
+}
+#[cfg(feature = "alloc")]
+impl ExactSizeIterator for CowBytesIter<'_> {
+    // No members to implement
 }
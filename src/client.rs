@@ -0,0 +1,52 @@
+//! A thin helper that bundles a stream with packet framing and the connection handshake
+//!
+//! # Note
+//! The connection does not own any policy (i.e. no automatic reconnects, keep-alive or QoS tracking); it only reads and
+//! writes complete packets.
+
+use crate::{
+    packets::{ToWriter, TryFromReader},
+    Connack, Connect, Disconnect, Packet,
+};
+use std::io::{Read, Write};
+
+/// An MQTT connection over a byte stream
+#[derive(Debug)]
+pub struct MqttConnection<S> {
+    /// The underlying stream
+    stream: S,
+}
+impl<S> MqttConnection<S>
+where
+    S: Read + Write,
+{
+    /// Sends the given `CONNECT` packet over the stream and awaits the `CONNACK` packet
+    ///
+    /// # Note
+    /// The connection is returned even if the server has refused the connection; it is up to the caller to validate the
+    /// [`Connack::return_code`].
+    pub fn connect(mut stream: S, connect: Connect) -> Result<(Self, Connack), std::io::Error> {
+        connect.write(&mut stream)?;
+        let connack = Connack::try_read(&mut stream)?;
+        Ok((Self { stream }, connack))
+    }
+
+    /// Sends a packet
+    pub fn send<P>(&mut self, packet: P) -> Result<(), std::io::Error>
+    where
+        P: IntoIterator<Item = u8>,
+    {
+        packet.write(&mut self.stream)
+    }
+
+    /// Receives the next packet
+    pub fn recv(&mut self) -> Result<Packet, std::io::Error> {
+        Packet::try_read(&mut self.stream)
+    }
+
+    /// Sends a `DISCONNECT` packet and returns the underlying stream
+    pub fn disconnect(mut self) -> Result<S, std::io::Error> {
+        Disconnect::new().write(&mut self.stream)?;
+        Ok(self.stream)
+    }
+}
@@ -1,13 +1,22 @@
 //! An iterator-based decoder
 
-use crate::anyvec::AnyVec;
-use core::iter::{Peekable, Take};
+use crate::{
+    anyvec::AnyVec,
+    coding::{encoder::BytesRef, varint},
+    error::DecoderError,
+    packets::flags::HeaderFlags,
+};
+use core::iter::{self, Peekable, Take};
 
 /// An iterator-based decoder
 #[derive(Debug)]
 pub struct Decoder<Iter> {
     /// The underlying iterator
     source: Iter,
+    /// The amount of bytes consumed so far
+    position: usize,
+    /// The position at which the decoder must end if it has been created via [`Self::take_exact`]
+    end: Option<usize>,
 }
 impl<Iter> Decoder<Iter> {
     /// Create a new decoder over an iterator
@@ -15,7 +24,16 @@ impl<Iter> Decoder<Iter> {
     where
         T: IntoIterator<IntoIter = Iter>,
     {
-        Self { source: source.into_iter() }
+        Self { source: source.into_iter(), position: 0, end: None }
+    }
+
+    /// The amount of bytes consumed so far
+    ///
+    /// # Note
+    /// The position is retained across [`Self::limit`] and [`Self::peekable`], so it always refers to the beginning of
+    /// the original source.
+    pub const fn position(&self) -> usize {
+        self.position
     }
 }
 impl<Iter> Decoder<Iter>
@@ -24,12 +42,49 @@ where
 {
     /// Limits the decoder to the given amount of bytes
     pub fn peekable(self) -> Decoder<Peekable<Iter>> {
-        Decoder { source: self.source.peekable() }
+        Decoder { source: self.source.peekable(), position: self.position, end: self.end }
     }
 
     /// Limits the decoder to the given amount of bytes
     pub fn limit(self, limit: usize) -> Decoder<Take<Iter>> {
-        Decoder { source: self.source.take(limit) }
+        Decoder { source: self.source.take(limit), position: self.position, end: None }
+    }
+
+    /// Limits the decoder to exactly the given amount of bytes
+    ///
+    /// # Note
+    /// Unlike [`Self::limit`], the amount of bytes is verified when the decoder is finalized via [`Self::finish`].
+    pub fn take_exact(self, len: usize) -> Decoder<Take<Iter>> {
+        let end = self.position.saturating_add(len);
+        Decoder { source: self.source.take(len), position: self.position, end: Some(end) }
+    }
+
+    /// Finalizes a decoder created via [`Self::take_exact`], or fails if not exactly the expected amount of bytes has
+    /// been available and consumed
+    ///
+    /// # Note
+    /// If the fields have not consumed all bytes, the remaining bytes are discarded to distinguish trailing bytes from
+    /// a truncated source; afterwards [`Self::position`] points to the end of the available bytes. For decoders that
+    /// have not been created via [`Self::take_exact`], this function is a no-op.
+    #[track_caller]
+    pub fn finish(mut self) -> Result<(), DecoderError> {
+        let Some(end) = self.end else {
+            return Ok(());
+        };
+
+        // Ensure that all bytes have been consumed
+        match self.position == end {
+            true => Ok(()),
+            false => {
+                // Distinguish trailing bytes from a truncated source
+                let trailing = self.position;
+                self.skip_remaining();
+                match self.position == end {
+                    true => Err(err!(SpecViolation, "Unexpected trailing bytes").with_offset(trailing)),
+                    false => Err(err!(Truncated, "Truncated input").with_offset(self.position)),
+                }
+            }
+        }
     }
 
     /// Reads the remaining data as-is
@@ -37,26 +92,76 @@ where
     /// # Note
     /// This function is greedy. As raw read is unbounded by definition, this function will simply read as much data as
     /// possible until the underlying source is exhausted. Limit the source using [`Self::limit`] if necessary.
-    pub fn raw_remainder<T>(&mut self) -> Result<T, &'static str>
+    #[track_caller]
+    pub fn raw_remainder<T>(&mut self) -> Result<T, DecoderError>
     where
         T: AnyVec<u8>,
     {
-        // Read all remaining bytes
         let mut raw = T::default();
-        for byte in &mut self.source {
-            // Try to append byte
-            raw.push(byte)?;
-        }
+        self.raw_remainder_into(&mut raw)?;
         Ok(raw)
     }
 
+    /// Reads the remaining data as-is into the given container, replacing its previous contents
+    ///
+    /// # Note
+    /// This function is greedy (see [`Self::raw_remainder`]).
+    #[track_caller]
+    pub fn raw_remainder_into<T>(&mut self, raw: &mut T) -> Result<(), DecoderError>
+    where
+        T: AnyVec<u8>,
+    {
+        // Reserve the known amount of remaining bytes once and read all remaining bytes
+        let start = self.position;
+        let length = self.source.size_hint().0;
+        raw.clear();
+        if let Err(e) = raw.reserve(length) {
+            return Err(DecoderError::container(raw, e, length).with_offset(start));
+        }
+        match raw.extend_from_iter(iter::from_fn(|| self.next_u8())) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                // The rejected byte has been consumed already
+                let needed = raw.as_ref().len().saturating_add(1).saturating_add(self.source.size_hint().0);
+                Err(DecoderError::container(raw, e, needed).with_offset(start))
+            }
+        }
+    }
+
+    /// Discards the remaining data
+    ///
+    /// # Note
+    /// This function is greedy. As raw read is unbounded by definition, this function will simply discard as much data
+    /// as possible until the underlying source is exhausted. Limit the source using [`Self::limit`] if necessary.
+    pub fn skip_remaining(&mut self) {
+        while self.next_u8().is_some() {
+            // Discard each byte
+        }
+    }
+
+    /// Discards exactly `len` bytes
+    #[track_caller]
+    pub fn skip(&mut self, len: usize) -> Result<(), DecoderError> {
+        for _ in 0..len {
+            // Require next byte
+            self.u8()?;
+        }
+        Ok(())
+    }
+
     /// Reads a `u8`
-    pub fn u8(&mut self) -> Result<u8, &'static str> {
-        self.source.next().ok_or("Truncated input")
+    #[track_caller]
+    pub fn u8(&mut self) -> Result<u8, DecoderError> {
+        let offset = self.position;
+        match self.next_u8() {
+            Some(byte) => Ok(byte),
+            None => Err(err!(Truncated, "Truncated input").with_offset(offset)),
+        }
     }
 
     /// Reads some raw bytes as-is into a fixed-size array
-    pub fn raw<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], &'static str> {
+    #[track_caller]
+    pub fn raw<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], DecoderError> {
         // Fill an entire array of the requested bytes
         let mut array = [0; SIZE];
         for slot in array.iter_mut() {
@@ -66,30 +171,168 @@ where
         Ok(array)
     }
 
+    /// Reads an expected constant byte sequence, or fails at the first mismatching byte
+    ///
+    /// # Note
+    /// On mismatch, [`Self::position`] points directly behind the offending byte.
+    #[track_caller]
+    pub fn constant(&mut self, expected: &[u8]) -> Result<(), DecoderError> {
+        for expected in expected {
+            // Compare each byte
+            let offset = self.position;
+            let found = self.u8()?;
+            if found != *expected {
+                let error = err!(SpecViolation, "Invalid constant", expected *expected, found found);
+                return Err(error.with_offset(offset));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads an expected protocol version byte, or fails with [`crate::error::Decoding::UnsupportedVersion`] if the
+    /// version is not supported
+    #[track_caller]
+    pub fn version_constant(&mut self, expected: u8) -> Result<(), DecoderError> {
+        let offset = self.position;
+        match self.u8()? {
+            version if version == expected => Ok(()),
+            version => {
+                let error = err!(UnsupportedVersion, "Unsupported protocol version", expected expected, found version);
+                Err(error.with_offset(offset))
+            }
+        }
+    }
+
     /// Reads a `u16`
-    pub fn u16(&mut self) -> Result<u16, &'static str> {
+    #[track_caller]
+    pub fn u16(&mut self) -> Result<u16, DecoderError> {
         let bytes = self.raw()?;
         Ok(u16::from_be_bytes(bytes))
     }
 
     /// Reads a length-prefixed byte field
-    pub fn bytes<T>(&mut self) -> Result<T, &'static str>
+    #[track_caller]
+    pub fn bytes<T>(&mut self) -> Result<T, DecoderError>
+    where
+        T: AnyVec<u8>,
+    {
+        let mut bytes = T::default();
+        self.bytes_into(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Reads a length-prefixed byte field into the given container, replacing its previous contents
+    #[track_caller]
+    pub fn bytes_into<T>(&mut self, bytes: &mut T) -> Result<(), DecoderError>
     where
         T: AnyVec<u8>,
     {
+        // Reserve the capacity once
+        let start = self.position;
+        let length = self.u16()? as usize;
+        bytes.clear();
+        if let Err(e) = bytes.reserve(length) {
+            return Err(DecoderError::container(bytes, e, length).with_offset(start));
+        }
+
         // Copy the exact amount of bytes from the source iterator
+        let bytes_iter = (0..length).map_while(|_| self.next_u8());
+        if let Err(e) = bytes.extend_from_iter(bytes_iter) {
+            return Err(DecoderError::container(bytes, e, length).with_offset(start));
+        }
+        match bytes.as_ref().len() == length {
+            true => Ok(()),
+            false => Err(err!(Truncated, "Truncated input").with_offset(self.position)),
+        }
+    }
+
+    /// Reads a length-prefixed UTF-8 string field
+    ///
+    /// # Note
+    /// The string is validated incrementally while it is read; it must be well-formed UTF-8 and must not contain the
+    /// null character `U+0000` (see
+    /// [MQTT 3.1.1 section 1.5.3](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718016)).
+    #[track_caller]
+    pub fn utf8<T>(&mut self) -> Result<T, DecoderError>
+    where
+        T: AnyVec<u8>,
+    {
+        // Copy the exact amount of bytes from the source iterator at once and validate them on the fly, so that
+        // containers which only accept complete characters (e.g. `Utf8`) receive multi-byte sequences in one go
+        let start = self.position;
         let length = self.u16()? as usize;
+        let (mut validator, mut error) = (Utf8Validator::default(), None);
         let mut bytes = T::default();
-        for _ in 0..length {
-            // Copy each byte
-            let byte = self.u8()?;
-            bytes.push(byte)?;
+        let bytes_iter = (0..length).map_while(|_| {
+            // Validate each byte
+            let offset = self.position;
+            let Some(byte) = self.next_u8() else {
+                error = Some(err!(Truncated, "Truncated input").with_offset(offset));
+                return None;
+            };
+            match validator.push(byte) {
+                Ok(()) => Some(byte),
+                Err(e) => {
+                    error = Some(err!(SpecViolation, e).with_offset(offset));
+                    None
+                }
+            }
+        });
+        let result = bytes.extend_from_iter(bytes_iter);
+        if let Some(error) = error {
+            return Err(error);
+        }
+        if let Err(e) = result {
+            return Err(DecoderError::container(&bytes, e, length).with_offset(start));
+        }
+
+        // Ensure the string does not end within a multi-byte sequence
+        match validator.finish() {
+            Ok(()) => Ok(bytes),
+            Err(e) => Err(err!(SpecViolation, e).with_offset(self.position)),
+        }
+    }
+
+    /// Reads a length-prefixed byte field as borrowed sub-slice of the given source
+    ///
+    /// # Important
+    /// `source` must be the slice this decoder reads from, so that the decoder position corresponds to the offset
+    /// within `source`.
+    #[track_caller]
+    pub fn bytes_ref<'a>(&mut self, source: &'a [u8]) -> Result<BytesRef<'a>, DecoderError> {
+        // Skip the exact amount of bytes and borrow them from the source
+        let length = self.u16()? as usize;
+        let start = self.position;
+        self.skip(length)?;
+        match source.get(start..self.position) {
+            Some(bytes) => Ok(BytesRef::new(bytes)),
+            None => Err(err!(Truncated, "Truncated input").with_offset(source.len())),
+        }
+    }
+
+    /// Reads the remaining data as borrowed sub-slice of the given source
+    ///
+    /// # Important
+    /// `source` must be the slice this decoder reads from, so that the decoder position corresponds to the offset
+    /// within `source`.
+    ///
+    /// # Note
+    /// This function is greedy. As raw read is unbounded by definition, this function will simply read as much data as
+    /// possible until the underlying source is exhausted. Limit the source using [`Self::limit`] if necessary.
+    #[track_caller]
+    pub fn raw_remainder_ref<'a>(&mut self, source: &'a [u8]) -> Result<BytesRef<'a>, DecoderError> {
+        // Skip all remaining bytes and borrow them from the source
+        let start = self.position;
+        self.skip_remaining();
+        match source.get(start..self.position) {
+            Some(bytes) => Ok(BytesRef::new(bytes)),
+            None => Err(err!(Truncated, "Truncated input").with_offset(source.len())),
         }
-        Ok(bytes)
     }
 
     /// Reads a byte as bitmap
-    pub fn bitmap(&mut self) -> Result<[bool; 8], &'static str> {
+    #[track_caller]
+    pub fn bitmap(&mut self) -> Result<[bool; 8], DecoderError> {
         let byte = self.u8()?;
         Ok([
             byte & 0b10000000 != 0,
@@ -104,39 +347,90 @@ where
     }
 
     /// Reads a header byte and decodes it into packet type and associated flags (as bitmap)
-    pub fn header(&mut self) -> Result<(u8, [bool; 4]), &'static str> {
+    #[track_caller]
+    pub fn header(&mut self) -> Result<(u8, [bool; 4]), DecoderError> {
+        let byte = self.u8()?;
+        Ok(split_header(byte))
+    }
+
+    /// Reads a header byte and decodes it into packet type and associated flags
+    #[track_caller]
+    pub fn header_typed(&mut self) -> Result<(u8, HeaderFlags), DecoderError> {
         let byte = self.u8()?;
-        Ok((byte >> 4, [byte & 0b1000 != 0, byte & 0b0100 != 0, byte & 0b0010 != 0, byte & 0b0001 != 0]))
+        Ok((byte >> 4, HeaderFlags::new(byte)))
+    }
+
+    /// Reads a variable byte integer
+    #[track_caller]
+    pub fn varint(&mut self) -> Result<usize, DecoderError> {
+        self.packetlen()
+    }
+
+    /// Reads a properties block that is prefixed with its length as variable byte integer into the given container,
+    /// replacing its previous contents
+    ///
+    /// # Note
+    /// The properties are copied as-is; use the packet-specific types to validate and interpret them.
+    #[track_caller]
+    pub fn properties_into<T>(&mut self, properties: &mut T) -> Result<(), DecoderError>
+    where
+        T: AnyVec<u8>,
+    {
+        // Ensure that the untrusted length is backed by the remaining input before reserving any capacity
+        let start = self.position;
+        let length = self.varint()?;
+        let remaining = self.source.size_hint().1;
+        if let Some(remaining) = remaining.filter(|remaining| length > *remaining) {
+            return Err(err!(Truncated, "Truncated input").with_offset(self.position.saturating_add(remaining)));
+        }
+
+        // Reserve the capacity once; if the input length is unknown, the capacity grows lazily instead
+        properties.clear();
+        if let Err(e) = properties.reserve(remaining.map_or(0, |_| length)) {
+            return Err(DecoderError::container(properties, e, length).with_offset(start));
+        }
+
+        // Copy the exact amount of bytes from the source iterator
+        let properties_iter = (0..length).map_while(|_| self.next_u8());
+        if let Err(e) = properties.extend_from_iter(properties_iter) {
+            return Err(DecoderError::container(properties, e, length).with_offset(start));
+        }
+        match properties.as_ref().len() == length {
+            true => Ok(()),
+            false => Err(err!(Truncated, "Truncated input").with_offset(self.position)),
+        }
     }
 
     /// Reads a packet length field
-    pub fn packetlen(&mut self) -> Result<usize, &'static str> {
-        // Parse length
-        let mut value = 0;
-        for (pos, byte) in (&mut self.source).enumerate() {
-            // Decode next length byte
-            value <<= 7;
-            value |= (byte & 0b0111_1111) as usize;
-
-            // Check for end-of-length
-            match byte & 0b1000_0000 {
-                // Multi-byte length with a leading zero heptet
-                0b1000_0000 if byte == 0b1000_0000 && value == 0 => return Err("Invalid packet length"),
-                // Not the last byte but further length bytes are invalid
-                0b1000_0000 if pos > 2 => return Err("Packet length is too large"),
-                // Not the last byte and further length bytes are allowed
-                0b1000_0000 => continue,
-                // Length byte is the last byte
-                _ => return Ok(value),
-            }
+    #[track_caller]
+    pub fn packetlen(&mut self) -> Result<usize, DecoderError> {
+        let start = self.position;
+        let (value, _) = varint::decode(iter::from_fn(|| self.next_u8()))
+            .map_err(|e| e.with_offset(start.saturating_add(e.offset())))?;
+        Ok(value)
+    }
+
+    /// Reads an optional `u8`
+    #[track_caller]
+    pub fn optional_u8(&mut self, condition: bool) -> Result<Option<u8>, DecoderError> {
+        match condition {
+            true => self.u8().map(Some),
+            false => Ok(None),
         }
+    }
 
-        // The packet length is truncated
-        Err("Truncated input")
+    /// Reads some optional raw bytes as-is into a fixed-size array
+    #[track_caller]
+    pub fn optional_raw<const SIZE: usize>(&mut self, condition: bool) -> Result<Option<[u8; SIZE]>, DecoderError> {
+        match condition {
+            true => self.raw().map(Some),
+            false => Ok(None),
+        }
     }
 
     /// Reads an optional `u16`
-    pub fn optional_u16(&mut self, condition: bool) -> Result<Option<u16>, &'static str> {
+    #[track_caller]
+    pub fn optional_u16(&mut self, condition: bool) -> Result<Option<u16>, DecoderError> {
         match condition {
             true => self.u16().map(Some),
             false => Ok(None),
@@ -144,7 +438,8 @@ where
     }
 
     /// Reads an optional length-prefixed byte field
-    pub fn optional_bytes<T>(&mut self, condition: bool) -> Result<Option<T>, &'static str>
+    #[track_caller]
+    pub fn optional_bytes<T>(&mut self, condition: bool) -> Result<Option<T>, DecoderError>
     where
         T: AnyVec<u8>,
     {
@@ -153,6 +448,70 @@ where
             false => Ok(None),
         }
     }
+
+    /// Reads an optional length-prefixed byte field as borrowed sub-slice of the given source
+    ///
+    /// # Important
+    /// `source` must be the slice this decoder reads from, so that the decoder position corresponds to the offset
+    /// within `source`.
+    #[track_caller]
+    pub fn optional_bytes_ref<'a>(
+        &mut self,
+        condition: bool,
+        source: &'a [u8],
+    ) -> Result<Option<BytesRef<'a>>, DecoderError> {
+        match condition {
+            true => self.bytes_ref(source).map(Some),
+            false => Ok(None),
+        }
+    }
+
+    /// Reads an optional length-prefixed UTF-8 string field
+    #[track_caller]
+    pub fn optional_utf8<T>(&mut self, condition: bool) -> Result<Option<T>, DecoderError>
+    where
+        T: AnyVec<u8>,
+    {
+        match condition {
+            true => self.utf8().map(Some),
+            false => Ok(None),
+        }
+    }
+
+    /// Reads the next byte from the underlying source and advances the position
+    fn next_u8(&mut self) -> Option<u8> {
+        let byte = self.source.next()?;
+        self.position = self.position.saturating_add(1);
+        Some(byte)
+    }
+}
+impl<Iter> Decoder<Take<Iter>>
+where
+    Iter: Iterator<Item = u8>,
+{
+    /// The amount of bytes that can still be read until the limit is reached
+    ///
+    /// # Note
+    /// If the underlying source is known to end before the limit is reached, the remaining length of the source is
+    /// returned instead.
+    pub fn remaining(&self) -> usize {
+        let (_, upper) = self.source.size_hint();
+        upper.unwrap_or_default()
+    }
+}
+impl<Iter> Decoder<Peekable<Take<Iter>>>
+where
+    Iter: Iterator<Item = u8>,
+{
+    /// The amount of bytes that can still be read until the limit is reached
+    ///
+    /// # Note
+    /// If the underlying source is known to end before the limit is reached, the remaining length of the source is
+    /// returned instead.
+    pub fn remaining(&self) -> usize {
+        let (_, upper) = self.source.size_hint();
+        upper.unwrap_or_default()
+    }
 }
 impl<Iter> Decoder<Peekable<Iter>>
 where
@@ -163,6 +522,15 @@ where
         self.source.peek().copied()
     }
 
+    /// Peeks at the header byte and decodes it into packet type and associated flags (as bitmap) without consuming it
+    #[track_caller]
+    pub fn peek_header(&mut self) -> Result<(u8, [bool; 4]), DecoderError> {
+        match self.peek_u8() {
+            Some(byte) => Ok(split_header(byte)),
+            None => Err(err!(Truncated, "Truncated input").with_offset(self.position)),
+        }
+    }
+
     /// Checks if the underlying source is empty
     #[must_use]
     pub fn is_empty(&mut self) -> bool {
@@ -175,7 +543,23 @@ where
     /// This function is greedy. As there is no way to know how much topics to read, this function will simply read as
     /// much bytes as possible until the underlying source is exhausted. Limit the source using [`Self::limit`] if
     /// necessary.
-    pub fn topics<S, T>(&mut self) -> Result<S, &'static str>
+    #[track_caller]
+    pub fn topics<S, T>(&mut self) -> Result<S, DecoderError>
+    where
+        S: AnyVec<T>,
+        T: AnyVec<u8>,
+    {
+        self.topics_capped(usize::MAX)
+    }
+
+    /// Reads a sequence of at most `max_entries` topics, or fails if the sequence contains more topics
+    ///
+    /// # Note
+    /// This function is greedy. As there is no way to know how much topics to read, this function will simply read as
+    /// much bytes as possible until the underlying source is exhausted. Limit the source using [`Self::limit`] if
+    /// necessary.
+    #[track_caller]
+    pub fn topics_capped<S, T>(&mut self, max_entries: usize) -> Result<S, DecoderError>
     where
         S: AnyVec<T>,
         T: AnyVec<u8>,
@@ -183,9 +567,19 @@ where
         // Read tuples
         let mut topics = S::default();
         while !self.is_empty() {
+            // Ensure that we don't exceed the limit
+            let start = self.position;
+            if topics.as_ref().len() >= max_entries {
+                let error = err!(Memory, "Too many topics", expected max_entries.saturating_add(1), found max_entries);
+                return Err(error.with_offset(start));
+            }
+
             // Read topic and associated QoS
-            let topic = self.bytes()?;
-            topics.push(topic)?;
+            let topic = self.bytes().map_err(|e| e.context("topic filter"))?;
+            if let Err(e) = topics.push(topic) {
+                let needed = topics.as_ref().len().saturating_add(1);
+                return Err(DecoderError::container(&topics, e, needed).with_offset(start));
+            }
         }
         Ok(topics)
     }
@@ -196,7 +590,24 @@ where
     /// This function is greedy. As there is no way to know how much tuples to read, this function will simply read as
     /// much bytes as possible until the underlying source is exhausted. Limit the source using [`Self::limit`] if
     /// necessary.
-    pub fn topics_qos<S, T>(&mut self) -> Result<S, &'static str>
+    #[track_caller]
+    pub fn topics_qos<S, T>(&mut self) -> Result<S, DecoderError>
+    where
+        S: AnyVec<(T, u8)>,
+        T: AnyVec<u8>,
+    {
+        self.topics_qos_capped(usize::MAX)
+    }
+
+    /// Reads a sequence of at most `max_entries` topic+quality-of-service tuples, or fails if the sequence contains
+    /// more tuples
+    ///
+    /// # Note
+    /// This function is greedy. As there is no way to know how much tuples to read, this function will simply read as
+    /// much bytes as possible until the underlying source is exhausted. Limit the source using [`Self::limit`] if
+    /// necessary.
+    #[track_caller]
+    pub fn topics_qos_capped<S, T>(&mut self, max_entries: usize) -> Result<S, DecoderError>
     where
         S: AnyVec<(T, u8)>,
         T: AnyVec<u8>,
@@ -204,10 +615,20 @@ where
         // Read tuples
         let mut topics_qos = S::default();
         while !self.is_empty() {
+            // Ensure that we don't exceed the limit
+            let start = self.position;
+            if topics_qos.as_ref().len() >= max_entries {
+                let error = err!(Memory, "Too many topics", expected max_entries.saturating_add(1), found max_entries);
+                return Err(error.with_offset(start));
+            }
+
             // Read topic and associated QoS
-            let topic = self.bytes()?;
-            let qos = self.u8()?;
-            topics_qos.push((topic, qos))?;
+            let topic = self.bytes().map_err(|e| e.context("topic filter"))?;
+            let qos = self.u8().map_err(|e| e.context("requested QoS"))?;
+            if let Err(e) = topics_qos.push((topic, qos)) {
+                let needed = topics_qos.as_ref().len().saturating_add(1);
+                return Err(DecoderError::container(&topics_qos, e, needed).with_offset(start));
+            }
         }
         Ok(topics_qos)
     }
@@ -223,3 +644,81 @@ where
         self.source
     }
 }
+
+/// An incremental validator for MQTT UTF-8 strings
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Utf8Validator {
+    /// The amount of pending continuation bytes
+    pending: u8,
+    /// The lowest valid value for the next continuation byte
+    lower: u8,
+    /// The highest valid value for the next continuation byte
+    upper: u8,
+}
+impl Utf8Validator {
+    /// The error if a byte is not valid within the string
+    const INVALID: &'static str = "Invalid UTF-8 string";
+    /// The error if the string contains the null character
+    const NULL_CHARACTER: &'static str = "UTF-8 string contains a null character";
+    /// The error if the string ends within a multi-byte sequence
+    const TRUNCATED: &'static str = "Truncated UTF-8 string";
+
+    /// Whether the given error is a validation error or not
+    pub fn is_error(error: &'static str) -> bool {
+        matches!(error, Self::INVALID | Self::NULL_CHARACTER | Self::TRUNCATED)
+    }
+
+    /// Validates the next byte
+    pub fn push(&mut self, byte: u8) -> Result<(), &'static str> {
+        // Validate a continuation byte
+        if self.pending > 0 {
+            let true = (self.lower..=self.upper).contains(&byte) else {
+                return Err(Self::INVALID);
+            };
+
+            // Reset the range to the default continuation byte range
+            self.pending = self.pending.saturating_sub(1);
+            (self.lower, self.upper) = (0x80, 0xBF);
+            return Ok(());
+        }
+
+        // Validate a start byte and set the range of the next continuation byte to reject overlong encodings,
+        // surrogates and code points above `U+10FFFF`
+        (self.pending, self.lower, self.upper) = match byte {
+            0x00 => return Err(Self::NULL_CHARACTER),
+            0x01..=0x7F => (0, 0x80, 0xBF),
+            0xC2..=0xDF => (1, 0x80, 0xBF),
+            0xE0 => (2, 0xA0, 0xBF),
+            0xE1..=0xEC | 0xEE..=0xEF => (2, 0x80, 0xBF),
+            0xED => (2, 0x80, 0x9F),
+            0xF0 => (3, 0x90, 0xBF),
+            0xF1..=0xF3 => (3, 0x80, 0xBF),
+            0xF4 => (3, 0x80, 0x8F),
+            _ => return Err(Self::INVALID),
+        };
+        Ok(())
+    }
+
+    /// Whether the validated bytes end on a character boundary
+    pub const fn is_complete(&self) -> bool {
+        self.pending == 0
+    }
+
+    /// Ensures that the validated bytes do not end within a multi-byte sequence
+    pub fn finish(self) -> Result<(), &'static str> {
+        match self.pending {
+            0 => Ok(()),
+            _ => Err(Self::TRUNCATED),
+        }
+    }
+}
+impl Default for Utf8Validator {
+    fn default() -> Self {
+        Self { pending: 0, lower: 0x80, upper: 0xBF }
+    }
+}
+
+/// Splits a header byte into packet type and associated flags (as bitmap)
+const fn split_header(byte: u8) -> (u8, [bool; 4]) {
+    (byte >> 4, [byte & 0b1000 != 0, byte & 0b0100 != 0, byte & 0b0010 != 0, byte & 0b0001 != 0])
+}
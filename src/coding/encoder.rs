@@ -1,10 +1,20 @@
 //! An iterator-based encoder
 
-use crate::anyvec::AnyVec;
-use core::iter::{self, Chain, Empty, FlatMap, Once, Take};
+use crate::{
+    anyvec::AnyVec,
+    coding::{length::Length, varint},
+    error::EncodeError,
+    packets::{flags::HeaderFlags, packet::PacketType},
+};
+use core::{
+    iter::{self, Chain, Copied, Empty, FlatMap, FusedIterator, Map, Once, Take},
+    slice,
+};
 
 /// An empty iterator
 pub type Unit = Empty<u8>;
+/// A result iterator when encoding a borrowed byte slice
+pub type SliceIter<'a> = Copied<slice::Iter<'a, u8>>;
 /// A result iterator when encoding a `u8`
 pub type U8Iter = Once<u8>;
 /// A result iterator when encoding a `u16`
@@ -13,8 +23,16 @@ pub type U16Iter = <[u8; 2] as IntoIterator>::IntoIter;
 pub type BytesIter<Bytes> = Chain<U16Iter, <Bytes as IntoIterator>::IntoIter>;
 /// A result iterator when encoding a packet length
 pub type PacketLenIter = Take<<[u8; 4] as IntoIterator>::IntoIter>;
+/// A result iterator when encoding a variable byte integer
+pub type VarintIter = PacketLenIter;
+/// A result iterator when encoding a (possibly absent) length-prefixed properties block
+pub type PropertiesIter<Bytes> = Chain<VarintIter, <Bytes as IntoIterator>::IntoIter>;
 /// A result iterator when encoding an optional `u16`
 pub type OptionalU16Iter = Take<U16Iter>;
+/// A result iterator when encoding an optional `u8`
+pub type OptionalU8Iter = Take<U8Iter>;
+/// A result iterator when encoding an optional fixed-size array
+pub type OptionalRawIter<const SIZE: usize> = Take<<[u8; SIZE] as IntoIterator>::IntoIter>;
 /// A result iterator when encoding an optional length-prefixed byte field
 pub type OptionalBytesIter<Bytes> = Chain<OptionalU16Iter, <Bytes as IntoIterator>::IntoIter>;
 /// A result iterator when encoding a sequence of topic+quality-of-service tuples
@@ -26,9 +44,45 @@ pub type TopicsQosIter<Sequence, Bytes> = FlatMap<
     Chain<BytesIter<Bytes>, U8Iter>,
     fn((Bytes, u8)) -> Chain<BytesIter<Bytes>, U8Iter>,
 >;
+/// A sequence of borrowed topics
+pub type TopicsRef<'a, Bytes> = Map<slice::Iter<'a, Bytes>, fn(&'a Bytes) -> BytesRef<'a>>;
+/// A sequence of borrowed topic+quality-of-service tuples
+pub type TopicsQosRef<'a, Bytes> = Map<slice::Iter<'a, (Bytes, u8)>, fn(&'a (Bytes, u8)) -> (BytesRef<'a>, u8)>;
+
+/// A borrowed byte field that yields its bytes by value, so that it can be encoded without copying the underlying
+/// container
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BytesRef<'a> {
+    /// The underlying bytes
+    bytes: &'a [u8],
+}
+impl<'a> BytesRef<'a> {
+    /// Creates a new borrowed byte field
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the underlying bytes
+    pub const fn into_slice(self) -> &'a [u8] {
+        self.bytes
+    }
+}
+impl AsRef<[u8]> for BytesRef<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.bytes
+    }
+}
+impl<'a> IntoIterator for BytesRef<'a> {
+    type Item = u8;
+    type IntoIter = SliceIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bytes.iter().copied()
+    }
+}
 
 /// An iterator-based encoder
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Encoder<Iter = Unit> {
     /// The underlying iterator
     sink: Iter,
@@ -45,6 +99,11 @@ where
         Encoder { sink: self.sink.chain(raw) }
     }
 
+    /// Writes a borrowed byte slice as-is
+    pub fn raw_slice(self, raw: &[u8]) -> Encoder<Chain<Iter, SliceIter<'_>>> {
+        Encoder { sink: self.sink.chain(raw.iter().copied()) }
+    }
+
     /// Writes a `u8`
     pub fn u8(self, u8_: u8) -> Encoder<Chain<Iter, U8Iter>> {
         let iter = iter::once(u8_);
@@ -76,6 +135,15 @@ where
         Encoder { sink: self.sink.chain(iter) }
     }
 
+    /// Writes a length-prefixed byte field, or fails if the length of the byte field is greater than `u16::MAX`
+    pub fn try_bytes<T>(self, bytes: T) -> Result<Encoder<Chain<Iter, BytesIter<T>>>, EncodeError>
+    where
+        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+    {
+        check_field_len(&bytes)?;
+        Ok(self.bytes(bytes))
+    }
+
     /// Writes a bitmap as byte
     pub fn bitmap(self, bits: [bool; 8]) -> Encoder<Chain<Iter, U8Iter>> {
         let byte = ((bits[0] as u8) << 7)
@@ -93,57 +161,94 @@ where
     /// Writes a packet type and associated flags (as bitmap) as header byte
     ///
     /// # Panics
-    /// This function panics if the packet type is greater than `15` (`2^4 - 1`).
+    /// This function panics if the packet type is greater than `15` (`2^4 - 1`). Prefer [`Self::typed_header`] for
+    /// known packet types, or [`Self::try_header`] for raw packet types.
     pub fn header(self, type_: u8, flags: [bool; 4]) -> Encoder<Chain<Iter, U8Iter>> {
         // Validate type value
         #[allow(clippy::panic, reason = "Serious API misuse")]
         (assert!(type_ <= 15, "Packet type is too large"));
 
         // Assemble byte
-        let byte = (type_ << 4)
-            | ((flags[0] as u8) << 3)
-            | ((flags[1] as u8) << 2)
-            | ((flags[2] as u8) << 1)
-            | (flags[3] as u8);
-        let iter = iter::once(byte);
+        let iter = iter::once(header_byte(type_, flags));
         Encoder { sink: self.sink.chain(iter) }
     }
 
+    /// Writes a known packet type and associated flags (as bitmap or [`HeaderFlags`]) as header byte
+    pub fn typed_header<F>(self, type_: PacketType, flags: F) -> Encoder<Chain<Iter, U8Iter>>
+    where
+        F: Into<HeaderFlags>,
+    {
+        // Known packet types are always within the valid range
+        let iter = iter::once(((type_ as u8) << 4) | flags.into().bits());
+        Encoder { sink: self.sink.chain(iter) }
+    }
+
+    /// Writes a packet type and associated flags (as bitmap) as header byte, or fails if the packet type is greater
+    /// than `15` (`2^4 - 1`)
+    pub fn try_header(self, type_: u8, flags: [bool; 4]) -> Result<Encoder<Chain<Iter, U8Iter>>, EncodeError> {
+        check_packet_type(type_)?;
+        Ok(self.header(type_, flags))
+    }
+
     /// Writes a packet length field
     ///
     /// # Panics
     /// This function panics if the packet length is greater than `2^28 - 1`.
-    pub fn packetlen(self, mut len: usize) -> Encoder<Chain<Iter, PacketLenIter>> {
-        // Validate and compute packet length size
-        #[allow(clippy::panic, reason = "Packet length must be encoded in 4 or less heptets")]
-        #[allow(clippy::unusual_byte_groupings, reason = "Length bytes are encoded in heptets")]
-        let len_size = match len {
-            0b1_0000000_0000000_0000000_0000000.. => panic!("Packet length is too large"),
-            0b1_0000000_0000000_0000000.. => 4,
-            0b1_0000000_0000000.. => 3,
-            0b1_0000000.. => 2,
-            _ => 1,
-        };
+    pub fn packetlen(self, len: usize) -> Encoder<Chain<Iter, PacketLenIter>> {
+        // Encode the length and truncate the length field accordingly
+        let (bytes, len_size) = varint::encode(len);
+        let iter = bytes.into_iter().take(len_size);
+        Encoder { sink: self.sink.chain(iter) }
+    }
 
-        // Encode the length in 7-bit nibbles
-        let mut bytes = [0; 4];
-        for index in 0..len_size {
-            // Push the next remaining least-significant 7 bits to the **front** of the encoded length
-            bytes.rotate_right(1);
-            bytes[0] = (len as u8) & 0b0111_1111;
-            len >>= 7;
-
-            // Insert the marker if the byte is not at the end-of-array
-            if index > 0 {
-                bytes[0] |= 0b1000_0000;
-            }
-        }
+    /// Writes a packet length field, or fails if the packet length is greater than `2^28 - 1`
+    pub fn try_packetlen(self, len: usize) -> Result<Encoder<Chain<Iter, PacketLenIter>>, EncodeError> {
+        check_packet_len(len)?;
+        Ok(self.packetlen(len))
+    }
 
-        // Truncate the length field accordingly
+    /// Writes a variable byte integer
+    ///
+    /// # Panics
+    /// This function panics if the value is greater than `2^28 - 1`.
+    pub fn varint(self, value: usize) -> Encoder<Chain<Iter, VarintIter>> {
+        // Encode the value and truncate the field accordingly
+        let (bytes, len_size) = varint::encode(value);
         let iter = bytes.into_iter().take(len_size);
         Encoder { sink: self.sink.chain(iter) }
     }
 
+    /// Writes a properties block that is prefixed with its length as variable byte integer
+    ///
+    /// # Panics
+    /// This function panics if the length of the properties block is greater than `2^28 - 1`.
+    pub fn properties<T>(self, properties: T) -> Encoder<Chain<Iter, PropertiesIter<T>>>
+    where
+        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+    {
+        // Encode the length and chain the properties
+        let (bytes, len_size) = varint::encode(properties.as_ref().len());
+        let iter = bytes.into_iter().take(len_size).chain(properties);
+        Encoder { sink: self.sink.chain(iter) }
+    }
+
+    /// Writes an optional properties block that is prefixed with its length as variable byte integer
+    ///
+    /// # Panics
+    /// This function panics if the length of the properties block is greater than `2^28 - 1`.
+    pub fn optional_properties<T>(self, properties: Option<T>) -> Encoder<Chain<Iter, PropertiesIter<T>>>
+    where
+        T: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+    {
+        // Find an iterator representation that works for both cases
+        let Some(properties) = properties else {
+            // Create two empty iterators that yield the same type signature as the Some-case
+            let iter = [0u8; 4].into_iter().take(0).chain(T::default());
+            return Encoder { sink: self.sink.chain(iter) };
+        };
+        self.properties(properties)
+    }
+
     /// Writes a `u16`
     pub fn optional_u16(self, u16_: Option<u16>) -> Encoder<Chain<Iter, OptionalU16Iter>> {
         // Map the `u16` iterator into a type representation that works for both cases
@@ -154,13 +259,36 @@ where
         Encoder { sink: self.sink.chain(iter) }
     }
 
+    /// Writes an optional `u8`
+    pub fn optional_u8(self, u8_: Option<u8>) -> Encoder<Chain<Iter, OptionalU8Iter>> {
+        // Map the `u8` iterator into a type representation that works for both cases
+        let iter = match u8_ {
+            Some(u8_) => iter::once(u8_).take(1),
+            None => iter::once(0).take(0),
+        };
+        Encoder { sink: self.sink.chain(iter) }
+    }
+
+    /// Writes an optional fixed-size array as-is
+    pub fn optional_raw<const SIZE: usize>(
+        self,
+        raw: Option<[u8; SIZE]>,
+    ) -> Encoder<Chain<Iter, OptionalRawIter<SIZE>>> {
+        // Map the array iterator into a type representation that works for both cases
+        let iter = match raw {
+            Some(raw) => raw.into_iter().take(SIZE),
+            None => [0; SIZE].into_iter().take(0),
+        };
+        Encoder { sink: self.sink.chain(iter) }
+    }
+
     /// Writes an optional length-prefixed byte field
     ///
     /// # Panics
     /// This function panics if the length of the byte field is greater than `u16::MAX`.
     pub fn optional_bytes<T>(self, bytes: Option<T>) -> Encoder<Chain<Iter, OptionalBytesIter<T>>>
     where
-        T: AnyVec<u8>,
+        T: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
     {
         // Find an iterator representation that works for both cases
         if let Some(bytes) = bytes {
@@ -183,6 +311,22 @@ where
         }
     }
 
+    /// Writes an optional length-prefixed byte field, or fails if the length of the byte field is greater than
+    /// `u16::MAX`
+    pub fn try_optional_bytes<T>(
+        self,
+        bytes: Option<T>,
+    ) -> Result<Encoder<Chain<Iter, OptionalBytesIter<T>>>, EncodeError>
+    where
+        T: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+    {
+        if let Some(bytes) = &bytes {
+            // Validate the field length
+            check_field_len(bytes)?;
+        }
+        Ok(self.optional_bytes(bytes))
+    }
+
     /// Writes a sequence of topic+quality-of-service tuples
     ///
     /// # Panics
@@ -242,15 +386,163 @@ where
         let topics_qos = topics_qos.into_iter().flat_map(flat_map_fn);
         Encoder { sink: self.sink.chain(topics_qos) }
     }
+
+    /// Writes a sequence of topics, or fails if the length of a topic is greater than `u16::MAX`
+    #[allow(clippy::type_complexity, reason = "The type is built out of public type aliases")]
+    pub fn try_topics<S, T>(self, topics: S) -> Result<Encoder<Chain<Iter, TopicsIter<S, T>>>, EncodeError>
+    where
+        S: AsRef<[T]> + IntoIterator<Item = T>,
+        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+    {
+        for topic in topics.as_ref() {
+            // Validate the topic length
+            check_field_len(topic)?;
+        }
+        Ok(self.topics(topics))
+    }
+
+    /// Writes a sequence of topic+quality-of-service tuples, or fails if the length of a topic is greater than
+    /// `u16::MAX`
+    #[allow(clippy::type_complexity, reason = "The type is built out of public type aliases")]
+    pub fn try_topics_qos<S, T>(self, topics_qos: S) -> Result<Encoder<Chain<Iter, TopicsQosIter<S, T>>>, EncodeError>
+    where
+        S: AsRef<[(T, u8)]> + IntoIterator<Item = (T, u8)>,
+        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+    {
+        for (topic, _) in topics_qos.as_ref() {
+            // Validate the topic length
+            check_field_len(topic)?;
+        }
+        Ok(self.topics_qos(topics_qos))
+    }
+
+    /// Collects the encoded bytes into a new vector, or fails if the vector cannot hold all bytes
+    pub fn collect_into<T>(self) -> Result<T, &'static str>
+    where
+        T: AnyVec<u8>,
+    {
+        // Push each byte so that capacity exhaustion is surfaced as error
+        let mut bytes = T::default();
+        for byte in self.sink {
+            // Try to append byte
+            bytes.push(byte)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Converts the encoder into an exact-size iterator over a packet with the given body length
+    ///
+    /// # Panics
+    /// This function panics if the packet length is greater than `2^28 - 1`.
+    pub fn into_sized_iter(self, len: usize) -> SizedIter<Iter> {
+        // Compute the total packet length
+        let remaining = Length::total_frame(len);
+        SizedIter { inner: self.sink, remaining }
+    }
 }
-impl<Iter> IntoIterator for Encoder<Iter>
+impl<Iter> Encoder<Iter>
+where
+    Iter: Iterator,
+{
+    /// The exact amount of pending bytes if it is known
+    pub fn len_hint(&self) -> Option<usize> {
+        match self.sink.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        }
+    }
+}
+impl<Iter> Iterator for Encoder<Iter>
 where
     Iter: Iterator,
 {
     type Item = Iter::Item;
-    type IntoIter = Iter;
 
-    fn into_iter(self) -> Self::IntoIter {
-        self.sink
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sink.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sink.size_hint()
+    }
+}
+
+/// An encoded packet with a known total length
+#[derive(Debug, Clone)]
+pub struct SizedIter<Iter> {
+    /// The underlying iterator
+    inner: Iter,
+    /// The amount of remaining bytes
+    remaining: usize,
+}
+impl<Iter> Iterator for SizedIter<Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Don't poll the underlying iterator again once the packet has been written
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // Get the next byte
+        let Some(byte) = self.inner.next() else {
+            // Also stop if the underlying iterator ends early to remain fused
+            self.remaining = 0;
+            return None;
+        };
+        self.remaining = self.remaining.saturating_sub(1);
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<Iter> ExactSizeIterator for SizedIter<Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    // No members to implement
+}
+impl<Iter> FusedIterator for SizedIter<Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    // No members to implement
+}
+
+/// Assembles a header byte from the packet type and associated flags (as bitmap)
+const fn header_byte(type_: u8, flags: [bool; 4]) -> u8 {
+    (type_ << 4) | ((flags[0] as u8) << 3) | ((flags[1] as u8) << 2) | ((flags[2] as u8) << 1) | (flags[3] as u8)
+}
+
+/// Ensures that the given byte field can be length-prefixed
+pub(crate) fn check_field_len<T>(bytes: &T) -> Result<(), EncodeError>
+where
+    T: AsRef<[u8]>,
+{
+    let len = bytes.as_ref().len();
+    match len {
+        ..=EncodeError::MAX_FIELD_LEN => Ok(()),
+        _ => Err(EncodeError::FieldTooLong { len }),
+    }
+}
+
+/// Ensures that the given packet type fits into the header nibble
+pub(crate) fn check_packet_type(type_: u8) -> Result<(), EncodeError> {
+    match type_ {
+        ..=EncodeError::MAX_PACKET_TYPE => Ok(()),
+        _ => Err(EncodeError::InvalidPacketType { type_ }),
+    }
+}
+
+/// Ensures that the given packet length can be encoded as packet length field
+pub(crate) fn check_packet_len(len: usize) -> Result<(), EncodeError> {
+    match len {
+        ..=EncodeError::MAX_PACKET_LEN => Ok(()),
+        _ => Err(EncodeError::PacketTooLarge { len }),
     }
 }
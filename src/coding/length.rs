@@ -1,6 +1,13 @@
 //! A helper to predict the encoded length
 
-use crate::anyvec::AnyVec;
+use crate::{
+    coding::{encoder, varint},
+    error::EncodeError,
+};
+use core::{
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
 
 /// A helper to predict the encoded length
 #[derive(Debug, Clone, Copy, Default)]
@@ -14,6 +21,16 @@ impl Length {
         Self { len: 0 }
     }
 
+    /// Computes the full on-wire frame length (i.e. header, packet length field and body) from the given body length
+    ///
+    /// # Panics
+    /// This function panics if the body length is greater than `2^28 - 1`.
+    pub fn total_frame(body_len: usize) -> usize {
+        let head_len: usize = Self::new().u8(&0).packetlen(&body_len).into();
+        #[allow(clippy::expect_used, reason = "The packet length is limited to `2^28 - 1`")]
+        head_len.checked_add(body_len).expect("Packet length is too large")
+    }
+
     /// Writes a `u8`
     ///
     /// # Panics
@@ -40,7 +57,7 @@ impl Length {
     /// This function panics if the total accumulated length is greater than `usize::MAX`.
     pub fn raw<T>(mut self, raw: &T) -> Self
     where
-        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+        T: AsRef<[u8]>,
     {
         #[allow(clippy::expect_used, reason = "Serious API misuse")]
         (self.len = self.len.checked_add(raw.as_ref().len()).expect("Accumulated length is too large"));
@@ -54,7 +71,7 @@ impl Length {
     /// the total accumulated length is greater than `usize::MAX`.
     pub fn bytes<T>(mut self, bytes: &T) -> Self
     where
-        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+        T: AsRef<[u8]>,
     {
         #[allow(clippy::expect_used, reason = "Serious API misuse")]
         (self.len = (self.len.checked_add(2))
@@ -63,6 +80,18 @@ impl Length {
         self
     }
 
+    /// Writes a length-prefixed byte field, or fails if the length of the byte field is greater than `u16::MAX`
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn try_bytes<T>(self, bytes: &T) -> Result<Self, EncodeError>
+    where
+        T: AsRef<[u8]>,
+    {
+        encoder::check_field_len(bytes)?;
+        Ok(self.bytes(bytes))
+    }
+
     /// Writes a bitmap as byte
     ///
     /// # Panics
@@ -89,22 +118,24 @@ impl Length {
         self
     }
 
+    /// Writes a packet type and associated flags (as bitmap) as header byte, or fails if the packet type is greater
+    /// than `15` (`2^4 - 1`)
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn try_header(self, type_: &u8, flags: &[bool; 4]) -> Result<Self, EncodeError> {
+        encoder::check_packet_type(*type_)?;
+        Ok(self.header(type_, flags))
+    }
+
     /// Writes a packet length field
     ///
     /// # Panics
     /// This function panics if the packet length is greater than `2^28 - 1`. This function also panics if the total
     /// accumulated length is greater than `usize::MAX`.
     pub fn packetlen(mut self, len: &usize) -> Self {
-        // Validate and compute packet length size
-        #[allow(clippy::panic, reason = "Packet length must be encoded in 4 or less heptets")]
-        #[allow(clippy::unusual_byte_groupings, reason = "Length bytes are encoded in heptets")]
-        let len_size = match len {
-            0b1_0000000_0000000_0000000_0000000.. => panic!("Packet length is too large"),
-            0b1_0000000_0000000_0000000.. => 4,
-            0b1_0000000_0000000.. => 3,
-            0b1_0000000.. => 2,
-            _ => 1,
-        };
+        // Compute packet length size
+        let (_, len_size) = varint::encode(*len);
 
         // Accumulate length
         #[allow(clippy::expect_used, reason = "Serious API misuse")]
@@ -112,6 +143,52 @@ impl Length {
         self
     }
 
+    /// Writes a packet length field, or fails if the packet length is greater than `2^28 - 1`
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn try_packetlen(self, len: &usize) -> Result<Self, EncodeError> {
+        encoder::check_packet_len(*len)?;
+        Ok(self.packetlen(len))
+    }
+
+    /// Writes a variable byte integer
+    ///
+    /// # Panics
+    /// This function panics if the value is greater than `2^28 - 1`. This function also panics if the total
+    /// accumulated length is greater than `usize::MAX`.
+    pub fn varint(self, value: &usize) -> Self {
+        // A variable byte integer is encoded like a packet length
+        self.packetlen(value)
+    }
+
+    /// Writes a properties block that is prefixed with its length as variable byte integer
+    ///
+    /// # Panics
+    /// This function panics if the length of the properties block is greater than `2^28 - 1`. This function also panics
+    /// if the total accumulated length is greater than `usize::MAX`.
+    pub fn properties<T>(self, properties: &T) -> Self
+    where
+        T: AsRef<[u8]>,
+    {
+        self.varint(&properties.as_ref().len()).raw(properties)
+    }
+
+    /// Writes an optional properties block that is prefixed with its length as variable byte integer
+    ///
+    /// # Panics
+    /// This function panics if the length of the properties block is greater than `2^28 - 1`. This function also panics
+    /// if the total accumulated length is greater than `usize::MAX`.
+    pub fn optional_properties<T>(self, properties: &Option<T>) -> Self
+    where
+        T: AsRef<[u8]>,
+    {
+        match properties {
+            Some(properties) => self.properties(properties),
+            None => self,
+        }
+    }
+
     /// Writes an optional `u16`
     ///
     /// # Panics
@@ -123,6 +200,28 @@ impl Length {
         }
     }
 
+    /// Writes an optional `u8`
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn optional_u8(self, u8_: &Option<u8>) -> Self {
+        match u8_ {
+            Some(u8_) => self.u8(u8_),
+            None => self,
+        }
+    }
+
+    /// Writes an optional fixed-size array as-is
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn optional_raw<const SIZE: usize>(self, raw: &Option<[u8; SIZE]>) -> Self {
+        match raw {
+            Some(raw) => self.raw(raw),
+            None => self,
+        }
+    }
+
     /// Writes an optional length-prefixed byte field
     ///
     /// # Panics
@@ -130,7 +229,7 @@ impl Length {
     /// the total accumulated length is greater than `usize::MAX`.
     pub fn optional_bytes<T>(self, bytes: &Option<T>) -> Self
     where
-        T: AnyVec<u8>,
+        T: AsRef<[u8]>,
     {
         match bytes {
             Some(bytes) => self.bytes(bytes),
@@ -138,6 +237,21 @@ impl Length {
         }
     }
 
+    /// Writes an optional length-prefixed byte field, or fails if the length of the byte field is greater than
+    /// `u16::MAX`
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn try_optional_bytes<T>(self, bytes: &Option<T>) -> Result<Self, EncodeError>
+    where
+        T: AsRef<[u8]>,
+    {
+        match bytes {
+            Some(bytes) => self.try_bytes(bytes),
+            None => Ok(self),
+        }
+    }
+
     /// Writes a sequence of topic+quality-of-service tuples
     ///
     /// # Panics
@@ -146,7 +260,7 @@ impl Length {
     pub fn topics<S, T>(mut self, topics: &S) -> Self
     where
         S: AsRef<[T]>,
-        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+        T: AsRef<[u8]>,
     {
         // Sum-up all topics
         for topic in topics.as_ref() {
@@ -164,7 +278,7 @@ impl Length {
     pub fn topics_qos<S, T>(mut self, topics_qos: &S) -> Self
     where
         S: AsRef<[(T, u8)]>,
-        T: AsRef<[u8]> + IntoIterator<Item = u8>,
+        T: AsRef<[u8]>,
     {
         // Sum-up all tuples
         for (topic, qos) in topics_qos.as_ref() {
@@ -174,9 +288,72 @@ impl Length {
         }
         self
     }
+
+    /// Writes a sequence of topics, or fails if the length of a topic is greater than `u16::MAX`
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn try_topics<S, T>(mut self, topics: &S) -> Result<Self, EncodeError>
+    where
+        S: AsRef<[T]>,
+        T: AsRef<[u8]>,
+    {
+        // Sum-up all topics
+        for topic in topics.as_ref() {
+            // Topics are just concatenated
+            self = self.try_bytes(topic)?;
+        }
+        Ok(self)
+    }
+
+    /// Writes a sequence of topic+quality-of-service tuples, or fails if the length of a topic is greater than
+    /// `u16::MAX`
+    ///
+    /// # Panics
+    /// This function panics if the total accumulated length is greater than `usize::MAX`.
+    pub fn try_topics_qos<S, T>(mut self, topics_qos: &S) -> Result<Self, EncodeError>
+    where
+        S: AsRef<[(T, u8)]>,
+        T: AsRef<[u8]>,
+    {
+        // Sum-up all tuples
+        for (topic, qos) in topics_qos.as_ref() {
+            // Topic+QoS tubles are just concatenated
+            self = self.try_bytes(topic)?;
+            self = self.u8(qos);
+        }
+        Ok(self)
+    }
 }
 impl From<Length> for usize {
     fn from(value: Length) -> Self {
         value.len
     }
 }
+impl From<usize> for Length {
+    fn from(value: usize) -> Self {
+        Self { len: value }
+    }
+}
+impl Add for Length {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        #[allow(clippy::expect_used, reason = "Serious API misuse")]
+        let len = self.len.checked_add(rhs.len).expect("Accumulated length is too large");
+        Self { len }
+    }
+}
+impl AddAssign for Length {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = Add::add(*self, rhs);
+    }
+}
+impl Sum for Length {
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.fold(Self::new(), Add::add)
+    }
+}
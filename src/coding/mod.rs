@@ -4,6 +4,9 @@
 pub mod decoder;
 pub mod encoder;
 pub mod length;
+#[cfg(feature = "v5")]
+pub mod properties;
+pub mod varint;
 
 /// An blank encoder
 pub type Encoder = encoder::Encoder;
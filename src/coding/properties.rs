@@ -0,0 +1,513 @@
+//! MQTT 5 [property](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901027) framing
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{decoder::Utf8Validator, encoder::check_field_len, varint, Decoder},
+    error::{DecoderError, EncodeError, Field},
+};
+use core::{iter::FusedIterator, str};
+
+/// The property identifiers
+pub mod id {
+    /// The payload format indicator (byte)
+    pub const PAYLOAD_FORMAT_INDICATOR: u8 = 0x01;
+    /// The message expiry interval in seconds (four byte integer)
+    pub const MESSAGE_EXPIRY_INTERVAL: u8 = 0x02;
+    /// The content type (UTF-8 string)
+    pub const CONTENT_TYPE: u8 = 0x03;
+    /// The response topic (UTF-8 string)
+    pub const RESPONSE_TOPIC: u8 = 0x08;
+    /// The correlation data (binary data)
+    pub const CORRELATION_DATA: u8 = 0x09;
+    /// The subscription identifier (variable byte integer)
+    pub const SUBSCRIPTION_IDENTIFIER: u8 = 0x0B;
+    /// The session expiry interval in seconds (four byte integer)
+    pub const SESSION_EXPIRY_INTERVAL: u8 = 0x11;
+    /// The client identifier assigned by the server (UTF-8 string)
+    pub const ASSIGNED_CLIENT_IDENTIFIER: u8 = 0x12;
+    /// The keep alive time in seconds assigned by the server (two byte integer)
+    pub const SERVER_KEEP_ALIVE: u8 = 0x13;
+    /// The authentication method (UTF-8 string)
+    pub const AUTHENTICATION_METHOD: u8 = 0x15;
+    /// The authentication data (binary data)
+    pub const AUTHENTICATION_DATA: u8 = 0x16;
+    /// Whether the server may send a reason string or user properties on failures (byte)
+    pub const REQUEST_PROBLEM_INFORMATION: u8 = 0x17;
+    /// The will delay interval in seconds (four byte integer)
+    pub const WILL_DELAY_INTERVAL: u8 = 0x18;
+    /// Whether the server should return response information (byte)
+    pub const REQUEST_RESPONSE_INFORMATION: u8 = 0x19;
+    /// The response information (UTF-8 string)
+    pub const RESPONSE_INFORMATION: u8 = 0x1A;
+    /// A reference to another server to use (UTF-8 string)
+    pub const SERVER_REFERENCE: u8 = 0x1C;
+    /// A human readable reason string (UTF-8 string)
+    pub const REASON_STRING: u8 = 0x1F;
+    /// The maximum amount of concurrent QoS 1 and 2 publications (two byte integer)
+    pub const RECEIVE_MAXIMUM: u8 = 0x21;
+    /// The highest accepted topic alias (two byte integer)
+    pub const TOPIC_ALIAS_MAXIMUM: u8 = 0x22;
+    /// The topic alias (two byte integer)
+    pub const TOPIC_ALIAS: u8 = 0x23;
+    /// The maximum supported QoS level (byte)
+    pub const MAXIMUM_QOS: u8 = 0x24;
+    /// Whether retained messages are supported (byte)
+    pub const RETAIN_AVAILABLE: u8 = 0x25;
+    /// A user-defined key-value pair (UTF-8 string pair)
+    pub const USER_PROPERTY: u8 = 0x26;
+    /// The maximum accepted packet size (four byte integer)
+    pub const MAXIMUM_PACKET_SIZE: u8 = 0x27;
+    /// Whether wildcard subscriptions are supported (byte)
+    pub const WILDCARD_SUBSCRIPTION_AVAILABLE: u8 = 0x28;
+    /// Whether subscription identifiers are supported (byte)
+    pub const SUBSCRIPTION_IDENTIFIER_AVAILABLE: u8 = 0x29;
+    /// Whether shared subscriptions are supported (byte)
+    pub const SHARED_SUBSCRIPTION_AVAILABLE: u8 = 0x2A;
+}
+
+/// A property value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Value<'a> {
+    /// A byte
+    Byte(u8),
+    /// A two byte integer
+    U16(u16),
+    /// A four byte integer
+    U32(u32),
+    /// A variable byte integer
+    Varint(u32),
+    /// Length-prefixed binary data
+    Binary(&'a [u8]),
+    /// A length-prefixed UTF-8 string
+    Utf8(&'a [u8]),
+    /// A pair of length-prefixed UTF-8 strings
+    Pair(&'a [u8], &'a [u8]),
+    /// The raw remainder of the properties block, starting with a property of unknown type
+    ///
+    /// # Note
+    /// Since the length of an unknown property is unknown too, all subsequent properties are part of the remainder.
+    Raw(&'a [u8]),
+}
+
+/// The wire type of a known property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PropertyType {
+    /// A byte
+    Byte,
+    /// A two byte integer
+    U16,
+    /// A four byte integer
+    U32,
+    /// A variable byte integer
+    Varint,
+    /// Length-prefixed binary data
+    Binary,
+    /// A length-prefixed UTF-8 string
+    Utf8,
+    /// A pair of length-prefixed UTF-8 strings
+    Pair,
+}
+impl PropertyType {
+    /// Gets the wire type of the given property identifier, or `None` if the identifier is unknown
+    pub const fn of(id: u8) -> Option<Self> {
+        match id {
+            id::PAYLOAD_FORMAT_INDICATOR
+            | id::REQUEST_PROBLEM_INFORMATION
+            | id::REQUEST_RESPONSE_INFORMATION
+            | id::MAXIMUM_QOS
+            | id::RETAIN_AVAILABLE
+            | id::WILDCARD_SUBSCRIPTION_AVAILABLE
+            | id::SUBSCRIPTION_IDENTIFIER_AVAILABLE
+            | id::SHARED_SUBSCRIPTION_AVAILABLE => Some(Self::Byte),
+            id::SERVER_KEEP_ALIVE | id::RECEIVE_MAXIMUM | id::TOPIC_ALIAS_MAXIMUM | id::TOPIC_ALIAS => Some(Self::U16),
+            id::MESSAGE_EXPIRY_INTERVAL
+            | id::SESSION_EXPIRY_INTERVAL
+            | id::WILL_DELAY_INTERVAL
+            | id::MAXIMUM_PACKET_SIZE => Some(Self::U32),
+            id::SUBSCRIPTION_IDENTIFIER => Some(Self::Varint),
+            id::CORRELATION_DATA | id::AUTHENTICATION_DATA => Some(Self::Binary),
+            id::CONTENT_TYPE
+            | id::RESPONSE_TOPIC
+            | id::ASSIGNED_CLIENT_IDENTIFIER
+            | id::AUTHENTICATION_METHOD
+            | id::RESPONSE_INFORMATION
+            | id::SERVER_REFERENCE
+            | id::REASON_STRING => Some(Self::Utf8),
+            id::USER_PROPERTY => Some(Self::Pair),
+            _ => None,
+        }
+    }
+
+    /// Whether the given value matches the wire type
+    pub const fn matches(&self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (Self::Byte, Value::Byte(_))
+                | (Self::U16, Value::U16(_))
+                | (Self::U32, Value::U32(_))
+                | (Self::Varint, Value::Varint(..=0x0FFF_FFFF))
+                | (Self::Binary, Value::Binary(_))
+                | (Self::Utf8, Value::Utf8(_))
+                | (Self::Pair, Value::Pair(_, _))
+        )
+    }
+}
+
+/// A policy that decides which properties may occur multiple times within a properties block
+pub trait DuplicatePolicy {
+    /// Whether the property with the given identifier may occur multiple times
+    fn is_repeatable(&self, id: u8) -> bool;
+}
+impl<T> DuplicatePolicy for T
+where
+    T: Fn(u8) -> bool,
+{
+    fn is_repeatable(&self, id: u8) -> bool {
+        self(id)
+    }
+}
+
+/// The duplicate policy of the specification, where only user properties and subscription identifiers may occur
+/// multiple times
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SpecPolicy;
+impl DuplicatePolicy for SpecPolicy {
+    fn is_repeatable(&self, id: u8) -> bool {
+        matches!(id, id::USER_PROPERTY | id::SUBSCRIPTION_IDENTIFIER)
+    }
+}
+
+/// A decoder that parses and validates the properties of an encoded properties block
+///
+/// # Note
+/// The decoder yields the properties as `(id, value)` tuples in their encoded order. A property of unknown type is
+/// yielded as [`Value::Raw`] together with the remainder of the block and ends the iteration, so that it can be
+/// re-encoded verbatim. After the first error, the decoder yields `None`.
+#[derive(Debug, Clone)]
+pub struct PropertyDecoder<'a, Policy = SpecPolicy> {
+    /// The remaining encoded properties
+    raw: &'a [u8],
+    /// The offset of the remaining properties within the packet
+    offset: usize,
+    /// The identifiers of the already decoded properties as bitmap
+    seen: u64,
+    /// The duplicate policy
+    policy: Policy,
+}
+impl<'a> PropertyDecoder<'a, SpecPolicy> {
+    /// Creates a new decoder for the given properties block (without length prefix), where `offset` is the offset of
+    /// the first property within the packet
+    pub const fn new(raw: &'a [u8], offset: usize) -> Self {
+        Self { raw, offset, seen: 0, policy: SpecPolicy }
+    }
+
+    /// Reads a properties block that is prefixed with its length as variable byte integer into the given container
+    /// and creates a decoder for it
+    #[track_caller]
+    pub fn read<Iter, T>(decoder: &mut Decoder<Iter>, raw: &'a mut T) -> Result<Self, DecoderError>
+    where
+        Iter: Iterator<Item = u8>,
+        T: AnyVec<u8>,
+    {
+        decoder.properties_into(raw).map_err(|e| Field::Properties.tag(e))?;
+        let raw: &'a T = raw;
+        let offset = decoder.position().saturating_sub(raw.as_ref().len());
+        Ok(Self::new(raw.as_ref(), offset))
+    }
+}
+impl<'a, Policy> PropertyDecoder<'a, Policy>
+where
+    Policy: DuplicatePolicy,
+{
+    /// Replaces the duplicate policy
+    ///
+    /// # Note
+    /// Properties that have already been decoded are not re-checked against the new policy.
+    pub fn with_policy<P>(self, policy: P) -> PropertyDecoder<'a, P>
+    where
+        P: DuplicatePolicy,
+    {
+        PropertyDecoder { raw: self.raw, offset: self.offset, seen: self.seen, policy }
+    }
+
+    /// Decodes all remaining properties to ensure they are well-formed and not duplicated
+    #[track_caller]
+    pub fn validate(self) -> Result<(), DecoderError> {
+        for property in self {
+            let _ = property?;
+        }
+        Ok(())
+    }
+}
+impl<'a, Policy> Iterator for PropertyDecoder<'a, Policy>
+where
+    Policy: DuplicatePolicy,
+{
+    type Item = Result<(u8, Value<'a>), DecoderError>;
+
+    #[track_caller]
+    fn next(&mut self) -> Option<Self::Item> {
+        // Parse the next property, and fuse the decoder on error
+        let offset = self.offset;
+        if self.raw.is_empty() {
+            return None;
+        }
+        let (id, value, len) = match parse(self.raw) {
+            Ok(property) => property,
+            Err(e) => {
+                self.raw = &[];
+                return Some(Err(err!(SpecViolation, e).with_offset(offset)));
+            }
+        };
+
+        // Reject duplicates of properties that must not occur multiple times; all known identifiers are below 64
+        let mask = 1u64.checked_shl(id as u32).unwrap_or_default();
+        if !matches!(value, Value::Raw(_)) && !self.policy.is_repeatable(id) && self.seen & mask != 0 {
+            self.raw = &[];
+            return Some(Err(err!(SpecViolation, "Duplicate property").with_offset(offset)));
+        }
+
+        // Advance the decoder
+        self.seen |= mask;
+        self.raw = self.raw.get(len..).unwrap_or_default();
+        self.offset = offset.saturating_add(len);
+        Some(Ok((id, value)))
+    }
+}
+impl<Policy> FusedIterator for PropertyDecoder<'_, Policy>
+where
+    Policy: DuplicatePolicy,
+{
+    // No members to implement
+}
+
+/// An encoder that appends validated properties to an encoded properties block
+///
+/// # Note
+/// The block is written without its length prefix; use [`Encoder::properties`](crate::coding::Encoder::properties) to
+/// write it together with the length prefix. Every operation either succeeds or leaves the block unchanged.
+#[derive(Debug)]
+pub struct PropertyEncoder<'a, T, Policy = SpecPolicy> {
+    /// The encoded properties
+    raw: &'a mut T,
+    /// The duplicate policy
+    policy: Policy,
+}
+impl<'a, T> PropertyEncoder<'a, T, SpecPolicy>
+where
+    T: AnyVec<u8>,
+{
+    /// Creates a new encoder that appends to the given, well-formed properties block
+    pub fn new(raw: &'a mut T) -> Self {
+        Self { raw, policy: SpecPolicy }
+    }
+}
+impl<'a, T, Policy> PropertyEncoder<'a, T, Policy>
+where
+    T: AnyVec<u8>,
+    Policy: DuplicatePolicy,
+{
+    /// Replaces the duplicate policy
+    pub fn with_policy<P>(self, policy: P) -> PropertyEncoder<'a, T, P>
+    where
+        P: DuplicatePolicy,
+    {
+        PropertyEncoder { raw: self.raw, policy }
+    }
+
+    /// Appends a property
+    ///
+    /// # Note
+    /// The value type must match the property identifier, and UTF-8 strings must be valid MQTT UTF-8 strings. Raw
+    /// values, duplicates of properties that must not occur multiple times and properties after an unknown property are
+    /// rejected.
+    pub fn push(&mut self, id: u8, value: Value) -> Result<(), &'static str> {
+        // Validate the value type
+        let true = PropertyType::of(id).is_some_and(|type_| type_.matches(&value)) else {
+            return Err("Invalid property value");
+        };
+
+        // Validate the byte fields
+        match value {
+            Value::Binary(bytes) => check_field_len(&bytes)?,
+            Value::Utf8(string) => {
+                check_field_len(&string)?;
+                validate_utf8(string)?;
+            }
+            Value::Pair(key, value) => {
+                check_field_len(&key)?;
+                check_field_len(&value)?;
+                validate_utf8(key)?;
+                validate_utf8(value)?;
+            }
+            _ => (),
+        }
+
+        // Validate the position within the block
+        let repeatable = self.policy.is_repeatable(id);
+        for (id_, value) in PropertyDecoder::new(self.raw.as_ref(), 0).with_policy(|_| true).flatten() {
+            match value {
+                Value::Raw(_) => return Err("Property after unknown property"),
+                _ if id_ == id && !repeatable => return Err("Duplicate property"),
+                _ => (),
+            }
+        }
+
+        // Append the property
+        self.append(id, value)
+    }
+
+    /// Appends the raw remainder of a properties block that starts with a property of unknown type (see [`Value::Raw`])
+    ///
+    /// # Note
+    /// Since the length of an unknown property is unknown too, no further properties can be appended afterwards.
+    pub fn push_unknown(&mut self, raw: &[u8]) -> Result<(), &'static str> {
+        // Validate the remainder and the position within the block
+        let Some(id) = raw.first().filter(|id| PropertyType::of(**id).is_none()) else {
+            return Err("Invalid unknown property");
+        };
+        if PropertyDecoder::new(self.raw.as_ref(), 0).flatten().any(|(_, value)| matches!(value, Value::Raw(_))) {
+            return Err("Property after unknown property");
+        }
+
+        // Append the remainder
+        self.append(*id, Value::Raw(raw))
+    }
+
+    /// The length of the encoded properties block without the length prefix
+    pub fn len(&self) -> usize {
+        self.raw.as_ref().len()
+    }
+    /// Whether the encoded properties block is empty
+    pub fn is_empty(&self) -> bool {
+        self.raw.as_ref().is_empty()
+    }
+
+    /// Appends the given property as-is, or restores the previous state on error
+    fn append(&mut self, id: u8, value: Value) -> Result<(), &'static str> {
+        // Ensure that the block can still be encoded
+        let previous_len = self.raw.as_ref().len();
+        let len = previous_len.saturating_add(encoded_len(&value));
+        let true = len <= EncodeError::MAX_PACKET_LEN else {
+            return Err("Properties block is too large");
+        };
+
+        // Append the property and restore the previous state on error
+        let result = self.raw.reserve(len.saturating_sub(previous_len)).and_then(|_| self.extend(id, value));
+        if result.is_err() {
+            self.raw.truncate(previous_len);
+        }
+        result
+    }
+
+    /// Writes the given property as-is
+    fn extend(&mut self, id: u8, value: Value) -> Result<(), &'static str> {
+        /// Appends a length-prefixed field
+        fn field<T>(raw: &mut T, bytes: &[u8]) -> Result<(), &'static str>
+        where
+            T: AnyVec<u8>,
+        {
+            raw.extend(&(bytes.len() as u16).to_be_bytes())?;
+            raw.extend(bytes)
+        }
+
+        // Write the identifier and the value; the raw remainder already starts with the identifier
+        match value {
+            Value::Byte(byte) => self.raw.extend(&[id, byte]),
+            Value::U16(u16_) => self.raw.push(id).and_then(|_| self.raw.extend(&u16_.to_be_bytes())),
+            Value::U32(u32_) => self.raw.push(id).and_then(|_| self.raw.extend(&u32_.to_be_bytes())),
+            Value::Varint(varint) => {
+                let (bytes, size) = varint::encode(varint as usize);
+                self.raw.push(id).and_then(|_| self.raw.extend(bytes.get(..size).unwrap_or_default()))
+            }
+            Value::Binary(bytes) | Value::Utf8(bytes) => self.raw.push(id).and_then(|_| field(self.raw, bytes)),
+            Value::Pair(key, value) => {
+                self.raw.push(id).and_then(|_| field(self.raw, key)).and_then(|_| field(self.raw, value))
+            }
+            Value::Raw(raw) => self.raw.extend(raw),
+        }
+    }
+}
+
+/// The encoded length of the given property including its identifier
+fn encoded_len(value: &Value) -> usize {
+    let value_len = match value {
+        Value::Byte(_) => 1,
+        Value::U16(_) => 2,
+        Value::U32(_) => 4,
+        Value::Varint(varint) => varint::encode(*varint as usize).1,
+        Value::Binary(bytes) | Value::Utf8(bytes) => bytes.len().saturating_add(2),
+        Value::Pair(key, value) => key.len().saturating_add(value.len()).saturating_add(4),
+        // The raw remainder already contains the identifier
+        Value::Raw(raw) => return raw.len(),
+    };
+    value_len.saturating_add(1)
+}
+
+/// Parses the next property from the given encoded properties and returns the identifier, the value and the amount of
+/// consumed bytes
+pub(crate) fn parse(raw: &[u8]) -> Result<(u8, Value<'_>, usize), &'static str> {
+    /// Splits a length-prefixed field from the given bytes
+    fn field(bytes: &[u8]) -> Result<(&[u8], &[u8]), &'static str> {
+        let [len0, len1, rest @ ..] = bytes else {
+            return Err("Truncated property");
+        };
+        let len = u16::from_be_bytes([*len0, *len1]) as usize;
+        match (rest.get(..len), rest.get(len..)) {
+            (Some(field), Some(rest)) => Ok((field, rest)),
+            _ => Err("Truncated property"),
+        }
+    }
+
+    // Parse the identifier and the value
+    let [id, body @ ..] = raw else {
+        return Err("Truncated property");
+    };
+    let (value, rest) = match PropertyType::of(*id) {
+        Some(PropertyType::Byte) => match body {
+            [byte, rest @ ..] => (Value::Byte(*byte), rest),
+            _ => return Err("Truncated property"),
+        },
+        Some(PropertyType::U16) => match body {
+            [b0, b1, rest @ ..] => (Value::U16(u16::from_be_bytes([*b0, *b1])), rest),
+            _ => return Err("Truncated property"),
+        },
+        Some(PropertyType::U32) => match body {
+            [b0, b1, b2, b3, rest @ ..] => (Value::U32(u32::from_be_bytes([*b0, *b1, *b2, *b3])), rest),
+            _ => return Err("Truncated property"),
+        },
+        Some(PropertyType::Varint) => {
+            let (varint, len) = varint::decode(body.iter().copied()).map_err(|_| "Invalid variable byte integer")?;
+            (Value::Varint(varint as u32), body.get(len..).unwrap_or_default())
+        }
+        Some(PropertyType::Binary) => {
+            let (bytes, rest) = field(body)?;
+            (Value::Binary(bytes), rest)
+        }
+        Some(PropertyType::Utf8) => {
+            let (string, rest) = field(body)?;
+            validate_utf8(string)?;
+            (Value::Utf8(string), rest)
+        }
+        Some(PropertyType::Pair) => {
+            let (key, rest) = field(body)?;
+            let (value, rest) = field(rest)?;
+            validate_utf8(key)?;
+            validate_utf8(value)?;
+            (Value::Pair(key, value), rest)
+        }
+        None => return Ok((*id, Value::Raw(raw), raw.len())),
+    };
+    Ok((*id, value, raw.len().saturating_sub(rest.len())))
+}
+
+/// Validates the given bytes as MQTT UTF-8 string
+pub(crate) fn validate_utf8(bytes: &[u8]) -> Result<(), &'static str> {
+    let mut validator = Utf8Validator::default();
+    for byte in bytes {
+        validator.push(*byte)?;
+    }
+    validator.finish()?;
+    str::from_utf8(bytes).map(|_| ()).map_err(|_| "Invalid UTF-8 string")
+}
@@ -0,0 +1,73 @@
+//! Variable-byte-integer en-/decoding as used by the MQTT packet length field
+
+use crate::error::DecoderError;
+
+/// Encodes a value as variable-byte-integer and returns the encoded bytes together with the amount of used bytes
+///
+/// # Panics
+/// This function panics if the value is greater than `2^28 - 1` (i.e. if it cannot be encoded in 4 or less heptets).
+pub fn encode(mut value: usize) -> ([u8; 4], usize) {
+    // Validate and compute encoded size
+    #[allow(clippy::panic, reason = "Value must be encoded in 4 or less heptets")]
+    #[allow(clippy::unusual_byte_groupings, reason = "Length bytes are encoded in heptets")]
+    let size = match value {
+        0b1_0000000_0000000_0000000_0000000.. => panic!("Packet length is too large"),
+        0b1_0000000_0000000_0000000.. => 4,
+        0b1_0000000_0000000.. => 3,
+        0b1_0000000.. => 2,
+        _ => 1,
+    };
+
+    // Encode the value in 7-bit nibbles
+    let mut bytes = [0; 4];
+    for index in 0..size {
+        // Push the next remaining least-significant 7 bits to the **front** of the encoded value
+        bytes.rotate_right(1);
+        bytes[0] = (value as u8) & 0b0111_1111;
+        value >>= 7;
+
+        // Insert the marker if the byte is not at the end-of-array
+        if index > 0 {
+            bytes[0] |= 0b1000_0000;
+        }
+    }
+    (bytes, size)
+}
+
+/// Decodes a variable-byte-integer from the given source and returns the decoded value together with the amount of
+/// consumed bytes
+///
+/// # Note
+/// This function consumes only the bytes that belong to the variable-byte-integer, so the source can be reused to read
+/// subsequent fields. The offset of a returned error is relative to the first byte of the variable-byte-integer.
+#[track_caller]
+pub fn decode<T>(source: T) -> Result<(usize, usize), DecoderError>
+where
+    T: IntoIterator<Item = u8>,
+{
+    // Parse value
+    let (mut value, mut consumed) = (0, 0usize);
+    for (pos, byte) in source.into_iter().enumerate() {
+        // Decode next byte
+        value <<= 7;
+        value |= (byte & 0b0111_1111) as usize;
+        consumed = pos.saturating_add(1);
+
+        // Check for end-of-value
+        match byte & 0b1000_0000 {
+            // Multi-byte value with a leading zero heptet
+            0b1000_0000 if byte == 0b1000_0000 && value == 0 => {
+                return Err(err!(SpecViolation, "Invalid packet length").with_offset(pos))
+            }
+            // Not the last byte but further bytes are invalid
+            0b1000_0000 if pos > 2 => return Err(err!(SpecViolation, "Packet length is too large").with_offset(pos)),
+            // Not the last byte and further bytes are allowed
+            0b1000_0000 => continue,
+            // Byte is the last byte
+            _ => return Ok((value, consumed)),
+        }
+    }
+
+    // The value is truncated
+    Err(err!(Truncated, "Truncated input").with_offset(consumed))
+}
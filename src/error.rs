@@ -0,0 +1,608 @@
+//! Typed error types
+
+use crate::anyvec::AnyVec;
+use core::fmt::{self, Debug, Display, Formatter};
+#[cfg(feature = "location")]
+use core::panic::Location;
+
+/// An encoding error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EncodeError {
+    /// A length-prefixed byte field is longer than [`Self::MAX_FIELD_LEN`]
+    FieldTooLong {
+        /// The length of the byte field
+        len: usize,
+    },
+    /// A packet body is longer than [`Self::MAX_PACKET_LEN`]
+    PacketTooLarge {
+        /// The length of the packet body
+        len: usize,
+    },
+    /// A packet type is greater than [`Self::MAX_PACKET_TYPE`]
+    InvalidPacketType {
+        /// The packet type
+        type_: u8,
+    },
+    /// A buffer is too small to hold the encoded packet
+    BufferTooSmall {
+        /// The required buffer length
+        required: usize,
+        /// The available buffer length
+        capacity: usize,
+    },
+}
+impl EncodeError {
+    /// The maximum length of a length-prefixed byte field
+    pub const MAX_FIELD_LEN: usize = u16::MAX as usize;
+    /// The maximum length of a packet body (i.e. the maximum value of the packet length field, `2^28 - 1`)
+    pub const MAX_PACKET_LEN: usize = 0b1111111_1111111_1111111_1111111;
+    /// The maximum packet type (`2^4 - 1`)
+    pub const MAX_PACKET_TYPE: u8 = 15;
+
+    /// A static description of the error
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Self::FieldTooLong { .. } => "Byte field is too long",
+            Self::PacketTooLarge { .. } => "Packet length is too large",
+            Self::InvalidPacketType { .. } => "Packet type is too large",
+            Self::BufferTooSmall { .. } => "Buffer is too small",
+        }
+    }
+
+    /// The limit that has been exceeded
+    pub const fn limit(&self) -> usize {
+        match self {
+            Self::FieldTooLong { .. } => Self::MAX_FIELD_LEN,
+            Self::PacketTooLarge { .. } => Self::MAX_PACKET_LEN,
+            Self::InvalidPacketType { .. } => Self::MAX_PACKET_TYPE as usize,
+            Self::BufferTooSmall { capacity, .. } => *capacity,
+        }
+    }
+    /// The value that exceeded the limit
+    pub const fn value(&self) -> usize {
+        match self {
+            Self::FieldTooLong { len } => *len,
+            Self::PacketTooLarge { len } => *len,
+            Self::InvalidPacketType { type_ } => *type_ as usize,
+            Self::BufferTooSmall { required, .. } => *required,
+        }
+    }
+    /// The amount by which the limit has been exceeded
+    pub const fn excess(&self) -> usize {
+        self.value().saturating_sub(self.limit())
+    }
+}
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} exceeds the limit of {} by {})",
+            self.description(),
+            self.value(),
+            self.limit(),
+            self.excess()
+        )
+    }
+}
+impl core::error::Error for EncodeError {
+    // No members to implement
+}
+impl From<EncodeError> for &'static str {
+    fn from(value: EncodeError) -> Self {
+        value.description()
+    }
+}
+
+/// The maximum amount of context frames of an [`Error`]
+pub const MAX_CONTEXT: usize = 3;
+
+/// A typed error consisting of a variant, a static description and the offset of the offending byte within the packet
+///
+/// # Equality
+/// Two errors are equal if their variant, offset and details are equal; the description, the context chain and the
+/// source location are not compared since they are only human-readable hints.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Error<Variant> {
+    /// The error variant
+    variant: Variant,
+    /// A static description of the error
+    description: &'static str,
+    /// The offset of the offending byte, counted from the first byte of the packet
+    offset: usize,
+    /// Additional details about the error, if any
+    detail: Option<Detail>,
+    /// The fields that were being decoded when the error occurred, from the innermost to the outermost field
+    context: [Option<&'static str>; MAX_CONTEXT],
+    /// The source location where the error has been created
+    #[cfg(feature = "location")]
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    location: &'static Location<'static>,
+}
+impl<Variant> Error<Variant> {
+    /// Creates a new error at offset `0` without details
+    ///
+    /// # Note
+    /// If the `location` feature is enabled, the location of the caller is captured (see [`Self::location`]).
+    #[track_caller]
+    pub const fn new(variant: Variant, description: &'static str) -> Self {
+        Self {
+            variant,
+            description,
+            offset: 0,
+            detail: None,
+            context: [None; MAX_CONTEXT],
+            #[cfg(feature = "location")]
+            location: Location::caller(),
+        }
+    }
+    /// Sets the offset of the offending byte, counted from the first byte of the packet
+    pub const fn with_offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+    /// Sets additional details about the error
+    pub const fn with_detail(mut self, detail: Detail) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+    /// Appends the field that was being decoded to the context chain
+    ///
+    /// # Note
+    /// The chain holds at most [`MAX_CONTEXT`] frames; further (outer) frames are discarded.
+    pub fn context(mut self, context: &'static str) -> Self {
+        if let Some(slot) = self.context.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(context);
+        }
+        self
+    }
+
+    /// The error variant
+    pub const fn variant(&self) -> &Variant {
+        &self.variant
+    }
+    /// A static description of the error
+    pub const fn description(&self) -> &'static str {
+        self.description
+    }
+    /// The offset of the offending byte, counted from the first byte of the packet
+    ///
+    /// # Note
+    /// If the input is truncated, the offset is the amount of available bytes (i.e. the offset of the first missing
+    /// byte). Errors of a whole field (e.g. memory errors) refer to the first byte of the field.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+    /// Additional details about the error, if any
+    pub const fn detail(&self) -> Option<Detail> {
+        self.detail
+    }
+    /// The fields that were being decoded when the error occurred, from the innermost to the outermost field
+    pub fn context_chain(&self) -> impl DoubleEndedIterator<Item = &'static str> + '_ {
+        self.context.iter().filter_map(|context| *context)
+    }
+    /// The source location where the error has been created
+    ///
+    /// # Note
+    /// The decoder methods propagate the location of their caller, so that the location points to the packet decoder
+    /// that failed instead of the generic decoder helpers.
+    #[cfg(feature = "location")]
+    pub const fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+impl<Variant> PartialEq for Error<Variant>
+where
+    Variant: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.variant == other.variant && self.offset == other.offset && self.detail == other.detail
+    }
+}
+impl<Variant> Eq for Error<Variant>
+where
+    Variant: Eq,
+{
+    // No members to implement
+}
+/// Renders the error as `MQTT error: <variant> (<description>[; <detail>]) at offset <offset>[ in <context>]`
+///
+/// # Note
+/// The alternate form (`{:#}`) additionally renders the source location on a separate line if the `location` feature is
+/// enabled.
+impl<Variant> Display for Error<Variant>
+where
+    Variant: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Render the variant, description and details
+        write!(f, "MQTT error: {:?} ({}", self.variant, self.description)?;
+        if let Some(detail) = self.detail {
+            write!(f, "; {detail}")?;
+        }
+        write!(f, ") at offset {}", self.offset)?;
+
+        // Render the context chain from the outermost to the innermost field
+        for (index, context) in self.context_chain().rev().enumerate() {
+            match index {
+                0 => write!(f, " in {context}")?,
+                _ => write!(f, " > {context}")?,
+            }
+        }
+
+        // Render the source location in the alternate form
+        #[cfg(feature = "location")]
+        if f.alternate() {
+            write!(f, "\n  at {}", self.location)?;
+        }
+        Ok(())
+    }
+}
+impl<Variant> core::error::Error for Error<Variant>
+where
+    Variant: Debug,
+{
+    // No members to implement
+}
+
+/// Additional details about an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Detail {
+    /// A value does not match the expected value (e.g. a packet type, protocol level or packet length)
+    Mismatch {
+        /// The expected value
+        expected: u32,
+        /// The value that has been found instead
+        found: u32,
+    },
+    /// A normative statement of the MQTT specification has been violated
+    ///
+    /// # Note
+    /// The rule is identified by its conformance statement identifier, e.g. `"MQTT-3.1.2-3"` (see
+    /// [MQTT 3.1.1 appendix B](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718140)).
+    Rule(&'static str),
+}
+impl Display for Detail {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatch { expected, found } => write!(f, "expected {expected}, found {found}"),
+            Self::Rule(rule) => write!(f, "violates {rule}"),
+        }
+    }
+}
+
+/// The variant of a decoding error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Decoding {
+    /// The input ended before the packet was complete
+    Truncated,
+    /// The input violates the MQTT specification
+    SpecViolation,
+    /// The packet type is reserved or otherwise unknown
+    UnknownPacketType,
+    /// The protocol name is valid, but the protocol level is not supported
+    ///
+    /// # Note
+    /// A server should respond with a `CONNACK` with return code `1` (unacceptable protocol version) and then close
+    /// the connection (see `MQTT-3.1.2-2` in
+    /// [MQTT 3.1.1 appendix B](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718140)).
+    UnsupportedVersion,
+    /// A container cannot hold a decoded field
+    Memory,
+}
+
+/// A decoding error
+pub type DecoderError = Error<Decoding>;
+impl DecoderError {
+    /// Whether the input ended before the packet was complete
+    pub const fn is_truncated(&self) -> bool {
+        matches!(self.variant, Decoding::Truncated)
+    }
+    /// Whether the input violates the MQTT specification
+    pub const fn is_spec_violation(&self) -> bool {
+        matches!(self.variant, Decoding::SpecViolation)
+    }
+    /// Whether the protocol level of a `CONNECT` packet is not supported
+    pub const fn is_unsupported_version(&self) -> bool {
+        matches!(self.variant, Decoding::UnsupportedVersion)
+    }
+    /// Whether a container cannot hold a decoded field
+    pub const fn is_memory(&self) -> bool {
+        matches!(self.variant, Decoding::Memory)
+    }
+
+    /// Classifies an error of a container that failed to hold `needed` elements as memory error with the needed and
+    /// available capacity, or, if the container rejected the value itself (e.g. invalid UTF-8), as specification
+    /// violation
+    #[track_caller]
+    pub(crate) fn container<T, V>(container: &V, error: &'static str, needed: usize) -> Self
+    where
+        V: AnyVec<T>,
+    {
+        match container.is_value_error(error) {
+            true => Self::new(Decoding::SpecViolation, error),
+            false => err!(Memory, error, expected needed, found container.capacity()),
+        }
+    }
+}
+/// Converts a decoding error into an I/O error
+///
+/// # Note
+/// [`Decoding::Truncated`] is mapped to [`std::io::ErrorKind::UnexpectedEof`], [`Decoding::SpecViolation`] to
+/// [`std::io::ErrorKind::InvalidData`], [`Decoding::UnknownPacketType`] and [`Decoding::UnsupportedVersion`] to
+/// [`std::io::ErrorKind::Unsupported`] and [`Decoding::Memory`] to [`std::io::ErrorKind::OutOfMemory`]. The I/O error
+/// always wraps the original error, so it can be recovered via
+/// `error.get_ref().and_then(|e| e.downcast_ref::<DecoderError>())`.
+#[cfg(feature = "std")]
+impl From<DecoderError> for std::io::Error {
+    fn from(error: DecoderError) -> Self {
+        use std::io::ErrorKind;
+        let kind = match error.variant {
+            Decoding::Truncated => ErrorKind::UnexpectedEof,
+            Decoding::SpecViolation => ErrorKind::InvalidData,
+            Decoding::UnknownPacketType | Decoding::UnsupportedVersion => ErrorKind::Unsupported,
+            Decoding::Memory => ErrorKind::OutOfMemory,
+        };
+        Self::new(kind, error)
+    }
+}
+
+/// A decoding error from a fallible byte source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SourceOrDecode<E> {
+    /// The byte source failed
+    Source(E),
+    /// The bytes could not be decoded
+    Decode(DecoderError),
+}
+impl<E> Display for SourceOrDecode<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source(e) => write!(f, "Byte source failed: {e}"),
+            Self::Decode(e) => Display::fmt(e, f),
+        }
+    }
+}
+impl<E> core::error::Error for SourceOrDecode<E>
+where
+    E: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Source(e) => Some(e),
+            Self::Decode(_) => None,
+        }
+    }
+}
+
+/// A variable-length packet field, used to tag memory errors during decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Field {
+    /// The client ID of a `CONNECT` packet
+    ClientId,
+    /// The last-will topic of a `CONNECT` packet
+    WillTopic,
+    /// The last-will message of a `CONNECT` packet
+    WillMessage,
+    /// The username of a `CONNECT` packet
+    Username,
+    /// The password of a `CONNECT` packet
+    Password,
+    /// The topic of a `PUBLISH` packet
+    Topic,
+    /// The payload of a `PUBLISH` packet
+    Payload,
+    /// The topic list of a `SUBSCRIBE` or `UNSUBSCRIBE` packet
+    Topics,
+    /// The body of a raw packet
+    Body,
+    /// The properties block of an MQTT 5 packet
+    Properties,
+    /// The reason code list of an MQTT 5 `SUBACK` or `UNSUBACK` packet
+    ReasonCodes,
+}
+impl Field {
+    /// The error message if the field exceeds the capacity of its container
+    pub const fn capacity_error(&self) -> &'static str {
+        match self {
+            Self::ClientId => "Client ID exceeds the container capacity",
+            Self::WillTopic => "Last-will topic exceeds the container capacity",
+            Self::WillMessage => "Last-will message exceeds the container capacity",
+            Self::Username => "Username exceeds the container capacity",
+            Self::Password => "Password exceeds the container capacity",
+            Self::Topic => "Topic exceeds the container capacity",
+            Self::Payload => "Payload exceeds the container capacity",
+            Self::Topics => "Topic list exceeds the container capacity",
+            Self::Body => "Body exceeds the container capacity",
+            Self::Properties => "Properties exceed the container capacity",
+            Self::ReasonCodes => "Reason codes exceed the container capacity",
+        }
+    }
+
+    /// Replaces the description of a memory error with [`Self::capacity_error`] and passes other errors through
+    ///
+    /// # Note
+    /// The needed and available capacity of the memory error (see [`Detail::Mismatch`]) are retained.
+    pub(crate) const fn tag(self, error: DecoderError) -> DecoderError {
+        match error.variant {
+            Decoding::Memory => DecoderError { description: self.capacity_error(), ..error },
+            _ => error,
+        }
+    }
+}
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.capacity_error())
+    }
+}
+
+/// A topic name or topic filter that violates the [topic
+/// rules](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718106)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TopicError {
+    /// The topic is empty
+    Empty,
+    /// The topic is longer than 65535 bytes
+    TooLong,
+    /// The topic is not well-formed UTF-8
+    InvalidUtf8 {
+        /// The offset of the first invalid byte
+        offset: usize,
+    },
+    /// The topic contains the null character `U+0000`
+    NullCharacter {
+        /// The offset of the null character
+        offset: usize,
+    },
+    /// A topic name contains a wildcard
+    WildcardInName {
+        /// The offset of the wildcard
+        offset: usize,
+    },
+    /// A single-level wildcard `+` does not occupy an entire level
+    InvalidSingleLevelWildcard {
+        /// The offset of the wildcard
+        offset: usize,
+    },
+    /// A multi-level wildcard `#` does not occupy an entire level or is not the last level
+    InvalidMultiLevelWildcard {
+        /// The offset of the wildcard
+        offset: usize,
+    },
+}
+impl TopicError {
+    /// The longest valid topic in bytes
+    pub const MAX_LEN: usize = 65535;
+
+    /// A static description of the error
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Self::Empty => "Empty topic",
+            Self::TooLong => "Topic is too long",
+            Self::InvalidUtf8 { .. } => "Invalid UTF-8 within topic",
+            Self::NullCharacter { .. } => "Null character within topic",
+            Self::WildcardInName { .. } => "Wildcard within topic name",
+            Self::InvalidSingleLevelWildcard { .. } => "Invalid single-level wildcard",
+            Self::InvalidMultiLevelWildcard { .. } => "Invalid multi-level wildcard",
+        }
+    }
+
+    /// The identifier of the violated normative statement of the specification
+    pub const fn rule(&self) -> &'static str {
+        match self {
+            Self::Empty => "MQTT-4.7.3-1",
+            Self::TooLong => "MQTT-4.7.3-3",
+            Self::InvalidUtf8 { .. } => "MQTT-1.5.3-1",
+            Self::NullCharacter { .. } => "MQTT-4.7.3-2",
+            Self::WildcardInName { .. } => "MQTT-4.7.1-1",
+            Self::InvalidSingleLevelWildcard { .. } => "MQTT-4.7.1-3",
+            Self::InvalidMultiLevelWildcard { .. } => "MQTT-4.7.1-2",
+        }
+    }
+
+    /// The offset of the offending byte within the topic
+    ///
+    /// # Note
+    /// For empty topics, the offset is `0`; for topics that are too long, the offset is [`Self::MAX_LEN`].
+    pub const fn offset(&self) -> usize {
+        match self {
+            Self::Empty => 0,
+            Self::TooLong => Self::MAX_LEN,
+            Self::InvalidUtf8 { offset }
+            | Self::NullCharacter { offset }
+            | Self::WildcardInName { offset }
+            | Self::InvalidSingleLevelWildcard { offset }
+            | Self::InvalidMultiLevelWildcard { offset } => *offset,
+        }
+    }
+}
+impl Display for TopicError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {} (violates {})", self.description(), self.offset(), self.rule())
+    }
+}
+impl core::error::Error for TopicError {
+    // No members to implement
+}
+impl From<TopicError> for &'static str {
+    fn from(value: TopicError) -> Self {
+        value.description()
+    }
+}
+
+/// An MQTT 5 protocol error that is detected while processing a well-formed packet
+#[cfg(feature = "v5")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ProtocolError {
+    /// A topic alias is `0`
+    ZeroTopicAlias,
+    /// A topic alias is greater than the advertised topic alias maximum
+    TopicAliasExceedsMaximum {
+        /// The topic alias
+        alias: u16,
+        /// The advertised topic alias maximum
+        maximum: u16,
+    },
+    /// A topic alias is used without being established first
+    UnknownTopicAlias {
+        /// The topic alias
+        alias: u16,
+    },
+    /// A topic exceeds the container capacity
+    TopicTooLong,
+}
+#[cfg(feature = "v5")]
+impl ProtocolError {
+    /// A static description of the error
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Self::ZeroTopicAlias => "Topic alias is zero",
+            Self::TopicAliasExceedsMaximum { .. } => "Topic alias exceeds the topic alias maximum",
+            Self::UnknownTopicAlias { .. } => "Topic alias is unknown",
+            Self::TopicTooLong => "Topic exceeds the container capacity",
+        }
+    }
+
+    /// The reason code to report the error to the peer (e.g. within a `DISCONNECT` packet)
+    pub const fn reason_code(&self) -> crate::packets::v5::reason::ReasonCode {
+        use crate::packets::v5::reason::ReasonCode;
+        match self {
+            Self::ZeroTopicAlias | Self::TopicAliasExceedsMaximum { .. } | Self::UnknownTopicAlias { .. } => {
+                ReasonCode::TopicAliasInvalid
+            }
+            Self::TopicTooLong => ReasonCode::ImplementationSpecificError,
+        }
+    }
+}
+#[cfg(feature = "v5")]
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TopicAliasExceedsMaximum { alias, maximum } => {
+                write!(f, "{} ({alias} exceeds the maximum of {maximum})", self.description())
+            }
+            Self::UnknownTopicAlias { alias } => write!(f, "{} ({alias})", self.description()),
+            _ => f.write_str(self.description()),
+        }
+    }
+}
+#[cfg(feature = "v5")]
+impl core::error::Error for ProtocolError {
+    // No members to implement
+}
+#[cfg(feature = "v5")]
+impl From<ProtocolError> for &'static str {
+    fn from(value: ProtocolError) -> Self {
+        value.description()
+    }
+}
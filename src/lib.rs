@@ -14,14 +14,34 @@
 #![warn(clippy::allow_attributes_without_reason)]
 #![warn(clippy::cognitive_complexity)]
 
+// Link `alloc` if enabled
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// Internal logging and error macros
+include!("_log.rs");
+include!("_err.rs");
+
+pub mod anystring;
 pub mod anyvec;
+#[cfg(feature = "std")]
+pub mod client;
 pub mod coding;
+pub mod error;
+#[cfg(feature = "embedded-nal")]
+pub mod nal;
 pub mod packets;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+#[cfg(all(feature = "test-util", any(feature = "alloc", feature = "arrayvec")))]
+pub mod test_util;
+pub mod topic;
+pub mod transport;
 
 // Re-export `arrayvec` if enabled
 #[cfg(feature = "arrayvec")]
 pub extern crate arrayvec;
 
 // Re-export default type aliases
-#[cfg(any(feature = "std", feature = "arrayvec"))]
+#[cfg(any(feature = "alloc", feature = "arrayvec"))]
 include!("_defaults.rs");
@@ -0,0 +1,151 @@
+//! A non-blocking broker session over an [`embedded_nal::TcpClientStack`]
+//!
+//! # Note
+//! All session functions follow the [`embedded_nal::nb`] conventions: if a function returns
+//! [`embedded_nal::nb::Error::WouldBlock`], the progress is retained and the function should be called again with the
+//! same arguments once the socket is ready.
+
+use crate::{
+    anyvec::AnyVec,
+    error::SourceOrDecode,
+    packets::{
+        connack::Connack,
+        raw::Raw,
+        stream::{EncodedChunks, PacketDecoder},
+        TryFromIterator,
+    },
+};
+use core::{net::SocketAddr, ops::Range};
+use embedded_nal::{nb, TcpClientStack};
+
+/// A non-blocking MQTT session over a TCP socket of an [`embedded_nal::TcpClientStack`]
+///
+/// # Note
+/// Outgoing packets are encoded into a caller-provided transmit buffer, so the transmit buffer must be large enough to
+/// hold the largest outgoing packet. Incoming packets are buffered in a `Buf` until they are complete.
+#[derive(Debug)]
+pub struct NalSession<'a, Stack, Buf>
+where
+    Stack: TcpClientStack,
+{
+    /// The underlying socket
+    socket: Stack::TcpSocket,
+    /// Whether the TCP connection has been established or not
+    connected: bool,
+    /// The transmit buffer
+    tx: &'a mut [u8],
+    /// The pending bytes within the transmit buffer
+    tx_pending: Range<usize>,
+    /// The resumable decoder for incoming packets
+    decoder: PacketDecoder<Raw<Buf>, Buf>,
+}
+impl<'a, Stack, Buf> NalSession<'a, Stack, Buf>
+where
+    Stack: TcpClientStack,
+    Buf: AnyVec<u8> + AsMut<[u8]>,
+{
+    /// Creates a new session with a fresh socket from the given stack
+    pub fn new(stack: &mut Stack, tx: &'a mut [u8]) -> Result<Self, Stack::Error> {
+        let socket = stack.socket()?;
+        Ok(Self { socket, connected: false, tx, tx_pending: 0..0, decoder: PacketDecoder::new() })
+    }
+
+    /// Connects to the given remote, sends the given `CONNECT` packet and awaits the `CONNACK` packet
+    ///
+    /// # Note
+    /// The `CONNACK` packet is returned even if the server has refused the connection; it is up to the caller to
+    /// validate the [`Connack::return_code`].
+    pub fn connect<P>(
+        &mut self,
+        stack: &mut Stack,
+        remote: SocketAddr,
+        connect: P,
+    ) -> nb::Result<Connack, SourceOrDecode<Stack::Error>>
+    where
+        P: IntoIterator<Item = u8>,
+    {
+        // Establish the TCP connection and queue the `CONNECT` packet once
+        if !self.connected {
+            stack.connect(&mut self.socket, remote).map_err(|e| e.map(SourceOrDecode::Source))?;
+            self.connected = true;
+            self.queue(connect)?;
+        }
+
+        // Send the `CONNECT` packet and await the `CONNACK` packet
+        self.flush(stack)?;
+        self.recv_packet(stack)
+    }
+
+    /// Sends a packet
+    ///
+    /// # Note
+    /// The packet is only encoded if no previous packet is pending; i.e. if this function returns
+    /// [`nb::Error::WouldBlock`], calling it again resumes sending the already encoded packet.
+    pub fn send_packet<P>(&mut self, stack: &mut Stack, packet: P) -> nb::Result<(), SourceOrDecode<Stack::Error>>
+    where
+        P: IntoIterator<Item = u8>,
+    {
+        if self.tx_pending.is_empty() {
+            self.queue(packet)?;
+        }
+        self.flush(stack)
+    }
+
+    /// Sends all pending bytes of the last packet
+    pub fn flush(&mut self, stack: &mut Stack) -> nb::Result<(), SourceOrDecode<Stack::Error>> {
+        while let Some(pending) = self.tx.get(self.tx_pending.clone()).filter(|pending| !pending.is_empty()) {
+            // Send the next bytes
+            let sent = stack.send(&mut self.socket, pending).map_err(|e| e.map(SourceOrDecode::Source))?;
+            if sent == 0 {
+                return Err(nb::Error::Other(SourceOrDecode::Decode(err!(Truncated, "Connection has been closed"))));
+            }
+            self.tx_pending.start = self.tx_pending.start.saturating_add(sent);
+        }
+        Ok(())
+    }
+
+    /// Receives the next packet
+    ///
+    /// # Note
+    /// If the packet cannot be decoded as `T`, it is discarded and an error is returned.
+    pub fn recv_packet<T>(&mut self, stack: &mut Stack) -> nb::Result<T, SourceOrDecode<Stack::Error>>
+    where
+        T: TryFromIterator,
+    {
+        let (mut chunk, mut read) = ([0; 256], 0);
+        loop {
+            // Feed the received bytes into the decoder
+            let received = chunk.get(..read).unwrap_or_default();
+            if let Some(raw) = self.decoder.feed(received).map_err(|e| nb::Error::Other(SourceOrDecode::Decode(e)))? {
+                return T::try_from_iter(&raw).map_err(|e| nb::Error::Other(SourceOrDecode::Decode(e)));
+            }
+
+            // Receive the next bytes
+            read = stack.receive(&mut self.socket, &mut chunk).map_err(|e| e.map(SourceOrDecode::Source))?;
+            if read == 0 {
+                return Err(nb::Error::Other(SourceOrDecode::Decode(err!(Truncated, "Connection has been closed"))));
+            }
+        }
+    }
+
+    /// Closes the underlying socket
+    pub fn close(self, stack: &mut Stack) -> Result<(), Stack::Error> {
+        stack.close(self.socket)
+    }
+
+    /// Encodes the given packet into the transmit buffer
+    fn queue<P>(&mut self, packet: P) -> Result<(), SourceOrDecode<Stack::Error>>
+    where
+        P: IntoIterator<Item = u8>,
+    {
+        // Encode the packet and ensure that it fits into the transmit buffer
+        let mut chunks = EncodedChunks::new(packet);
+        let len = chunks.fill(self.tx);
+        if !chunks.is_complete() {
+            return Err(SourceOrDecode::Decode(err!(Memory, "Packet is too large for the transmit buffer")));
+        }
+
+        self.tx_pending = 0..len;
+        Ok(())
+    }
+}
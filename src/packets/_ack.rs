@@ -3,7 +3,8 @@
 macro_rules! acklike {
     ($docstr:expr, $type:ident => $typeconst:expr) => {
         #[doc = $docstr]
-        #[derive(Debug, Clone, PartialEq, Eq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
         pub struct $type {
             /// The packet identifier
             packet_id: u16,
@@ -26,7 +27,7 @@ macro_rules! acklike {
             }
         }
         impl $crate::packets::TryFromIterator for $type {
-            fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+            fn try_from_iter<T>(iter: T) -> Result<Self, $crate::error::DecoderError>
             where
                 T: IntoIterator<Item = u8>,
             {
@@ -37,14 +38,18 @@ macro_rules! acklike {
                 //  - packet len
                 //  - packet ID
                 let mut decoder = Decoder::new(iter);
-                let (Self::TYPE, _flags) = decoder.header()? else {
-                    return Err("Invalid packet type");
+                let (type_, _flags) = decoder.header()?;
+                let Self::TYPE = type_ else {
+                    return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
                 };
-                let Self::BODY_LEN = decoder.packetlen()? else {
-                    return Err("Invalid packet length");
+                let len = decoder.packetlen()?;
+                let Self::BODY_LEN = len else {
+                    let error = err!(SpecViolation, "Invalid packet length", expected Self::BODY_LEN, found len);
+                    return Err(error.with_offset(1));
                 };
                 // Read fields
-                let packet_id = decoder.u16()?;
+                let packet_id = decoder.u16().map_err(|e| e.context("packet identifier"))?;
+                log_trace!("decoded {} (packet ID: {})", $crate::packets::packet::PacketType::$type.name(), packet_id);
         
                 // Init self
                 Ok(Self { packet_id })
@@ -55,13 +60,13 @@ macro_rules! acklike {
             #[rustfmt::skip]
             type IntoIter = 
                 // Complex iterator built out of the individual message fields
-                core::iter::Chain<core::iter::Chain<core::iter::Chain<
+                $crate::coding::encoder::SizedIter<core::iter::Chain<core::iter::Chain<core::iter::Chain<
                     // - header type and `0` flags
                     $crate::coding::encoder::Unit, $crate::coding::encoder::U8Iter>, 
                     // - packet len
                     $crate::coding::encoder::PacketLenIter>,
                     // - packet ID
-                    $crate::coding::encoder::U16Iter>;
+                    $crate::coding::encoder::U16Iter>>;
         
             fn into_iter(self) -> Self::IntoIter {
                 use crate::coding::Encoder;
@@ -71,10 +76,27 @@ macro_rules! acklike {
                 //  - packet len
                 //  - packet ID
                 Encoder::default()
-                    .header(Self::TYPE, [false, false, false, false])
+                    .typed_header($crate::packets::packet::PacketType::$type, [false, false, false, false])
                     .packetlen(Self::BODY_LEN)
                     .u16(self.packet_id)
-                    .into_iter()
+                    .into_sized_iter(Self::BODY_LEN)
+            }
+        }
+        impl IntoIterator for &$type {
+            type Item = u8;
+            type IntoIter = <$type as IntoIterator>::IntoIter;
+
+            fn into_iter(self) -> Self::IntoIter {
+                // The packet is tiny, so we can simply encode a copy
+                self.clone().into_iter()
+            }
+        }
+        #[cfg(feature = "arbitrary")]
+        impl<'a> arbitrary::Arbitrary<'a> for $type {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                // Packet IDs must be non-zero
+                let packet_id = u.int_in_range(1..=u16::MAX)?;
+                Ok(Self { packet_id })
             }
         }
     };
@@ -118,6 +140,55 @@ pub mod suback {
         "An MQTT [`SUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718068)",
         Suback => 9
     }
+
+    /// A [`SUBACK` return code](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718071)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub enum GrantedQos {
+        /// `0x00`: Success with maximum QoS 0
+        AtMostOnce = 0x00,
+        /// `0x01`: Success with maximum QoS 1
+        AtLeastOnce = 0x01,
+        /// `0x02`: Success with maximum QoS 2
+        ExactlyOnce = 0x02,
+        /// `0x80`: Failure
+        Failure = 0x80,
+    }
+    impl GrantedQos {
+        /// All return codes
+        pub const ALL: [Self; 4] = [Self::AtMostOnce, Self::AtLeastOnce, Self::ExactlyOnce, Self::Failure];
+
+        /// The granted QoS level, or `None` if the subscription has been rejected
+        pub const fn qos(&self) -> Option<u8> {
+            match self {
+                Self::Failure => None,
+                granted => Some(*granted as u8),
+            }
+        }
+        /// Whether the subscription has been rejected or not
+        pub const fn is_failure(&self) -> bool {
+            matches!(self, Self::Failure)
+        }
+    }
+    impl TryFrom<u8> for GrantedQos {
+        type Error = crate::error::DecoderError;
+
+        fn try_from(value: u8) -> Result<Self, Self::Error> {
+            // Find the matching return code
+            for granted in Self::ALL {
+                // Compare the return code constant
+                if granted as u8 == value {
+                    return Ok(granted);
+                }
+            }
+            Err(err!(SpecViolation, "Invalid SUBACK return code", rule "MQTT-3.9.3-2"))
+        }
+    }
+    impl From<GrantedQos> for u8 {
+        fn from(value: GrantedQos) -> Self {
+            value as u8
+        }
+    }
 }
 
 pub mod unsuback {
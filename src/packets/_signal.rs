@@ -3,7 +3,8 @@
 macro_rules! emptylike {
     ($docstr:expr, $type:ident => $typeconst:expr) => {
         #[doc = $docstr]
-        #[derive(Debug, Clone, PartialEq, Eq)]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
         pub struct $type {
             _private: ()
         }
@@ -21,7 +22,7 @@ macro_rules! emptylike {
             }
         }
         impl $crate::packets::TryFromIterator for $type {
-            fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+            fn try_from_iter<T>(iter: T) -> Result<Self, $crate::error::DecoderError>
             where
                 T: IntoIterator<Item = u8>,
             {
@@ -32,12 +33,16 @@ macro_rules! emptylike {
                 //  - packet len
                 //  - packet I
                 let mut decoder = Decoder::new(iter);
-                let (Self::TYPE, _flags) = decoder.header()? else {
-                    return Err("Invalid packet type");
+                let (type_, _flags) = decoder.header()?;
+                let Self::TYPE = type_ else {
+                    return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
                 };
-                let Self::BODY_LEN = decoder.packetlen()? else {
-                    return Err("Invalid packet length");
+                let len = decoder.packetlen()?;
+                let Self::BODY_LEN = len else {
+                    let error = err!(SpecViolation, "Invalid packet length", expected Self::BODY_LEN, found len);
+                    return Err(error.with_offset(1));
                 };
+                log_trace!("decoded {}", $crate::packets::packet::PacketType::$type.name());
         
                 // Init self
                 Ok(Self { _private: () })
@@ -48,11 +53,11 @@ macro_rules! emptylike {
             #[rustfmt::skip]
             type IntoIter = 
                 // Complex iterator built out of the individual message fields
-                core::iter::Chain<core::iter::Chain<
+                $crate::coding::encoder::SizedIter<core::iter::Chain<core::iter::Chain<
                     // - header type and `0` flags
                     $crate::coding::encoder::Unit, $crate::coding::encoder::U8Iter>, 
                     // - packet len
-                    $crate::coding::encoder::PacketLenIter>;
+                    $crate::coding::encoder::PacketLenIter>>;
         
             fn into_iter(self) -> Self::IntoIter {
                 use crate::coding::Encoder;
@@ -61,9 +66,24 @@ macro_rules! emptylike {
                 //  - header type and `0` flags
                 //  - packet len
                 Encoder::default()
-                    .header(Self::TYPE, [false, false, false, false])
+                    .typed_header($crate::packets::packet::PacketType::$type, [false, false, false, false])
                     .packetlen(Self::BODY_LEN)
-                    .into_iter()
+                    .into_sized_iter(Self::BODY_LEN)
+            }
+        }
+        impl IntoIterator for &$type {
+            type Item = u8;
+            type IntoIter = <$type as IntoIterator>::IntoIter;
+
+            fn into_iter(self) -> Self::IntoIter {
+                // The packet is tiny, so we can simply encode a copy
+                self.clone().into_iter()
+            }
+        }
+        #[cfg(feature = "arbitrary")]
+        impl<'a> arbitrary::Arbitrary<'a> for $type {
+            fn arbitrary(_u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                Ok(Self::new())
             }
         }
     };
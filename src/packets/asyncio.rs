@@ -0,0 +1,143 @@
+//! Asynchronous packet I/O over the [`futures_io`] traits
+
+use crate::packets::{framing::FrameState, TryFromIterator};
+use core::{
+    future::{poll_fn, Future},
+    pin::Pin,
+};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::io::{Error, ErrorKind};
+
+/// Traits for elements that can be built from an asynchronous byte reader
+pub trait TryFromAsyncReader
+where
+    Self: Sized,
+{
+    /// Tries to build `Self` from the given asynchronous byte reader (see [`super::TryFromReader::try_read`])
+    fn try_read_async<T>(reader: T) -> impl Future<Output = Result<Self, Error>>
+    where
+        T: AsyncRead + Unpin;
+
+    /// Tries to build `Self` from the given asynchronous byte reader, or fails if the packet length indicated by the
+    /// packet header is greater than `max_packet_size` (see [`super::TryFromReader::try_read_with_limit`])
+    fn try_read_async_with_limit<T>(reader: T, max_packet_size: usize) -> impl Future<Output = Result<Self, Error>>
+    where
+        T: AsyncRead + Unpin;
+}
+impl<T> TryFromAsyncReader for T
+where
+    T: TryFromIterator,
+{
+    async fn try_read_async<R>(reader: R) -> Result<Self, Error>
+    where
+        R: AsyncRead + Unpin,
+    {
+        Self::try_read_async_with_limit(reader, usize::MAX).await
+    }
+
+    async fn try_read_async_with_limit<R>(mut reader: R, max_packet_size: usize) -> Result<Self, Error>
+    where
+        R: AsyncRead + Unpin,
+    {
+        // Read the frame and try to build `Self` from it
+        let frame = read_frame(&mut reader, max_packet_size).await?;
+        Ok(Self::try_from_iter(frame)?)
+    }
+}
+
+/// Traits for elements that can be written to an asynchronous byte writer
+pub trait ToAsyncWriter {
+    /// Writes `self` to the given asynchronous byte writer (see [`super::ToWriter::write`])
+    fn write_async<T>(self, writer: T) -> impl Future<Output = Result<(), Error>>
+    where
+        T: AsyncWrite + Unpin;
+
+    /// Writes `self` to the given asynchronous byte writer and returns the amount of written bytes (see
+    /// [`super::ToWriter::write_counted`])
+    fn write_async_counted<T>(self, writer: T) -> impl Future<Output = Result<usize, Error>>
+    where
+        T: AsyncWrite + Unpin;
+}
+impl<T> ToAsyncWriter for T
+where
+    T: IntoIterator<Item = u8>,
+{
+    async fn write_async<W>(self, writer: W) -> Result<(), Error>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        self.write_async_counted(writer).await?;
+        Ok(())
+    }
+
+    async fn write_async_counted<W>(self, mut writer: W) -> Result<usize, Error>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        // Drain the packet into a small stack chunk and write it chunk-wise for performance
+        let mut iter = self.into_iter();
+        let (mut chunk, mut written) = ([0; 128], 0usize);
+        loop {
+            // Fill the chunk; zipping the chunk first ensures that no byte is lost if the chunk is full
+            let len = chunk.iter_mut().zip(&mut iter).map(|(slot, byte)| *slot = byte).count();
+            write_all(&mut writer, chunk.get(..len).unwrap_or_default()).await?;
+            written = written.saturating_add(len);
+            if len < chunk.len() {
+                // The packet has been written completely
+                break;
+            }
+        }
+        log_trace!("wrote packet ({} bytes)", written);
+
+        // Flush writer
+        poll_fn(|cx| Pin::new(&mut writer).poll_flush(cx)).await?;
+        Ok(written)
+    }
+}
+
+/// Reads the next packet frame from the given asynchronous reader (see [`FrameState`])
+async fn read_frame<R>(reader: &mut R, max_packet_size: usize) -> Result<Vec<u8>, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    // Read until the frame is complete
+    let mut frame = Vec::new();
+    let mut state = FrameState::new(max_packet_size);
+    while state.demand() > 0 {
+        // Grow the frame by the demanded amount of bytes, but in limited steps to not trust the packet length blindly
+        let start = frame.len();
+        frame.resize(start.saturating_add(state.demand().min(8192)), 0);
+        let buf = frame.get_mut(start..).unwrap_or_default();
+
+        // Read the next bytes
+        let read = loop {
+            match poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, buf)).await {
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                result => break result?,
+            }
+        };
+
+        // Record the read bytes
+        frame.truncate(start.saturating_add(read));
+        let read = frame.get(start..).unwrap_or_default();
+        state.advance(read)?;
+    }
+    Ok(frame)
+}
+
+/// Writes all bytes to the given asynchronous writer
+async fn write_all<W>(writer: &mut W, mut bytes: &[u8]) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    while !bytes.is_empty() {
+        // Write the next bytes
+        match poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, bytes)).await {
+            Ok(0) => return Err(Error::from(ErrorKind::WriteZero)),
+            Ok(written) => bytes = bytes.get(written..).unwrap_or_default(),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! A [`tokio_util::codec`] codec for framed transports
+
+use crate::packets::{self, FrameStatus, TryFromIterator};
+use bytes::BytesMut;
+use core::marker::PhantomData;
+use std::io::Error;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// A codec that en-/decodes packets for [`tokio_util::codec::Framed`] transports
+#[derive(Debug, Clone, Copy)]
+pub struct MqttCodec<T> {
+    /// The maximum accepted frame length (i.e. header, packet length field and body)
+    max_frame_length: usize,
+    /// The packet type
+    _packet: PhantomData<T>,
+}
+impl<T> MqttCodec<T> {
+    /// Creates a new codec without a frame length limit
+    pub const fn new() -> Self {
+        Self { max_frame_length: usize::MAX, _packet: PhantomData }
+    }
+
+    /// Configures the maximum accepted frame length (i.e. header, packet length field and body)
+    ///
+    /// # Note
+    /// Oversized frames are rejected as soon as the packet length field has been buffered, before the body is buffered.
+    pub const fn with_max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// The maximum accepted frame length
+    pub const fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+}
+impl<T> Default for MqttCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Decoder for MqttCodec<T>
+where
+    T: TryFromIterator,
+{
+    type Item = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        // Check whether the buffer contains a complete frame
+        let status = packets::check(src)?;
+        match status {
+            FrameStatus::Complete { total_len } => {
+                // Validate the frame length
+                if total_len > self.max_frame_length {
+                    let error = err!(Memory, "Packet is too large", expected total_len, found self.max_frame_length);
+                    return Err(Error::from(error.with_offset(1)));
+                }
+
+                // Decode the frame
+                let frame = src.split_to(total_len);
+                let packet = T::try_from_iter(frame.iter().copied());
+                Ok(Some(packet?))
+            }
+            FrameStatus::Incomplete { needed_hint: Some(needed) } => {
+                // Validate the frame length
+                let total_len = src.len().saturating_add(needed);
+                if total_len > self.max_frame_length {
+                    let error = err!(Memory, "Packet is too large", expected total_len, found self.max_frame_length);
+                    return Err(Error::from(error.with_offset(1)));
+                }
+
+                // Reserve space for the remaining bytes
+                src.reserve(needed);
+                Ok(None)
+            }
+            FrameStatus::Incomplete { needed_hint: None } => Ok(None),
+        }
+    }
+}
+impl<T, P> Encoder<P> for MqttCodec<T>
+where
+    P: IntoIterator<Item = u8>,
+{
+    type Error = Error;
+
+    fn encode(&mut self, item: P, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        // Reserve the frame length and write the packet
+        let packet = item.into_iter();
+        dst.reserve(packet.size_hint().0);
+        dst.extend(packet);
+        Ok(())
+    }
+}
@@ -2,15 +2,17 @@
 
 use crate::{
     coding::{
-        encoder::{PacketLenIter, U8Iter, Unit},
+        encoder::{PacketLenIter, SizedIter, U8Iter, Unit},
         Decoder, Encoder,
     },
-    packets::TryFromIterator,
+    error::DecoderError,
+    packets::{packet::PacketType, TryFromIterator},
 };
 use core::iter::Chain;
 
 /// An MQTT [`CONNACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718033)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Connack {
     /// Whether a previous session is present or not
     session_present: bool,
@@ -38,8 +40,17 @@ impl Connack {
         self.return_code
     }
 }
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Connack {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // The session-present flag must not be set if the connection has been refused
+        let return_code = u.int_in_range(0..=5)?;
+        let session_present = return_code == 0 && u.arbitrary()?;
+        Ok(Self { session_present, return_code })
+    }
+}
 impl TryFromIterator for Connack {
-    fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
     where
         T: IntoIterator<Item = u8>,
     {
@@ -49,15 +60,18 @@ impl TryFromIterator for Connack {
         //  - ACK flags
         //  - return code
         let mut decoder = Decoder::new(iter);
-        let (Self::TYPE, _flags) = decoder.header()? else {
-            return Err("Invalid packet type");
+        let (type_, _flags) = decoder.header()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
         };
-        let Self::BODY_LEN = decoder.packetlen()? else {
-            return Err("Invalid packet length");
+        let len = decoder.packetlen()?;
+        let Self::BODY_LEN = len else {
+            return Err(err!(SpecViolation, "Invalid packet length", expected Self::BODY_LEN, found len).with_offset(1));
         };
         // Read fields
-        let [_, _, _, _, _, _, _, session_present] = decoder.bitmap()?;
-        let return_code = decoder.u8()?;
+        let [_, _, _, _, _, _, _, session_present] = decoder.bitmap().map_err(|e| e.context("acknowledge flags"))?;
+        let return_code = decoder.u8().map_err(|e| e.context("return code"))?;
+        log_trace!("decoded CONNACK (session present: {}, return code: {})", session_present, return_code);
 
         // Init self
         Ok(Self { session_present, return_code })
@@ -68,7 +82,7 @@ impl IntoIterator for Connack {
     #[rustfmt::skip]
     type IntoIter =
         // Complex iterator built out of the individual message fields
-        Chain<Chain<Chain<Chain<
+        SizedIter<Chain<Chain<Chain<Chain<
             // - header type and `0` flags
             Unit, U8Iter>,
             // - packet len
@@ -76,7 +90,7 @@ impl IntoIterator for Connack {
             // - ACK flags
             U8Iter>,
             // - return code
-            U8Iter>;
+            U8Iter>>;
 
     fn into_iter(self) -> Self::IntoIter {
         // Write packet:
@@ -85,10 +99,19 @@ impl IntoIterator for Connack {
         //  - ACK flags
         //  - return code
         Encoder::default()
-            .header(Self::TYPE, [false, false, false, false])
+            .typed_header(PacketType::Connack, [false, false, false, false])
             .packetlen(Self::BODY_LEN)
             .bitmap([false, false, false, false, false, false, false, self.session_present])
             .u8(self.return_code)
-            .into_iter()
+            .into_sized_iter(Self::BODY_LEN)
+    }
+}
+impl IntoIterator for &Connack {
+    type Item = u8;
+    type IntoIter = <Connack as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // The packet is tiny, so we can simply encode a copy
+        self.clone().into_iter()
     }
 }
@@ -3,16 +3,30 @@
 use crate::{
     anyvec::AnyVec,
     coding::{
-        encoder::{BytesIter, OptionalBytesIter, PacketLenIter, U16Iter, U8Iter, Unit},
+        encoder::{BytesIter, BytesRef, OptionalBytesIter, PacketLenIter, SizedIter, SliceIter, U16Iter, U8Iter, Unit},
         length::Length,
         Decoder, Encoder,
     },
-    packets::TryFromIterator,
+    error::{DecoderError, Field},
+    packets::{
+        flags::ConnectFlags,
+        packet::{PacketType, ProtocolVersion},
+        TryFromIterator,
+    },
 };
 use core::iter::Chain;
 
+/// A borrowed view of an MQTT [`CONNECT`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718033) (see [`Connect::parse`])
+pub type ConnectRef<'a> = Connect<BytesRef<'a>>;
+
 /// An MQTT [`CONNECT` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718033)
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// # Note
+/// To avoid leaking credentials into logs, the `Debug` implementation redacts the will message and the password and
+/// only displays their length. Enable the `debug-credentials` feature to display them verbatim.
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "debug-credentials", derive(Debug))]
 pub struct Connect<Bytes> {
     /// The seconds to keep the connection alive
     keep_alive_secs: u16,
@@ -43,10 +57,7 @@ pub struct Connect<Bytes> {
     /// The password
     password: Option<Bytes>,
 }
-impl<Bytes> Connect<Bytes>
-where
-    Bytes: AnyVec<u8>,
-{
+impl<Bytes> Connect<Bytes> {
     /// The packet type constant
     pub const TYPE: u8 = 1;
 
@@ -54,12 +65,20 @@ where
     const PROTOCOL_NAME: [u8; 6] = *b"\x00\x04MQTT";
     /// The protocol constant for MQTT 3.1.1
     const PROTOCOL_LEVEL_MQTT_3_1_1: u8 = 0x04;
-
+}
+impl<Bytes> Connect<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
     /// Creates a new packet
     pub fn new<T>(keep_alive_secs: u16, clean_session: bool, client_id: T) -> Result<Self, &'static str>
     where
         T: AsRef<[u8]>,
     {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&client_id)?;
+
+        // Init self
         let client_id = Bytes::new(client_id.as_ref())?;
         Ok(Self {
             keep_alive_secs,
@@ -85,6 +104,10 @@ where
         T: AsRef<[u8]>,
         M: AsRef<[u8]>,
     {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&topic)?.try_bytes(&message)?;
+
+        // Configure last-will
         self.will_topic = Bytes::new(topic.as_ref()).map(Some)?;
         self.will_message = Bytes::new(message.as_ref()).map(Some)?;
         self.will_retain = retain;
@@ -97,11 +120,19 @@ where
         U: AsRef<[u8]>,
         P: AsRef<[u8]>,
     {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&username)?.try_bytes(&password)?;
+
+        // Configure login data
         self.username = Bytes::new(username.as_ref()).map(Some)?;
         self.password = Bytes::new(password.as_ref()).map(Some)?;
         Ok(self)
     }
-
+}
+impl<Bytes> Connect<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
     /// Gets the seconds to keep the connection alive
     pub const fn keep_alive_secs(&self) -> u16 {
         self.keep_alive_secs
@@ -148,11 +179,223 @@ where
         self.password.as_ref().map(|bytes| bytes.as_ref())
     }
 }
+#[cfg(not(feature = "debug-credentials"))]
+impl<Bytes> core::fmt::Debug for Connect<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        /// A redacted field that only displays its length
+        struct Redacted(usize);
+        impl core::fmt::Debug for Redacted {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "<redacted, {} bytes>", self.0)
+            }
+        }
+
+        // Redact the will message and password
+        let will_message = self.will_message.as_ref().map(|bytes| Redacted(bytes.as_ref().len()));
+        let password = self.password.as_ref().map(|bytes| Redacted(bytes.as_ref().len()));
+        f.debug_struct("Connect")
+            .field("keep_alive_secs", &self.keep_alive_secs)
+            .field("clean_session", &self.clean_session)
+            .field("will_retain", &self.will_retain)
+            .field("will_qos", &self.will_qos)
+            .field("client_id", &self.client_id.as_ref())
+            .field("will_topic", &self.will_topic.as_ref().map(|bytes| bytes.as_ref()))
+            .field("will_message", &will_message)
+            .field("username", &self.username.as_ref().map(|bytes| bytes.as_ref()))
+            .field("password", &password)
+            .finish()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for Connect<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the length of the byte fields, which also redacts the will message and password
+        let len = |bytes: &Option<Bytes>| bytes.as_ref().map(|bytes| bytes.as_ref().len());
+        defmt::write!(
+            f,
+            "Connect {{ keep_alive_secs: {=u16}, clean_session: {=bool}, will_retain: {=bool}, will_qos: {=u8}, client_id_len: {=usize}, will_topic_len: {}, will_message_len: {}, username_len: {}, password_len: {} }}",
+            self.keep_alive_secs,
+            self.clean_session,
+            self.will_retain,
+            self.will_qos,
+            self.client_id.as_ref().len(),
+            len(&self.will_topic),
+            len(&self.will_message),
+            len(&self.username),
+            len(&self.password)
+        )
+    }
+}
+impl<'a> Connect<BytesRef<'a>> {
+    /// Creates a new encode-only packet that borrows the given client identifier
+    ///
+    /// # Note
+    /// No bytes are copied; the packet can be encoded as-is, but it cannot be decoded into (see [`Self::parse`] for
+    /// borrowed decoding).
+    pub fn new_ref(keep_alive_secs: u16, clean_session: bool, client_id: &'a [u8]) -> Result<Self, &'static str> {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&client_id)?;
+
+        // Init self
+        Ok(Self {
+            keep_alive_secs,
+            clean_session,
+            will_retain: false,
+            will_qos: 0,
+            client_id: BytesRef::new(client_id),
+            will_topic: None,
+            will_message: None,
+            username: None,
+            password: None,
+        })
+    }
+    /// Configures a borrowed last-will topic and message (see [`Connect::with_will`])
+    pub fn with_will_ref(
+        mut self,
+        topic: &'a [u8],
+        message: &'a [u8],
+        qos: u8,
+        retain: bool,
+    ) -> Result<Self, &'static str> {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&topic)?.try_bytes(&message)?;
+
+        // Configure last-will
+        self.will_topic = Some(BytesRef::new(topic));
+        self.will_message = Some(BytesRef::new(message));
+        self.will_retain = retain;
+        self.will_qos = qos;
+        Ok(self)
+    }
+    /// Configures a borrowed username and password (see [`Connect::with_username_password`])
+    pub fn with_username_password_ref(mut self, username: &'a [u8], password: &'a [u8]) -> Result<Self, &'static str> {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&username)?.try_bytes(&password)?;
+
+        // Configure login data
+        self.username = Some(BytesRef::new(username));
+        self.password = Some(BytesRef::new(password));
+        Ok(self)
+    }
+
+    /// Parses a borrowed view of a packet from the beginning of the given slice and returns the amount of consumed
+    /// bytes
+    ///
+    /// # Note
+    /// All byte fields are borrowed sub-slices of the given slice, so no bytes are copied. The amount of consumed bytes
+    /// is the full frame length (i.e. header, packet length field and body) of the packet.
+    pub fn parse(slice: &'a [u8]) -> Result<(Self, usize), DecoderError> {
+        // Read packet:
+        //  - header type and `0` flags
+        //  - packet len
+        let mut decoder = Decoder::new(slice.iter().copied());
+        let (type_, _flags) = decoder.header()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+        };
+        // Limit length to the frame
+        let len = decoder.packetlen()?;
+        let too_large = err!(SpecViolation, "Packet length is too large").with_offset(1);
+        let frame_len = decoder.position().checked_add(len).ok_or(too_large)?;
+        let frame = slice.get(..frame_len).ok_or(err!(Truncated, "Truncated input").with_offset(slice.len()))?;
+        let mut decoder = decoder.take_exact(len);
+        // Read protocol name byte-by-byte and version
+        decoder.constant(&Self::PROTOCOL_NAME).map_err(|e| e.context("protocol name"))?;
+        decoder.version_constant(Self::PROTOCOL_LEVEL_MQTT_3_1_1).map_err(|e| e.context("protocol level"))?;
+        // Read fields
+        let flags_offset = decoder.position();
+        let flags = decoder.u8().map_err(|e| e.context("connect flags"))?;
+        let flags = ConnectFlags::from_bits(flags).map_err(|e| e.with_offset(flags_offset).context("connect flags"))?;
+        let keep_alive_secs = decoder.u16().map_err(|e| e.context("keep alive"))?;
+        let client_id = decoder.bytes_ref(frame).map_err(|e| e.context("client identifier"))?;
+        let will_topic = decoder.optional_bytes_ref(flags.will, frame).map_err(|e| e.context("will topic"))?;
+        let will_message = decoder.optional_bytes_ref(flags.will, frame).map_err(|e| e.context("will message"))?;
+        let username = decoder.optional_bytes_ref(flags.username, frame).map_err(|e| e.context("user name"))?;
+        let password = decoder.optional_bytes_ref(flags.password, frame).map_err(|e| e.context("password"))?;
+        decoder.finish()?;
+
+        // Init self
+        let this = Self {
+            keep_alive_secs,
+            clean_session: flags.clean_session,
+            will_retain: flags.will_retain,
+            will_qos: flags.will_qos,
+            client_id,
+            will_topic,
+            will_message,
+            username,
+            password,
+        };
+        Ok((this, frame_len))
+    }
+
+    /// Copies the borrowed view into an owned packet
+    pub fn to_owned<Bytes>(&self) -> Result<Connect<Bytes>, &'static str>
+    where
+        Bytes: AnyVec<u8>,
+    {
+        /// Copies an optional field
+        fn copy<Bytes>(field: &Option<BytesRef>) -> Result<Option<Bytes>, &'static str>
+        where
+            Bytes: AnyVec<u8>,
+        {
+            field.as_ref().map(|bytes| Bytes::new(bytes.as_ref())).transpose()
+        }
+
+        // Copy fields
+        Ok(Connect {
+            keep_alive_secs: self.keep_alive_secs,
+            clean_session: self.clean_session,
+            will_retain: self.will_retain,
+            will_qos: self.will_qos,
+            client_id: Bytes::new(self.client_id.as_ref())?,
+            will_topic: copy(&self.will_topic)?,
+            will_message: copy(&self.will_message)?,
+            username: copy(&self.username)?,
+            password: copy(&self.password)?,
+        })
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, Bytes> arbitrary::Arbitrary<'a> for Connect<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::packets::arbitrary_bytes;
+
+        // Generate the mandatory fields
+        let keep_alive_secs = u.arbitrary()?;
+        let clean_session = u.arbitrary()?;
+        let client_id = arbitrary_bytes(u)?;
+        let mut this =
+            Self::new(keep_alive_secs, clean_session, client_id).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+
+        // Generate an optional last-will
+        if u.arbitrary()? {
+            let (topic, message) = (arbitrary_bytes(u)?, arbitrary_bytes(u)?);
+            let (qos, retain) = (u.int_in_range(0..=2)?, u.arbitrary()?);
+            this = this.with_will(topic, message, qos, retain).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+        // Generate optional login data
+        if u.arbitrary()? {
+            let (username, password) = (arbitrary_bytes(u)?, arbitrary_bytes(u)?);
+            this = this.with_username_password(username, password).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+        Ok(this)
+    }
+}
 impl<Bytes> TryFromIterator for Connect<Bytes>
 where
     Bytes: AnyVec<u8>,
 {
-    fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
     where
         T: IntoIterator<Item = u8>,
     {
@@ -169,35 +412,46 @@ where
         //  - username
         //  - password
         let mut decoder = Decoder::new(iter);
-        let (Self::TYPE, _flags) = decoder.header()? else {
-            return Err("Invalid packet type");
+        let (type_, _flags) = decoder.header()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
         };
         // Limit length
         let len = decoder.packetlen()?;
-        let mut decoder = decoder.limit(len);
+        let mut decoder = decoder.take_exact(len);
         // Read protocol name byte-by-byte and version
-        let Self::PROTOCOL_NAME = decoder.raw()? else {
-            return Err("Invalid protocol name");
-        };
-        let Self::PROTOCOL_LEVEL_MQTT_3_1_1 = decoder.u8()? else {
-            return Err("Invalid protocol version");
-        };
+        decoder.constant(&Self::PROTOCOL_NAME).map_err(|e| e.context("protocol name"))?;
+        decoder.version_constant(Self::PROTOCOL_LEVEL_MQTT_3_1_1).map_err(|e| e.context("protocol level"))?;
         // Read fields
-        let [f_user, f_pass, will_retain, will_qos0, will_qos1, f_will, clean_session, _] = decoder.bitmap()?;
-        let keep_alive_secs = decoder.u16()?;
-        let client_id = decoder.bytes()?;
-        let will_topic = decoder.optional_bytes(f_will)?;
-        let will_message = decoder.optional_bytes(f_will)?;
-        let username = decoder.optional_bytes(f_user)?;
-        let password = decoder.optional_bytes(f_pass)?;
+        let flags_offset = decoder.position();
+        let flags = decoder.u8().map_err(|e| e.context("connect flags"))?;
+        let flags = ConnectFlags::from_bits(flags).map_err(|e| e.with_offset(flags_offset).context("connect flags"))?;
+        let keep_alive_secs = decoder.u16().map_err(|e| e.context("keep alive"))?;
+        let client_id: Bytes = decoder.bytes().map_err(|e| Field::ClientId.tag(e).context("client identifier"))?;
+        let will_topic =
+            decoder.optional_bytes(flags.will).map_err(|e| Field::WillTopic.tag(e).context("will topic"))?;
+        let will_message =
+            decoder.optional_bytes(flags.will).map_err(|e| Field::WillMessage.tag(e).context("will message"))?;
+        let username =
+            decoder.optional_bytes(flags.username).map_err(|e| Field::Username.tag(e).context("user name"))?;
+        let password =
+            decoder.optional_bytes(flags.password).map_err(|e| Field::Password.tag(e).context("password"))?;
+        decoder.finish()?;
+        log_trace!(
+            "decoded CONNECT (client ID: {} bytes, keep-alive: {} s, will: {}, username: {}, password: {})",
+            client_id.as_ref().len(),
+            keep_alive_secs,
+            flags.will,
+            flags.username,
+            flags.password
+        );
 
         // Init self
-        let will_qos = ((will_qos0 as u8) << 1) | (will_qos1 as u8);
         Ok(Self {
             keep_alive_secs,
-            clean_session,
-            will_retain,
-            will_qos,
+            clean_session: flags.clean_session,
+            will_retain: flags.will_retain,
+            will_qos: flags.will_qos,
             client_id,
             will_topic,
             will_message,
@@ -208,19 +462,19 @@ where
 }
 impl<Bytes> IntoIterator for Connect<Bytes>
 where
-    Bytes: AnyVec<u8>,
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
 {
     type Item = u8;
     #[rustfmt::skip]
     type IntoIter =
         // Complex iterator built out of the individual message fields
-        Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<
+        SizedIter<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<
             // - header type and `0` flags
             Unit, U8Iter>,
             // - packet len
             PacketLenIter>,
             // - protocol name
-            <[u8; 6] as IntoIterator>::IntoIter>,
+            SliceIter<'static>>,
             // - protocol level
             U8Iter>,
             // - connect flags
@@ -236,20 +490,18 @@ where
             // - username
             OptionalBytesIter<Bytes>>,
             // - password
-            OptionalBytesIter<Bytes>>;
+            OptionalBytesIter<Bytes>>>;
 
     fn into_iter(self) -> Self::IntoIter {
         // Assemble protocol name and flags
-        let flags = [
-            self.username.is_some(),
-            self.password.is_some(),
-            self.will_retain,
-            (self.will_qos >> 1) != 0,
-            (self.will_qos & 1) != 0,
-            self.will_topic.is_some(),
-            self.clean_session,
-            false,
-        ];
+        let flags = ConnectFlags {
+            username: self.username.is_some(),
+            password: self.password.is_some(),
+            will_retain: self.will_retain,
+            will_qos: self.will_qos,
+            will: self.will_topic.is_some(),
+            clean_session: self.clean_session,
+        };
 
         // Precompute body length:
         //  - protocol name
@@ -264,7 +516,7 @@ where
         let len = Length::new()
             .raw(&Self::PROTOCOL_NAME)
             .u8(&Self::PROTOCOL_LEVEL_MQTT_3_1_1)
-            .bitmap(&flags)
+            .u8(&flags.to_bits())
             .u16(&self.keep_alive_secs)
             .bytes(&self.client_id)
             .optional_bytes(&self.will_topic)
@@ -286,17 +538,103 @@ where
         //  - username
         //  - password
         Encoder::default()
-            .header(Self::TYPE, [false, false, false, false])
+            .typed_header(PacketType::Connect, [false, false, false, false])
             .packetlen(len)
-            .raw(Self::PROTOCOL_NAME)
+            .raw_slice(&Self::PROTOCOL_NAME)
             .u8(Self::PROTOCOL_LEVEL_MQTT_3_1_1)
-            .bitmap(flags)
+            .u8(flags.to_bits())
             .u16(self.keep_alive_secs)
             .bytes(self.client_id)
             .optional_bytes(self.will_topic)
             .optional_bytes(self.will_message)
             .optional_bytes(self.username)
             .optional_bytes(self.password)
-            .into_iter()
+            .into_sized_iter(len)
     }
 }
+impl<'a, Bytes> IntoIterator for &'a Connect<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = <Connect<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Encode a borrowed view of the packet
+        let borrow = |bytes: &'a Option<Bytes>| bytes.as_ref().map(|bytes| BytesRef::new(bytes.as_ref()));
+        Connect {
+            keep_alive_secs: self.keep_alive_secs,
+            clean_session: self.clean_session,
+            will_retain: self.will_retain,
+            will_qos: self.will_qos,
+            client_id: BytesRef::new(self.client_id.as_ref()),
+            will_topic: borrow(&self.will_topic),
+            will_message: borrow(&self.will_message),
+            username: borrow(&self.username),
+            password: borrow(&self.password),
+        }
+        .into_iter()
+    }
+}
+
+/// Detects the protocol version of a connection from the start of its first packet, which must be a `CONNECT` packet
+/// (see [`detect_version_iter`])
+#[track_caller]
+pub fn detect_version(bytes: &[u8]) -> Result<ProtocolVersion, DecoderError> {
+    detect_version_iter(bytes.iter().copied())
+}
+
+/// Detects the protocol version of a connection from the start of its first packet, which must be a `CONNECT` packet
+///
+/// # Note
+/// Only the header, the packet length, the protocol name and the protocol level are read; pass the iterator by
+/// reference to keep the remaining bytes. If the input ends before the protocol level, a
+/// [`Decoding::Truncated`](crate::error::Decoding::Truncated) error is returned so that the caller can wait for more
+/// bytes. A valid protocol name with an unknown protocol level is reported as
+/// [`Decoding::UnsupportedVersion`](crate::error::Decoding::UnsupportedVersion).
+#[track_caller]
+pub fn detect_version_iter<T>(iter: T) -> Result<ProtocolVersion, DecoderError>
+where
+    T: IntoIterator<Item = u8>,
+{
+    // Read header and packet length
+    let mut decoder = Decoder::new(iter);
+    let (type_, _flags) = decoder.header()?;
+    let Connect::<()>::TYPE = type_ else {
+        return Err(err!(SpecViolation, "Invalid packet type", expected Connect::<()>::TYPE, found type_));
+    };
+    let len = decoder.packetlen()?;
+    let body_offset = decoder.position();
+
+    // Read the protocol name, which also determines the protocol family
+    let name_offset = decoder.position();
+    let name_len = decoder.u16().map_err(|e| e.context("protocol name"))?;
+    let family = match name_len {
+        4 => ProtocolVersion::V3_1_1,
+        6 => ProtocolVersion::V3_1,
+        _ => {
+            return Err(err!(SpecViolation, "Invalid protocol name").with_offset(name_offset).context("protocol name"))
+        }
+    };
+    decoder.constant(family.protocol_name()).map_err(|e| e.context("protocol name"))?;
+
+    // Read and classify the protocol level
+    let level_offset = decoder.position();
+    let level = decoder.u8().map_err(|e| e.context("protocol level"))?;
+    let version = match (family, level) {
+        (ProtocolVersion::V3_1, 3) => ProtocolVersion::V3_1,
+        (ProtocolVersion::V3_1_1, 4) => ProtocolVersion::V3_1_1,
+        (ProtocolVersion::V3_1_1, 5) => ProtocolVersion::V5,
+        _ => {
+            let error = err!(UnsupportedVersion, "Unsupported protocol version").with_offset(level_offset);
+            return Err(error.context("protocol level"));
+        }
+    };
+
+    // Ensure that the fields are within the packet
+    let body_len = decoder.position().saturating_sub(body_offset);
+    let true = body_len <= len else {
+        return Err(err!(SpecViolation, "Invalid packet length", expected body_len, found len).with_offset(1));
+    };
+    Ok(version)
+}
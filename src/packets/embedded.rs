@@ -0,0 +1,112 @@
+//! Blocking packet I/O over the [`embedded_io`] traits
+
+use crate::{
+    error::SourceOrDecode,
+    packets::{framing::FrameState, TryFromIterator},
+};
+use embedded_io::{Read, Write};
+
+/// Traits for elements that can be built from an [`embedded_io::Read`] reader
+pub trait TryFromEmbeddedReader
+where
+    Self: Sized,
+{
+    /// Tries to build `Self` from the given reader, using the caller-provided scratch buffer to buffer the packet frame
+    ///
+    /// # Note
+    /// The header and packet length are read byte-wise so that the reader is never advanced beyond the packet boundary;
+    /// the packet body is read in bulk afterwards. The full packet frame (i.e. header, packet length field and body)
+    /// must fit into the scratch buffer, otherwise the packet is rejected before the body is read.
+    fn try_read_embedded<T>(reader: T, scratch: &mut [u8]) -> Result<Self, SourceOrDecode<T::Error>>
+    where
+        T: Read;
+}
+impl<T> TryFromEmbeddedReader for T
+where
+    T: TryFromIterator,
+{
+    fn try_read_embedded<R>(mut reader: R, scratch: &mut [u8]) -> Result<Self, SourceOrDecode<R::Error>>
+    where
+        R: Read,
+    {
+        // Read the frame and try to build `Self` from it
+        let frame = read_frame(&mut reader, scratch)?;
+        Self::try_from_iter(frame.iter().copied()).map_err(SourceOrDecode::Decode)
+    }
+}
+
+/// Traits for elements that can be written to an [`embedded_io::Write`] writer
+pub trait ToEmbeddedWriter {
+    /// Writes `self` to the given writer
+    fn write_embedded<T>(self, writer: T) -> Result<(), T::Error>
+    where
+        T: Write;
+
+    /// Writes `self` to the given writer and returns the amount of written bytes
+    fn write_embedded_counted<T>(self, writer: T) -> Result<usize, T::Error>
+    where
+        T: Write;
+}
+impl<T> ToEmbeddedWriter for T
+where
+    T: IntoIterator<Item = u8>,
+{
+    fn write_embedded<W>(self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.write_embedded_counted(writer)?;
+        Ok(())
+    }
+
+    fn write_embedded_counted<W>(self, mut writer: W) -> Result<usize, W::Error>
+    where
+        W: Write,
+    {
+        // Drain the packet into a small stack chunk and write it chunk-wise for performance
+        let mut iter = self.into_iter();
+        let (mut chunk, mut written) = ([0; 128], 0usize);
+        loop {
+            // Fill the chunk; zipping the chunk first ensures that no byte is lost if the chunk is full
+            let len = chunk.iter_mut().zip(&mut iter).map(|(slot, byte)| *slot = byte).count();
+            writer.write_all(chunk.get(..len).unwrap_or_default())?;
+            written = written.saturating_add(len);
+            if len < chunk.len() {
+                // The packet has been written completely
+                break;
+            }
+        }
+        log_trace!("wrote packet ({} bytes)", written);
+
+        // Flush writer
+        writer.flush()?;
+        Ok(written)
+    }
+}
+
+/// Reads the next packet frame from the given reader into the scratch buffer (see [`FrameState`])
+///
+/// # Note
+/// If the reader ends before the frame is complete, the truncated frame is returned so that it is rejected during
+/// decoding.
+pub(crate) fn read_frame<'a, R>(reader: &mut R, scratch: &'a mut [u8]) -> Result<&'a [u8], SourceOrDecode<R::Error>>
+where
+    R: Read,
+{
+    // Read until the frame is complete
+    let mut state = FrameState::new(usize::MAX);
+    let mut position = 0usize;
+    while state.demand() > 0 {
+        // Get the demanded slice of the scratch buffer
+        let (end, capacity) = (position.saturating_add(state.demand()), scratch.len());
+        let buf = scratch.get_mut(position..end).ok_or(SourceOrDecode::Decode(
+            err!(Memory, "Scratch buffer is too small", expected end, found capacity).with_offset(position),
+        ))?;
+
+        // Read and record the next bytes
+        let read = reader.read(buf).map_err(SourceOrDecode::Source)?;
+        state.advance(buf.get(..read).unwrap_or_default()).map_err(SourceOrDecode::Decode)?;
+        position = position.saturating_add(read);
+    }
+    Ok(scratch.get(..position).unwrap_or_default())
+}
@@ -0,0 +1,155 @@
+//! Typed representations of packet flags
+
+use crate::error::DecoderError;
+
+/// The flags of a packet header (i.e. the lower nibble of the header byte)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HeaderFlags(u8);
+impl HeaderFlags {
+    /// Creates new header flags from the lower nibble of the given byte
+    pub const fn new(bits: u8) -> Self {
+        Self(bits & 0b1111)
+    }
+
+    /// Creates new header flags from the given bitmap (most-significant bit first)
+    pub const fn from_bitmap(bitmap: [bool; 4]) -> Self {
+        let [bit3, bit2, bit1, bit0] = bitmap;
+        Self(((bit3 as u8) << 3) | ((bit2 as u8) << 2) | ((bit1 as u8) << 1) | (bit0 as u8))
+    }
+
+    /// The flags as raw nibble
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+    /// The flags as bitmap (most-significant bit first)
+    pub const fn to_bitmap(&self) -> [bool; 4] {
+        [self.bit(3), self.bit(2), self.bit(1), self.bit(0)]
+    }
+
+    /// The flag at the given bit position, where `0` is the least-significant bit
+    ///
+    /// # Note
+    /// Positions greater than `3` are always `false`.
+    pub const fn bit(&self, position: u8) -> bool {
+        match position {
+            0..=3 => (self.0 >> position) & 1 != 0,
+            _ => false,
+        }
+    }
+
+    /// The `PUBLISH` duplicate delivery flag
+    pub const fn dup(&self) -> bool {
+        self.bit(3)
+    }
+    /// The `PUBLISH` QoS level
+    pub const fn qos(&self) -> u8 {
+        (self.0 >> 1) & 0b11
+    }
+    /// The `PUBLISH` retain flag
+    pub const fn retain(&self) -> bool {
+        self.bit(0)
+    }
+
+    /// Whether all flags are zero (as required by most packet types) or not
+    pub const fn is_reserved_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+impl From<[bool; 4]> for HeaderFlags {
+    fn from(value: [bool; 4]) -> Self {
+        Self::from_bitmap(value)
+    }
+}
+impl From<PublishFlags> for HeaderFlags {
+    fn from(value: PublishFlags) -> Self {
+        Self::new(value.to_bits())
+    }
+}
+
+/// The flags of an MQTT [`CONNECT`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718030)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConnectFlags {
+    /// Whether the packet contains a username or not
+    pub username: bool,
+    /// Whether the packet contains a password or not
+    pub password: bool,
+    /// Whether the last-will message should be retained
+    pub will_retain: bool,
+    /// The last-will QoS
+    pub will_qos: u8,
+    /// Whether the packet contains a last-will topic and message or not
+    pub will: bool,
+    /// Whether the session should be cleared or not
+    pub clean_session: bool,
+}
+impl ConnectFlags {
+    /// Decodes the flags from the connect flags byte, or fails if the reserved bit is set or the QoS is invalid
+    #[track_caller]
+    pub const fn from_bits(bits: u8) -> Result<Self, DecoderError> {
+        // Validate reserved bit and QoS
+        let will_qos = (bits >> 3) & 0b11;
+        match (bits & 0b0000_0001, will_qos) {
+            (0, 0..=2) => Ok(Self {
+                username: bits & 0b1000_0000 != 0,
+                password: bits & 0b0100_0000 != 0,
+                will_retain: bits & 0b0010_0000 != 0,
+                will_qos,
+                will: bits & 0b0000_0100 != 0,
+                clean_session: bits & 0b0000_0010 != 0,
+            }),
+            (0, _) => Err(err!(SpecViolation, "Invalid QoS level", rule "MQTT-3.1.2-14")),
+            _ => Err(err!(SpecViolation, "Invalid connect flags", rule "MQTT-3.1.2-3")),
+        }
+    }
+
+    /// Encodes the flags into the connect flags byte
+    pub const fn to_bits(&self) -> u8 {
+        ((self.username as u8) << 7)
+            | ((self.password as u8) << 6)
+            | ((self.will_retain as u8) << 5)
+            | ((self.will_qos & 0b11) << 3)
+            | ((self.will as u8) << 2)
+            | ((self.clean_session as u8) << 1)
+    }
+}
+
+/// The header flags of an MQTT [`PUBLISH`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718038)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PublishFlags {
+    /// Whether this packet is a redelivery or not
+    pub dup: bool,
+    /// The packet QoS
+    pub qos: u8,
+    /// Whether the message should be retained
+    pub retain: bool,
+}
+impl PublishFlags {
+    /// Decodes the flags from the lower nibble of the header byte, or fails if the upper nibble is set or the QoS is
+    /// invalid
+    #[track_caller]
+    pub const fn from_bits(bits: u8) -> Result<Self, DecoderError> {
+        // Validate nibble and QoS
+        let qos = (bits >> 1) & 0b11;
+        match (bits & 0b1111_0000, qos) {
+            (0, 0..=2) => Ok(Self { dup: bits & 0b1000 != 0, qos, retain: bits & 0b0001 != 0 }),
+            (0, _) => Err(err!(SpecViolation, "Invalid QoS level", rule "MQTT-3.3.1-4")),
+            _ => Err(err!(SpecViolation, "Invalid publish flags")),
+        }
+    }
+
+    /// Encodes the flags into the lower nibble of the header byte
+    pub const fn to_bits(&self) -> u8 {
+        ((self.dup as u8) << 3) | ((self.qos & 0b11) << 1) | (self.retain as u8)
+    }
+
+    /// The flags as bitmap as used by the header encoder
+    pub const fn to_bitmap(&self) -> [bool; 4] {
+        let bits = self.to_bits();
+        [bits & 0b1000 != 0, bits & 0b0100 != 0, bits & 0b0010 != 0, bits & 0b0001 != 0]
+    }
+}
@@ -0,0 +1,87 @@
+//! A sans-I/O state machine that tracks the packet boundary while a frame is read from a transport
+
+use crate::{coding::varint, error::DecoderError};
+
+/// The state of a packet frame that is read from a transport
+///
+/// # Note
+/// The header and packet length are demanded byte-wise so that the transport is never read beyond the packet boundary;
+/// the packet body is demanded in bulk afterwards. The I/O driver reads the demanded amount of bytes (or less) and
+/// records them via [`Self::advance`]; the frame is complete if the demand is zero.
+#[derive(Debug, Clone)]
+pub struct FrameState {
+    /// The maximum accepted packet length
+    max_packet_size: usize,
+    /// The header and packet length bytes
+    head: [u8; 5],
+    /// The amount of bytes read so far
+    read: usize,
+    /// The full frame length if the packet length has been read already
+    frame_len: Option<usize>,
+    /// Whether the transport has reached end-of-file or not
+    eof: bool,
+}
+impl FrameState {
+    /// Creates a new frame state that rejects packets with a packet length greater than `max_packet_size`
+    pub const fn new(max_packet_size: usize) -> Self {
+        Self { max_packet_size, head: [0; 5], read: 0, frame_len: None, eof: false }
+    }
+
+    /// The amount of bytes to read next, or `0` if the frame is complete or the transport has reached end-of-file
+    pub fn demand(&self) -> usize {
+        match (self.eof, self.frame_len) {
+            (true, _) => 0,
+            (false, Some(frame_len)) => frame_len.saturating_sub(self.read),
+            (false, None) => 1,
+        }
+    }
+
+    /// Records the bytes that have been read from the transport; an empty slice signals end-of-file
+    ///
+    /// # Note
+    /// If the packet length is invalid, the frame is considered complete so that it is rejected during decoding.
+    pub fn advance(&mut self, bytes: &[u8]) -> Result<(), DecoderError> {
+        // Record end-of-file
+        if bytes.is_empty() {
+            self.eof = true;
+            return Ok(());
+        }
+
+        // Record body bytes
+        if self.frame_len.is_some() {
+            self.read = self.read.saturating_add(bytes.len());
+            return Ok(());
+        }
+
+        // Record the header byte or next packet length byte
+        for byte in bytes {
+            let error = err!(SpecViolation, "Packet length is too large").with_offset(self.read);
+            let slot = self.head.get_mut(self.read).ok_or(error)?;
+            *slot = *byte;
+            self.read = self.read.saturating_add(1);
+        }
+        let Some(length_bytes @ [.., last]) = self.head.get(1..self.read) else {
+            // The header byte has been read only
+            return Ok(());
+        };
+        if last & 0b1000_0000 != 0 && self.read < self.head.len() {
+            // The packet length is incomplete
+            return Ok(());
+        }
+
+        // Compute the frame length
+        let Ok((len, _)) = varint::decode(length_bytes.iter().copied()) else {
+            // The packet length is invalid
+            self.frame_len = Some(self.read);
+            return Ok(());
+        };
+        if len > self.max_packet_size {
+            let error = err!(Memory, "Packet is too large", expected len, found self.max_packet_size);
+            return Err(error.with_offset(1));
+        }
+        let frame_len =
+            self.read.checked_add(len).ok_or(err!(SpecViolation, "Packet length is too large").with_offset(1))?;
+        self.frame_len = Some(frame_len);
+        Ok(())
+    }
+}
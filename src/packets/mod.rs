@@ -1,35 +1,432 @@
 //! MQTT packet types
 
+use crate::{
+    anyvec::AnyVec,
+    coding::Decoder,
+    error::{DecoderError, EncodeError, SourceOrDecode},
+    packets::framing::FrameState,
+};
+
 pub mod packet;
+#[cfg(feature = "futures-io")]
+pub mod asyncio;
+#[cfg(feature = "tokio-util")]
+pub mod codec;
 pub mod connack;
 pub mod connect;
+#[cfg(feature = "embedded-io")]
+pub mod embedded;
+#[cfg(feature = "embedded-io-async")]
+pub mod embedded_async;
+pub mod flags;
 pub mod publish;
+pub mod raw;
+pub mod stream;
 pub mod subscribe;
 pub mod unsubscribe;
+#[cfg(feature = "v5")]
+pub mod v5;
+mod framing;
 include!("_ack.rs");
 include!("_signal.rs");
 
+/// The completeness status of a buffered packet frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameStatus {
+    /// The buffer contains a complete packet frame
+    Complete {
+        /// The full frame length (i.e. header, packet length field and body) of the packet
+        total_len: usize,
+    },
+    /// The buffer does not contain a complete packet frame yet
+    Incomplete {
+        /// The amount of missing bytes, if the packet length field is complete already
+        needed_hint: Option<usize>,
+    },
+}
+
+/// Checks whether the given buffer starts with a complete packet frame by parsing the header and packet length only
+///
+/// # Note
+/// This function does not validate the packet body, so a complete frame may still fail to decode.
+pub fn check(buf: &[u8]) -> Result<FrameStatus, DecoderError> {
+    // The packet length ends with the first byte without continuation bit, and is at most 4 bytes long
+    let mut length_bytes = buf.iter().skip(1).take(4);
+    if !length_bytes.any(|byte| byte & 0b1000_0000 == 0) && buf.len() < 5 {
+        // The packet length is incomplete
+        return Ok(FrameStatus::Incomplete { needed_hint: None });
+    }
+
+    // Read the header and packet length to compute the frame length
+    let mut decoder = Decoder::new(buf.iter().copied());
+    let _header = decoder.header()?;
+    let len = decoder.packetlen()?;
+    let too_large = err!(SpecViolation, "Packet length is too large").with_offset(1);
+    let total_len = decoder.position().checked_add(len).ok_or(too_large)?;
+    match total_len.checked_sub(buf.len()) {
+        Some(needed @ 1..) => Ok(FrameStatus::Incomplete { needed_hint: Some(needed) }),
+        _ => Ok(FrameStatus::Complete { total_len }),
+    }
+}
+
+/// Encodes the given packet, decodes the encoded representation again and ensures that the decoded packet is equal to
+/// the original packet
+///
+/// # Note
+/// The packet is decoded directly from the encoding iterator, so no intermediate buffer is allocated. This is useful
+/// for fuzzing targets (see the `arbitrary` feature).
+pub fn roundtrip<T>(packet: &T) -> Result<(), DecoderError>
+where
+    T: TryFromIterator + PartialEq,
+    for<'a> &'a T: IntoIterator<Item = u8>,
+{
+    let decoded = T::try_from_iter(packet)?;
+    match decoded == *packet {
+        true => Ok(()),
+        false => Err(err!(SpecViolation, "Decoded packet does not match the original packet")),
+    }
+}
+
+/// Generates an arbitrary byte field that can be encoded as length-prefixed field (i.e. is at most `u16::MAX` bytes
+/// long)
+#[cfg(feature = "arbitrary")]
+pub(crate) fn arbitrary_bytes<'a>(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<&'a [u8]> {
+    let len = u.arbitrary_len::<u8>()?.min(usize::from(u16::MAX));
+    u.bytes(len)
+}
+
+/// Generates an arbitrary valid topic name, or topic filter with wildcards if `wildcards` is set
+#[cfg(feature = "arbitrary")]
+pub(crate) fn arbitrary_topic<T>(u: &mut arbitrary::Unstructured, wildcards: bool) -> arbitrary::Result<T>
+where
+    T: AnyVec<u8>,
+{
+    // Generate up to four levels of lowercase words or wildcards
+    let push = |topic: &mut T, byte: u8| topic.push(byte).map_err(|_| arbitrary::Error::IncorrectFormat);
+    let mut topic = T::default();
+    let levels = u.int_in_range(1..=4usize)?;
+    for level in 1..=levels {
+        match wildcards.then(|| u.int_in_range(0..=3u8)).transpose()? {
+            Some(2) => push(&mut topic, b'+')?,
+            Some(3) if level == levels => push(&mut topic, b'#')?,
+            _ => {
+                for _ in 0..u.int_in_range(1..=8usize)? {
+                    push(&mut topic, u.int_in_range(b'a'..=b'z')?)?;
+                }
+            }
+        }
+        if level < levels {
+            push(&mut topic, b'/')?;
+        }
+    }
+    Ok(topic)
+}
+
+/// Captures one complete packet frame (i.e. header, packet length field and body) from the given byte iterator without
+/// decoding it, or fails if the frame is longer than `max_len`
+///
+/// # Note
+/// Only the packet length encoding is validated; the iterator is never advanced beyond the packet boundary.
+pub fn raw_packet_from_iter<T, I>(iter: I, max_len: usize) -> Result<T, DecoderError>
+where
+    T: AnyVec<u8>,
+    I: IntoIterator<Item = u8>,
+{
+    // Read until the frame is complete
+    let mut iter = iter.into_iter();
+    let mut frame = T::default();
+    let mut state = FrameState::new(usize::MAX);
+    while state.demand() > 0 {
+        // Validate the frame length before reading further bytes
+        let needed = frame.as_ref().len().saturating_add(state.demand());
+        if needed > max_len {
+            return Err(err!(Memory, "Packet is too large", expected needed, found max_len).with_offset(1));
+        }
+
+        // Record and buffer the next byte
+        let offset = frame.as_ref().len();
+        let byte = iter.next().ok_or(err!(Truncated, "Truncated input").with_offset(offset))?;
+        state.advance(&[byte])?;
+        frame.push(byte).map_err(|e| DecoderError::container(&frame, e, offset.saturating_add(1)).with_offset(offset))?;
+    }
+
+    // Validate the packet length encoding
+    match check(frame.as_ref())? {
+        FrameStatus::Complete { total_len } if total_len == frame.as_ref().len() => Ok(frame),
+        _ => Err(err!(Truncated, "Truncated input").with_offset(frame.as_ref().len())),
+    }
+}
+
+/// Captures one complete packet frame (i.e. header, packet length field and body) from the given reader without
+/// decoding it, or fails if the frame is longer than `max_len` (see [`raw_packet_from_iter`])
+///
+/// # Note
+/// The header and packet length are read byte-wise so that the reader is never advanced beyond the packet boundary;
+/// the packet body is read in bulk afterwards. If the reader ends before the frame is complete, an
+/// [`std::io::ErrorKind::UnexpectedEof`] error is returned.
+#[cfg(feature = "std")]
+pub fn read_raw_packet<R, T>(reader: &mut R, max_len: usize) -> Result<T, std::io::Error>
+where
+    R: std::io::Read,
+    T: AnyVec<u8>,
+{
+    use std::io::{Error, ErrorKind};
+
+    // Read until the frame is complete
+    let mut frame = T::default();
+    let mut state = FrameState::new(usize::MAX);
+    let mut chunk = [0; 256];
+    while state.demand() > 0 {
+        // Validate the frame length before reading further bytes
+        let needed = frame.as_ref().len().saturating_add(state.demand());
+        if needed > max_len {
+            return Err(Error::from(err!(Memory, "Packet is too large", expected needed, found max_len).with_offset(1)));
+        }
+
+        // Read the next bytes
+        let len = state.demand().min(chunk.len());
+        let buf = chunk.get_mut(..len).unwrap_or_default();
+        let read = match reader.read(buf) {
+            Ok(read) => buf.get(..read).unwrap_or_default(),
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        // Record and buffer the read bytes
+        state.advance(read)?;
+        let offset = frame.as_ref().len();
+        let needed = offset.saturating_add(read.len());
+        frame.extend(read).map_err(|e| DecoderError::container(&frame, e, needed).with_offset(offset))?;
+    }
+
+    // Validate the packet length encoding
+    match check(frame.as_ref())? {
+        FrameStatus::Complete { total_len } if total_len == frame.as_ref().len() => Ok(frame),
+        _ => Err(Error::from(err!(Truncated, "Truncated input").with_offset(frame.as_ref().len()))),
+    }
+}
+
 /// Traits for elements that can be build from a byte iterator
 pub trait TryFromIterator
 where
     Self: Sized,
 {
     /// Tries to build `Self` from the given byte iterator
-    fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
     where
         T: IntoIterator<Item = u8>;
+
+    /// Tries to decode the given byte iterator into `self`, reusing the containers of `self` where possible
+    ///
+    /// # Note
+    /// The default implementation replaces `self` with a newly built value; packets with variable-length fields reuse
+    /// their existing containers instead. If an error occurs, the contents of `self` are unspecified.
+    fn try_from_iter_into<T>(&mut self, iter: T) -> Result<(), DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        *self = Self::try_from_iter(iter)?;
+        Ok(())
+    }
+
+    /// Tries to build `Self` from the given byte iterator, or fails if the packet length indicated by the packet header
+    /// is greater than `max_packet_size`
+    ///
+    /// # Note
+    /// The packet length is checked before any body bytes are read, so that oversized packets are rejected without
+    /// buffering them.
+    fn try_from_iter_with_limit<T>(iter: T, max_packet_size: usize) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read the header and packet length into a small stack buffer
+        let mut source = iter.into_iter();
+        let mut head = [0; 5];
+        let mut decoder = Decoder::new(head.iter_mut().map_while(|slot| {
+            // Copy the next byte from the source
+            *slot = source.next()?;
+            Some(*slot)
+        }));
+        let _header = decoder.header()?;
+        let len = decoder.packetlen()?;
+        let head_len = decoder.position();
+
+        // Validate the packet length and decode the packet
+        if len > max_packet_size {
+            return Err(err!(Memory, "Packet is too large", expected len, found max_packet_size).with_offset(1));
+        }
+        Self::try_from_iter(head.into_iter().take(head_len).chain(source))
+    }
+
+    /// Tries to build `Self` from the beginning of the given byte iterator and returns the iterator positioned after
+    /// the packet
+    ///
+    /// # Note
+    /// The decoder never reads beyond the packet length indicated by the packet header, so the returned iterator can be
+    /// used to decode the next packet.
+    fn try_from_iter_partial<I>(mut iter: I) -> Result<(Self, I), DecoderError>
+    where
+        I: Iterator<Item = u8>,
+    {
+        let this = Self::try_from_iter(&mut iter)?;
+        Ok((this, iter))
+    }
+
+    /// Tries to build `Self` from the given byte iterator and returns the amount of consumed bytes
+    ///
+    /// # Note
+    /// The amount of consumed bytes includes the header and packet length field.
+    fn try_from_iter_counted<T>(iter: T) -> Result<(Self, usize), DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Count each byte that is pulled from the source
+        let mut consumed = 0usize;
+        let counted = iter.into_iter().inspect(|_| consumed = consumed.saturating_add(1));
+        let this = Self::try_from_iter(counted)?;
+        Ok((this, consumed))
+    }
+
+    /// Tries to build `Self` from the beginning of the given byte slice and returns the amount of consumed bytes
+    ///
+    /// # Note
+    /// The amount of consumed bytes is the full frame length (i.e. header, packet length field and body) of the
+    /// packet, so that the remaining bytes start exactly at the next packet boundary.
+    fn try_from_slice(slice: &[u8]) -> Result<(Self, usize), DecoderError> {
+        // Read the header and packet length to compute the frame length
+        let FrameStatus::Complete { total_len: frame_len } = check(slice)? else {
+            return Err(err!(Truncated, "Truncated input").with_offset(slice.len()));
+        };
+
+        // Decode the frame
+        let frame = slice.get(..frame_len).ok_or(err!(Truncated, "Truncated input").with_offset(slice.len()))?;
+        let this = Self::try_from_iter(frame.iter().copied())?;
+        Ok((this, frame_len))
+    }
+}
+
+/// Traits for elements that can be built from a fallible byte iterator
+pub trait TryFromFallibleIterator
+where
+    Self: Sized,
+{
+    /// Tries to build `Self` from the given fallible byte iterator
+    ///
+    /// # Note
+    /// If the source yields an error, decoding stops and the source error is returned.
+    fn try_from_fallible_iter<T, E>(iter: T) -> Result<Self, SourceOrDecode<E>>
+    where
+        T: IntoIterator<Item = Result<u8, E>>;
+}
+impl<T> TryFromFallibleIterator for T
+where
+    T: TryFromIterator,
+{
+    fn try_from_fallible_iter<I, E>(iter: I) -> Result<Self, SourceOrDecode<E>>
+    where
+        I: IntoIterator<Item = Result<u8, E>>,
+    {
+        // Create a byte iterator from the fallible iterator
+        let mut last_error = None;
+        let iter = iter.into_iter()
+            // Retain a source error if any
+            .map(|result| result.map_err(|e| last_error = Some(e)))
+            // Yield bytes as long as there is not an error
+            .map_while(|result| result.ok());
+
+        // Try to build `Self` from iterator; a source error takes precedence as the input may be truncated
+        let result = Self::try_from_iter(iter);
+        match (result, last_error) {
+            (_, Some(e)) => Err(SourceOrDecode::Source(e)),
+            (Ok(value), None) => Ok(value),
+            (Err(e), None) => Err(SourceOrDecode::Decode(e)),
+        }
+    }
+}
+
+/// Traits for elements that can be written into a byte slice
+pub trait ToSlice {
+    /// Writes the encoded representation of `self` into the given buffer and returns the amount of written bytes
+    ///
+    /// # Note
+    /// If the buffer is too small, the error contains the required buffer length. The contents of the buffer are
+    /// unspecified in this case.
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Writes the encoded representation of `self` into a new `N`-byte array and returns the array together with the
+    /// amount of written bytes
+    ///
+    /// # Note
+    /// If the array is too small, the error contains the required array length (see [`Self::encode_into`]).
+    fn encode_into_array<const N: usize>(&self) -> Result<([u8; N], usize), EncodeError> {
+        let mut buf = [0; N];
+        let written = self.encode_into(&mut buf)?;
+        Ok((buf, written))
+    }
+}
+impl<T> ToSlice for T
+where
+    for<'a> &'a T: IntoIterator<Item = u8>,
+{
+    fn encode_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        // Write each byte into the next slot
+        let mut iter = self.into_iter();
+        let written = buf.iter_mut().zip(iter.by_ref()).map(|(slot, byte)| *slot = byte).count();
+
+        // Ensure that the entire packet has been written
+        match iter.count() {
+            0 => Ok(written),
+            excess => {
+                let required = written.saturating_add(excess);
+                Err(EncodeError::BufferTooSmall { required, capacity: buf.len() })
+            }
+        }
+    }
 }
 
 /// Traits for elements that can be built from a byte reader
+///
+/// # Note
+/// Decoding errors are converted into [`std::io::Error`]s whose kind depends on the [`crate::error::Decoding`] variant
+/// (see [`DecoderError`]).
 #[cfg(feature = "std")]
 pub trait TryFromReader
 where
     Self: Sized,
 {
-    /// Tries to build `Self` from the given byte iterator
+    /// Tries to build `Self` from the given byte reader
+    ///
+    /// # Note
+    /// The header and packet length are read byte-wise so that the reader is never advanced beyond the packet boundary;
+    /// the packet body is read in bulk afterwards.
     fn try_read<T>(reader: T) -> Result<Self, std::io::Error>
     where
         T: std::io::Read;
+
+    /// Tries to build `Self` from the given byte reader, or fails if the packet length indicated by the packet header
+    /// is greater than `max_packet_size` (see [`TryFromIterator::try_from_iter_with_limit`])
+    fn try_read_with_limit<T>(reader: T, max_packet_size: usize) -> Result<Self, std::io::Error>
+    where
+        T: std::io::Read;
+
+    /// Tries to build `Self` from the given byte reader, or returns `None` if the reader ends cleanly before the first
+    /// header byte (see [`Self::try_read`])
+    ///
+    /// # Note
+    /// If the reader ends after the first header byte, the packet is truncated and an error is returned.
+    fn try_read_opt<T>(reader: T) -> Result<Option<Self>, std::io::Error>
+    where
+        T: std::io::Read;
+
+    /// Tries to build `Self` from the given buffered byte reader
+    ///
+    /// # Note
+    /// Unlike [`Self::try_read`], the header and packet length are served from the reader buffer instead of individual
+    /// read calls. If the reader ends before the packet is complete, the same error as with [`Self::try_read`] is
+    /// returned.
+    fn try_read_buffered<T>(reader: T) -> Result<Self, std::io::Error>
+    where
+        T: std::io::BufRead;
 }
 #[cfg(feature = "std")]
 impl<T> TryFromReader for T
@@ -40,25 +437,116 @@ where
     where
         R: std::io::Read,
     {
-        use std::io::{Error, ErrorKind};
+        Self::try_read_with_limit(reader, usize::MAX)
+    }
 
-        // Create a byte iterator from the reader
-        let mut last_error = None;
-        let iter = reader.bytes()
-            // Retain an I/O error if any
-            .map(|result| result.map_err(|e| last_error = Some(e)))
-            // Yield bytes as long as there is not an error
-            .map_while(|result| result.ok());
+    fn try_read_with_limit<R>(mut reader: R, max_packet_size: usize) -> Result<Self, std::io::Error>
+    where
+        R: std::io::Read,
+    {
+        // Read the frame and try to build `Self` from it
+        let frame = read_frame(&mut reader, max_packet_size)?;
+        Ok(Self::try_from_iter(frame)?)
+    }
 
-        // Try to build `Self` from iterator
-        match (Self::try_from_iter(iter), last_error) {
-            (Ok(value), _) => Ok(value),
-            (Err(_), Some(e)) => Err(e),
-            (Err(e), _) => Err(Error::new(ErrorKind::InvalidData, e)),
+    fn try_read_opt<R>(mut reader: R) -> Result<Option<Self>, std::io::Error>
+    where
+        R: std::io::Read,
+    {
+        // Read the frame and check for a clean end-of-file
+        let frame = read_frame(&mut reader, usize::MAX)?;
+        if frame.is_empty() {
+            return Ok(None);
         }
+
+        // Try to build `Self` from the frame
+        let this = Self::try_from_iter(frame)?;
+        Ok(Some(this))
+    }
+
+    fn try_read_buffered<R>(mut reader: R) -> Result<Self, std::io::Error>
+    where
+        R: std::io::BufRead,
+    {
+        // Read the frame from the reader buffer and try to build `Self` from it
+        let frame = read_frame_buffered(&mut reader, usize::MAX)?;
+        Ok(Self::try_from_iter(frame)?)
     }
 }
 
+/// Reads the next packet frame from the given reader
+///
+/// # Note
+/// The header and packet length are read byte-wise to never read beyond the packet boundary, the body is read in bulk
+/// (see [`framing::FrameState`]). If the reader ends before the frame is complete, the truncated frame is returned so
+/// that it is rejected during decoding.
+#[cfg(feature = "std")]
+fn read_frame<R>(reader: &mut R, max_packet_size: usize) -> Result<std::vec::Vec<u8>, std::io::Error>
+where
+    R: std::io::Read,
+{
+    use std::io::ErrorKind;
+
+    // Read until the frame is complete
+    let mut frame = std::vec::Vec::new();
+    let mut state = FrameState::new(max_packet_size);
+    while state.demand() > 0 {
+        // Grow the frame by the demanded amount of bytes, but in limited steps to not trust the packet length blindly
+        let start = frame.len();
+        frame.resize(start.saturating_add(state.demand().min(8192)), 0);
+        let buf = frame.get_mut(start..).unwrap_or_default();
+
+        // Read the next bytes
+        let read = loop {
+            match reader.read(buf) {
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                result => break result?,
+            }
+        };
+
+        // Record the read bytes
+        frame.truncate(start.saturating_add(read));
+        let read = frame.get(start..).unwrap_or_default();
+        state.advance(read)?;
+    }
+    Ok(frame)
+}
+
+/// Reads the next packet frame from the given buffered reader
+///
+/// # Note
+/// The header, packet length and body are copied from the reader buffer and only the bytes of the frame are consumed.
+/// If the reader ends before the frame is complete, the truncated frame is returned so that it is rejected during
+/// decoding.
+#[cfg(feature = "std")]
+fn read_frame_buffered<R>(reader: &mut R, max_packet_size: usize) -> Result<std::vec::Vec<u8>, std::io::Error>
+where
+    R: std::io::BufRead,
+{
+    use std::io::ErrorKind;
+
+    // Read until the frame is complete
+    let mut frame = std::vec::Vec::new();
+    let mut state = FrameState::new(max_packet_size);
+    while state.demand() > 0 {
+        // Fill the reader buffer
+        let buf = loop {
+            match reader.fill_buf() {
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                result => break result?,
+            }
+        };
+
+        // Copy and consume the demanded bytes from the reader buffer
+        let start = frame.len();
+        frame.extend_from_slice(buf.get(..state.demand()).unwrap_or(buf));
+        let read = frame.get(start..).unwrap_or_default();
+        state.advance(read)?;
+        reader.consume(read.len());
+    }
+    Ok(frame)
+}
+
 /// Traits for elements that can be written to a byte writer
 #[cfg(feature = "std")]
 pub trait ToWriter {
@@ -66,6 +554,11 @@ pub trait ToWriter {
     fn write<T>(self, writer: T) -> Result<(), std::io::Error>
     where
         T: std::io::Write;
+
+    /// Writes `self` to the given byte writer and returns the amount of written bytes
+    fn write_counted<T>(self, writer: T) -> Result<usize, std::io::Error>
+    where
+        T: std::io::Write;
 }
 #[cfg(feature = "std")]
 impl<T> ToWriter for T
@@ -76,16 +569,31 @@ where
     where
         W: std::io::Write,
     {
-        use std::io::{BufWriter, Write};
+        self.write_counted(writer)?;
+        Ok(())
+    }
 
-        // Write each byte in a buffered way for performance
-        let mut writer = BufWriter::new(writer);
-        for byte in self {
-            // Write byte
-            writer.write_all(&[byte])?;
+    fn write_counted<W>(self, mut writer: W) -> Result<usize, std::io::Error>
+    where
+        W: std::io::Write,
+    {
+        // Drain the packet into a small stack chunk and write it chunk-wise for performance
+        let mut iter = self.into_iter();
+        let (mut chunk, mut written) = ([0; 128], 0usize);
+        loop {
+            // Fill the chunk; zipping the chunk first ensures that no byte is lost if the chunk is full
+            let len = chunk.iter_mut().zip(&mut iter).map(|(slot, byte)| *slot = byte).count();
+            writer.write_all(chunk.get(..len).unwrap_or_default())?;
+            written = written.saturating_add(len);
+            if len < chunk.len() {
+                // The packet has been written completely
+                break;
+            }
         }
+        log_trace!("wrote packet ({} bytes)", written);
 
-        // Flush buffer
-        writer.flush()
+        // Flush writer
+        writer.flush()?;
+        Ok(written)
     }
 }
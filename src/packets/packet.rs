@@ -2,15 +2,164 @@
 
 use crate::{
     anyvec::AnyVec,
+    coding::Decoder,
+    error::DecoderError,
     packets::{
         connack::Connack, connect::Connect, disconnect::Disconnect, pingreq::Pingreq, pingresp::Pingresp,
-        puback::Puback, pubcomp::Pubcomp, publish::Publish, pubrec::Pubrec, pubrel::Pubrel, suback::Suback,
+        puback::Puback, pubcomp::Pubcomp, publish::Publish, pubrec::Pubrec, pubrel::Pubrel, raw::Raw, suback::Suback,
         subscribe::Subscribe, unsuback::Unsuback, unsubscribe::Unsubscribe, TryFromIterator,
     },
 };
+use core::{
+    cell::Cell,
+    fmt::{self, Debug, Display, Formatter},
+    iter::FusedIterator,
+};
+
+/// An MQTT packet type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum PacketType {
+    /// A [`Connack`] packet
+    Connack = 2,
+    /// A [`Connect`] packet
+    Connect = 1,
+    /// A [`Disconnect`] packet
+    Disconnect = 14,
+    /// A [`Pingreq`] packet
+    Pingreq = 12,
+    /// A [`Pingresp`] packet
+    Pingresp = 13,
+    /// A [`Puback`] packet
+    Puback = 4,
+    /// A [`Pubcomp`] packet
+    Pubcomp = 7,
+    /// A [`Publish`] packet
+    Publish = 3,
+    /// A [`Pubrec`] packet
+    Pubrec = 5,
+    /// A [`Pubrel`] packet
+    Pubrel = 6,
+    /// A [`Suback`] packet
+    Suback = 9,
+    /// A [`Subscribe`] packet
+    Subscribe = 8,
+    /// A [`Unsuback`] packet
+    Unsuback = 11,
+    /// A [`Unsubscribe`] packet
+    Unsubscribe = 10,
+}
+impl PacketType {
+    /// All packet types
+    pub const ALL: [Self; 14] = [
+        Self::Connack,
+        Self::Connect,
+        Self::Disconnect,
+        Self::Pingreq,
+        Self::Pingresp,
+        Self::Puback,
+        Self::Pubcomp,
+        Self::Publish,
+        Self::Pubrec,
+        Self::Pubrel,
+        Self::Suback,
+        Self::Subscribe,
+        Self::Unsuback,
+        Self::Unsubscribe,
+    ];
+
+    /// The canonical upper-case packet name (e.g. `"CONNECT"`)
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Connack => "CONNACK",
+            Self::Connect => "CONNECT",
+            Self::Disconnect => "DISCONNECT",
+            Self::Pingreq => "PINGREQ",
+            Self::Pingresp => "PINGRESP",
+            Self::Puback => "PUBACK",
+            Self::Pubcomp => "PUBCOMP",
+            Self::Publish => "PUBLISH",
+            Self::Pubrec => "PUBREC",
+            Self::Pubrel => "PUBREL",
+            Self::Suback => "SUBACK",
+            Self::Subscribe => "SUBSCRIBE",
+            Self::Unsuback => "UNSUBACK",
+            Self::Unsubscribe => "UNSUBSCRIBE",
+        }
+    }
+}
+impl TryFrom<u8> for PacketType {
+    type Error = DecoderError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        // Find the matching type
+        for type_ in Self::ALL {
+            // Compare the type constant
+            if type_ as u8 == value {
+                return Ok(type_);
+            }
+        }
+        Err(err!(UnknownPacketType, "Unknown packet type"))
+    }
+}
+impl From<PacketType> for u8 {
+    fn from(value: PacketType) -> Self {
+        value as u8
+    }
+}
+impl Display for PacketType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An MQTT protocol version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ProtocolVersion {
+    /// MQTT 3.1 (protocol name `MQIsdp`, protocol level `3`)
+    V3_1,
+    /// MQTT 3.1.1 (protocol name `MQTT`, protocol level `4`)
+    V3_1_1,
+    /// MQTT 5 (protocol name `MQTT`, protocol level `5`)
+    V5,
+}
+impl ProtocolVersion {
+    /// The protocol name as used in the `CONNECT` packet
+    pub const fn protocol_name(&self) -> &'static [u8] {
+        match self {
+            Self::V3_1 => b"MQIsdp",
+            Self::V3_1_1 | Self::V5 => b"MQTT",
+        }
+    }
+
+    /// The protocol level as used in the `CONNECT` packet
+    pub const fn level(&self) -> u8 {
+        match self {
+            Self::V3_1 => 3,
+            Self::V3_1_1 => 4,
+            Self::V5 => 5,
+        }
+    }
+
+    /// The human readable version (e.g. `"3.1.1"`)
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::V3_1 => "3.1",
+            Self::V3_1_1 => "3.1.1",
+            Self::V5 => "5",
+        }
+    }
+}
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
 /// A type-erased MQTT packet
-#[derive(Debug, Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub enum Packet<TopicsSeq, TopicsQosSeq, Bytes> {
     /// An [`Connack`] packet
     Connack(Connack),
@@ -32,6 +181,8 @@ pub enum Packet<TopicsSeq, TopicsQosSeq, Bytes> {
     Pubrec(Pubrec),
     /// An [`Pubrel`] packet
     Pubrel(Pubrel),
+    /// A [`Raw`] packet of a type that is not modelled by this crate (see [`Packet::try_from_iter_lenient`])
+    Raw(Raw<Bytes>),
     /// An [`Suback`] packet
     Suback(Suback),
     /// An [`Subscribe`] packet
@@ -41,22 +192,198 @@ pub enum Packet<TopicsSeq, TopicsQosSeq, Bytes> {
     /// An [`Unsubscribe`] packet
     Unsubscribe(Unsubscribe<TopicsSeq, Bytes>),
 }
+impl<TopicsSeq, TopicsQosSeq, Bytes> Packet<TopicsSeq, TopicsQosSeq, Bytes> {
+    /// The packet type, or `None` for [`Raw`] packets
+    pub const fn packet_type(&self) -> Option<PacketType> {
+        match self {
+            Self::Connack(_) => Some(PacketType::Connack),
+            Self::Connect(_) => Some(PacketType::Connect),
+            Self::Disconnect(_) => Some(PacketType::Disconnect),
+            Self::Pingreq(_) => Some(PacketType::Pingreq),
+            Self::Pingresp(_) => Some(PacketType::Pingresp),
+            Self::Puback(_) => Some(PacketType::Puback),
+            Self::Pubcomp(_) => Some(PacketType::Pubcomp),
+            Self::Publish(_) => Some(PacketType::Publish),
+            Self::Pubrec(_) => Some(PacketType::Pubrec),
+            Self::Pubrel(_) => Some(PacketType::Pubrel),
+            Self::Raw(_) => None,
+            Self::Suback(_) => Some(PacketType::Suback),
+            Self::Subscribe(_) => Some(PacketType::Subscribe),
+            Self::Unsuback(_) => Some(PacketType::Unsuback),
+            Self::Unsubscribe(_) => Some(PacketType::Unsubscribe),
+        }
+    }
+
+    /// The canonical upper-case packet name (e.g. `"CONNECT"`), or `"RAW"` for [`Raw`] packets
+    pub const fn name(&self) -> &'static str {
+        match self.packet_type() {
+            Some(type_) => type_.name(),
+            None => "RAW",
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Packet<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    /// Encodes the packet into a new vector, or fails if the vector cannot hold the encoded packet
+    pub fn to_bytes<T>(&self) -> Result<T, &'static str>
+    where
+        T: AnyVec<u8>,
+    {
+        // Push each byte so that capacity exhaustion is surfaced as error
+        let mut bytes = T::default();
+        for byte in self {
+            // Try to append byte
+            bytes.push(byte)?;
+        }
+        Ok(bytes)
+    }
+}
+#[cfg(feature = "std")]
+impl<TopicsSeq, TopicsQosSeq, Bytes> Packet<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    /// Creates a [`std::io::Read`] adapter that serves the encoded representation of the packet
+    pub fn into_reader(self) -> crate::packets::stream::PacketReader<PacketIter<TopicsSeq, TopicsQosSeq, Bytes>> {
+        crate::packets::stream::PacketReader::new(self)
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Debug for Packet<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    Connect<Bytes>: Debug,
+    Publish<Bytes>: Debug,
+    Raw<Bytes>: Debug,
+    Subscribe<TopicsQosSeq, Bytes>: Debug,
+    Unsubscribe<TopicsSeq, Bytes>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Use a manual implementation so that the bounds are inherited from the packets (see [`Connect`])
+        match self {
+            Self::Connack(this) => f.debug_tuple("Connack").field(this).finish(),
+            Self::Connect(this) => f.debug_tuple("Connect").field(this).finish(),
+            Self::Disconnect(this) => f.debug_tuple("Disconnect").field(this).finish(),
+            Self::Pingreq(this) => f.debug_tuple("Pingreq").field(this).finish(),
+            Self::Pingresp(this) => f.debug_tuple("Pingresp").field(this).finish(),
+            Self::Puback(this) => f.debug_tuple("Puback").field(this).finish(),
+            Self::Pubcomp(this) => f.debug_tuple("Pubcomp").field(this).finish(),
+            Self::Publish(this) => f.debug_tuple("Publish").field(this).finish(),
+            Self::Pubrec(this) => f.debug_tuple("Pubrec").field(this).finish(),
+            Self::Pubrel(this) => f.debug_tuple("Pubrel").field(this).finish(),
+            Self::Raw(this) => f.debug_tuple("Raw").field(this).finish(),
+            Self::Suback(this) => f.debug_tuple("Suback").field(this).finish(),
+            Self::Subscribe(this) => f.debug_tuple("Subscribe").field(this).finish(),
+            Self::Unsuback(this) => f.debug_tuple("Unsuback").field(this).finish(),
+            Self::Unsubscribe(this) => f.debug_tuple("Unsubscribe").field(this).finish(),
+        }
+    }
+}
+#[cfg(feature = "defmt")]
+impl<TopicsSeq, TopicsQosSeq, Bytes> defmt::Format for Packet<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    Connect<Bytes>: defmt::Format,
+    Publish<Bytes>: defmt::Format,
+    Raw<Bytes>: defmt::Format,
+    Subscribe<TopicsQosSeq, Bytes>: defmt::Format,
+    Unsubscribe<TopicsSeq, Bytes>: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Delegate to the packets so that the summaries are consistent
+        match self {
+            Self::Connack(this) => defmt::write!(f, "{}", this),
+            Self::Connect(this) => defmt::write!(f, "{}", this),
+            Self::Disconnect(this) => defmt::write!(f, "{}", this),
+            Self::Pingreq(this) => defmt::write!(f, "{}", this),
+            Self::Pingresp(this) => defmt::write!(f, "{}", this),
+            Self::Puback(this) => defmt::write!(f, "{}", this),
+            Self::Pubcomp(this) => defmt::write!(f, "{}", this),
+            Self::Publish(this) => defmt::write!(f, "{}", this),
+            Self::Pubrec(this) => defmt::write!(f, "{}", this),
+            Self::Pubrel(this) => defmt::write!(f, "{}", this),
+            Self::Raw(this) => defmt::write!(f, "{}", this),
+            Self::Suback(this) => defmt::write!(f, "{}", this),
+            Self::Subscribe(this) => defmt::write!(f, "{}", this),
+            Self::Unsuback(this) => defmt::write!(f, "{}", this),
+            Self::Unsubscribe(this) => defmt::write!(f, "{}", this),
+        }
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, TopicsSeq, TopicsQosSeq, Bytes> arbitrary::Arbitrary<'a> for Packet<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Generate a packet of a known type; raw packets are not generated since they do not round-trip strictly
+        match u.int_in_range(0..=13)? {
+            0 => u.arbitrary().map(Self::Connack),
+            1 => u.arbitrary().map(Self::Connect),
+            2 => u.arbitrary().map(Self::Disconnect),
+            3 => u.arbitrary().map(Self::Pingreq),
+            4 => u.arbitrary().map(Self::Pingresp),
+            5 => u.arbitrary().map(Self::Puback),
+            6 => u.arbitrary().map(Self::Pubcomp),
+            7 => u.arbitrary().map(Self::Publish),
+            8 => u.arbitrary().map(Self::Pubrec),
+            9 => u.arbitrary().map(Self::Pubrel),
+            10 => u.arbitrary().map(Self::Suback),
+            11 => u.arbitrary().map(Self::Subscribe),
+            12 => u.arbitrary().map(Self::Unsuback),
+            _ => u.arbitrary().map(Self::Unsubscribe),
+        }
+    }
+}
 impl<TopicsSeq, TopicsQosSeq, Bytes> TryFromIterator for Packet<TopicsSeq, TopicsQosSeq, Bytes>
 where
     TopicsSeq: AnyVec<Bytes>,
     TopicsQosSeq: AnyVec<(Bytes, u8)>,
     Bytes: AnyVec<u8>,
 {
-    fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        Self::try_from_iter_with(iter, false)
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Packet<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    /// Tries to build `Self` from the given byte iterator, decoding packets of unknown types as [`Raw`] packets instead
+    /// of failing
+    ///
+    /// # Note
+    /// Known packet types are still decoded strictly; use [`TryFromIterator::try_from_iter`] to reject unknown types.
+    pub fn try_from_iter_lenient<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        Self::try_from_iter_with(iter, true)
+    }
+
+    /// Tries to build `Self` from the given byte iterator, optionally decoding unknown types as [`Raw`] packets
+    fn try_from_iter_with<T>(iter: T, lenient: bool) -> Result<Self, DecoderError>
     where
         T: IntoIterator<Item = u8>,
     {
         // We have to peek at the header to determine the type
-        let mut decoder = iter.into_iter().peekable();
-        let header = decoder.next().ok_or("Empty packet")?;
+        let mut decoder = Decoder::new(iter).peekable();
+        let (type_, _) = decoder.peek_header()?;
+        let consumed = Cell::new(0usize);
+        let mut decoder = decoder.into_iter().inspect(|_| consumed.set(consumed.get().saturating_add(1)));
 
         // Select the appropriate packet depending on the type
-        match header >> 4 {
+        let packet = match type_ {
             Connack::TYPE => Connack::try_from_iter(&mut decoder).map(Self::Connack),
             Connect::<Bytes>::TYPE => Connect::try_from_iter(&mut decoder).map(Self::Connect),
             Disconnect::TYPE => Disconnect::try_from_iter(&mut decoder).map(Self::Disconnect),
@@ -71,8 +398,16 @@ where
             Subscribe::<TopicsQosSeq, Bytes>::TYPE => Subscribe::try_from_iter(&mut decoder).map(Self::Subscribe),
             Unsuback::TYPE => Unsuback::try_from_iter(&mut decoder).map(Self::Unsuback),
             Unsubscribe::<TopicsSeq, Bytes>::TYPE => Unsubscribe::try_from_iter(&mut decoder).map(Self::Unsubscribe),
-            _ => Err("Unknown packet type"),
+            _ if lenient => Raw::try_from_iter(&mut decoder).map(Self::Raw),
+            _ => Err(err!(UnknownPacketType, "Unknown packet type")),
+        };
+
+        // Log the result
+        match &packet {
+            Ok(packet) => log_trace!("decoded {} packet ({} bytes)", packet.name(), consumed.get()),
+            Err(e) => log_debug!("failed to decode packet: {}", e),
         }
+        packet
     }
 }
 impl<TopicsSeq, TopicsQosSeq, Bytes> IntoIterator for Packet<TopicsSeq, TopicsQosSeq, Bytes>
@@ -96,6 +431,7 @@ where
             Self::Publish(this) => PacketIter::Publish(this.into_iter()),
             Self::Pubrec(this) => PacketIter::Pubreq(this.into_iter()),
             Self::Pubrel(this) => PacketIter::Pubrel(this.into_iter()),
+            Self::Raw(this) => PacketIter::Raw(this.into_iter()),
             Self::Suback(this) => PacketIter::Suback(this.into_iter()),
             Self::Subscribe(this) => PacketIter::Subscribe(this.into_iter()),
             Self::Unsuback(this) => PacketIter::Unsuback(this.into_iter()),
@@ -104,6 +440,36 @@ where
     }
 }
 
+impl<'a, TopicsSeq, TopicsQosSeq, Bytes> IntoIterator for &'a Packet<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = PacketRefIter<'a, TopicsSeq, TopicsQosSeq, Bytes>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Packet::Connack(this) => PacketRefIter::Connack(this.into_iter()),
+            Packet::Connect(this) => PacketRefIter::Connect(this.into_iter()),
+            Packet::Disconnect(this) => PacketRefIter::Disconnect(this.into_iter()),
+            Packet::Pingreq(this) => PacketRefIter::Pingreq(this.into_iter()),
+            Packet::Pingresp(this) => PacketRefIter::Pingresp(this.into_iter()),
+            Packet::Puback(this) => PacketRefIter::Puback(this.into_iter()),
+            Packet::Pubcomp(this) => PacketRefIter::Pubcomp(this.into_iter()),
+            Packet::Publish(this) => PacketRefIter::Publish(this.into_iter()),
+            Packet::Pubrec(this) => PacketRefIter::Pubrec(this.into_iter()),
+            Packet::Pubrel(this) => PacketRefIter::Pubrel(this.into_iter()),
+            Packet::Raw(this) => PacketRefIter::Raw(this.into_iter()),
+            Packet::Suback(this) => PacketRefIter::Suback(this.into_iter()),
+            Packet::Subscribe(this) => PacketRefIter::Subscribe(this.into_iter()),
+            Packet::Unsuback(this) => PacketRefIter::Unsuback(this.into_iter()),
+            Packet::Unsubscribe(this) => PacketRefIter::Unsubscribe(this.into_iter()),
+        }
+    }
+}
+
 /// A packet-type-erased iterator over the encoded representation
 pub enum PacketIter<TopicsSeq, TopicsQosSeq, Bytes>
 where
@@ -131,6 +497,8 @@ where
     Pubreq(<Pubrec as IntoIterator>::IntoIter),
     /// An [`Pubrel`] packet iterator
     Pubrel(<Pubrel as IntoIterator>::IntoIter),
+    /// A [`Raw`] packet iterator
+    Raw(<Raw<Bytes> as IntoIterator>::IntoIter),
     /// An [`Suback`] packet iterator
     Suback(<Suback as IntoIterator>::IntoIter),
     /// An [`Subscribe`] packet iterator
@@ -140,6 +508,35 @@ where
     /// An [`Unsubscribe`] packet iterator
     Unsubscribe(<Unsubscribe<TopicsSeq, Bytes> as IntoIterator>::IntoIter),
 }
+impl<TopicsSeq, TopicsQosSeq, Bytes> Clone for PacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+    <TopicsSeq as IntoIterator>::IntoIter: Clone,
+    <TopicsQosSeq as IntoIterator>::IntoIter: Clone,
+    <Bytes as IntoIterator>::IntoIter: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Connack(iter) => Self::Connack(iter.clone()),
+            Self::Connect(iter) => Self::Connect(iter.clone()),
+            Self::Disconnect(iter) => Self::Disconnect(iter.clone()),
+            Self::Pingreq(iter) => Self::Pingreq(iter.clone()),
+            Self::Pingresp(iter) => Self::Pingresp(iter.clone()),
+            Self::Puback(iter) => Self::Puback(iter.clone()),
+            Self::Pubcomp(iter) => Self::Pubcomp(iter.clone()),
+            Self::Publish(iter) => Self::Publish(iter.clone()),
+            Self::Pubreq(iter) => Self::Pubreq(iter.clone()),
+            Self::Pubrel(iter) => Self::Pubrel(iter.clone()),
+            Self::Raw(iter) => Self::Raw(iter.clone()),
+            Self::Suback(iter) => Self::Suback(iter.clone()),
+            Self::Subscribe(iter) => Self::Subscribe(iter.clone()),
+            Self::Unsuback(iter) => Self::Unsuback(iter.clone()),
+            Self::Unsubscribe(iter) => Self::Unsubscribe(iter.clone()),
+        }
+    }
+}
 impl<TopicsSeq, TopicsQosSeq, Bytes> Iterator for PacketIter<TopicsSeq, TopicsQosSeq, Bytes>
 where
     TopicsSeq: AnyVec<Bytes>,
@@ -160,10 +557,176 @@ where
             Self::Publish(iter) => iter.next(),
             Self::Pubreq(iter) => iter.next(),
             Self::Pubrel(iter) => iter.next(),
+            Self::Raw(iter) => iter.next(),
+            Self::Suback(iter) => iter.next(),
+            Self::Subscribe(iter) => iter.next(),
+            Self::Unsuback(iter) => iter.next(),
+            Self::Unsubscribe(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Connack(iter) => iter.size_hint(),
+            Self::Connect(iter) => iter.size_hint(),
+            Self::Disconnect(iter) => iter.size_hint(),
+            Self::Pingreq(iter) => iter.size_hint(),
+            Self::Pingresp(iter) => iter.size_hint(),
+            Self::Puback(iter) => iter.size_hint(),
+            Self::Pubcomp(iter) => iter.size_hint(),
+            Self::Publish(iter) => iter.size_hint(),
+            Self::Pubreq(iter) => iter.size_hint(),
+            Self::Pubrel(iter) => iter.size_hint(),
+            Self::Raw(iter) => iter.size_hint(),
+            Self::Suback(iter) => iter.size_hint(),
+            Self::Subscribe(iter) => iter.size_hint(),
+            Self::Unsuback(iter) => iter.size_hint(),
+            Self::Unsubscribe(iter) => iter.size_hint(),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> ExactSizeIterator for PacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    // No members to implement
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> FusedIterator for PacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    // No members to implement
+}
+
+/// A packet-type-erased iterator over the encoded representation of a borrowed packet
+pub enum PacketRefIter<'a, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    /// An [`Connack`] packet iterator
+    Connack(<&'a Connack as IntoIterator>::IntoIter),
+    /// An [`Connect`] packet iterator
+    Connect(<&'a Connect<Bytes> as IntoIterator>::IntoIter),
+    /// An [`Disconnect`] packet iterator
+    Disconnect(<&'a Disconnect as IntoIterator>::IntoIter),
+    /// An [`Pingreq`] packet iterator
+    Pingreq(<&'a Pingreq as IntoIterator>::IntoIter),
+    /// An [`Pingresp`] packet iterator
+    Pingresp(<&'a Pingresp as IntoIterator>::IntoIter),
+    /// An [`Puback`] packet iterator
+    Puback(<&'a Puback as IntoIterator>::IntoIter),
+    /// An [`Pubcomp`] packet iterator
+    Pubcomp(<&'a Pubcomp as IntoIterator>::IntoIter),
+    /// An [`Publish`] packet iterator
+    Publish(<&'a Publish<Bytes> as IntoIterator>::IntoIter),
+    /// An [`Pubrec`] packet iterator
+    Pubrec(<&'a Pubrec as IntoIterator>::IntoIter),
+    /// An [`Pubrel`] packet iterator
+    Pubrel(<&'a Pubrel as IntoIterator>::IntoIter),
+    /// A [`Raw`] packet iterator
+    Raw(<&'a Raw<Bytes> as IntoIterator>::IntoIter),
+    /// An [`Suback`] packet iterator
+    Suback(<&'a Suback as IntoIterator>::IntoIter),
+    /// An [`Subscribe`] packet iterator
+    Subscribe(<&'a Subscribe<TopicsQosSeq, Bytes> as IntoIterator>::IntoIter),
+    /// An [`Unsuback`] packet iterator
+    Unsuback(<&'a Unsuback as IntoIterator>::IntoIter),
+    /// An [`Unsubscribe`] packet iterator
+    Unsubscribe(<&'a Unsubscribe<TopicsSeq, Bytes> as IntoIterator>::IntoIter),
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Clone for PacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Connack(iter) => Self::Connack(iter.clone()),
+            Self::Connect(iter) => Self::Connect(iter.clone()),
+            Self::Disconnect(iter) => Self::Disconnect(iter.clone()),
+            Self::Pingreq(iter) => Self::Pingreq(iter.clone()),
+            Self::Pingresp(iter) => Self::Pingresp(iter.clone()),
+            Self::Puback(iter) => Self::Puback(iter.clone()),
+            Self::Pubcomp(iter) => Self::Pubcomp(iter.clone()),
+            Self::Publish(iter) => Self::Publish(iter.clone()),
+            Self::Pubrec(iter) => Self::Pubrec(iter.clone()),
+            Self::Pubrel(iter) => Self::Pubrel(iter.clone()),
+            Self::Raw(iter) => Self::Raw(iter.clone()),
+            Self::Suback(iter) => Self::Suback(iter.clone()),
+            Self::Subscribe(iter) => Self::Subscribe(iter.clone()),
+            Self::Unsuback(iter) => Self::Unsuback(iter.clone()),
+            Self::Unsubscribe(iter) => Self::Unsubscribe(iter.clone()),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Iterator for PacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Connack(iter) => iter.next(),
+            Self::Connect(iter) => iter.next(),
+            Self::Disconnect(iter) => iter.next(),
+            Self::Pingreq(iter) => iter.next(),
+            Self::Pingresp(iter) => iter.next(),
+            Self::Puback(iter) => iter.next(),
+            Self::Pubcomp(iter) => iter.next(),
+            Self::Publish(iter) => iter.next(),
+            Self::Pubrec(iter) => iter.next(),
+            Self::Pubrel(iter) => iter.next(),
+            Self::Raw(iter) => iter.next(),
             Self::Suback(iter) => iter.next(),
             Self::Subscribe(iter) => iter.next(),
             Self::Unsuback(iter) => iter.next(),
             Self::Unsubscribe(iter) => iter.next(),
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Connack(iter) => iter.size_hint(),
+            Self::Connect(iter) => iter.size_hint(),
+            Self::Disconnect(iter) => iter.size_hint(),
+            Self::Pingreq(iter) => iter.size_hint(),
+            Self::Pingresp(iter) => iter.size_hint(),
+            Self::Puback(iter) => iter.size_hint(),
+            Self::Pubcomp(iter) => iter.size_hint(),
+            Self::Publish(iter) => iter.size_hint(),
+            Self::Pubrec(iter) => iter.size_hint(),
+            Self::Pubrel(iter) => iter.size_hint(),
+            Self::Raw(iter) => iter.size_hint(),
+            Self::Suback(iter) => iter.size_hint(),
+            Self::Subscribe(iter) => iter.size_hint(),
+            Self::Unsuback(iter) => iter.size_hint(),
+            Self::Unsubscribe(iter) => iter.size_hint(),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> ExactSizeIterator for PacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    // No members to implement
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> FusedIterator for PacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    // No members to implement
 }
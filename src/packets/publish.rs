@@ -1,19 +1,33 @@
 //! MQTT [`PUBLISH`](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037)
 
 use crate::{
+    anystring::{AnyString, Utf8},
     anyvec::AnyVec,
     coding::{
-        encoder::{BytesIter, OptionalU16Iter, PacketLenIter, U8Iter, Unit},
+        encoder::{BytesIter, BytesRef, OptionalU16Iter, PacketLenIter, SizedIter, U8Iter, Unit},
         length::Length,
         Decoder, Encoder,
     },
-    packets::TryFromIterator,
+    error::{DecoderError, Field},
+    packets::{flags::PublishFlags, packet::PacketType, TryFromIterator},
+    topic,
 };
-use core::iter::Chain;
+use core::iter::{Chain, Repeat, Take};
+
+#[cfg(feature = "alloc")]
+use crate::anyvec::CowBytes;
+
+/// A borrowed view of an MQTT [`PUBLISH`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037) (see [`Publish::parse`])
+pub type PublishRef<'a> = Publish<BytesRef<'a>>;
 
 /// An MQTT [`PUBLISH` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037)
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Publish<Bytes> {
+///
+/// # Note
+/// The topic uses the same container type as the payload by default; use a string-backed container (e.g.
+/// [`crate::anystring::Utf8`]) for string-typed topics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Publish<Bytes, Topic = Bytes> {
     /// Whether this packet is a redelivery or not
     dup: bool,
     /// The packet QoS
@@ -27,29 +41,16 @@ pub struct Publish<Bytes> {
     /// Whether the message should be retained
     retain: bool,
     /// The message topic
-    topic: Bytes,
+    topic: Topic,
     /// The packet ID
     packet_id: Option<u16>,
     /// The payload
     payload: Bytes,
 }
-impl<Bytes> Publish<Bytes>
-where
-    Bytes: AnyVec<u8>,
-{
+impl<Bytes, Topic> Publish<Bytes, Topic> {
     /// The packet type constant
     pub const TYPE: u8 = 3;
 
-    /// Creates a new packet
-    pub fn new<T, P>(topic: T, payload: P, retain: bool) -> Result<Self, &'static str>
-    where
-        T: AsRef<[u8]>,
-        P: AsRef<[u8]>,
-    {
-        let topic = Bytes::new(topic.as_ref())?;
-        let payload = Bytes::new(payload.as_ref())?;
-        Ok(Self { dup: false, qos: 0, retain, topic, packet_id: None, payload })
-    }
     /// Configures the packet quality-of-service level and specifies whether this packet is a duplicate transmission
     /// (aka retry) or not
     ///
@@ -64,7 +65,94 @@ where
         self.packet_id = Some(packet_id);
         self
     }
+}
+impl<Bytes, Topic> Publish<Bytes, Topic>
+where
+    Bytes: AnyVec<u8>,
+    Topic: AnyVec<u8>,
+{
+    /// Creates a new packet
+    ///
+    /// # Note
+    /// The topic must be a valid topic name (see [`topic::validate_name`]).
+    pub fn new<T, P>(topic: T, payload: P, retain: bool) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        // Validate the topic and ensure that the packet can be encoded (reserving space for an optional packet ID)
+        topic::validate_name(topic.as_ref())?;
+        let len = Length::new().try_bytes(&topic)?.u16(&0).raw(&payload).into();
+        Length::new().try_packetlen(&len)?;
+
+        // Init self
+        let topic = Topic::new(topic.as_ref())?;
+        let payload = Bytes::new(payload.as_ref())?;
+        Ok(Self { dup: false, qos: 0, retain, topic, packet_id: None, payload })
+    }
+    /// Creates a new packet whose payload is streamed from the given iterator during encoding
+    ///
+    /// # Note
+    /// The packet length is computed from `payload_len`, so the payload is never buffered. See [`StreamingPublish`] for
+    /// how a payload iterator of the wrong length is handled.
+    pub fn new_streaming<T, P>(
+        topic: T,
+        payload_len: usize,
+        payload: P,
+        retain: bool,
+    ) -> Result<StreamingPublish<Topic, P::IntoIter>, &'static str>
+    where
+        T: AsRef<[u8]>,
+        P: IntoIterator<Item = u8>,
+    {
+        // Validate the topic and ensure that the packet can be encoded (reserving space for an optional packet ID)
+        topic::validate_name(topic.as_ref())?;
+        let len: usize = Length::new().try_bytes(&topic)?.u16(&0).into();
+        let len = len.checked_add(payload_len).ok_or("Packet length is too large")?;
+        Length::new().try_packetlen(&len)?;
+
+        // Init self
+        let topic = Topic::new(topic.as_ref())?;
+        let payload = payload.into_iter();
+        Ok(StreamingPublish { dup: false, qos: 0, retain, topic, packet_id: None, payload_len, payload })
+    }
+    /// Replaces the message topic, which must be a valid topic name (see [`topic::validate_name`])
+    pub fn with_topic<T>(mut self, topic: T) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        // Validate the topic and ensure that the packet can be encoded (reserving space for an optional packet ID)
+        topic::validate_name(topic.as_ref())?;
+        let len = Length::new().try_bytes(&topic)?.u16(&0).raw(&self.payload).into();
+        Length::new().try_packetlen(&len)?;
 
+        // Replace the topic
+        self.topic = Topic::new(topic.as_ref())?;
+        Ok(self)
+    }
+    /// Creates a copy of this packet that is marked as duplicate transmission (aka retry), preserving the
+    /// quality-of-service level and packet ID
+    ///
+    /// # Note
+    /// Only packets with a packet ID (i.e. with a quality-of-service level greater than `0`) can be retransmitted; this
+    /// function fails if the packet has no packet ID.
+    pub fn as_duplicate(&self) -> Result<Self, &'static str> {
+        // Validate packet ID
+        let Some(packet_id) = self.packet_id else {
+            return Err("Packet has no packet ID");
+        };
+
+        // Copy fields and set the duplicate flag
+        let topic = Topic::new(self.topic.as_ref())?;
+        let payload = Bytes::new(self.payload.as_ref())?;
+        Ok(Self { dup: true, qos: self.qos, retain: self.retain, topic, packet_id: Some(packet_id), payload })
+    }
+}
+impl<Bytes, Topic> Publish<Bytes, Topic>
+where
+    Bytes: AsRef<[u8]>,
+    Topic: AsRef<[u8]>,
+{
     /// The message topic
     pub fn topic(&self) -> &[u8] {
         self.topic.as_ref()
@@ -93,11 +181,282 @@ where
         self.packet_id
     }
 }
-impl<Bytes> TryFromIterator for Publish<Bytes>
+impl<Bytes, S> Publish<Bytes, Utf8<S>>
+where
+    S: AnyString,
+{
+    /// The message topic as string
+    pub fn topic_str(&self) -> &str {
+        self.topic.as_str()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes, Topic> defmt::Format for Publish<Bytes, Topic>
+where
+    Bytes: AsRef<[u8]>,
+    Topic: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the length of the topic and payload
+        defmt::write!(
+            f,
+            "Publish {{ dup: {=bool}, qos: {=u8}, retain: {=bool}, packet_id: {}, topic_len: {=usize}, payload_len: {=usize} }}",
+            self.dup,
+            self.qos,
+            self.retain,
+            self.packet_id,
+            self.topic.as_ref().len(),
+            self.payload.as_ref().len()
+        )
+    }
+}
+/// The header fields of an MQTT [`PUBLISH`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037) without the payload (see
+/// [`Publish::decode_into`] and [`Publish::decode_header`])
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PublishHeader<Bytes> {
+    /// Whether this packet is a redelivery or not
+    dup: bool,
+    /// The packet QoS
+    qos: u8,
+    /// Whether the message should be retained
+    retain: bool,
+    /// The message topic
+    topic: Bytes,
+    /// The packet ID
+    packet_id: Option<u16>,
+}
+impl<Bytes> PublishHeader<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    /// The message topic
+    pub fn topic(&self) -> &[u8] {
+        self.topic.as_ref()
+    }
+
+    /// Whether the message should be retained
+    pub fn retain(&self) -> bool {
+        self.retain
+    }
+
+    /// Whether this packet is a redelivery or not
+    pub fn dup(&self) -> bool {
+        self.dup
+    }
+    /// The packet QoS
+    pub fn qos(&self) -> u8 {
+        self.qos
+    }
+    /// The packet ID
+    pub fn packet_id(&self) -> Option<u16> {
+        self.packet_id
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for PublishHeader<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the length of the topic
+        defmt::write!(
+            f,
+            "PublishHeader {{ dup: {=bool}, qos: {=u8}, retain: {=bool}, packet_id: {}, topic_len: {=usize} }}",
+            self.dup,
+            self.qos,
+            self.retain,
+            self.packet_id,
+            self.topic.as_ref().len()
+        )
+    }
+}
+impl<'a> Publish<BytesRef<'a>> {
+    /// Creates a new encode-only packet that borrows the given topic and payload
+    ///
+    /// # Note
+    /// The topic must be a valid topic name (see [`topic::validate_name`]). No bytes are copied; the packet can be
+    /// encoded as-is, but it cannot be decoded into (see [`Self::parse`] for borrowed decoding).
+    pub fn new_ref(topic: &'a [u8], payload: &'a [u8], retain: bool) -> Result<Self, &'static str> {
+        // Validate the topic and ensure that the packet can be encoded (reserving space for an optional packet ID)
+        topic::validate_name(topic)?;
+        let len = Length::new().try_bytes(&topic)?.u16(&0).raw(&payload).into();
+        Length::new().try_packetlen(&len)?;
+
+        // Init self
+        let (topic, payload) = (BytesRef::new(topic), BytesRef::new(payload));
+        Ok(Self { dup: false, qos: 0, retain, topic, packet_id: None, payload })
+    }
+
+    /// Parses a borrowed view of a packet from the beginning of the given slice and returns the amount of consumed
+    /// bytes
+    ///
+    /// # Note
+    /// Topic and payload are borrowed sub-slices of the given slice, so no bytes are copied. The amount of consumed
+    /// bytes is the full frame length (i.e. header, packet length field and body) of the packet.
+    pub fn parse(slice: &'a [u8]) -> Result<(Self, usize), DecoderError> {
+        // Read packet:
+        //  - header type and flags
+        //  - packet len
+        let mut decoder = Decoder::new(slice.iter().copied());
+        let (type_, flags) = decoder.header_typed()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+        };
+        let flags = PublishFlags::from_bits(flags.bits())?;
+        // Limit length to the frame
+        let len = decoder.packetlen()?;
+        let too_large = err!(SpecViolation, "Packet length is too large").with_offset(1);
+        let frame_len = decoder.position().checked_add(len).ok_or(too_large)?;
+        let frame = slice.get(..frame_len).ok_or(err!(Truncated, "Truncated input").with_offset(slice.len()))?;
+        let mut decoder = decoder.take_exact(len);
+        // Read fields
+        let topic = decoder.bytes_ref(frame).map_err(|e| e.context("topic name"))?;
+        if flags.qos > 0 && decoder.remaining() < 2 {
+            // The packet is too short to contain the indicated packet ID
+            return Err(err!(Truncated, "Truncated packet ID")
+                .with_offset(decoder.position())
+                .context("packet identifier"));
+        }
+        let packet_id = decoder.optional_u16(flags.qos > 0).map_err(|e| e.context("packet identifier"))?;
+        let payload = decoder.raw_remainder_ref(frame).map_err(|e| e.context("payload"))?;
+        decoder.finish()?;
+
+        // Init self
+        let PublishFlags { dup, qos, retain } = flags;
+        Ok((Self { dup, qos, retain, topic, packet_id, payload }, frame_len))
+    }
+
+    /// Copies the borrowed view into an owned packet
+    pub fn to_owned<Bytes>(&self) -> Result<Publish<Bytes>, &'static str>
+    where
+        Bytes: AnyVec<u8>,
+    {
+        let topic = Bytes::new(self.topic.as_ref())?;
+        let payload = Bytes::new(self.payload.as_ref())?;
+        Ok(Publish { dup: self.dup, qos: self.qos, retain: self.retain, topic, packet_id: self.packet_id, payload })
+    }
+
+    /// Converts the borrowed view into a packet with copy-on-write fields without copying any bytes
+    #[cfg(feature = "alloc")]
+    pub fn to_cow(&self) -> Publish<CowBytes<'a>> {
+        let (topic, payload) = (self.topic.into(), self.payload.into());
+        Publish { dup: self.dup, qos: self.qos, retain: self.retain, topic, packet_id: self.packet_id, payload }
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, Bytes, Topic> arbitrary::Arbitrary<'a> for Publish<Bytes, Topic>
+where
+    Bytes: AnyVec<u8>,
+    Topic: AnyVec<u8>,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::packets::{arbitrary_bytes, arbitrary_topic};
+
+        // Generate the fields
+        let (topic, payload): (Topic, _) = (arbitrary_topic(u, false)?, arbitrary_bytes(u)?);
+        let mut this = Self::new(topic, payload, u.arbitrary()?).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+
+        // Generate an optional QoS; packets with a QoS greater than `0` require a non-zero packet ID
+        let qos = u.int_in_range(0..=2)?;
+        if qos > 0 {
+            let packet_id = u.int_in_range(1..=u16::MAX)?;
+            this = this.with_qos(qos, packet_id, u.arbitrary()?);
+        }
+        Ok(this)
+    }
+}
+impl<Bytes, Topic> Publish<Bytes, Topic>
 where
     Bytes: AnyVec<u8>,
+    Topic: AnyVec<u8>,
 {
-    fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+    /// Decodes a packet from the given byte iterator, copying the payload into the given buffer instead of a new
+    /// container, and returns the header fields together with the filled prefix of the buffer
+    ///
+    /// # Note
+    /// If the buffer is too small, a [`crate::error::Decoding::Memory`] error is returned whose detail contains the
+    /// required and the available buffer length.
+    pub fn decode_into<T>(iter: T, payload_buf: &mut [u8]) -> Result<(PublishHeader<Topic>, &[u8]), DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read the header fields
+        let mut topic = Topic::default();
+        let (flags, packet_id, payload_len, mut decoder) = Self::decode_head(iter, &mut topic)?;
+
+        // Validate the buffer length and read the payload
+        let (required, capacity) = (payload_len, payload_buf.len());
+        let Some(payload) = payload_buf.get_mut(..required) else {
+            let error = err!(Memory, Field::Payload.capacity_error(), expected required, found capacity);
+            return Err(error.with_offset(decoder.position()).context("payload"));
+        };
+        for slot in payload.iter_mut() {
+            // Copy each byte into the next slot
+            *slot = decoder.u8().map_err(|e| e.context("payload"))?;
+        }
+        decoder.finish()?;
+
+        // Init header
+        let PublishFlags { dup, qos, retain } = flags;
+        Ok((PublishHeader { dup, qos, retain, topic, packet_id }, payload))
+    }
+
+    /// Decodes the header fields of a packet from the given byte iterator and returns them together with the length of
+    /// the payload
+    ///
+    /// # Note
+    /// The iterator is not advanced beyond the packet ID, so if it is passed by reference, it is positioned exactly at
+    /// the beginning of the payload afterwards. The payload bytes are left untouched for the caller to skip or copy.
+    pub fn decode_header<T>(iter: T) -> Result<(PublishHeader<Topic>, usize), DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read the header fields
+        let mut topic = Topic::default();
+        let (flags, packet_id, payload_len, _decoder) = Self::decode_head(iter, &mut topic)?;
+        log_trace!("decoded PUBLISH header (topic: {} bytes, payload: {} bytes)", topic.as_ref().len(), payload_len);
+
+        // Init header
+        let PublishFlags { dup, qos, retain } = flags;
+        Ok((PublishHeader { dup, qos, retain, topic, packet_id }, payload_len))
+    }
+
+    /// Reads the header fields of a packet from the given byte reader and returns them together with a reader over the
+    /// payload
+    ///
+    /// # Note
+    /// The header fields are read byte-wise so that the reader is never advanced beyond the packet ID; the payload can
+    /// then be consumed incrementally via the returned [`PayloadReader`] without buffering it.
+    #[cfg(feature = "std")]
+    pub fn read_header<R>(mut reader: R) -> Result<(PublishHeader<Topic>, PayloadReader<R>), std::io::Error>
+    where
+        R: std::io::Read,
+    {
+        use std::io::Read;
+
+        // Create a byte iterator from the reader and retain a read error if any
+        let mut last_error = None;
+        #[allow(clippy::unbuffered_bytes, reason = "The header is read byte-wise to not read beyond the packet ID")]
+        let bytes = (&mut reader).bytes().map_while(|result| result.map_err(|e| last_error = Some(e)).ok());
+
+        // Read the header fields; a read error takes precedence as the input may be truncated
+        let result = Self::decode_header(bytes);
+        let (header, payload_len) = match (result, last_error) {
+            (_, Some(e)) => return Err(e),
+            (Ok(decoded), None) => decoded,
+            (Err(e), None) => return Err(e.into()),
+        };
+        Ok((header, PayloadReader { reader, remaining: payload_len }))
+    }
+
+    /// Decodes the fixed header, topic and packet ID into the given topic container and returns the flags, packet ID
+    /// and payload length together with a decoder that is positioned at the beginning of the payload
+    #[allow(clippy::type_complexity, reason = "The type is built out of the decoder and the decoded fields")]
+    fn decode_head<T>(
+        iter: T,
+        topic: &mut Topic,
+    ) -> Result<(PublishFlags, Option<u16>, usize, Decoder<Take<T::IntoIter>>), DecoderError>
     where
         T: IntoIterator<Item = u8>,
     {
@@ -107,51 +466,92 @@ where
         //  - topic
         //  - packet ID
         let mut decoder = Decoder::new(iter);
-        let (Self::TYPE, [dup, qos0, qos1, retain]) = decoder.header()? else {
-            return Err("Invalid packet type");
+        let (type_, flags) = decoder.header_typed()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
         };
+        let flags = PublishFlags::from_bits(flags.bits())?;
         // Limit length
         let len = decoder.packetlen()?;
-        let mut decoder = decoder.limit(len);
+        let too_large = err!(SpecViolation, "Packet length is too large").with_offset(1);
+        let end = decoder.position().checked_add(len).ok_or(too_large)?;
+        let mut decoder = decoder.take_exact(len);
         // Read fields
-        let topic = decoder.bytes()?;
-        let packet_id = decoder.optional_u16(qos0 || qos1)?;
-        let payload = decoder.raw_remainder()?;
+        decoder.bytes_into(topic).map_err(|e| Field::Topic.tag(e).context("topic name"))?;
+        if flags.qos > 0 && decoder.remaining() < 2 {
+            // The packet is too short to contain the indicated packet ID
+            return Err(err!(Truncated, "Truncated packet ID")
+                .with_offset(decoder.position())
+                .context("packet identifier"));
+        }
+        let packet_id = decoder.optional_u16(flags.qos > 0).map_err(|e| e.context("packet identifier"))?;
 
-        // Init self
-        let qos = ((qos0 as u8) << 1) | (qos1 as u8);
-        Ok(Self { dup, qos, retain, topic, packet_id, payload })
+        // Compute the payload length from the packet length
+        let payload_len = end.saturating_sub(decoder.position());
+        Ok((flags, packet_id, payload_len, decoder))
     }
 }
-impl<Bytes> IntoIterator for Publish<Bytes>
+impl<Bytes, Topic> TryFromIterator for Publish<Bytes, Topic>
 where
     Bytes: AnyVec<u8>,
+    Topic: AnyVec<u8>,
+{
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Decode into an empty packet
+        let (topic, payload) = (Topic::default(), Bytes::default());
+        let mut this = Self { dup: false, qos: 0, retain: false, topic, packet_id: None, payload };
+        this.try_from_iter_into(iter)?;
+        Ok(this)
+    }
+
+    fn try_from_iter_into<T>(&mut self, iter: T) -> Result<(), DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read the header fields and the payload
+        let (flags, packet_id, _payload_len, mut decoder) = Self::decode_head(iter, &mut self.topic)?;
+        decoder.raw_remainder_into(&mut self.payload).map_err(|e| Field::Payload.tag(e).context("payload"))?;
+        decoder.finish()?;
+        log_trace!(
+            "decoded PUBLISH (topic: {} bytes, packet ID: {:?}, payload: {} bytes)",
+            self.topic.as_ref().len(),
+            packet_id,
+            self.payload.as_ref().len()
+        );
+
+        // Update self
+        let PublishFlags { dup, qos, retain } = flags;
+        (self.dup, self.qos, self.retain, self.packet_id) = (dup, qos, retain, packet_id);
+        Ok(())
+    }
+}
+impl<Bytes, Topic> IntoIterator for Publish<Bytes, Topic>
+where
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+    Topic: AsRef<[u8]> + IntoIterator<Item = u8>,
 {
     type Item = u8;
     #[rustfmt::skip]
     type IntoIter =
         // Complex iterator built out of the individual message fields
-        Chain<Chain<Chain<Chain<Chain<
+        SizedIter<Chain<Chain<Chain<Chain<Chain<
             // - header type and flags
             Unit, U8Iter>,
             // - packet len
             PacketLenIter>,
             // - topic
-            BytesIter<Bytes>>,
+            BytesIter<Topic>>,
             // - packet ID
             OptionalU16Iter>,
             //  - payload
-            <Bytes as IntoIterator>::IntoIter>;
+            <Bytes as IntoIterator>::IntoIter>>;
 
     fn into_iter(self) -> Self::IntoIter {
         // Assemble flags
-        #[rustfmt::skip]
-        let flags = [
-            self.dup,
-            (self.qos >> 1) != 0,
-            (self.qos & 1) != 0,
-            self.retain
-        ];
+        let flags = PublishFlags { dup: self.dup, qos: self.qos, retain: self.retain };
 
         // Precompute body length:
         //  - header type and flags
@@ -171,11 +571,245 @@ where
         //  - packet ID
         //  - payload
         Encoder::default()
-            .header(Self::TYPE, flags)
+            .typed_header(PacketType::Publish, flags)
             .packetlen(len)
             .bytes(self.topic)
             .optional_u16(self.packet_id)
             .raw(self.payload)
+            .into_sized_iter(len)
+    }
+}
+impl<'a, Bytes, Topic> IntoIterator for &'a Publish<Bytes, Topic>
+where
+    Bytes: AsRef<[u8]>,
+    Topic: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = <Publish<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Encode a borrowed view of the packet
+        let topic = BytesRef::new(self.topic.as_ref());
+        let payload = BytesRef::new(self.payload.as_ref());
+        Publish { dup: self.dup, qos: self.qos, retain: self.retain, topic, packet_id: self.packet_id, payload }
             .into_iter()
     }
 }
+
+/// An encode-only MQTT [`PUBLISH`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037) whose payload is
+/// streamed from an iterator (see [`Publish::new_streaming`])
+///
+/// # Note
+/// The packet length is computed from the announced payload length. If the payload iterator yields more bytes, the
+/// payload is truncated to the announced length; if it yields fewer bytes, the payload is padded with zero bytes to the
+/// announced length. This way, the encoded packet is always exactly [`Self::len`] bytes long and the peer stays in sync
+/// with the packet boundaries. Use [`Self::write`] to detect a too short payload iterator.
+#[derive(Debug, Clone)]
+pub struct StreamingPublish<Bytes, Payload> {
+    /// Whether this packet is a redelivery or not
+    dup: bool,
+    /// The packet QoS
+    qos: u8,
+    /// Whether the message should be retained
+    retain: bool,
+    /// The message topic
+    topic: Bytes,
+    /// The packet ID
+    packet_id: Option<u16>,
+    /// The announced payload length
+    payload_len: usize,
+    /// The payload
+    payload: Payload,
+}
+impl<Bytes, Payload> StreamingPublish<Bytes, Payload>
+where
+    Bytes: AsRef<[u8]>,
+{
+    /// Configures the packet quality-of-service level and specifies whether this packet is a duplicate transmission
+    /// (aka retry) or not (see [`Publish::with_qos`])
+    pub fn with_qos(mut self, qos: u8, packet_id: u16, dup: bool) -> Self {
+        self.dup = dup;
+        self.qos = qos;
+        self.packet_id = Some(packet_id);
+        self
+    }
+
+    /// The message topic
+    pub fn topic(&self) -> &[u8] {
+        self.topic.as_ref()
+    }
+    /// The announced payload length
+    pub fn payload_len(&self) -> usize {
+        self.payload_len
+    }
+
+    /// The full frame length (i.e. header, packet length field and body) of the encoded packet
+    pub fn len(&self) -> usize {
+        Length::total_frame(self.body_len())
+    }
+    /// Whether the encoded packet is empty (which is never the case)
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The packet body length
+    fn body_len(&self) -> usize {
+        // The packet length has been validated during construction
+        let len: usize = Length::new().bytes(&self.topic).optional_u16(&self.packet_id).into();
+        len.saturating_add(self.payload_len)
+    }
+}
+#[cfg(feature = "std")]
+impl<Bytes, Payload> StreamingPublish<Bytes, Payload>
+where
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+    Payload: Iterator<Item = u8>,
+{
+    /// Writes the packet to the given byte writer, or fails with [`std::io::ErrorKind::UnexpectedEof`] if the payload
+    /// iterator yields fewer bytes than announced
+    ///
+    /// # Note
+    /// A too short payload is padded with zero bytes (see [`StreamingPublish`]), so the packet has been written
+    /// completely if the error is returned.
+    pub fn write<W>(self, writer: W) -> Result<(), std::io::Error>
+    where
+        W: std::io::Write,
+    {
+        use crate::packets::ToWriter;
+        use std::io::{Error, ErrorKind};
+
+        // Count the bytes that are yielded by the payload iterator itself
+        let mut yielded = 0usize;
+        let Self { dup, qos, retain, topic, packet_id, payload_len, payload } = self;
+        let payload = payload.inspect(|_| yielded = yielded.saturating_add(1));
+        let this = StreamingPublish { dup, qos, retain, topic, packet_id, payload_len, payload };
+        ToWriter::write(this, writer)?;
+
+        // Ensure that the payload has not been padded
+        match yielded < payload_len {
+            true => Err(Error::new(ErrorKind::UnexpectedEof, "Payload iterator has ended early")),
+            false => Ok(()),
+        }
+    }
+}
+impl<Bytes, Payload> IntoIterator for StreamingPublish<Bytes, Payload>
+where
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+    Payload: Iterator<Item = u8>,
+{
+    type Item = u8;
+    #[rustfmt::skip]
+    type IntoIter =
+        // Complex iterator built out of the individual message fields
+        SizedIter<Chain<Chain<Chain<Chain<Chain<
+            // - header type and flags
+            Unit, U8Iter>,
+            // - packet len
+            PacketLenIter>,
+            // - topic
+            BytesIter<Bytes>>,
+            // - packet ID
+            OptionalU16Iter>,
+            //  - payload
+            Take<Chain<Payload, Repeat<u8>>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Assemble flags and precompute body length
+        let flags = PublishFlags { dup: self.dup, qos: self.qos, retain: self.retain };
+        let len = self.body_len();
+
+        // Write packet:
+        //  - header type and flags
+        //  - packet len
+        //  - topic
+        //  - packet ID
+        //  - payload, truncated or zero-padded to the announced length
+        Encoder::default()
+            .typed_header(PacketType::Publish, flags)
+            .packetlen(len)
+            .bytes(self.topic)
+            .optional_u16(self.packet_id)
+            .raw(self.payload.chain(core::iter::repeat(0)).take(self.payload_len))
+            .into_sized_iter(len)
+    }
+}
+
+/// A reader over the payload of a [`PUBLISH`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718037) that is limited to the
+/// remaining payload length (see [`Publish::read_header`])
+///
+/// # Note
+/// If the reader is dropped before the payload has been consumed entirely, the remaining payload bytes are skipped so
+/// that the underlying reader is positioned at the next packet boundary. Use [`Self::skip_remaining`] to handle errors
+/// during skipping explicitly.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct PayloadReader<R>
+where
+    R: std::io::Read,
+{
+    /// The underlying reader
+    reader: R,
+    /// The amount of remaining payload bytes
+    remaining: usize,
+}
+#[cfg(feature = "std")]
+impl<R> PayloadReader<R>
+where
+    R: std::io::Read,
+{
+    /// The amount of remaining payload bytes
+    pub const fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Discards the remaining payload bytes
+    pub fn skip_remaining(&mut self) -> Result<(), std::io::Error> {
+        use std::io::{ErrorKind, Read};
+
+        let mut chunk = [0; 256];
+        while self.remaining > 0 {
+            // Read and discard the next chunk
+            match self.read(&mut chunk) {
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                result => result?,
+            };
+        }
+        Ok(())
+    }
+}
+#[cfg(feature = "std")]
+impl<R> std::io::Read for PayloadReader<R>
+where
+    R: std::io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use std::io::{Error, ErrorKind};
+
+        // Limit the read to the remaining payload
+        let len = buf.len().min(self.remaining);
+        let buf = buf.get_mut(..len).unwrap_or_default();
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // Read the next bytes and fail if the payload is truncated
+        let read = self.reader.read(buf)?;
+        if read == 0 {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Truncated payload"));
+        }
+        self.remaining = self.remaining.saturating_sub(read);
+        Ok(read)
+    }
+}
+#[cfg(feature = "std")]
+impl<R> Drop for PayloadReader<R>
+where
+    R: std::io::Read,
+{
+    fn drop(&mut self) {
+        // Skip the remaining payload; errors cannot be reported here
+        let _ = self.skip_remaining();
+    }
+}
@@ -0,0 +1,172 @@
+//! A raw MQTT packet of an arbitrary type that preserves the original body bytes
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{
+        encoder::{BytesRef, PacketLenIter, SizedIter, U8Iter, Unit},
+        length::Length,
+        Decoder, Encoder,
+    },
+    error::{DecoderError, Field},
+    packets::TryFromIterator,
+};
+use core::iter::Chain;
+
+/// A raw MQTT packet of an arbitrary type that preserves the original body bytes
+///
+/// # Note
+/// Raw packets are used for packet types that are not modelled by this crate (e.g. `AUTH` from an MQTT 5 peer or vendor
+/// extensions), or to pass packets through without decoding them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Raw<Bytes> {
+    /// The packet type
+    type_: u8,
+    /// The header flags
+    flags: [bool; 4],
+    /// The packet body
+    body: Bytes,
+}
+impl<Bytes> Raw<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    /// Creates a new packet
+    pub fn new<T>(type_: u8, flags: [bool; 4], body: T) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        // Ensure that the packet can be encoded
+        if type_ > 15 {
+            return Err("Packet type is too large");
+        }
+        let len = Length::new().raw(&body).into();
+        Length::new().try_packetlen(&len)?;
+
+        // Init self
+        let body = Bytes::new(body.as_ref())?;
+        Ok(Self { type_, flags, body })
+    }
+}
+impl<Bytes> Raw<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    /// The packet type
+    pub const fn type_(&self) -> u8 {
+        self.type_
+    }
+
+    /// The header flags
+    pub const fn flags(&self) -> [bool; 4] {
+        self.flags
+    }
+
+    /// The packet body
+    pub fn body(&self) -> &[u8] {
+        self.body.as_ref()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for Raw<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the length of the body
+        let len = self.body.as_ref().len();
+        defmt::write!(f, "Raw {{ type_: {=u8}, flags: {}, body_len: {=usize} }}", self.type_, self.flags, len)
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, Bytes> arbitrary::Arbitrary<'a> for Raw<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Generate the fields
+        let (type_, flags) = (u.int_in_range(0..=15)?, u.arbitrary()?);
+        let body: &[u8] = u.arbitrary()?;
+        Self::new(type_, flags, body).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+impl<Bytes> TryFromIterator for Raw<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Decode into an empty packet
+        let mut this = Self { type_: 0, flags: [false; 4], body: Bytes::default() };
+        this.try_from_iter_into(iter)?;
+        Ok(this)
+    }
+
+    fn try_from_iter_into<T>(&mut self, iter: T) -> Result<(), DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read packet:
+        //  - header type and flags
+        //  - packet len
+        //  - body
+        let mut decoder = Decoder::new(iter);
+        let (type_, flags) = decoder.header()?;
+        // Limit length
+        let len = decoder.packetlen()?;
+        let mut decoder = decoder.take_exact(len);
+        // Read fields
+        decoder.raw_remainder_into(&mut self.body).map_err(|e| Field::Body.tag(e).context("body"))?;
+        decoder.finish()?;
+        log_trace!("decoded raw packet (type: {}, body: {} bytes)", type_, self.body.as_ref().len());
+
+        // Update self
+        (self.type_, self.flags) = (type_, flags);
+        Ok(())
+    }
+}
+impl<Bytes> IntoIterator for Raw<Bytes>
+where
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    #[rustfmt::skip]
+    type IntoIter =
+        // Complex iterator built out of the individual message fields
+        SizedIter<Chain<Chain<Chain<
+            // - header type and flags
+            Unit, U8Iter>,
+            // - packet len
+            PacketLenIter>,
+            // - body
+            <Bytes as IntoIterator>::IntoIter>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Precompute body length:
+        //  - body
+        #[rustfmt::skip]
+        let len = Length::new()
+            .raw(&self.body)
+            .into();
+
+        // Write packet:
+        //  - header type and flags
+        //  - packet len
+        //  - body
+        Encoder::default().header(self.type_, self.flags).packetlen(len).raw(self.body).into_sized_iter(len)
+    }
+}
+impl<'a, Bytes> IntoIterator for &'a Raw<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = <Raw<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Encode a borrowed view of the packet
+        let body = BytesRef::new(self.body.as_ref());
+        Raw { type_: self.type_, flags: self.flags, body }.into_iter()
+    }
+}
@@ -0,0 +1,404 @@
+//! Resumable en-/decoding of packets in arbitrary chunks
+
+#[cfg(feature = "std")]
+use crate::packets::framing::FrameState;
+use crate::{
+    anyvec::AnyVec,
+    coding::varint,
+    error::DecoderError,
+    packets::{self, FrameStatus, TryFromIterator},
+};
+use core::{
+    array,
+    iter::{Flatten, FusedIterator, Peekable},
+    marker::PhantomData,
+    slice,
+};
+
+/// A resumable decoder that buffers arbitrary chunks until a packet is complete
+#[derive(Debug, Clone)]
+pub struct PacketDecoder<T, Buf> {
+    /// The buffered bytes
+    buf: Buf,
+    /// The frame length of the current packet if the header and packet length have been read already
+    frame_len: Option<usize>,
+    /// The maximum accepted packet length
+    max_packet_size: usize,
+    /// The packet type
+    _packet: PhantomData<T>,
+}
+impl<T, Buf> PacketDecoder<T, Buf>
+where
+    T: TryFromIterator,
+    Buf: AnyVec<u8> + AsMut<[u8]>,
+{
+    /// Creates a new, empty decoder without a packet length limit
+    pub fn new() -> Self {
+        Self { buf: Buf::default(), frame_len: None, max_packet_size: usize::MAX, _packet: PhantomData }
+    }
+
+    /// Configures the maximum accepted packet length (i.e. the body length without header and packet length field)
+    ///
+    /// # Note
+    /// Oversized packets are rejected with a [`crate::error::Decoding::Memory`] error as soon as the packet length
+    /// field has been buffered, before the body is buffered.
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    /// The amount of buffered bytes
+    pub fn buffered(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    /// Feeds the next chunk into the decoder and yields a packet once it is complete
+    ///
+    /// # Note
+    /// If the buffered bytes contain more than one packet, only the first packet is returned; call this function with
+    /// an empty chunk to get the next buffered packet. If an error occurs, all buffered bytes are discarded.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Option<T>, DecoderError> {
+        match self.try_feed(chunk) {
+            Ok(packet) => Ok(packet),
+            Err(e) => {
+                // Reset the decoder so that it can be reused
+                self.buf.clear();
+                self.frame_len = None;
+                Err(e)
+            }
+        }
+    }
+
+    /// Feeds the next chunk into the decoder and yields a packet once it is complete
+    fn try_feed(&mut self, chunk: &[u8]) -> Result<Option<T>, DecoderError> {
+        // Buffer chunk
+        let offset = self.buffered();
+        let needed = offset.saturating_add(chunk.len());
+        self.buf.extend(chunk).map_err(|e| DecoderError::container(&self.buf, e, needed).with_offset(offset))?;
+
+        // Get the frame length
+        let frame_len = match self.frame_len {
+            Some(frame_len) => frame_len,
+            None => {
+                let frame_len = match packets::check(self.buf.as_ref())? {
+                    FrameStatus::Complete { total_len } => total_len,
+                    // The frame length is known, but the frame is incomplete
+                    FrameStatus::Incomplete { needed_hint: Some(needed) } => self.buffered().saturating_add(needed),
+                    FrameStatus::Incomplete { needed_hint: None } => return Ok(None),
+                };
+
+                // Validate the packet length as soon as it is known
+                let (len, _) = varint::decode(self.buf.as_ref().iter().skip(1).copied())?;
+                if len > self.max_packet_size {
+                    let error = err!(Memory, "Packet is too large", expected len, found self.max_packet_size);
+                    return Err(error.with_offset(1));
+                }
+                *self.frame_len.insert(frame_len)
+            }
+        };
+
+        // Decode the frame if it is complete
+        let Some(frame) = self.buf.as_ref().get(..frame_len) else {
+            return Ok(None);
+        };
+        let packet = T::try_from_iter(frame.iter().copied())?;
+
+        // Shift the remaining bytes to the front, so that the buffer is reused
+        let remaining = self.buffered().saturating_sub(frame_len);
+        self.buf.as_mut().copy_within(frame_len.., 0);
+        self.buf.truncate(remaining);
+        self.frame_len = None;
+        Ok(Some(packet))
+    }
+}
+impl<T, Buf> Default for PacketDecoder<T, Buf>
+where
+    T: TryFromIterator,
+    Buf: AnyVec<u8> + AsMut<[u8]>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A resumable reader that reads packets from a non-blocking byte reader
+///
+/// # Note
+/// If the reader would block, the bytes read so far are retained and reading resumes with the next call. The reader is
+/// never read beyond the packet boundary, so one instance can be used to read many packets in sequence.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct PartialReader<T, Buf> {
+    /// The buffered bytes of the current frame
+    buf: Buf,
+    /// The framing state of the current frame
+    state: FrameState,
+    /// The packet type
+    _packet: PhantomData<T>,
+}
+#[cfg(feature = "std")]
+impl<T, Buf> PartialReader<T, Buf>
+where
+    T: TryFromIterator,
+    Buf: AnyVec<u8>,
+{
+    /// Creates a new, empty reader
+    pub fn new() -> Self {
+        Self { buf: Buf::default(), state: FrameState::new(usize::MAX), _packet: PhantomData }
+    }
+
+    /// The amount of buffered bytes
+    pub fn buffered(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    /// Reads from the given reader until the current packet is complete or the reader would block
+    ///
+    /// # Note
+    /// If the reader would block, `None` is returned; call this function again once the reader is readable. If the
+    /// reader ends before the first header byte, an [`std::io::ErrorKind::UnexpectedEof`] error is returned. If an
+    /// error occurs, all buffered bytes are discarded.
+    pub fn poll_read<R>(&mut self, reader: &mut R) -> Result<Option<T>, std::io::Error>
+    where
+        R: std::io::Read,
+    {
+        match self.try_poll_read(reader) {
+            Ok(packet) => Ok(packet),
+            Err(e) => {
+                // Reset the reader so that it can be reused
+                *self = Self::new();
+                Err(e)
+            }
+        }
+    }
+
+    /// Reads from the given reader until the current packet is complete or the reader would block
+    fn try_poll_read<R>(&mut self, reader: &mut R) -> Result<Option<T>, std::io::Error>
+    where
+        R: std::io::Read,
+    {
+        use std::io::{Error, ErrorKind};
+
+        // Read until the frame is complete
+        let mut chunk = [0; 256];
+        while self.state.demand() > 0 {
+            // Read the next bytes
+            let len = self.state.demand().min(chunk.len());
+            let buf = chunk.get_mut(..len).unwrap_or_default();
+            let read = match reader.read(buf) {
+                Ok(read) => buf.get(..read).unwrap_or_default(),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            if read.is_empty() && self.buf.as_ref().is_empty() {
+                // The reader has ended at a packet boundary
+                return Err(Error::new(ErrorKind::UnexpectedEof, "Reader has ended"));
+            }
+
+            // Record and buffer the read bytes
+            self.state.advance(read)?;
+            let offset = self.buf.as_ref().len();
+            let needed = offset.saturating_add(read.len());
+            self.buf.extend(read).map_err(|e| DecoderError::container(&self.buf, e, needed).with_offset(offset))?;
+        }
+
+        // Decode the frame and reset the reader for the next packet
+        let packet = T::try_from_iter(self.buf.as_ref().iter().copied());
+        *self = Self::new();
+        Ok(Some(packet?))
+    }
+}
+#[cfg(feature = "std")]
+impl<T, Buf> Default for PartialReader<T, Buf>
+where
+    T: TryFromIterator,
+    Buf: AnyVec<u8>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator that decodes concatenated packets from a byte iterator until the source is exhausted
+///
+/// # Note
+/// If a packet cannot be decoded, the error is yielded once and the stream ends afterwards, as the packet boundaries
+/// are lost.
+#[derive(Debug, Clone)]
+pub struct PacketStream<T, Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    /// The underlying byte source
+    source: Peekable<Iter>,
+    /// Whether an error has occurred or not
+    failed: bool,
+    /// The packet type
+    _packet: PhantomData<T>,
+}
+impl<T, Iter> PacketStream<T, Iter>
+where
+    T: TryFromIterator,
+    Iter: Iterator<Item = u8>,
+{
+    /// Creates a new packet stream over the given byte source
+    pub fn new<S>(source: S) -> Self
+    where
+        S: IntoIterator<IntoIter = Iter>,
+    {
+        Self { source: source.into_iter().peekable(), failed: false, _packet: PhantomData }
+    }
+}
+impl<T, Iter> Iterator for PacketStream<T, Iter>
+where
+    T: TryFromIterator,
+    Iter: Iterator<Item = u8>,
+{
+    type Item = Result<T, DecoderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Stop after an error or at the end of the source
+        if self.failed || self.source.peek().is_none() {
+            return None;
+        }
+
+        // Decode the next packet
+        let packet = T::try_from_iter(&mut self.source);
+        self.failed = packet.is_err();
+        Some(packet)
+    }
+}
+impl<T, Iter> FusedIterator for PacketStream<T, Iter>
+where
+    T: TryFromIterator,
+    Iter: Iterator<Item = u8>,
+{
+    // No members to implement
+}
+
+/// A chunked encoder that copies the encoded representation of a packet into caller-provided buffers
+#[derive(Debug, Clone)]
+pub struct EncodedChunks<Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    /// The underlying packet iterator
+    iter: Peekable<Iter>,
+}
+impl<Iter> EncodedChunks<Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    /// Creates a new chunked encoder for the given packet
+    pub fn new<T>(packet: T) -> Self
+    where
+        T: IntoIterator<IntoIter = Iter>,
+    {
+        Self { iter: packet.into_iter().peekable() }
+    }
+
+    /// Copies as many pending bytes into the given buffer as possible and returns the amount of written bytes
+    pub fn fill(&mut self, buf: &mut [u8]) -> usize {
+        buf.iter_mut().zip(&mut self.iter).map(|(slot, byte)| *slot = byte).count()
+    }
+
+    /// Whether all bytes have been written or not
+    pub fn is_complete(&mut self) -> bool {
+        self.iter.peek().is_none()
+    }
+}
+
+/// A [`std::io::Read`] adapter that serves the encoded representation of a packet
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct PacketReader<Iter> {
+    /// The underlying packet iterator
+    iter: Iter,
+}
+#[cfg(feature = "std")]
+impl<Iter> PacketReader<Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    /// Creates a new reader for the given packet
+    pub fn new<T>(packet: T) -> Self
+    where
+        T: IntoIterator<IntoIter = Iter>,
+    {
+        Self { iter: packet.into_iter() }
+    }
+}
+#[cfg(feature = "std")]
+impl<Iter> std::io::Read for PacketReader<Iter>
+where
+    Iter: Iterator<Item = u8>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Fill the buffer; zipping the buffer first ensures that no byte is lost if the buffer is full
+        Ok(buf.iter_mut().zip(&mut self.iter).map(|(slot, byte)| *slot = byte).count())
+    }
+}
+
+/// A batch of up to `N` packets that are encoded back-to-back into a single byte stream without allocation
+#[derive(Debug, Clone)]
+pub struct PacketBatch<T, const N: usize> {
+    /// The batched packets
+    packets: [Option<T>; N],
+}
+impl<T, const N: usize> PacketBatch<T, N> {
+    /// Creates a new, empty batch
+    pub fn new() -> Self {
+        Self { packets: array::from_fn(|_| None) }
+    }
+
+    /// Appends a packet to the batch, or fails if the batch is full
+    pub fn push(&mut self, packet: T) -> Result<(), &'static str> {
+        let slot = self.packets.iter_mut().find(|slot| slot.is_none()).ok_or("Packet batch is full")?;
+        *slot = Some(packet);
+        Ok(())
+    }
+
+    /// Appends a packet to the batch, or fails if the batch is full
+    pub fn with(mut self, packet: T) -> Result<Self, &'static str> {
+        self.push(packet)?;
+        Ok(self)
+    }
+
+    /// The amount of batched packets
+    pub fn len(&self) -> usize {
+        self.packets.iter().flatten().count()
+    }
+
+    /// Whether the batch is empty or not
+    pub fn is_empty(&self) -> bool {
+        self.packets.iter().all(Option::is_none)
+    }
+}
+impl<T, const N: usize> Default for PacketBatch<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> IntoIterator for PacketBatch<T, N>
+where
+    T: IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    type IntoIter = Flatten<Flatten<array::IntoIter<Option<T>, N>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.packets.into_iter().flatten().flatten()
+    }
+}
+impl<'a, T, const N: usize> IntoIterator for &'a PacketBatch<T, N>
+where
+    &'a T: IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    type IntoIter = Flatten<Flatten<slice::Iter<'a, Option<T>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.packets.iter().flatten().flatten()
+    }
+}
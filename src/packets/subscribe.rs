@@ -3,16 +3,26 @@
 use crate::{
     anyvec::AnyVec,
     coding::{
-        encoder::{PacketLenIter, TopicsQosIter, U16Iter, U8Iter, Unit},
+        encoder::{BytesRef, PacketLenIter, SizedIter, TopicsQosIter, TopicsQosRef, U16Iter, U8Iter, Unit},
         length::Length,
         Decoder, Encoder,
     },
-    packets::TryFromIterator,
+    error::{DecoderError, Field},
+    packets::{packet::PacketType, TryFromIterator},
+    topic,
 };
 use core::{iter::Chain, marker::PhantomData};
 
+/// An encode-only MQTT [`SUBSCRIBE`
+/// packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718063) that borrows its
+/// `(topic, qos)`-tuples (see [`Subscribe::new_ref`])
+///
+/// # Note
+/// The packet is encoded by reference, i.e. via `&SubscribeRef`.
+pub type SubscribeRef<'a> = Subscribe<&'a [(&'a [u8], u8)], &'a [u8]>;
+
 /// An MQTT [`SUBSCRIBE` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718063)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Subscribe<Seq, Bytes> {
     /// The packet ID
     packet_id: u16,
@@ -27,14 +37,17 @@ pub struct Subscribe<Seq, Bytes> {
     /// The byte vector type
     _vec: PhantomData<Bytes>,
 }
+impl<Seq, Bytes> Subscribe<Seq, Bytes> {
+    /// The packet type constant
+    pub const TYPE: u8 = 8;
+    /// The default limit for the amount of topics per decoded packet
+    pub const MAX_TOPICS: usize = 256;
+}
 impl<Seq, Bytes> Subscribe<Seq, Bytes>
 where
     Seq: AnyVec<(Bytes, u8)>,
     Bytes: AnyVec<u8>,
 {
-    /// The packet type constant
-    pub const TYPE: u8 = 8;
-
     /// Creates a new packet
     ///
     /// # QoS Levels
@@ -50,11 +63,55 @@ where
         // Collect all topic-qos pairs
         let mut topics_qos = Seq::default();
         for (topic, qos) in topics {
-            // Copy topic and append pair
+            // Ensure that the topic can be encoded, copy it and append pair
+            Length::new().try_bytes(&topic)?;
             let topic = Bytes::new(topic.as_ref())?;
             topics_qos.push((topic, qos))?;
         }
 
+        // Ensure that the packet can be encoded
+        let len = Length::new().u16(&packet_id).topics_qos(&topics_qos).into();
+        Length::new().try_packetlen(&len)?;
+
+        // Init self
+        Ok(Self { packet_id, topics_qos, _vec: PhantomData })
+    }
+    /// Tries to build `Self` from the given byte iterator, accepting at most `max_topics` topics
+    ///
+    /// # Note
+    /// [`TryFromIterator::try_from_iter`] uses [`Self::MAX_TOPICS`] as limit to avoid excessive allocations on
+    /// unbounded backends.
+    pub fn try_from_iter_with_limits<T>(iter: T, max_topics: usize) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read packet:
+        //  - header type and `2` flags
+        //  - packet len
+        //  - packet ID
+        //  - sequence
+        //     - topic filter
+        //     - qos
+        let mut decoder = Decoder::new(iter);
+        let (type_, flags) = decoder.header()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+        };
+        let [false, false, true, false] = flags else {
+            return Err(err!(SpecViolation, "Invalid packet header", rule "MQTT-3.8.1-1"));
+        };
+        // Limit length and make decoder peekable
+        let len = decoder.packetlen()?;
+        let mut decoder = decoder.limit(len).peekable();
+        // Read fields
+        let packet_id = decoder.u16().map_err(|e| e.context("packet identifier"))?;
+        let start = decoder.position();
+        let topics_qos: Seq =
+            decoder.topics_qos_capped(max_topics).map_err(|e| Field::Topics.tag(e).context("topic filters"))?;
+        let filters = topics_qos.as_ref().iter().map(|(topic, _)| topic.as_ref());
+        topic::validate_filters(filters, start, 1).map_err(|e| e.context("topic filters"))?;
+        log_trace!("decoded SUBSCRIBE (packet ID: {}, topics: {})", packet_id, topics_qos.as_ref().len());
+
         // Init self
         Ok(Self { packet_id, topics_qos, _vec: PhantomData })
     }
@@ -69,47 +126,125 @@ where
         &self.topics_qos
     }
 }
+impl<'a> Subscribe<&'a [(&'a [u8], u8)], &'a [u8]> {
+    /// Creates a new encode-only packet that borrows the given `(topic, qos)`-tuples
+    ///
+    /// # Note
+    /// No bytes are copied; the packet can be encoded by reference, but it cannot be decoded into.
+    pub fn new_ref(packet_id: u16, topics_qos: &'a [(&'a [u8], u8)]) -> Result<Self, &'static str> {
+        // Ensure that the packet can be encoded
+        let len = Length::new().u16(&packet_id).try_topics_qos(&topics_qos)?.into();
+        Length::new().try_packetlen(&len)?;
+
+        // Init self
+        Ok(Self { packet_id, topics_qos, _vec: PhantomData })
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Seq, Bytes> defmt::Format for Subscribe<Seq, Bytes>
+where
+    Seq: AsRef<[(Bytes, u8)]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the amount of topics
+        let topics = self.topics_qos.as_ref().len();
+        defmt::write!(f, "Subscribe {{ packet_id: {=u16}, topics: {=usize} }}", self.packet_id, topics)
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, Seq, Bytes> arbitrary::Arbitrary<'a> for Subscribe<Seq, Bytes>
+where
+    Seq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::packets::arbitrary_topic;
+
+        // Generate at least one and at most `MAX_TOPICS` topic-qos pairs
+        let mut topics_qos = Seq::default();
+        for _ in 0..u.arbitrary_len::<(&[u8], u8)>()?.clamp(1, Self::MAX_TOPICS) {
+            let topic = arbitrary_topic(u, true)?;
+            let qos = u.int_in_range(0..=2)?;
+            topics_qos.push((topic, qos)).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+
+        // Generate a non-zero packet ID and ensure that the packet can be encoded
+        let packet_id = u.int_in_range(1..=u16::MAX)?;
+        let len = Length::new().u16(&packet_id).topics_qos(&topics_qos).into();
+        Length::new().try_packetlen(&len).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        Ok(Self { packet_id, topics_qos, _vec: PhantomData })
+    }
+}
 impl<Seq, Bytes> TryFromIterator for Subscribe<Seq, Bytes>
 where
     Seq: AnyVec<(Bytes, u8)>,
     Bytes: AnyVec<u8>,
 {
-    fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
     where
         T: IntoIterator<Item = u8>,
     {
-        // Read packet:
+        Self::try_from_iter_with_limits(iter, Self::MAX_TOPICS)
+    }
+}
+impl<Seq, Bytes> IntoIterator for Subscribe<Seq, Bytes>
+where
+    Seq: AsRef<[(Bytes, u8)]> + IntoIterator<Item = (Bytes, u8)>,
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    #[rustfmt::skip]
+    type IntoIter =
+        // Complex iterator built out of the individual message fields
+        SizedIter<Chain<Chain<Chain<Chain<
+            // - header type and `2` flags
+            Unit, U8Iter>,
+            // - packet len
+            PacketLenIter>,
+            // - packet ID
+            U16Iter>,
+            // - sequence
+            //    - topic filter
+            //    - qos
+            TopicsQosIter<Seq, Bytes>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Precompute body length:
+        //  - packet ID
+        //  - sequence
+        //     - topic filter
+        //     - qos
+        #[rustfmt::skip]
+        let len = Length::new()
+            .u16(&self.packet_id)
+            .topics_qos(&self.topics_qos)
+            .into();
+
+        // Write packet:
         //  - header type and `2` flags
         //  - packet len
         //  - packet ID
         //  - sequence
         //     - topic filter
         //     - qos
-        let mut decoder = Decoder::new(iter);
-        let (Self::TYPE, [false, false, true, false]) = decoder.header()? else {
-            return Err("Invalid packet type/header");
-        };
-        // Limit length and make decoder peekable
-        let len = decoder.packetlen()?;
-        let mut decoder = decoder.limit(len).peekable();
-        // Read fields
-        let packet_id = decoder.u16()?;
-        let topics_qos = decoder.topics_qos()?;
-
-        // Init self
-        Ok(Self { packet_id, topics_qos, _vec: PhantomData })
+        Encoder::default()
+            .typed_header(PacketType::Subscribe, [false, false, true, false])
+            .packetlen(len)
+            .u16(self.packet_id)
+            .topics_qos(self.topics_qos)
+            .into_sized_iter(len)
     }
 }
-impl<Seq, Bytes> IntoIterator for Subscribe<Seq, Bytes>
+impl<'a, Seq, Bytes> IntoIterator for &'a Subscribe<Seq, Bytes>
 where
-    Seq: AnyVec<(Bytes, u8)>,
-    Bytes: AnyVec<u8>,
+    Seq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
 {
     type Item = u8;
     #[rustfmt::skip]
     type IntoIter =
         // Complex iterator built out of the individual message fields
-        Chain<Chain<Chain<Chain<
+        SizedIter<Chain<Chain<Chain<Chain<
             // - header type and `2` flags
             Unit, U8Iter>,
             // - packet len
@@ -119,9 +254,17 @@ where
             // - sequence
             //    - topic filter
             //    - qos
-            TopicsQosIter<Seq, Bytes>>;
+            TopicsQosIter<TopicsQosRef<'a, Bytes>, BytesRef<'a>>>>;
 
     fn into_iter(self) -> Self::IntoIter {
+        /// Static helper function for `map` so that the iterator doesn't capture state
+        fn borrow<Bytes>((topic, qos): &(Bytes, u8)) -> (BytesRef<'_>, u8)
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            (BytesRef::new(topic.as_ref()), *qos)
+        }
+
         // Precompute body length:
         //  - packet ID
         //  - sequence
@@ -133,6 +276,10 @@ where
             .topics_qos(&self.topics_qos)
             .into();
 
+        // Borrow the sequence
+        let borrow_fn: fn(&'a (Bytes, u8)) -> (BytesRef<'a>, u8) = borrow::<Bytes>;
+        let topics_qos = self.topics_qos.as_ref().iter().map(borrow_fn);
+
         // Write packet:
         //  - header type and `2` flags
         //  - packet len
@@ -141,10 +288,10 @@ where
         //     - topic filter
         //     - qos
         Encoder::default()
-            .header(Self::TYPE, [false, false, true, false])
+            .typed_header(PacketType::Subscribe, [false, false, true, false])
             .packetlen(len)
             .u16(self.packet_id)
-            .topics_qos(self.topics_qos)
-            .into_iter()
+            .topics_qos(topics_qos)
+            .into_sized_iter(len)
     }
 }
@@ -3,16 +3,18 @@
 use crate::{
     anyvec::AnyVec,
     coding::{
-        encoder::{PacketLenIter, TopicsIter, U16Iter, U8Iter, Unit},
+        encoder::{BytesRef, PacketLenIter, SizedIter, TopicsIter, TopicsRef, U16Iter, U8Iter, Unit},
         length::Length,
         Decoder, Encoder,
     },
-    packets::TryFromIterator,
+    error::{DecoderError, Field},
+    packets::{packet::PacketType, TryFromIterator},
+    topic,
 };
 use core::{iter::Chain, marker::PhantomData};
 
 /// An MQTT [`UNSUBSCRIBE` packet](https://docs.oasis-open.org/mqtt/mqtt/v3.1.1/os/mqtt-v3.1.1-os.html#_Toc398718072)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Unsubscribe<Seq, Bytes> {
     /// The packet ID
     packet_id: u16,
@@ -21,14 +23,17 @@ pub struct Unsubscribe<Seq, Bytes> {
     /// The byte vector type
     _vec: PhantomData<Bytes>,
 }
+impl<Seq, Bytes> Unsubscribe<Seq, Bytes> {
+    /// The packet type constant
+    pub const TYPE: u8 = 10;
+    /// The default limit for the amount of topics per decoded packet
+    pub const MAX_TOPICS: usize = 256;
+}
 impl<Seq, Bytes> Unsubscribe<Seq, Bytes>
 where
     Seq: AnyVec<Bytes>,
     Bytes: AnyVec<u8>,
 {
-    /// The packet type constant
-    pub const TYPE: u8 = 10;
-
     /// Creates a new packet
     pub fn new<S, T>(packet_id: u16, topics: S) -> Result<Self, &'static str>
     where
@@ -38,14 +43,57 @@ where
         // Collect all topic-qos pairs
         let mut topics_ = Seq::default();
         for topic in topics {
-            // Copy topic and append pair
+            // Ensure that the topic can be encoded, copy it and append pair
+            Length::new().try_bytes(&topic)?;
             let topic = Bytes::new(topic.as_ref())?;
             topics_.push(topic)?;
         }
 
+        // Ensure that the packet can be encoded
+        let len = Length::new().u16(&packet_id).topics(&topics_).into();
+        Length::new().try_packetlen(&len)?;
+
         // Init self
         Ok(Self { packet_id, topics: topics_, _vec: PhantomData })
     }
+    /// Tries to build `Self` from the given byte iterator, accepting at most `max_topics` topics
+    ///
+    /// # Note
+    /// [`TryFromIterator::try_from_iter`] uses [`Self::MAX_TOPICS`] as limit to avoid excessive allocations on
+    /// unbounded backends.
+    pub fn try_from_iter_with_limits<T>(iter: T, max_topics: usize) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read packet:
+        //  - header type and `2` flags
+        //  - packet len
+        //  - packed ID
+        //  - sequence
+        //     - topic filter
+        let mut decoder = Decoder::new(iter);
+        let (type_, flags) = decoder.header()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+        };
+        let [false, false, true, false] = flags else {
+            return Err(err!(SpecViolation, "Invalid packet header", rule "MQTT-3.10.1-1"));
+        };
+        // Limit length and make decoder peekable
+        let len = decoder.packetlen()?;
+        let mut decoder = decoder.limit(len).peekable();
+        // Read fields
+        let packet_id = decoder.u16().map_err(|e| e.context("packet identifier"))?;
+        let start = decoder.position();
+        let topics: Seq =
+            decoder.topics_capped(max_topics).map_err(|e| Field::Topics.tag(e).context("topic filters"))?;
+        let filters = topics.as_ref().iter().map(|topic| topic.as_ref());
+        topic::validate_filters(filters, start, 0).map_err(|e| e.context("topic filters"))?;
+        log_trace!("decoded UNSUBSCRIBE (packet ID: {}, topics: {})", packet_id, topics.as_ref().len());
+
+        // Init self
+        Ok(Self { packet_id, topics, _vec: PhantomData })
+    }
 
     /// The packet ID
     pub fn packet_id(&self) -> u16 {
@@ -57,46 +105,107 @@ where
         &self.topics
     }
 }
+#[cfg(feature = "defmt")]
+impl<Seq, Bytes> defmt::Format for Unsubscribe<Seq, Bytes>
+where
+    Seq: AsRef<[Bytes]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the amount of topics
+        let topics = self.topics.as_ref().len();
+        defmt::write!(f, "Unsubscribe {{ packet_id: {=u16}, topics: {=usize} }}", self.packet_id, topics)
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a, Seq, Bytes> arbitrary::Arbitrary<'a> for Unsubscribe<Seq, Bytes>
+where
+    Seq: AnyVec<Bytes>,
+    Bytes: AnyVec<u8>,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        use crate::packets::arbitrary_topic;
+
+        // Generate at least one and at most `MAX_TOPICS` topics
+        let mut topics = Seq::default();
+        for _ in 0..u.arbitrary_len::<&[u8]>()?.clamp(1, Self::MAX_TOPICS) {
+            let topic = arbitrary_topic(u, true)?;
+            topics.push(topic).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+
+        // Generate a non-zero packet ID and ensure that the packet can be encoded
+        let packet_id = u.int_in_range(1..=u16::MAX)?;
+        let len = Length::new().u16(&packet_id).topics(&topics).into();
+        Length::new().try_packetlen(&len).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        Ok(Self { packet_id, topics, _vec: PhantomData })
+    }
+}
 impl<Seq, Bytes> TryFromIterator for Unsubscribe<Seq, Bytes>
 where
     Seq: AnyVec<Bytes>,
     Bytes: AnyVec<u8>,
 {
-    fn try_from_iter<T>(iter: T) -> Result<Self, &'static str>
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
     where
         T: IntoIterator<Item = u8>,
     {
-        // Read packet:
+        Self::try_from_iter_with_limits(iter, Self::MAX_TOPICS)
+    }
+}
+impl<Seq, Bytes> IntoIterator for Unsubscribe<Seq, Bytes>
+where
+    Seq: AsRef<[Bytes]> + IntoIterator<Item = Bytes>,
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    #[rustfmt::skip]
+    type IntoIter =
+        // Complex iterator built out of the individual message fields
+        SizedIter<Chain<Chain<Chain<Chain<
+            // - header type and `2` flags
+            Unit, U8Iter>,
+            // - packet len
+            PacketLenIter>,
+            // - packed ID
+            U16Iter>,
+            // - sequence
+            //    - topic filter
+            TopicsIter<Seq, Bytes>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Precompute body length:
+        //  - packet ID
+        //  - sequence
+        //     - topic filter
+        #[rustfmt::skip]
+        let len = Length::new()
+            .u16(&self.packet_id)
+            .topics(&self.topics)
+            .into();
+
+        // Write packet:
         //  - header type and `2` flags
         //  - packet len
         //  - packed ID
         //  - sequence
         //     - topic filter
-        let mut decoder = Decoder::new(iter);
-        let (Self::TYPE, [false, false, true, false]) = decoder.header()? else {
-            return Err("Invalid packet type/header");
-        };
-        // Limit length and make decoder peekable
-        let len = decoder.packetlen()?;
-        let mut decoder = decoder.limit(len).peekable();
-        // Read fields
-        let packet_id = decoder.u16()?;
-        let topics = decoder.topics()?;
-
-        // Init self
-        Ok(Self { packet_id, topics, _vec: PhantomData })
+        Encoder::default()
+            .typed_header(PacketType::Unsubscribe, [false, false, true, false])
+            .packetlen(len)
+            .u16(self.packet_id)
+            .topics(self.topics)
+            .into_sized_iter(len)
     }
 }
-impl<Seq, Bytes> IntoIterator for Unsubscribe<Seq, Bytes>
+impl<'a, Seq, Bytes> IntoIterator for &'a Unsubscribe<Seq, Bytes>
 where
-    Seq: AnyVec<Bytes>,
-    Bytes: AnyVec<u8>,
+    Seq: AsRef<[Bytes]>,
+    Bytes: AsRef<[u8]>,
 {
     type Item = u8;
     #[rustfmt::skip]
     type IntoIter =
         // Complex iterator built out of the individual message fields
-        Chain<Chain<Chain<Chain<
+        SizedIter<Chain<Chain<Chain<Chain<
             // - header type and `2` flags
             Unit, U8Iter>,
             // - packet len
@@ -105,9 +214,17 @@ where
             U16Iter>,
             // - sequence
             //    - topic filter
-            TopicsIter<Seq, Bytes>>;
+            TopicsIter<TopicsRef<'a, Bytes>, BytesRef<'a>>>>;
 
     fn into_iter(self) -> Self::IntoIter {
+        /// Static helper function for `map` so that the iterator doesn't capture state
+        fn borrow<Bytes>(topic: &Bytes) -> BytesRef<'_>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            BytesRef::new(topic.as_ref())
+        }
+
         // Precompute body length:
         //  - packet ID
         //  - sequence
@@ -118,6 +235,10 @@ where
             .topics(&self.topics)
             .into();
 
+        // Borrow the sequence
+        let borrow_fn: fn(&'a Bytes) -> BytesRef<'a> = borrow::<Bytes>;
+        let topics = self.topics.as_ref().iter().map(borrow_fn);
+
         // Write packet:
         //  - header type and `2` flags
         //  - packet len
@@ -125,10 +246,10 @@ where
         //  - sequence
         //     - topic filter
         Encoder::default()
-            .header(Self::TYPE, [false, false, true, false])
+            .typed_header(PacketType::Unsubscribe, [false, false, true, false])
             .packetlen(len)
             .u16(self.packet_id)
-            .topics(self.topics)
-            .into_iter()
+            .topics(topics)
+            .into_sized_iter(len)
     }
 }
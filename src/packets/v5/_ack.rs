@@ -0,0 +1,550 @@
+/// Defines an MQTT 5 ACK-like packet (i.e. a response packet with a 16bit packet-ID field, an optional reason code and
+/// optional properties)
+#[rustfmt::skip]
+macro_rules! acklike_v5 {
+    ($docstr:expr, $type:ident => $packettype:ident) => {
+        use $crate::{
+            anyvec::AnyVec,
+            coding::{
+                encoder::{BytesRef, OptionalU8Iter, PacketLenIter, PropertiesIter, SizedIter, U16Iter, U8Iter, Unit},
+                length::Length,
+                Decoder, Encoder,
+            },
+            error::DecoderError,
+            packets::{
+                packet::PacketType,
+                v5::{
+                    properties::{id, Properties, Value},
+                    reason::ReasonCode,
+                    user_properties::UserProperties,
+                },
+                TryFromIterator,
+            },
+        };
+        use core::iter::Chain;
+
+        #[doc = $docstr]
+        ///
+        /// # Note
+        /// The reason code and properties are optional on the wire; an absent reason code is decoded as
+        /// [`ReasonCode::Success`], and the packet is always encoded in the shortest legal form.
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        pub struct $type<Bytes> {
+            /// The packet identifier
+            packet_id: u16,
+            /// The reason code
+            reason_code: ReasonCode,
+            /// The packet properties
+            properties: Properties<Bytes>,
+        }
+        impl<Bytes> $type<Bytes> {
+            /// The packet type constant
+            pub const TYPE: u8 = PacketType::$packettype as u8;
+
+            /// The packet ID
+            pub const fn packet_id(&self) -> u16 {
+                self.packet_id
+            }
+            /// The reason code, which defaults to [`ReasonCode::Success`] if it is absent on the wire
+            pub const fn reason_code(&self) -> ReasonCode {
+                self.reason_code
+            }
+        }
+        impl<Bytes> $type<Bytes>
+        where
+            Bytes: AnyVec<u8>,
+        {
+            /// Creates a new packet with a [`ReasonCode::Success`] reason code and no properties
+            pub fn new(packet_id: u16) -> Self {
+                Self { packet_id, reason_code: ReasonCode::Success, properties: Properties::new() }
+            }
+            /// Sets the reason code
+            pub fn with_reason_code(mut self, reason_code: ReasonCode) -> Self {
+                self.reason_code = reason_code;
+                self
+            }
+            /// Appends a property (see [`Properties::push`])
+            pub fn with_property(mut self, id: u8, value: Value) -> Result<Self, &'static str> {
+                self.properties.push(id, value)?;
+                Ok(self)
+            }
+            /// Appends the given user properties, preserving their order
+            pub fn with_user_properties<Seq, T>(mut self, user_properties: &UserProperties<Seq, T>) -> Result<Self, &'static str>
+            where
+                Seq: AsRef<[(T, T)]>,
+                T: AsRef<[u8]>,
+            {
+                user_properties.append_to(&mut self.properties)?;
+                Ok(self)
+            }
+        }
+        impl<Bytes> $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            /// The packet properties
+            pub const fn properties(&self) -> &Properties<Bytes> {
+                &self.properties
+            }
+            /// The human readable reason string
+            pub fn reason_string(&self) -> Option<&[u8]> {
+                self.properties.get_bytes(id::REASON_STRING)
+            }
+            /// The user properties as `(key, value)` tuples
+            pub fn user_properties(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+                self.properties.user_properties()
+            }
+        }
+        impl<Bytes> core::fmt::Debug for $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($type))
+                    .field("packet_id", &self.packet_id)
+                    .field("reason_code", &self.reason_code)
+                    .field("properties", &self.properties)
+                    .finish()
+            }
+        }
+        #[cfg(feature = "defmt")]
+        impl<Bytes> defmt::Format for $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            fn format(&self, f: defmt::Formatter<'_>) {
+                defmt::write!(
+                    f,
+                    "{=str} {{ packet_id: {=u16}, reason_code: {}, properties: {} }}",
+                    stringify!($type),
+                    self.packet_id,
+                    self.reason_code,
+                    self.properties
+                )
+            }
+        }
+        impl<Bytes> TryFromIterator for $type<Bytes>
+        where
+            Bytes: AnyVec<u8>,
+        {
+            fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+            where
+                T: IntoIterator<Item = u8>,
+            {
+                // Read packet:
+                //  - header type and `0` flags
+                //  - packet len
+                //  - packet ID
+                //  - optional reason code
+                //  - optional properties
+                let mut decoder = Decoder::new(iter);
+                let (type_, _flags) = decoder.header()?;
+                let Self::TYPE = type_ else {
+                    return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+                };
+                let len = decoder.packetlen()?;
+                let 2.. = len else {
+                    return Err(err!(SpecViolation, "Invalid packet length").with_offset(1));
+                };
+                // Limit length
+                let mut decoder = decoder.take_exact(len);
+                // Read fields
+                let packet_id = decoder.u16().map_err(|e| e.context("packet identifier"))?;
+                let reason_code = match decoder.remaining() {
+                    0 => ReasonCode::Success,
+                    _ => decoder.u8().map(ReasonCode::from_u8).map_err(|e| e.context("reason code"))?,
+                };
+                let properties = match decoder.remaining() {
+                    0 => Properties::new(),
+                    _ => Properties::read(&mut decoder).map_err(|e| e.context("properties"))?,
+                };
+                decoder.finish()?;
+                log_trace!(
+                    "decoded {} v5 (packet ID: {}, reason code: {})",
+                    PacketType::$packettype.name(),
+                    packet_id,
+                    reason_code.to_u8()
+                );
+
+                // Init self
+                Ok(Self { packet_id, reason_code, properties })
+            }
+        }
+        impl<Bytes> IntoIterator for $type<Bytes>
+        where
+            Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+        {
+            type Item = u8;
+            #[rustfmt::skip]
+            type IntoIter =
+                // Complex iterator built out of the individual message fields
+                SizedIter<Chain<Chain<Chain<Chain<Chain<
+                    // - header type and `0` flags
+                    Unit, U8Iter>,
+                    // - packet len
+                    PacketLenIter>,
+                    // - packet ID
+                    U16Iter>,
+                    // - optional reason code
+                    OptionalU8Iter>,
+                    // - optional properties
+                    PropertiesIter<Properties<Bytes>>>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                // Select the shortest form: the reason code may be omitted if it is `0x00` and there are no properties
+                let properties = match self.properties.is_empty() {
+                    true => None,
+                    false => Some(self.properties),
+                };
+                let reason_code = match (self.reason_code, &properties) {
+                    (ReasonCode::Success, None) => None,
+                    (reason_code, _) => Some(reason_code.to_u8()),
+                };
+
+                // Precompute body length:
+                //  - packet ID
+                //  - optional reason code
+                //  - optional properties
+                let len =
+                    Length::new().u16(&self.packet_id).optional_u8(&reason_code).optional_properties(&properties).into();
+
+                // Write packet:
+                //  - header type and `0` flags
+                //  - packet len
+                //  - packet ID
+                //  - optional reason code
+                //  - optional properties
+                Encoder::default()
+                    .typed_header(PacketType::$packettype, [false, false, false, false])
+                    .packetlen(len)
+                    .u16(self.packet_id)
+                    .optional_u8(reason_code)
+                    .optional_properties(properties)
+                    .into_sized_iter(len)
+            }
+        }
+        impl<'a, Bytes> IntoIterator for &'a $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            type Item = u8;
+            type IntoIter = <$type<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+            fn into_iter(self) -> Self::IntoIter {
+                // Encode a borrowed view of the packet
+                $type { packet_id: self.packet_id, reason_code: self.reason_code, properties: self.properties.to_ref() }
+                    .into_iter()
+            }
+        }
+    };
+}
+
+/// Defines an MQTT 5 subscription ACK-like packet (i.e. a response packet with a 16bit packet-ID field, properties and
+/// one reason code per topic filter)
+#[rustfmt::skip]
+macro_rules! subacklike_v5 {
+    ($docstr:expr, $type:ident => $packettype:ident) => {
+        use $crate::{
+            anyvec::AnyVec,
+            coding::{
+                encoder::{BytesRef, PacketLenIter, PropertiesIter, SizedIter, U16Iter, U8Iter, Unit},
+                length::Length,
+                Decoder, Encoder,
+            },
+            error::{DecoderError, Field},
+            packets::{
+                packet::PacketType,
+                v5::{
+                    properties::{id, Properties, Value},
+                    reason::ReasonCode,
+                    user_properties::UserProperties,
+                },
+                TryFromIterator,
+            },
+        };
+        use core::iter::Chain;
+
+        #[doc = $docstr]
+        ///
+        /// # Note
+        /// The packet carries one reason code per topic filter of the request, in the same order as the filters.
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        pub struct $type<Bytes> {
+            /// The packet identifier
+            packet_id: u16,
+            /// The packet properties
+            properties: Properties<Bytes>,
+            /// The raw reason codes
+            reason_codes: Bytes,
+        }
+        impl<Bytes> $type<Bytes> {
+            /// The packet type constant
+            pub const TYPE: u8 = PacketType::$packettype as u8;
+
+            /// The packet ID
+            pub const fn packet_id(&self) -> u16 {
+                self.packet_id
+            }
+        }
+        impl<Bytes> $type<Bytes>
+        where
+            Bytes: AnyVec<u8>,
+        {
+            /// Creates a new packet with the given per-filter reason codes
+            pub fn new<T>(packet_id: u16, reason_codes: T) -> Result<Self, &'static str>
+            where
+                T: IntoIterator<Item = ReasonCode>,
+            {
+                // Collect the reason codes
+                let mut reason_codes_ = Bytes::default();
+                reason_codes_.extend_from_iter(reason_codes.into_iter().map(|reason_code| reason_code.to_u8()))?;
+                if reason_codes_.as_ref().is_empty() {
+                    return Err("Missing reason codes");
+                }
+
+                // Ensure that the packet can be encoded (reserving space for an empty properties block)
+                let len = Length::new().u16(&packet_id).u8(&0).raw(&reason_codes_).into();
+                Length::new().try_packetlen(&len)?;
+                Ok(Self { packet_id, properties: Properties::new(), reason_codes: reason_codes_ })
+            }
+            /// Appends a property (see [`Properties::push`])
+            pub fn with_property(mut self, id: u8, value: Value) -> Result<Self, &'static str> {
+                self.properties.push(id, value)?;
+                Ok(self)
+            }
+            /// Appends the given user properties, preserving their order
+            pub fn with_user_properties<Seq, T>(mut self, user_properties: &UserProperties<Seq, T>) -> Result<Self, &'static str>
+            where
+                Seq: AsRef<[(T, T)]>,
+                T: AsRef<[u8]>,
+            {
+                user_properties.append_to(&mut self.properties)?;
+                Ok(self)
+            }
+            /// Sets the human readable reason string
+            pub fn with_reason_string<T>(self, reason: T) -> Result<Self, &'static str>
+            where
+                T: AsRef<[u8]>,
+            {
+                self.with_property(id::REASON_STRING, Value::Utf8(reason.as_ref()))
+            }
+            /// Appends a user property
+            pub fn with_user_property<K, V>(self, key: K, value: V) -> Result<Self, &'static str>
+            where
+                K: AsRef<[u8]>,
+                V: AsRef<[u8]>,
+            {
+                self.with_property(id::USER_PROPERTY, Value::Pair(key.as_ref(), value.as_ref()))
+            }
+        }
+        impl<Bytes> $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            /// The per-filter reason codes
+            pub fn reason_codes(&self) -> impl ExactSizeIterator<Item = ReasonCode> + '_ {
+                self.reason_codes.as_ref().iter().map(|code| ReasonCode::from_u8(*code))
+            }
+            /// The raw per-filter reason codes
+            pub fn reason_codes_raw(&self) -> &[u8] {
+                self.reason_codes.as_ref()
+            }
+            /// Whether the requests for all topic filters succeeded or not
+            pub fn all_succeeded(&self) -> bool {
+                self.reason_codes().all(|reason_code| reason_code.is_success())
+            }
+
+            /// The packet properties
+            pub const fn properties(&self) -> &Properties<Bytes> {
+                &self.properties
+            }
+            /// The human readable reason string
+            pub fn reason_string(&self) -> Option<&[u8]> {
+                self.properties.get_bytes(id::REASON_STRING)
+            }
+            /// The user properties as `(key, value)` tuples
+            pub fn user_properties(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+                self.properties.user_properties()
+            }
+        }
+        impl<Bytes> core::fmt::Debug for $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($type))
+                    .field("packet_id", &self.packet_id)
+                    .field("properties", &self.properties)
+                    .field("reason_codes", &self.reason_codes.as_ref())
+                    .finish()
+            }
+        }
+        #[cfg(feature = "defmt")]
+        impl<Bytes> defmt::Format for $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            fn format(&self, f: defmt::Formatter<'_>) {
+                defmt::write!(
+                    f,
+                    "{=str} {{ packet_id: {=u16}, properties: {}, reason_codes: {=[u8]} }}",
+                    stringify!($type),
+                    self.packet_id,
+                    self.properties,
+                    self.reason_codes.as_ref()
+                )
+            }
+        }
+        impl<Bytes> TryFromIterator for $type<Bytes>
+        where
+            Bytes: AnyVec<u8>,
+        {
+            fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+            where
+                T: IntoIterator<Item = u8>,
+            {
+                // Read packet:
+                //  - header type and `0` flags
+                //  - packet len
+                //  - packet ID
+                //  - properties
+                //  - reason codes
+                let mut decoder = Decoder::new(iter);
+                let (type_, _flags) = decoder.header()?;
+                let Self::TYPE = type_ else {
+                    return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+                };
+                // Limit length
+                let len = decoder.packetlen()?;
+                let mut decoder = decoder.take_exact(len);
+                // Read fields
+                let packet_id = decoder.u16().map_err(|e| e.context("packet identifier"))?;
+                let properties = Properties::read(&mut decoder).map_err(|e| e.context("properties"))?;
+                let codes_offset = decoder.position();
+                let reason_codes: Bytes =
+                    decoder.raw_remainder().map_err(|e| Field::ReasonCodes.tag(e).context("reason codes"))?;
+                decoder.finish()?;
+                if reason_codes.as_ref().is_empty() {
+                    let error = err!(SpecViolation, "Missing reason codes").with_offset(codes_offset);
+                    return Err(error.context("reason codes"));
+                }
+                log_trace!(
+                    "decoded {} v5 (packet ID: {}, reason codes: {})",
+                    PacketType::$packettype.name(),
+                    packet_id,
+                    reason_codes.as_ref().len()
+                );
+
+                // Init self
+                Ok(Self { packet_id, properties, reason_codes })
+            }
+        }
+        impl<Bytes> IntoIterator for $type<Bytes>
+        where
+            Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+        {
+            type Item = u8;
+            #[rustfmt::skip]
+            type IntoIter =
+                // Complex iterator built out of the individual message fields
+                SizedIter<Chain<Chain<Chain<Chain<Chain<
+                    // - header type and `0` flags
+                    Unit, U8Iter>,
+                    // - packet len
+                    PacketLenIter>,
+                    // - packet ID
+                    U16Iter>,
+                    // - properties
+                    PropertiesIter<Properties<Bytes>>>,
+                    // - reason codes
+                    <Bytes as IntoIterator>::IntoIter>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                // Precompute body length:
+                //  - packet ID
+                //  - properties
+                //  - reason codes
+                let len = Length::new().u16(&self.packet_id).properties(&self.properties).raw(&self.reason_codes).into();
+
+                // Write packet:
+                //  - header type and `0` flags
+                //  - packet len
+                //  - packet ID
+                //  - properties
+                //  - reason codes
+                Encoder::default()
+                    .typed_header(PacketType::$packettype, [false, false, false, false])
+                    .packetlen(len)
+                    .u16(self.packet_id)
+                    .properties(self.properties)
+                    .raw(self.reason_codes)
+                    .into_sized_iter(len)
+            }
+        }
+        impl<'a, Bytes> IntoIterator for &'a $type<Bytes>
+        where
+            Bytes: AsRef<[u8]>,
+        {
+            type Item = u8;
+            type IntoIter = <$type<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+            fn into_iter(self) -> Self::IntoIter {
+                // Encode a borrowed view of the packet
+                $type {
+                    packet_id: self.packet_id,
+                    properties: self.properties.to_ref(),
+                    reason_codes: BytesRef::new(self.reason_codes.as_ref()),
+                }
+                .into_iter()
+            }
+        }
+    };
+}
+
+pub mod puback {
+    //! MQTT 5 [`PUBACK`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901121)
+    acklike_v5! {
+        "An MQTT 5 [`PUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901121)",
+        PubackV5 => Puback
+    }
+}
+
+pub mod pubcomp {
+    //! MQTT 5 [`PUBCOMP`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901151)
+    acklike_v5! {
+        "An MQTT 5 [`PUBCOMP` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901151)",
+        PubcompV5 => Pubcomp
+    }
+}
+
+pub mod pubrec {
+    //! MQTT 5 [`PUBREC`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901131)
+    acklike_v5! {
+        "An MQTT 5 [`PUBREC` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901131)",
+        PubrecV5 => Pubrec
+    }
+}
+
+pub mod pubrel {
+    //! MQTT 5 [`PUBREL`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901141)
+    acklike_v5! {
+        "An MQTT 5 [`PUBREL` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901141)",
+        PubrelV5 => Pubrel
+    }
+}
+
+pub mod suback {
+    //! MQTT 5 [`SUBACK`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901171)
+    subacklike_v5! {
+        "An MQTT 5 [`SUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901171)",
+        SubackV5 => Suback
+    }
+}
+
+pub mod unsuback {
+    //! MQTT 5 [`UNSUBACK`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901187)
+    subacklike_v5! {
+        "An MQTT 5 [`UNSUBACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901187)",
+        UnsubackV5 => Unsuback
+    }
+}
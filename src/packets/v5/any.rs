@@ -0,0 +1,293 @@
+//! A version-agnostic type-erased MQTT packet
+
+use crate::{
+    anyvec::AnyVec,
+    coding::encoder::BytesRef,
+    error::DecoderError,
+    packets::{
+        packet::{Packet, PacketIter, PacketRefIter, PacketType, ProtocolVersion},
+        v5::packet::{PacketV5, PacketV5Iter},
+        TryFromIterator,
+    },
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+};
+
+/// A version-agnostic type-erased MQTT packet
+///
+/// # Note
+/// MQTT 3.1.1 and MQTT 5 packets share their packet types but not their wire format, so decoding requires the
+/// negotiated protocol version (see [`AnyPacket::try_from_iter_versioned`]). There are no conversions between MQTT
+/// 3.1.1 and MQTT 5 packets; to encode a packet for a session, use [`AnyPacket::encode_for`] which rejects packets of
+/// the other version.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum AnyPacket<TopicsSeq, TopicsQosSeq, Bytes> {
+    /// An MQTT 3.1 or 3.1.1 [`Packet`]
+    V3(Packet<TopicsSeq, TopicsQosSeq, Bytes>),
+    /// An MQTT 5 [`PacketV5`]
+    V5(PacketV5<Bytes>),
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> AnyPacket<TopicsSeq, TopicsQosSeq, Bytes> {
+    /// The packet type, or `None` for [`Raw`](crate::packets::raw::Raw) packets
+    pub const fn packet_type(&self) -> Option<PacketType> {
+        match self {
+            Self::V3(this) => this.packet_type(),
+            Self::V5(this) => Some(this.packet_type()),
+        }
+    }
+
+    /// The canonical upper-case packet name (e.g. `"CONNECT"`), or `"RAW"` for [`Raw`](crate::packets::raw::Raw)
+    /// packets
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::V3(this) => this.name(),
+            Self::V5(this) => this.name(),
+        }
+    }
+
+    /// Whether the packet can be sent on a session with the given protocol version
+    pub const fn is_compatible(&self, version: ProtocolVersion) -> bool {
+        matches!(
+            (self, version),
+            (Self::V3(_), ProtocolVersion::V3_1 | ProtocolVersion::V3_1_1) | (Self::V5(_), ProtocolVersion::V5)
+        )
+    }
+
+    /// Gets the MQTT 3.1 or 3.1.1 packet, or returns `self` if the packet is an MQTT 5 packet
+    pub fn into_v3(self) -> Result<Packet<TopicsSeq, TopicsQosSeq, Bytes>, Self> {
+        match self {
+            Self::V3(this) => Ok(this),
+            this => Err(this),
+        }
+    }
+    /// Gets the MQTT 5 packet, or returns `self` if the packet is an MQTT 3.1 or 3.1.1 packet
+    pub fn into_v5(self) -> Result<PacketV5<Bytes>, Self> {
+        match self {
+            Self::V5(this) => Ok(this),
+            this => Err(this),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> AnyPacket<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    /// Tries to build `Self` from the given byte iterator using the wire format of the given protocol version
+    pub fn try_from_iter_versioned<T>(iter: T, version: ProtocolVersion) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        match version {
+            ProtocolVersion::V3_1 | ProtocolVersion::V3_1_1 => Packet::try_from_iter(iter).map(Self::V3),
+            ProtocolVersion::V5 => PacketV5::try_from_iter(iter).map(Self::V5),
+        }
+    }
+
+    /// Encodes the packet for a session with the given protocol version, or fails if the packet is not compatible with
+    /// the protocol version
+    pub fn encode_for(
+        self,
+        version: ProtocolVersion,
+    ) -> Result<AnyPacketIter<TopicsSeq, TopicsQosSeq, Bytes>, &'static str> {
+        match (self, version) {
+            (Self::V3(this), ProtocolVersion::V3_1 | ProtocolVersion::V3_1_1) => {
+                Ok(AnyPacketIter::V3(this.into_iter()))
+            }
+            (Self::V5(this), ProtocolVersion::V5) => Ok(AnyPacketIter::V5(this.into_iter())),
+            _ => Err("Packet does not match the protocol version"),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> AnyPacket<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    /// Encodes the borrowed packet for a session with the given protocol version, or fails if the packet is not
+    /// compatible with the protocol version
+    pub fn encode_ref_for(
+        &self,
+        version: ProtocolVersion,
+    ) -> Result<AnyPacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>, &'static str> {
+        match (self, version) {
+            (Self::V3(this), ProtocolVersion::V3_1 | ProtocolVersion::V3_1_1) => {
+                Ok(AnyPacketRefIter::V3(this.into_iter()))
+            }
+            (Self::V5(this), ProtocolVersion::V5) => Ok(AnyPacketRefIter::V5(this.into_iter())),
+            _ => Err("Packet does not match the protocol version"),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> From<Packet<TopicsSeq, TopicsQosSeq, Bytes>>
+    for AnyPacket<TopicsSeq, TopicsQosSeq, Bytes>
+{
+    fn from(packet: Packet<TopicsSeq, TopicsQosSeq, Bytes>) -> Self {
+        Self::V3(packet)
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> From<PacketV5<Bytes>> for AnyPacket<TopicsSeq, TopicsQosSeq, Bytes> {
+    fn from(packet: PacketV5<Bytes>) -> Self {
+        Self::V5(packet)
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Debug for AnyPacket<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    Packet<TopicsSeq, TopicsQosSeq, Bytes>: Debug,
+    PacketV5<Bytes>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V3(this) => f.debug_tuple("V3").field(this).finish(),
+            Self::V5(this) => f.debug_tuple("V5").field(this).finish(),
+        }
+    }
+}
+#[cfg(feature = "defmt")]
+impl<TopicsSeq, TopicsQosSeq, Bytes> defmt::Format for AnyPacket<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    Packet<TopicsSeq, TopicsQosSeq, Bytes>: defmt::Format,
+    PacketV5<Bytes>: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::V3(this) => defmt::write!(f, "V3({})", this),
+            Self::V5(this) => defmt::write!(f, "V5({})", this),
+        }
+    }
+}
+
+/// A version-agnostic packet-type-erased iterator over the encoded representation
+pub enum AnyPacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    /// An MQTT 3.1 or 3.1.1 packet iterator
+    V3(PacketIter<TopicsSeq, TopicsQosSeq, Bytes>),
+    /// An MQTT 5 packet iterator
+    V5(PacketV5Iter<Bytes>),
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Clone for AnyPacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+    <TopicsSeq as IntoIterator>::IntoIter: Clone,
+    <TopicsQosSeq as IntoIterator>::IntoIter: Clone,
+    <Bytes as IntoIterator>::IntoIter: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::V3(iter) => Self::V3(iter.clone()),
+            Self::V5(iter) => Self::V5(iter.clone()),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Iterator for AnyPacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::V3(iter) => iter.next(),
+            Self::V5(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::V3(iter) => iter.size_hint(),
+            Self::V5(iter) => iter.size_hint(),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> ExactSizeIterator for AnyPacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    // No members to implement
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> FusedIterator for AnyPacketIter<TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AnyVec<Bytes>,
+    TopicsQosSeq: AnyVec<(Bytes, u8)>,
+    Bytes: AnyVec<u8>,
+{
+    // No members to implement
+}
+
+/// A version-agnostic packet-type-erased iterator over the encoded representation of a borrowed packet
+pub enum AnyPacketRefIter<'a, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    /// An MQTT 3.1 or 3.1.1 packet iterator
+    V3(PacketRefIter<'a, TopicsSeq, TopicsQosSeq, Bytes>),
+    /// An MQTT 5 packet iterator
+    V5(PacketV5Iter<BytesRef<'a>>),
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Clone for AnyPacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::V3(iter) => Self::V3(iter.clone()),
+            Self::V5(iter) => Self::V5(iter.clone()),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> Iterator for AnyPacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::V3(iter) => iter.next(),
+            Self::V5(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::V3(iter) => iter.size_hint(),
+            Self::V5(iter) => iter.size_hint(),
+        }
+    }
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> ExactSizeIterator for AnyPacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    // No members to implement
+}
+impl<TopicsSeq, TopicsQosSeq, Bytes> FusedIterator for AnyPacketRefIter<'_, TopicsSeq, TopicsQosSeq, Bytes>
+where
+    TopicsSeq: AsRef<[Bytes]>,
+    TopicsQosSeq: AsRef<[(Bytes, u8)]>,
+    Bytes: AsRef<[u8]>,
+{
+    // No members to implement
+}
@@ -0,0 +1,253 @@
+//! MQTT 5 [`CONNACK`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901074)
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{
+        encoder::{BytesRef, PacketLenIter, PropertiesIter, SizedIter, U8Iter, Unit},
+        length::Length,
+        Decoder, Encoder,
+    },
+    error::DecoderError,
+    packets::{
+        packet::PacketType,
+        v5::{
+            properties::{id, Properties, Value},
+            session::MAX_PACKET_SIZE,
+            user_properties::UserProperties,
+        },
+        TryFromIterator,
+    },
+};
+use core::iter::Chain;
+
+/// An MQTT 5 [`CONNACK` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901074)
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ConnackV5<Bytes> {
+    /// Whether a previous session is present or not
+    session_present: bool,
+    /// The reason code
+    reason_code: u8,
+    /// The connack properties
+    properties: Properties<Bytes>,
+}
+impl<Bytes> ConnackV5<Bytes> {
+    /// The packet type constant
+    pub const TYPE: u8 = 2;
+}
+impl<Bytes> ConnackV5<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    /// Creates a new packet
+    pub fn new(session_present: bool, reason_code: u8) -> Self {
+        Self { session_present, reason_code, properties: Properties::new() }
+    }
+    /// Appends a connack property (see [`Properties::push`])
+    pub fn with_property(mut self, id: u8, value: Value) -> Result<Self, &'static str> {
+        self.properties.push(id, value)?;
+        Ok(self)
+    }
+    /// Appends the given user properties, preserving their order
+    pub fn with_user_properties<Seq, T>(
+        mut self,
+        user_properties: &UserProperties<Seq, T>,
+    ) -> Result<Self, &'static str>
+    where
+        Seq: AsRef<[(T, T)]>,
+        T: AsRef<[u8]>,
+    {
+        user_properties.append_to(&mut self.properties)?;
+        Ok(self)
+    }
+}
+impl<Bytes> ConnackV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    /// Whether a previous session is present or not
+    pub const fn session_present(&self) -> bool {
+        self.session_present
+    }
+    /// The reason code
+    pub const fn reason_code(&self) -> u8 {
+        self.reason_code
+    }
+
+    /// Gets the connack properties
+    pub const fn properties(&self) -> &Properties<Bytes> {
+        &self.properties
+    }
+    /// Gets the session expiry interval in seconds if it overrides the interval requested by the client
+    pub fn session_expiry_interval(&self) -> Option<u32> {
+        self.properties.get_u32(id::SESSION_EXPIRY_INTERVAL)
+    }
+    /// Gets the maximum amount of concurrent QoS 1 and 2 publications the server is willing to process, which defaults
+    /// to `65535`
+    pub fn receive_maximum(&self) -> u16 {
+        self.properties.get_u16(id::RECEIVE_MAXIMUM).unwrap_or(u16::MAX)
+    }
+    /// Gets the maximum supported QoS level
+    pub fn maximum_qos(&self) -> Option<u8> {
+        self.properties.get_u8(id::MAXIMUM_QOS)
+    }
+    /// Gets whether retained messages are supported
+    pub fn retain_available(&self) -> Option<bool> {
+        self.properties.get_u8(id::RETAIN_AVAILABLE).map(|available| available != 0)
+    }
+    /// Gets the maximum packet size the server is willing to accept, which defaults to [`MAX_PACKET_SIZE`]
+    pub fn maximum_packet_size(&self) -> u32 {
+        self.properties.get_u32(id::MAXIMUM_PACKET_SIZE).unwrap_or(MAX_PACKET_SIZE)
+    }
+    /// Gets the client identifier assigned by the server
+    pub fn assigned_client_identifier(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::ASSIGNED_CLIENT_IDENTIFIER)
+    }
+    /// Gets the highest topic alias the server is willing to accept
+    pub fn topic_alias_maximum(&self) -> Option<u16> {
+        self.properties.get_u16(id::TOPIC_ALIAS_MAXIMUM)
+    }
+    /// Gets the human readable reason string
+    pub fn reason_string(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::REASON_STRING)
+    }
+    /// Gets the user properties as `(key, value)` tuples
+    pub fn user_properties(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.properties.user_properties()
+    }
+    /// Gets the keep alive time in seconds if it overrides the keep alive time requested by the client
+    pub fn server_keep_alive(&self) -> Option<u16> {
+        self.properties.get_u16(id::SERVER_KEEP_ALIVE)
+    }
+    /// Gets the authentication method
+    pub fn authentication_method(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::AUTHENTICATION_METHOD)
+    }
+    /// Gets the authentication data
+    pub fn authentication_data(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::AUTHENTICATION_DATA)
+    }
+}
+impl<Bytes> core::fmt::Debug for ConnackV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ConnackV5")
+            .field("session_present", &self.session_present)
+            .field("reason_code", &self.reason_code)
+            .field("properties", &self.properties)
+            .finish()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for ConnackV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "ConnackV5 {{ session_present: {=bool}, reason_code: {=u8}, properties: {} }}",
+            self.session_present,
+            self.reason_code,
+            self.properties
+        )
+    }
+}
+impl<Bytes> TryFromIterator for ConnackV5<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read packet:
+        //  - header type and `0` flags
+        //  - packet len
+        //  - ACK flags
+        //  - reason code
+        //  - properties
+        let mut decoder = Decoder::new(iter);
+        let (type_, _flags) = decoder.header()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+        };
+        // Limit length
+        let len = decoder.packetlen()?;
+        let mut decoder = decoder.take_exact(len);
+        // Read fields
+        let flags_offset = decoder.position();
+        let flags = decoder.u8().map_err(|e| e.context("acknowledge flags"))?;
+        let 0b0000_0000..=0b0000_0001 = flags else {
+            let error = err!(SpecViolation, "Invalid acknowledge flags", rule "MQTT-3.2.2-1");
+            return Err(error.with_offset(flags_offset).context("acknowledge flags"));
+        };
+        let reason_code = decoder.u8().map_err(|e| e.context("reason code"))?;
+        let properties = Properties::read(&mut decoder).map_err(|e| e.context("properties"))?;
+        decoder.finish()?;
+        log_trace!("decoded CONNACK v5 (session present: {}, reason code: {})", flags == 1, reason_code);
+
+        // Init self
+        Ok(Self { session_present: flags == 1, reason_code, properties })
+    }
+}
+impl<Bytes> IntoIterator for ConnackV5<Bytes>
+where
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    #[rustfmt::skip]
+    type IntoIter =
+        // Complex iterator built out of the individual message fields
+        SizedIter<Chain<Chain<Chain<Chain<Chain<
+            // - header type and `0` flags
+            Unit, U8Iter>,
+            // - packet len
+            PacketLenIter>,
+            // - ACK flags
+            U8Iter>,
+            // - reason code
+            U8Iter>,
+            // - properties
+            PropertiesIter<Properties<Bytes>>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Precompute body length:
+        //  - ACK flags
+        //  - reason code
+        //  - properties
+        let len = Length::new().u8(&0).u8(&self.reason_code).properties(&self.properties).into();
+
+        // Write packet:
+        //  - header type and `0` flags
+        //  - packet len
+        //  - ACK flags
+        //  - reason code
+        //  - properties
+        Encoder::default()
+            .typed_header(PacketType::Connack, [false, false, false, false])
+            .packetlen(len)
+            .bitmap([false, false, false, false, false, false, false, self.session_present])
+            .u8(self.reason_code)
+            .properties(self.properties)
+            .into_sized_iter(len)
+    }
+}
+impl<'a, Bytes> IntoIterator for &'a ConnackV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = <ConnackV5<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Encode a borrowed view of the packet
+        ConnackV5 {
+            session_present: self.session_present,
+            reason_code: self.reason_code,
+            properties: self.properties.to_ref(),
+        }
+        .into_iter()
+    }
+}
@@ -0,0 +1,512 @@
+//! MQTT 5 [`CONNECT`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901033)
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{
+        encoder::{
+            BytesIter, BytesRef, OptionalBytesIter, PacketLenIter, PropertiesIter, SizedIter, SliceIter, U16Iter,
+            U8Iter, Unit,
+        },
+        length::Length,
+        Decoder, Encoder,
+    },
+    error::{DecoderError, Field},
+    packets::{
+        flags::ConnectFlags,
+        packet::PacketType,
+        v5::{
+            properties::{id, Properties, Value},
+            session::MAX_PACKET_SIZE,
+            user_properties::UserProperties,
+        },
+        TryFromIterator,
+    },
+};
+use core::iter::Chain;
+
+/// An MQTT 5 [`CONNECT` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901033)
+///
+/// # Note
+/// To avoid leaking credentials into logs, the `Debug` implementation redacts the will payload and the password and
+/// only displays their length. Enable the `debug-credentials` feature to display them verbatim.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ConnectV5<Bytes> {
+    /// The seconds to keep the connection alive
+    keep_alive_secs: u16,
+    /// Whether the connection starts a new session or is a continuation of an existing session
+    clean_start: bool,
+    /// This bit specifies if the will message is to be Retained when it is published
+    will_retain: bool,
+    /// The QoS level to be used when publishing the will message
+    ///
+    /// # QoS Levels
+    /// Valid QoS levels are:
+    ///  - `0`: At most one delivery
+    ///  - `1`: At least one delivery
+    ///  - `2`: Exactly one delivery
+    will_qos: u8,
+    /// The connect properties
+    properties: Properties<Bytes>,
+    /// The client identifier
+    client_id: Bytes,
+    /// The will properties
+    will_properties: Option<Properties<Bytes>>,
+    /// The will topic
+    will_topic: Option<Bytes>,
+    /// The will payload
+    will_payload: Option<Bytes>,
+    /// The username
+    username: Option<Bytes>,
+    /// The password
+    password: Option<Bytes>,
+}
+impl<Bytes> ConnectV5<Bytes> {
+    /// The packet type constant
+    pub const TYPE: u8 = 1;
+
+    /// The protocol name
+    const PROTOCOL_NAME: [u8; 6] = *b"\x00\x04MQTT";
+    /// The protocol constant for MQTT 5
+    const PROTOCOL_LEVEL_MQTT_5: u8 = 0x05;
+}
+impl<Bytes> ConnectV5<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    /// Creates a new packet
+    pub fn new<T>(keep_alive_secs: u16, clean_start: bool, client_id: T) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&client_id)?;
+
+        // Init self
+        let client_id = Bytes::new(client_id.as_ref())?;
+        Ok(Self {
+            keep_alive_secs,
+            clean_start,
+            will_retain: false,
+            will_qos: 0,
+            properties: Properties::new(),
+            client_id,
+            will_properties: None,
+            will_topic: None,
+            will_payload: None,
+            username: None,
+            password: None,
+        })
+    }
+    /// Appends a connect property (see [`Properties::push`])
+    pub fn with_property(mut self, id: u8, value: Value) -> Result<Self, &'static str> {
+        self.properties.push(id, value)?;
+        Ok(self)
+    }
+    /// Appends the given user properties, preserving their order
+    pub fn with_user_properties<Seq, T>(
+        mut self,
+        user_properties: &UserProperties<Seq, T>,
+    ) -> Result<Self, &'static str>
+    where
+        Seq: AsRef<[(T, T)]>,
+        T: AsRef<[u8]>,
+    {
+        user_properties.append_to(&mut self.properties)?;
+        Ok(self)
+    }
+    /// Configures a last-will topic and payload without will properties
+    ///
+    /// # QoS Levels
+    /// Valid QoS levels are:
+    ///  - `0`: At most one delivery
+    ///  - `1`: At least one delivery
+    ///  - `2`: Exactly one delivery
+    pub fn with_will<T, P>(mut self, topic: T, payload: P, qos: u8, retain: bool) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&topic)?.try_bytes(&payload)?;
+
+        // Configure last-will
+        self.will_properties = Some(Properties::new());
+        self.will_topic = Bytes::new(topic.as_ref()).map(Some)?;
+        self.will_payload = Bytes::new(payload.as_ref()).map(Some)?;
+        self.will_retain = retain;
+        self.will_qos = qos;
+        Ok(self)
+    }
+    /// Appends a will property (see [`Properties::push`]), or fails if no last-will has been configured
+    pub fn with_will_property(mut self, id: u8, value: Value) -> Result<Self, &'static str> {
+        let Some(will_properties) = &mut self.will_properties else {
+            return Err("Last-will is not configured");
+        };
+        will_properties.push(id, value)?;
+        Ok(self)
+    }
+    /// Configures a username and password
+    pub fn with_username_password<U, P>(mut self, username: U, password: P) -> Result<Self, &'static str>
+    where
+        U: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        // Ensure that the packet can be encoded
+        Length::new().try_bytes(&username)?.try_bytes(&password)?;
+
+        // Configure login data
+        self.username = Bytes::new(username.as_ref()).map(Some)?;
+        self.password = Bytes::new(password.as_ref()).map(Some)?;
+        Ok(self)
+    }
+}
+impl<Bytes> ConnectV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    /// Gets the seconds to keep the connection alive
+    pub const fn keep_alive_secs(&self) -> u16 {
+        self.keep_alive_secs
+    }
+
+    /// Gets the clean start bit which indicates if the connection starts a new session
+    pub const fn clean_start(&self) -> bool {
+        self.clean_start
+    }
+
+    /// Gets the connect properties
+    pub const fn properties(&self) -> &Properties<Bytes> {
+        &self.properties
+    }
+    /// Gets the session expiry interval in seconds, which defaults to `0` (i.e. the session ends with the connection)
+    pub fn session_expiry_interval(&self) -> u32 {
+        self.properties.get_u32(id::SESSION_EXPIRY_INTERVAL).unwrap_or(0)
+    }
+    /// Gets the maximum amount of concurrent QoS 1 and 2 publications the client is willing to process, which defaults
+    /// to `65535`
+    pub fn receive_maximum(&self) -> u16 {
+        self.properties.get_u16(id::RECEIVE_MAXIMUM).unwrap_or(u16::MAX)
+    }
+    /// Gets the maximum packet size the client is willing to accept, which defaults to [`MAX_PACKET_SIZE`]
+    pub fn maximum_packet_size(&self) -> u32 {
+        self.properties.get_u32(id::MAXIMUM_PACKET_SIZE).unwrap_or(MAX_PACKET_SIZE)
+    }
+    /// Gets the highest topic alias the client is willing to accept
+    pub fn topic_alias_maximum(&self) -> Option<u16> {
+        self.properties.get_u16(id::TOPIC_ALIAS_MAXIMUM)
+    }
+    /// Gets the user properties as `(key, value)` tuples
+    pub fn user_properties(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.properties.user_properties()
+    }
+    /// Gets the authentication method
+    pub fn authentication_method(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::AUTHENTICATION_METHOD)
+    }
+    /// Gets the authentication data
+    pub fn authentication_data(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::AUTHENTICATION_DATA)
+    }
+
+    /// Gets the client identifier
+    pub fn client_id(&self) -> &[u8] {
+        self.client_id.as_ref()
+    }
+
+    /// Gets the will-retain bit to indicate if the will message is to be Retained when it is published
+    pub const fn will_retain(&self) -> bool {
+        self.will_retain
+    }
+    /// Gets the QoS level to be used when publishing the will message
+    pub const fn will_qos(&self) -> u8 {
+        self.will_qos
+    }
+    /// Gets the will properties
+    pub const fn will_properties(&self) -> Option<&Properties<Bytes>> {
+        self.will_properties.as_ref()
+    }
+    /// Gets the will topic
+    pub fn will_topic(&self) -> Option<&[u8]> {
+        self.will_topic.as_ref().map(|bytes| bytes.as_ref())
+    }
+    /// Gets the will payload
+    pub fn will_payload(&self) -> Option<&[u8]> {
+        self.will_payload.as_ref().map(|bytes| bytes.as_ref())
+    }
+
+    /// Gets the username
+    pub fn username(&self) -> Option<&[u8]> {
+        self.username.as_ref().map(|bytes| bytes.as_ref())
+    }
+    /// Gets the password
+    pub fn password(&self) -> Option<&[u8]> {
+        self.password.as_ref().map(|bytes| bytes.as_ref())
+    }
+}
+impl<Bytes> core::fmt::Debug for ConnectV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        /// A redacted field that only displays its length unless `debug-credentials` is enabled
+        struct Redacted<'a>(&'a [u8]);
+        impl core::fmt::Debug for Redacted<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match cfg!(feature = "debug-credentials") {
+                    true => core::fmt::Debug::fmt(self.0, f),
+                    false => write!(f, "<redacted, {} bytes>", self.0.len()),
+                }
+            }
+        }
+
+        // Redact the will payload and password
+        let will_payload = self.will_payload.as_ref().map(|bytes| Redacted(bytes.as_ref()));
+        let password = self.password.as_ref().map(|bytes| Redacted(bytes.as_ref()));
+        f.debug_struct("ConnectV5")
+            .field("keep_alive_secs", &self.keep_alive_secs)
+            .field("clean_start", &self.clean_start)
+            .field("will_retain", &self.will_retain)
+            .field("will_qos", &self.will_qos)
+            .field("properties", &self.properties)
+            .field("client_id", &self.client_id.as_ref())
+            .field("will_properties", &self.will_properties)
+            .field("will_topic", &self.will_topic.as_ref().map(|bytes| bytes.as_ref()))
+            .field("will_payload", &will_payload)
+            .field("username", &self.username.as_ref().map(|bytes| bytes.as_ref()))
+            .field("password", &password)
+            .finish()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for ConnectV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the length of the byte fields, which also redacts the will payload and password
+        let len = |bytes: &Option<Bytes>| bytes.as_ref().map(|bytes| bytes.as_ref().len());
+        defmt::write!(
+            f,
+            "ConnectV5 {{ keep_alive_secs: {=u16}, clean_start: {=bool}, will_retain: {=bool}, will_qos: {=u8}, properties: {}, client_id_len: {=usize}, will_properties: {}, will_topic_len: {}, will_payload_len: {}, username_len: {}, password_len: {} }}",
+            self.keep_alive_secs,
+            self.clean_start,
+            self.will_retain,
+            self.will_qos,
+            self.properties,
+            self.client_id.as_ref().len(),
+            self.will_properties,
+            len(&self.will_topic),
+            len(&self.will_payload),
+            len(&self.username),
+            len(&self.password)
+        )
+    }
+}
+impl<Bytes> TryFromIterator for ConnectV5<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read packet:
+        //  - header type and `0` flags
+        //  - packet len
+        //  - protocol name
+        //  - protocol level
+        //  - connect flags
+        //  - keep-alive
+        //  - properties
+        //  - client id
+        //  - will properties
+        //  - will topic
+        //  - will payload
+        //  - username
+        //  - password
+        let mut decoder = Decoder::new(iter);
+        let (type_, _flags) = decoder.header()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+        };
+        // Limit length
+        let len = decoder.packetlen()?;
+        let mut decoder = decoder.take_exact(len);
+        // Read protocol name byte-by-byte and version
+        decoder.constant(&Self::PROTOCOL_NAME).map_err(|e| e.context("protocol name"))?;
+        decoder.version_constant(Self::PROTOCOL_LEVEL_MQTT_5).map_err(|e| e.context("protocol level"))?;
+        // Read fields
+        let flags_offset = decoder.position();
+        let flags = decoder.u8().map_err(|e| e.context("connect flags"))?;
+        let flags = ConnectFlags::from_bits(flags).map_err(|e| e.with_offset(flags_offset).context("connect flags"))?;
+        let keep_alive_secs = decoder.u16().map_err(|e| e.context("keep alive"))?;
+        let properties = Properties::read(&mut decoder).map_err(|e| e.context("properties"))?;
+        let client_id: Bytes = decoder.bytes().map_err(|e| Field::ClientId.tag(e).context("client identifier"))?;
+        let will_properties = match flags.will {
+            true => Some(Properties::read(&mut decoder).map_err(|e| e.context("will properties"))?),
+            false => None,
+        };
+        let will_topic =
+            decoder.optional_bytes(flags.will).map_err(|e| Field::WillTopic.tag(e).context("will topic"))?;
+        let will_payload =
+            decoder.optional_bytes(flags.will).map_err(|e| Field::WillMessage.tag(e).context("will payload"))?;
+        let username =
+            decoder.optional_bytes(flags.username).map_err(|e| Field::Username.tag(e).context("user name"))?;
+        let password =
+            decoder.optional_bytes(flags.password).map_err(|e| Field::Password.tag(e).context("password"))?;
+        decoder.finish()?;
+        log_trace!(
+            "decoded CONNECT v5 (client ID: {} bytes, keep-alive: {} s, will: {}, username: {}, password: {})",
+            client_id.as_ref().len(),
+            keep_alive_secs,
+            flags.will,
+            flags.username,
+            flags.password
+        );
+
+        // Init self
+        Ok(Self {
+            keep_alive_secs,
+            clean_start: flags.clean_session,
+            will_retain: flags.will_retain,
+            will_qos: flags.will_qos,
+            properties,
+            client_id,
+            will_properties,
+            will_topic,
+            will_payload,
+            username,
+            password,
+        })
+    }
+}
+impl<Bytes> IntoIterator for ConnectV5<Bytes>
+where
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    #[rustfmt::skip]
+    type IntoIter =
+        // Complex iterator built out of the individual message fields
+        SizedIter<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<Chain<
+            // - header type and `0` flags
+            Unit, U8Iter>,
+            // - packet len
+            PacketLenIter>,
+            // - protocol name
+            SliceIter<'static>>,
+            // - protocol level
+            U8Iter>,
+            // - connect flags
+            U8Iter>,
+            // - keep-alive
+            U16Iter>,
+            // - properties
+            PropertiesIter<Properties<Bytes>>>,
+            // - client id
+            BytesIter<Bytes>>,
+            // - will properties
+            PropertiesIter<Properties<Bytes>>>,
+            // - will topic
+            OptionalBytesIter<Bytes>>,
+            // - will payload
+            OptionalBytesIter<Bytes>>,
+            // - username
+            OptionalBytesIter<Bytes>>,
+            // - password
+            OptionalBytesIter<Bytes>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Assemble protocol name and flags
+        let flags = ConnectFlags {
+            username: self.username.is_some(),
+            password: self.password.is_some(),
+            will_retain: self.will_retain,
+            will_qos: self.will_qos,
+            will: self.will_topic.is_some(),
+            clean_session: self.clean_start,
+        };
+
+        // Precompute body length:
+        //  - protocol name
+        //  - protocol level
+        //  - connect flags
+        //  - keep-alive
+        //  - properties
+        //  - client id
+        //  - will properties
+        //  - will topic
+        //  - will payload
+        //  - username
+        //  - password
+        let len = Length::new()
+            .raw(&Self::PROTOCOL_NAME)
+            .u8(&Self::PROTOCOL_LEVEL_MQTT_5)
+            .u8(&flags.to_bits())
+            .u16(&self.keep_alive_secs)
+            .properties(&self.properties)
+            .bytes(&self.client_id)
+            .optional_properties(&self.will_properties)
+            .optional_bytes(&self.will_topic)
+            .optional_bytes(&self.will_payload)
+            .optional_bytes(&self.username)
+            .optional_bytes(&self.password)
+            .into();
+
+        // Write header:
+        //  - header type and `0` flags
+        //  - packet len
+        //  - protocol name
+        //  - protocol level
+        //  - connect flags
+        //  - keep-alive
+        //  - properties
+        //  - client id
+        //  - will properties
+        //  - will topic
+        //  - will payload
+        //  - username
+        //  - password
+        Encoder::default()
+            .typed_header(PacketType::Connect, [false, false, false, false])
+            .packetlen(len)
+            .raw_slice(&Self::PROTOCOL_NAME)
+            .u8(Self::PROTOCOL_LEVEL_MQTT_5)
+            .u8(flags.to_bits())
+            .u16(self.keep_alive_secs)
+            .properties(self.properties)
+            .bytes(self.client_id)
+            .optional_properties(self.will_properties)
+            .optional_bytes(self.will_topic)
+            .optional_bytes(self.will_payload)
+            .optional_bytes(self.username)
+            .optional_bytes(self.password)
+            .into_sized_iter(len)
+    }
+}
+impl<'a, Bytes> IntoIterator for &'a ConnectV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = <ConnectV5<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Encode a borrowed view of the packet
+        let borrow = |bytes: &'a Option<Bytes>| bytes.as_ref().map(|bytes| BytesRef::new(bytes.as_ref()));
+        ConnectV5 {
+            keep_alive_secs: self.keep_alive_secs,
+            clean_start: self.clean_start,
+            will_retain: self.will_retain,
+            will_qos: self.will_qos,
+            properties: self.properties.to_ref(),
+            client_id: BytesRef::new(self.client_id.as_ref()),
+            will_properties: self.will_properties.as_ref().map(Properties::to_ref),
+            will_topic: borrow(&self.will_topic),
+            will_payload: borrow(&self.will_payload),
+            username: borrow(&self.username),
+            password: borrow(&self.password),
+        }
+        .into_iter()
+    }
+}
@@ -0,0 +1,19 @@
+//! MQTT 5 packet types
+//!
+//! # Note
+//! The packets in this module use protocol level `5` and carry a
+//! [properties block](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901027); they are not part
+//! of the type-erased [`crate::packets::packet::Packet`] but of the type-erased [`packet::PacketV5`] instead. Use
+//! [`any::AnyPacket`] to handle packets of both protocol versions.
+
+pub mod any;
+pub mod connack;
+pub mod connect;
+pub mod packet;
+pub mod properties;
+pub mod publish;
+pub mod reason;
+pub mod session;
+pub mod topic_alias;
+pub mod user_properties;
+include!("_ack.rs");
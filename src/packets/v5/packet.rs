@@ -0,0 +1,271 @@
+//! A type-erased MQTT 5 packet
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{encoder::BytesRef, Decoder},
+    error::DecoderError,
+    packets::{
+        packet::PacketType,
+        v5::{
+            connack::ConnackV5, connect::ConnectV5, puback::PubackV5, pubcomp::PubcompV5, publish::PublishV5,
+            pubrec::PubrecV5, pubrel::PubrelV5, suback::SubackV5, unsuback::UnsubackV5,
+        },
+        TryFromIterator,
+    },
+};
+use core::{
+    cell::Cell,
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+};
+
+/// A type-erased MQTT 5 packet
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum PacketV5<Bytes> {
+    /// A [`ConnackV5`] packet
+    Connack(ConnackV5<Bytes>),
+    /// A [`ConnectV5`] packet
+    Connect(ConnectV5<Bytes>),
+    /// A [`PubackV5`] packet
+    Puback(PubackV5<Bytes>),
+    /// A [`PubcompV5`] packet
+    Pubcomp(PubcompV5<Bytes>),
+    /// A [`PublishV5`] packet
+    Publish(PublishV5<Bytes>),
+    /// A [`PubrecV5`] packet
+    Pubrec(PubrecV5<Bytes>),
+    /// A [`PubrelV5`] packet
+    Pubrel(PubrelV5<Bytes>),
+    /// A [`SubackV5`] packet
+    Suback(SubackV5<Bytes>),
+    /// A [`UnsubackV5`] packet
+    Unsuback(UnsubackV5<Bytes>),
+}
+impl<Bytes> PacketV5<Bytes> {
+    /// The packet type
+    pub const fn packet_type(&self) -> PacketType {
+        match self {
+            Self::Connack(_) => PacketType::Connack,
+            Self::Connect(_) => PacketType::Connect,
+            Self::Puback(_) => PacketType::Puback,
+            Self::Pubcomp(_) => PacketType::Pubcomp,
+            Self::Publish(_) => PacketType::Publish,
+            Self::Pubrec(_) => PacketType::Pubrec,
+            Self::Pubrel(_) => PacketType::Pubrel,
+            Self::Suback(_) => PacketType::Suback,
+            Self::Unsuback(_) => PacketType::Unsuback,
+        }
+    }
+
+    /// The canonical upper-case packet name (e.g. `"CONNECT"`)
+    pub const fn name(&self) -> &'static str {
+        self.packet_type().name()
+    }
+}
+impl<Bytes> Debug for PacketV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connack(this) => f.debug_tuple("Connack").field(this).finish(),
+            Self::Connect(this) => f.debug_tuple("Connect").field(this).finish(),
+            Self::Puback(this) => f.debug_tuple("Puback").field(this).finish(),
+            Self::Pubcomp(this) => f.debug_tuple("Pubcomp").field(this).finish(),
+            Self::Publish(this) => f.debug_tuple("Publish").field(this).finish(),
+            Self::Pubrec(this) => f.debug_tuple("Pubrec").field(this).finish(),
+            Self::Pubrel(this) => f.debug_tuple("Pubrel").field(this).finish(),
+            Self::Suback(this) => f.debug_tuple("Suback").field(this).finish(),
+            Self::Unsuback(this) => f.debug_tuple("Unsuback").field(this).finish(),
+        }
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for PacketV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Delegate to the packets so that the summaries are consistent
+        match self {
+            Self::Connack(this) => defmt::write!(f, "{}", this),
+            Self::Connect(this) => defmt::write!(f, "{}", this),
+            Self::Puback(this) => defmt::write!(f, "{}", this),
+            Self::Pubcomp(this) => defmt::write!(f, "{}", this),
+            Self::Publish(this) => defmt::write!(f, "{}", this),
+            Self::Pubrec(this) => defmt::write!(f, "{}", this),
+            Self::Pubrel(this) => defmt::write!(f, "{}", this),
+            Self::Suback(this) => defmt::write!(f, "{}", this),
+            Self::Unsuback(this) => defmt::write!(f, "{}", this),
+        }
+    }
+}
+impl<Bytes> TryFromIterator for PacketV5<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // We have to peek at the header to determine the type
+        let mut decoder = Decoder::new(iter).peekable();
+        let (type_, _) = decoder.peek_header()?;
+        let consumed = Cell::new(0usize);
+        let mut decoder = decoder.into_iter().inspect(|_| consumed.set(consumed.get().saturating_add(1)));
+
+        // Select the appropriate packet depending on the type
+        let packet = match type_ {
+            ConnackV5::<Bytes>::TYPE => ConnackV5::try_from_iter(&mut decoder).map(Self::Connack),
+            ConnectV5::<Bytes>::TYPE => ConnectV5::try_from_iter(&mut decoder).map(Self::Connect),
+            PubackV5::<Bytes>::TYPE => PubackV5::try_from_iter(&mut decoder).map(Self::Puback),
+            PubcompV5::<Bytes>::TYPE => PubcompV5::try_from_iter(&mut decoder).map(Self::Pubcomp),
+            PublishV5::<Bytes>::TYPE => PublishV5::try_from_iter(&mut decoder).map(Self::Publish),
+            PubrecV5::<Bytes>::TYPE => PubrecV5::try_from_iter(&mut decoder).map(Self::Pubrec),
+            PubrelV5::<Bytes>::TYPE => PubrelV5::try_from_iter(&mut decoder).map(Self::Pubrel),
+            SubackV5::<Bytes>::TYPE => SubackV5::try_from_iter(&mut decoder).map(Self::Suback),
+            UnsubackV5::<Bytes>::TYPE => UnsubackV5::try_from_iter(&mut decoder).map(Self::Unsuback),
+            _ => Err(err!(UnknownPacketType, "Unknown packet type")),
+        };
+
+        // Log the result
+        match &packet {
+            Ok(packet) => log_trace!("decoded {} v5 packet ({} bytes)", packet.name(), consumed.get()),
+            Err(e) => log_debug!("failed to decode v5 packet: {}", e),
+        }
+        packet
+    }
+}
+impl<Bytes> IntoIterator for PacketV5<Bytes>
+where
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    type IntoIter = PacketV5Iter<Bytes>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Self::Connack(this) => PacketV5Iter::Connack(this.into_iter()),
+            Self::Connect(this) => PacketV5Iter::Connect(this.into_iter()),
+            Self::Puback(this) => PacketV5Iter::Puback(this.into_iter()),
+            Self::Pubcomp(this) => PacketV5Iter::Pubcomp(this.into_iter()),
+            Self::Publish(this) => PacketV5Iter::Publish(this.into_iter()),
+            Self::Pubrec(this) => PacketV5Iter::Pubrec(this.into_iter()),
+            Self::Pubrel(this) => PacketV5Iter::Pubrel(this.into_iter()),
+            Self::Suback(this) => PacketV5Iter::Suback(this.into_iter()),
+            Self::Unsuback(this) => PacketV5Iter::Unsuback(this.into_iter()),
+        }
+    }
+}
+impl<'a, Bytes> IntoIterator for &'a PacketV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = PacketV5Iter<BytesRef<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // The borrowed packets encode via a `BytesRef` view, so they share the owned iterator type
+        match self {
+            PacketV5::Connack(this) => PacketV5Iter::Connack(this.into_iter()),
+            PacketV5::Connect(this) => PacketV5Iter::Connect(this.into_iter()),
+            PacketV5::Puback(this) => PacketV5Iter::Puback(this.into_iter()),
+            PacketV5::Pubcomp(this) => PacketV5Iter::Pubcomp(this.into_iter()),
+            PacketV5::Publish(this) => PacketV5Iter::Publish(this.into_iter()),
+            PacketV5::Pubrec(this) => PacketV5Iter::Pubrec(this.into_iter()),
+            PacketV5::Pubrel(this) => PacketV5Iter::Pubrel(this.into_iter()),
+            PacketV5::Suback(this) => PacketV5Iter::Suback(this.into_iter()),
+            PacketV5::Unsuback(this) => PacketV5Iter::Unsuback(this.into_iter()),
+        }
+    }
+}
+
+/// A packet-type-erased iterator over the encoded representation of an MQTT 5 packet
+pub enum PacketV5Iter<Bytes>
+where
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    /// A [`ConnackV5`] packet iterator
+    Connack(<ConnackV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`ConnectV5`] packet iterator
+    Connect(<ConnectV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`PubackV5`] packet iterator
+    Puback(<PubackV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`PubcompV5`] packet iterator
+    Pubcomp(<PubcompV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`PublishV5`] packet iterator
+    Publish(<PublishV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`PubrecV5`] packet iterator
+    Pubrec(<PubrecV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`PubrelV5`] packet iterator
+    Pubrel(<PubrelV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`SubackV5`] packet iterator
+    Suback(<SubackV5<Bytes> as IntoIterator>::IntoIter),
+    /// A [`UnsubackV5`] packet iterator
+    Unsuback(<UnsubackV5<Bytes> as IntoIterator>::IntoIter),
+}
+impl<Bytes> Clone for PacketV5Iter<Bytes>
+where
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+    <Bytes as IntoIterator>::IntoIter: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Connack(iter) => Self::Connack(iter.clone()),
+            Self::Connect(iter) => Self::Connect(iter.clone()),
+            Self::Puback(iter) => Self::Puback(iter.clone()),
+            Self::Pubcomp(iter) => Self::Pubcomp(iter.clone()),
+            Self::Publish(iter) => Self::Publish(iter.clone()),
+            Self::Pubrec(iter) => Self::Pubrec(iter.clone()),
+            Self::Pubrel(iter) => Self::Pubrel(iter.clone()),
+            Self::Suback(iter) => Self::Suback(iter.clone()),
+            Self::Unsuback(iter) => Self::Unsuback(iter.clone()),
+        }
+    }
+}
+impl<Bytes> Iterator for PacketV5Iter<Bytes>
+where
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Connack(iter) => iter.next(),
+            Self::Connect(iter) => iter.next(),
+            Self::Puback(iter) => iter.next(),
+            Self::Pubcomp(iter) => iter.next(),
+            Self::Publish(iter) => iter.next(),
+            Self::Pubrec(iter) => iter.next(),
+            Self::Pubrel(iter) => iter.next(),
+            Self::Suback(iter) => iter.next(),
+            Self::Unsuback(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Connack(iter) => iter.size_hint(),
+            Self::Connect(iter) => iter.size_hint(),
+            Self::Puback(iter) => iter.size_hint(),
+            Self::Pubcomp(iter) => iter.size_hint(),
+            Self::Publish(iter) => iter.size_hint(),
+            Self::Pubrec(iter) => iter.size_hint(),
+            Self::Pubrel(iter) => iter.size_hint(),
+            Self::Suback(iter) => iter.size_hint(),
+            Self::Unsuback(iter) => iter.size_hint(),
+        }
+    }
+}
+impl<Bytes> ExactSizeIterator for PacketV5Iter<Bytes>
+where
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    // No members to implement
+}
+impl<Bytes> FusedIterator for PacketV5Iter<Bytes>
+where
+    Bytes: Default + AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    // No members to implement
+}
@@ -0,0 +1,240 @@
+//! MQTT 5 [properties](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901027)
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{
+        encoder::BytesRef,
+        properties::{self, PropertyDecoder, PropertyEncoder},
+        Decoder,
+    },
+    error::{DecoderError, EncodeError},
+};
+use core::iter::FusedIterator;
+
+pub use crate::coding::properties::{id, Value};
+
+/// An MQTT 5 properties block
+///
+/// # Note
+/// The properties are stored in their encoded form (without the length prefix), so unknown properties are preserved
+/// verbatim. Properties must not be duplicated, except for user properties and subscription identifiers which may occur
+/// multiple times.
+///
+/// To avoid leaking credentials into logs, the `Debug` implementation redacts the authentication data and only displays
+/// its length. Enable the `debug-credentials` feature to display it verbatim.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Properties<Bytes> {
+    /// The encoded properties
+    raw: Bytes,
+}
+impl<Bytes> Properties<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    /// The maximum length of a properties block (i.e. the maximum value of a variable byte integer, `2^28 - 1`)
+    pub const MAX_LEN: usize = EncodeError::MAX_PACKET_LEN;
+
+    /// Creates a new, empty properties block
+    pub fn new() -> Self {
+        Self { raw: Bytes::default() }
+    }
+
+    /// Appends a property (see [`PropertyEncoder::push`])
+    pub fn push(&mut self, id: u8, value: Value) -> Result<(), &'static str> {
+        PropertyEncoder::new(&mut self.raw).push(id, value)
+    }
+    /// Appends the raw remainder of another properties block that starts with a property of unknown type (see
+    /// [`PropertyEncoder::push_unknown`])
+    pub fn push_unknown(&mut self, raw: &[u8]) -> Result<(), &'static str> {
+        PropertyEncoder::new(&mut self.raw).push_unknown(raw)
+    }
+    /// Appends a property (see [`Self::push`])
+    pub fn with(mut self, id: u8, value: Value) -> Result<Self, &'static str> {
+        self.push(id, value)?;
+        Ok(self)
+    }
+    /// Appends the given user properties, or restores the previous state if not all of them can be appended
+    pub(crate) fn push_user_properties<'a, T>(&mut self, pairs: T) -> Result<(), &'static str>
+    where
+        T: IntoIterator<Item = (&'a [u8], &'a [u8])>,
+    {
+        let previous_len = self.raw.as_ref().len();
+        let result =
+            (pairs.into_iter()).try_for_each(|(key, value)| self.push(id::USER_PROPERTY, Value::Pair(key, value)));
+        if result.is_err() {
+            self.raw.truncate(previous_len);
+        }
+        result
+    }
+
+    /// Reads and validates a properties block that is prefixed with its length as variable byte integer
+    #[track_caller]
+    pub(crate) fn read<Iter>(decoder: &mut Decoder<Iter>) -> Result<Self, DecoderError>
+    where
+        Iter: Iterator<Item = u8>,
+    {
+        let mut raw = Bytes::default();
+        PropertyDecoder::read(decoder, &mut raw)?.validate()?;
+        Ok(Self { raw })
+    }
+}
+impl<Bytes> Properties<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    /// Whether the properties block is empty
+    pub fn is_empty(&self) -> bool {
+        self.raw.as_ref().is_empty()
+    }
+
+    /// Creates a borrowed view of the properties block
+    pub fn to_ref(&self) -> Properties<BytesRef<'_>> {
+        Properties { raw: BytesRef::new(self.raw.as_ref()) }
+    }
+
+    /// An iterator over the properties as `(id, value)` tuples in their encoded order
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { raw: self.raw.as_ref() }
+    }
+
+    /// Gets the value of the first property with the given identifier
+    pub fn get(&self, id: u8) -> Option<Value<'_>> {
+        self.iter().find_map(|(id_, value)| (id_ == id).then_some(value))
+    }
+    /// Gets the value of the first byte property with the given identifier
+    pub(crate) fn get_u8(&self, id: u8) -> Option<u8> {
+        match self.get(id) {
+            Some(Value::Byte(byte)) => Some(byte),
+            _ => None,
+        }
+    }
+    /// Gets the value of the first two byte integer property with the given identifier
+    pub(crate) fn get_u16(&self, id: u8) -> Option<u16> {
+        match self.get(id) {
+            Some(Value::U16(u16_)) => Some(u16_),
+            _ => None,
+        }
+    }
+    /// Gets the value of the first four byte integer property with the given identifier
+    pub(crate) fn get_u32(&self, id: u8) -> Option<u32> {
+        match self.get(id) {
+            Some(Value::U32(u32_)) => Some(u32_),
+            _ => None,
+        }
+    }
+    /// Gets the value of the first binary data or UTF-8 string property with the given identifier
+    pub(crate) fn get_bytes(&self, id: u8) -> Option<&[u8]> {
+        match self.get(id) {
+            Some(Value::Binary(bytes) | Value::Utf8(bytes)) => Some(bytes),
+            _ => None,
+        }
+    }
+    /// Gets the raw remainder of the properties block, starting with the first property of unknown type
+    pub fn unknown(&self) -> Option<&[u8]> {
+        self.iter().find_map(|(_, value)| match value {
+            Value::Raw(raw) => Some(raw),
+            _ => None,
+        })
+    }
+
+    /// An iterator over the values of all variable byte integer properties with the given identifier
+    pub(crate) fn get_varints(&self, id: u8) -> impl Iterator<Item = u32> + '_ {
+        self.iter().filter_map(move |(id_, value)| match value {
+            Value::Varint(varint) if id_ == id => Some(varint),
+            _ => None,
+        })
+    }
+    /// An iterator over all user properties as `(key, value)` tuples
+    pub(crate) fn user_properties(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.iter().filter_map(|(_, value)| match value {
+            Value::Pair(key, value) => Some((key, value)),
+            _ => None,
+        })
+    }
+}
+impl<Bytes> AsRef<[u8]> for Properties<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn as_ref(&self) -> &[u8] {
+        self.raw.as_ref()
+    }
+}
+impl<Bytes> IntoIterator for Properties<Bytes>
+where
+    Bytes: IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    type IntoIter = <Bytes as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.raw.into_iter()
+    }
+}
+impl<'a, Bytes> IntoIterator for &'a Properties<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = (u8, Value<'a>);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<Bytes> core::fmt::Debug for Properties<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        /// A property that redacts the authentication data
+        struct Entry<'a>(u8, Value<'a>);
+        impl core::fmt::Debug for Entry<'_> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    #[cfg(not(feature = "debug-credentials"))]
+                    Self(id::AUTHENTICATION_DATA, Value::Binary(bytes)) => {
+                        write!(f, "({}, <redacted, {} bytes>)", self.0, bytes.len())
+                    }
+                    Self(id, value) => f.debug_tuple("").field(id).field(value).finish(),
+                }
+            }
+        }
+
+        // Display the parsed properties
+        f.debug_list().entries(self.iter().map(|(id, value)| Entry(id, value))).finish()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for Properties<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the length, which also redacts sensitive properties like the authentication data
+        defmt::write!(f, "Properties {{ len: {=usize} }}", self.raw.as_ref().len())
+    }
+}
+
+/// An iterator over the properties of a properties block
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    /// The remaining encoded properties
+    raw: &'a [u8],
+}
+impl<'a> Iterator for Iter<'a> {
+    type Item = (u8, Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Stop on the first malformed property; this cannot happen for validated blocks
+        let Ok((id, value, len)) = properties::parse(self.raw) else {
+            self.raw = &[];
+            return None;
+        };
+        self.raw = self.raw.get(len..).unwrap_or_default();
+        Some((id, value))
+    }
+}
+impl FusedIterator for Iter<'_> {
+    // No members to implement
+}
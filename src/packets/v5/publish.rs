@@ -0,0 +1,476 @@
+//! MQTT 5 [`PUBLISH`](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901100)
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{
+        encoder::{BytesIter, BytesRef, OptionalU16Iter, PacketLenIter, PropertiesIter, SizedIter, U8Iter, Unit},
+        length::Length,
+        Decoder, Encoder,
+    },
+    error::{DecoderError, Field},
+    packets::{
+        flags::PublishFlags,
+        packet::PacketType,
+        v5::{
+            properties::{id, Properties, Value},
+            user_properties::UserProperties,
+        },
+        TryFromIterator,
+    },
+    topic,
+};
+use core::iter::Chain;
+
+/// An MQTT 5 [`PUBLISH` packet](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901100)
+///
+/// # Note
+/// The topic may only be empty if a topic alias is present; see [`Self::new_aliased`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PublishV5<Bytes> {
+    /// Whether this packet is a redelivery or not
+    dup: bool,
+    /// The packet QoS
+    ///
+    /// # QoS Levels
+    /// Valid QoS levels are:
+    ///  - `0`: At most one delivery
+    ///  - `1`: At least one delivery
+    ///  - `2`: Exactly one delivery
+    qos: u8,
+    /// Whether the message should be retained
+    retain: bool,
+    /// The message topic
+    topic: Bytes,
+    /// The packet ID
+    packet_id: Option<u16>,
+    /// The publish properties
+    properties: Properties<Bytes>,
+    /// The payload
+    payload: Bytes,
+}
+impl<Bytes> PublishV5<Bytes> {
+    /// The packet type constant
+    pub const TYPE: u8 = 3;
+
+    /// Configures the packet quality-of-service level and specifies whether this packet is a duplicate transmission
+    /// (aka retry) or not
+    ///
+    /// # QoS Levels
+    /// Valid QoS levels are:
+    ///  - `0`: At most one delivery
+    ///  - `1`: At least one delivery
+    ///  - `2`: Exactly one delivery
+    pub fn with_qos(mut self, qos: u8, packet_id: u16, dup: bool) -> Self {
+        self.dup = dup;
+        self.qos = qos;
+        self.packet_id = Some(packet_id);
+        self
+    }
+}
+impl<Bytes> PublishV5<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    /// Creates a new packet
+    ///
+    /// # Note
+    /// The topic must be a valid topic name (see [`topic::validate_name`]); use [`Self::new_aliased`] to publish via a
+    /// previously established topic alias.
+    pub fn new<T, P>(topic: T, payload: P, retain: bool) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        // Validate the topic
+        if topic.as_ref().is_empty() {
+            return Err("Empty topic without topic alias");
+        }
+        topic::validate_name(topic.as_ref())?;
+        Self::new_unchecked(topic.as_ref(), payload.as_ref(), retain)
+    }
+    /// Creates a new packet with an empty topic that is published via the given topic alias
+    pub fn new_aliased<P>(topic_alias: u16, payload: P, retain: bool) -> Result<Self, &'static str>
+    where
+        P: AsRef<[u8]>,
+    {
+        Self::new_unchecked(&[], payload.as_ref(), retain)?.with_topic_alias(topic_alias)
+    }
+
+    /// Appends a publish property (see [`Properties::push`])
+    pub fn with_property(mut self, id: u8, value: Value) -> Result<Self, &'static str> {
+        // Append the property and ensure that the packet can still be encoded
+        self.properties.push(id, value)?;
+        self.validate_len()?;
+        Ok(self)
+    }
+    /// Appends the given user properties, preserving their order
+    pub fn with_user_properties<Seq, T>(
+        mut self,
+        user_properties: &UserProperties<Seq, T>,
+    ) -> Result<Self, &'static str>
+    where
+        Seq: AsRef<[(T, T)]>,
+        T: AsRef<[u8]>,
+    {
+        // Append the user properties and ensure that the packet can still be encoded
+        user_properties.append_to(&mut self.properties)?;
+        self.validate_len()?;
+        Ok(self)
+    }
+    /// Sets the payload format indicator (`0` for unspecified bytes, `1` for UTF-8 encoded character data)
+    pub fn with_payload_format_indicator(self, indicator: u8) -> Result<Self, &'static str> {
+        self.with_property(id::PAYLOAD_FORMAT_INDICATOR, Value::Byte(indicator))
+    }
+    /// Sets the message expiry interval in seconds
+    pub fn with_message_expiry_interval(self, secs: u32) -> Result<Self, &'static str> {
+        self.with_property(id::MESSAGE_EXPIRY_INTERVAL, Value::U32(secs))
+    }
+    /// Sets the topic alias, which must not be `0`
+    pub fn with_topic_alias(self, topic_alias: u16) -> Result<Self, &'static str> {
+        match topic_alias {
+            0 => Err("Invalid topic alias"),
+            _ => self.with_property(id::TOPIC_ALIAS, Value::U16(topic_alias)),
+        }
+    }
+    /// Sets the response topic
+    pub fn with_response_topic<T>(self, topic: T) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.with_property(id::RESPONSE_TOPIC, Value::Utf8(topic.as_ref()))
+    }
+    /// Sets the correlation data
+    pub fn with_correlation_data<T>(self, data: T) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.with_property(id::CORRELATION_DATA, Value::Binary(data.as_ref()))
+    }
+    /// Appends a user property
+    pub fn with_user_property<K, V>(self, key: K, value: V) -> Result<Self, &'static str>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.with_property(id::USER_PROPERTY, Value::Pair(key.as_ref(), value.as_ref()))
+    }
+    /// Appends a subscription identifier, which must be within `1..=268_435_455`
+    pub fn with_subscription_identifier(self, identifier: u32) -> Result<Self, &'static str> {
+        match identifier {
+            0 => Err("Invalid subscription identifier"),
+            _ => self.with_property(id::SUBSCRIPTION_IDENTIFIER, Value::Varint(identifier)),
+        }
+    }
+    /// Sets the content type
+    pub fn with_content_type<T>(self, content_type: T) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.with_property(id::CONTENT_TYPE, Value::Utf8(content_type.as_ref()))
+    }
+
+    /// Replaces the topic if given and the topic alias, preserving all other properties
+    pub(crate) fn rewrite_topic(
+        mut self,
+        topic: Option<&[u8]>,
+        topic_alias: Option<u16>,
+    ) -> Result<Self, &'static str> {
+        // Rebuild the properties with the new topic alias first, so that unknown properties remain at the end
+        let mut properties = Properties::<Bytes>::new();
+        if let Some(topic_alias) = topic_alias {
+            properties.push(id::TOPIC_ALIAS, Value::U16(topic_alias))?;
+        }
+        for (id_, value) in self.properties.iter() {
+            match value {
+                _ if id_ == id::TOPIC_ALIAS => (),
+                Value::Raw(raw) => properties.push_unknown(raw)?,
+                value => properties.push(id_, value)?,
+            }
+        }
+
+        // Validate the topic and ensure that the packet can still be encoded
+        let topic = topic.unwrap_or(self.topic.as_ref());
+        if topic.is_empty() && topic_alias.is_none() {
+            return Err("Empty topic without topic alias");
+        }
+        let len = Length::new()
+            .try_bytes(&topic)?
+            .optional_u16(&self.packet_id)
+            .properties(&properties)
+            .raw(&self.payload)
+            .into();
+        Length::new().try_packetlen(&len)?;
+
+        // Update self
+        self.topic = Bytes::new(topic)?;
+        self.properties = properties;
+        Ok(self)
+    }
+
+    /// Ensures that the packet can be encoded (reserving space for an optional packet ID)
+    fn validate_len(&self) -> Result<(), &'static str> {
+        let len = Length::new().try_bytes(&self.topic)?.u16(&0).properties(&self.properties).raw(&self.payload).into();
+        Length::new().try_packetlen(&len)?;
+        Ok(())
+    }
+
+    /// Creates a new packet without validating the topic
+    fn new_unchecked(topic: &[u8], payload: &[u8], retain: bool) -> Result<Self, &'static str> {
+        // Ensure that the packet can be encoded (reserving space for an optional packet ID and empty properties)
+        let len = Length::new().try_bytes(&topic)?.u16(&0).u8(&0).raw(&payload).into();
+        Length::new().try_packetlen(&len)?;
+
+        // Init self
+        let (topic, payload) = (Bytes::new(topic)?, Bytes::new(payload)?);
+        Ok(Self { dup: false, qos: 0, retain, topic, packet_id: None, properties: Properties::new(), payload })
+    }
+}
+impl<Bytes> PublishV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    /// The message topic
+    pub fn topic(&self) -> &[u8] {
+        self.topic.as_ref()
+    }
+
+    /// The payload
+    pub fn payload(&self) -> &[u8] {
+        self.payload.as_ref()
+    }
+
+    /// Whether the message should be retained
+    pub fn retain(&self) -> bool {
+        self.retain
+    }
+
+    /// Whether this packet is a redelivery or not
+    pub fn dup(&self) -> bool {
+        self.dup
+    }
+    /// The packet QoS
+    pub fn qos(&self) -> u8 {
+        self.qos
+    }
+    /// The packet ID
+    pub fn packet_id(&self) -> Option<u16> {
+        self.packet_id
+    }
+
+    /// The publish properties
+    pub const fn properties(&self) -> &Properties<Bytes> {
+        &self.properties
+    }
+    /// The payload format indicator
+    pub fn payload_format_indicator(&self) -> Option<u8> {
+        self.properties.get_u8(id::PAYLOAD_FORMAT_INDICATOR)
+    }
+    /// The message expiry interval in seconds
+    pub fn message_expiry_interval(&self) -> Option<u32> {
+        self.properties.get_u32(id::MESSAGE_EXPIRY_INTERVAL)
+    }
+    /// The topic alias
+    pub fn topic_alias(&self) -> Option<u16> {
+        self.properties.get_u16(id::TOPIC_ALIAS)
+    }
+    /// The response topic
+    pub fn response_topic(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::RESPONSE_TOPIC)
+    }
+    /// The correlation data
+    pub fn correlation_data(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::CORRELATION_DATA)
+    }
+    /// The user properties as `(key, value)` tuples
+    pub fn user_properties(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.properties.user_properties()
+    }
+    /// The subscription identifiers
+    pub fn subscription_identifiers(&self) -> impl Iterator<Item = u32> + '_ {
+        self.properties.get_varints(id::SUBSCRIPTION_IDENTIFIER)
+    }
+    /// The content type
+    pub fn content_type(&self) -> Option<&[u8]> {
+        self.properties.get_bytes(id::CONTENT_TYPE)
+    }
+    /// The raw remainder of the properties, starting with the first property of unknown type
+    pub fn unknown_properties(&self) -> Option<&[u8]> {
+        self.properties.unknown()
+    }
+}
+impl<Bytes> core::fmt::Debug for PublishV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PublishV5")
+            .field("dup", &self.dup)
+            .field("qos", &self.qos)
+            .field("retain", &self.retain)
+            .field("topic", &self.topic.as_ref())
+            .field("packet_id", &self.packet_id)
+            .field("properties", &self.properties)
+            .field("payload", &self.payload.as_ref())
+            .finish()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Bytes> defmt::Format for PublishV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the length of the topic and payload
+        defmt::write!(
+            f,
+            "PublishV5 {{ dup: {=bool}, qos: {=u8}, retain: {=bool}, packet_id: {}, topic_len: {=usize}, properties: {}, payload_len: {=usize} }}",
+            self.dup,
+            self.qos,
+            self.retain,
+            self.packet_id,
+            self.topic.as_ref().len(),
+            self.properties,
+            self.payload.as_ref().len()
+        )
+    }
+}
+impl<Bytes> TryFromIterator for PublishV5<Bytes>
+where
+    Bytes: AnyVec<u8>,
+{
+    fn try_from_iter<T>(iter: T) -> Result<Self, DecoderError>
+    where
+        T: IntoIterator<Item = u8>,
+    {
+        // Read packet:
+        //  - header type and flags
+        //  - packet len
+        //  - topic
+        //  - packet ID
+        //  - properties
+        //  - payload
+        let mut decoder = Decoder::new(iter);
+        let (type_, flags) = decoder.header_typed()?;
+        let Self::TYPE = type_ else {
+            return Err(err!(SpecViolation, "Invalid packet type", expected Self::TYPE, found type_));
+        };
+        let flags = PublishFlags::from_bits(flags.bits())?;
+        // Limit length
+        let len = decoder.packetlen()?;
+        let mut decoder = decoder.take_exact(len);
+        // Read fields
+        let topic_offset = decoder.position();
+        let topic: Bytes = decoder.bytes().map_err(|e| Field::Topic.tag(e).context("topic name"))?;
+        if flags.qos > 0 && decoder.remaining() < 2 {
+            // The packet is too short to contain the indicated packet ID
+            return Err(err!(Truncated, "Truncated packet ID")
+                .with_offset(decoder.position())
+                .context("packet identifier"));
+        }
+        let packet_id = decoder.optional_u16(flags.qos > 0).map_err(|e| e.context("packet identifier"))?;
+        let properties_offset = decoder.position();
+        let properties: Properties<Bytes> = Properties::read(&mut decoder).map_err(|e| e.context("properties"))?;
+        let payload: Bytes = decoder.raw_remainder().map_err(|e| Field::Payload.tag(e).context("payload"))?;
+        decoder.finish()?;
+
+        // Validate the topic alias
+        match properties.get_u16(id::TOPIC_ALIAS) {
+            Some(0) => {
+                let error = err!(SpecViolation, "Invalid topic alias").with_offset(properties_offset);
+                return Err(error.context("properties"));
+            }
+            None if topic.as_ref().is_empty() => {
+                let error = err!(SpecViolation, "Empty topic without topic alias").with_offset(topic_offset);
+                return Err(error.context("topic name"));
+            }
+            _ => (),
+        }
+        log_trace!(
+            "decoded PUBLISH v5 (topic: {} bytes, packet ID: {:?}, properties: {} bytes, payload: {} bytes)",
+            topic.as_ref().len(),
+            packet_id,
+            properties.as_ref().len(),
+            payload.as_ref().len()
+        );
+
+        // Init self
+        let PublishFlags { dup, qos, retain } = flags;
+        Ok(Self { dup, qos, retain, topic, packet_id, properties, payload })
+    }
+}
+impl<Bytes> IntoIterator for PublishV5<Bytes>
+where
+    Bytes: AsRef<[u8]> + IntoIterator<Item = u8>,
+{
+    type Item = u8;
+    #[rustfmt::skip]
+    type IntoIter =
+        // Complex iterator built out of the individual message fields
+        SizedIter<Chain<Chain<Chain<Chain<Chain<Chain<
+            // - header type and flags
+            Unit, U8Iter>,
+            // - packet len
+            PacketLenIter>,
+            // - topic
+            BytesIter<Bytes>>,
+            // - packet ID
+            OptionalU16Iter>,
+            // - properties
+            PropertiesIter<Properties<Bytes>>>,
+            //  - payload
+            <Bytes as IntoIterator>::IntoIter>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Assemble flags
+        let flags = PublishFlags { dup: self.dup, qos: self.qos, retain: self.retain };
+
+        // Precompute body length:
+        //  - topic
+        //  - packet ID
+        //  - properties
+        //  - payload
+        let len = Length::new()
+            .bytes(&self.topic)
+            .optional_u16(&self.packet_id)
+            .properties(&self.properties)
+            .raw(&self.payload)
+            .into();
+
+        // Write packet:
+        //  - header type and flags
+        //  - packet len
+        //  - topic
+        //  - packet ID
+        //  - properties
+        //  - payload
+        Encoder::default()
+            .typed_header(PacketType::Publish, flags)
+            .packetlen(len)
+            .bytes(self.topic)
+            .optional_u16(self.packet_id)
+            .properties(self.properties)
+            .raw(self.payload)
+            .into_sized_iter(len)
+    }
+}
+impl<'a, Bytes> IntoIterator for &'a PublishV5<Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = u8;
+    type IntoIter = <PublishV5<BytesRef<'a>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // Encode a borrowed view of the packet
+        PublishV5 {
+            dup: self.dup,
+            qos: self.qos,
+            retain: self.retain,
+            topic: BytesRef::new(self.topic.as_ref()),
+            packet_id: self.packet_id,
+            properties: self.properties.to_ref(),
+            payload: BytesRef::new(self.payload.as_ref()),
+        }
+        .into_iter()
+    }
+}
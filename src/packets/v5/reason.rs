@@ -0,0 +1,216 @@
+//! MQTT 5 [reason codes](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901031)
+
+/// An MQTT 5 [reason code](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901031)
+///
+/// # Note
+/// Codes that are not assigned by the specification are represented as [`ReasonCode::Other`]; the conversion from `u8`
+/// never yields [`ReasonCode::Other`] for an assigned code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReasonCode {
+    /// `0x00`: Success (also "normal disconnection" and "granted QoS 0")
+    #[default]
+    Success,
+    /// `0x01`: Granted QoS 1
+    GrantedQos1,
+    /// `0x02`: Granted QoS 2
+    GrantedQos2,
+    /// `0x04`: Disconnect with will message
+    DisconnectWithWillMessage,
+    /// `0x10`: No matching subscribers
+    NoMatchingSubscribers,
+    /// `0x11`: No subscription existed
+    NoSubscriptionExisted,
+    /// `0x18`: Continue authentication
+    ContinueAuthentication,
+    /// `0x19`: Re-authenticate
+    ReAuthenticate,
+    /// `0x80`: Unspecified error
+    UnspecifiedError,
+    /// `0x81`: Malformed packet
+    MalformedPacket,
+    /// `0x82`: Protocol error
+    ProtocolError,
+    /// `0x83`: Implementation specific error
+    ImplementationSpecificError,
+    /// `0x84`: Unsupported protocol version
+    UnsupportedProtocolVersion,
+    /// `0x85`: Client identifier not valid
+    ClientIdentifierNotValid,
+    /// `0x86`: Bad user name or password
+    BadUserNameOrPassword,
+    /// `0x87`: Not authorized
+    NotAuthorized,
+    /// `0x88`: Server unavailable
+    ServerUnavailable,
+    /// `0x89`: Server busy
+    ServerBusy,
+    /// `0x8A`: Banned
+    Banned,
+    /// `0x8B`: Server shutting down
+    ServerShuttingDown,
+    /// `0x8C`: Bad authentication method
+    BadAuthenticationMethod,
+    /// `0x8D`: Keep alive timeout
+    KeepAliveTimeout,
+    /// `0x8E`: Session taken over
+    SessionTakenOver,
+    /// `0x8F`: Topic filter invalid
+    TopicFilterInvalid,
+    /// `0x90`: Topic name invalid
+    TopicNameInvalid,
+    /// `0x91`: Packet identifier in use
+    PacketIdentifierInUse,
+    /// `0x92`: Packet identifier not found
+    PacketIdentifierNotFound,
+    /// `0x93`: Receive maximum exceeded
+    ReceiveMaximumExceeded,
+    /// `0x94`: Topic alias invalid
+    TopicAliasInvalid,
+    /// `0x95`: Packet too large
+    PacketTooLarge,
+    /// `0x96`: Message rate too high
+    MessageRateTooHigh,
+    /// `0x97`: Quota exceeded
+    QuotaExceeded,
+    /// `0x98`: Administrative action
+    AdministrativeAction,
+    /// `0x99`: Payload format invalid
+    PayloadFormatInvalid,
+    /// `0x9A`: Retain not supported
+    RetainNotSupported,
+    /// `0x9B`: QoS not supported
+    QosNotSupported,
+    /// `0x9C`: Use another server
+    UseAnotherServer,
+    /// `0x9D`: Server moved
+    ServerMoved,
+    /// `0x9E`: Shared subscriptions not supported
+    SharedSubscriptionsNotSupported,
+    /// `0x9F`: Connection rate exceeded
+    ConnectionRateExceeded,
+    /// `0xA0`: Maximum connect time
+    MaximumConnectTime,
+    /// `0xA1`: Subscription identifiers not supported
+    SubscriptionIdentifiersNotSupported,
+    /// `0xA2`: Wildcard subscriptions not supported
+    WildcardSubscriptionsNotSupported,
+    /// A reason code that is not assigned by the specification
+    Other(u8),
+}
+impl ReasonCode {
+    /// Converts a raw reason code
+    pub const fn from_u8(code: u8) -> Self {
+        match code {
+            0x00 => Self::Success,
+            0x01 => Self::GrantedQos1,
+            0x02 => Self::GrantedQos2,
+            0x04 => Self::DisconnectWithWillMessage,
+            0x10 => Self::NoMatchingSubscribers,
+            0x11 => Self::NoSubscriptionExisted,
+            0x18 => Self::ContinueAuthentication,
+            0x19 => Self::ReAuthenticate,
+            0x80 => Self::UnspecifiedError,
+            0x81 => Self::MalformedPacket,
+            0x82 => Self::ProtocolError,
+            0x83 => Self::ImplementationSpecificError,
+            0x84 => Self::UnsupportedProtocolVersion,
+            0x85 => Self::ClientIdentifierNotValid,
+            0x86 => Self::BadUserNameOrPassword,
+            0x87 => Self::NotAuthorized,
+            0x88 => Self::ServerUnavailable,
+            0x89 => Self::ServerBusy,
+            0x8A => Self::Banned,
+            0x8B => Self::ServerShuttingDown,
+            0x8C => Self::BadAuthenticationMethod,
+            0x8D => Self::KeepAliveTimeout,
+            0x8E => Self::SessionTakenOver,
+            0x8F => Self::TopicFilterInvalid,
+            0x90 => Self::TopicNameInvalid,
+            0x91 => Self::PacketIdentifierInUse,
+            0x92 => Self::PacketIdentifierNotFound,
+            0x93 => Self::ReceiveMaximumExceeded,
+            0x94 => Self::TopicAliasInvalid,
+            0x95 => Self::PacketTooLarge,
+            0x96 => Self::MessageRateTooHigh,
+            0x97 => Self::QuotaExceeded,
+            0x98 => Self::AdministrativeAction,
+            0x99 => Self::PayloadFormatInvalid,
+            0x9A => Self::RetainNotSupported,
+            0x9B => Self::QosNotSupported,
+            0x9C => Self::UseAnotherServer,
+            0x9D => Self::ServerMoved,
+            0x9E => Self::SharedSubscriptionsNotSupported,
+            0x9F => Self::ConnectionRateExceeded,
+            0xA0 => Self::MaximumConnectTime,
+            0xA1 => Self::SubscriptionIdentifiersNotSupported,
+            0xA2 => Self::WildcardSubscriptionsNotSupported,
+            other => Self::Other(other),
+        }
+    }
+
+    /// The raw reason code
+    pub const fn to_u8(&self) -> u8 {
+        match self {
+            Self::Success => 0x00,
+            Self::GrantedQos1 => 0x01,
+            Self::GrantedQos2 => 0x02,
+            Self::DisconnectWithWillMessage => 0x04,
+            Self::NoMatchingSubscribers => 0x10,
+            Self::NoSubscriptionExisted => 0x11,
+            Self::ContinueAuthentication => 0x18,
+            Self::ReAuthenticate => 0x19,
+            Self::UnspecifiedError => 0x80,
+            Self::MalformedPacket => 0x81,
+            Self::ProtocolError => 0x82,
+            Self::ImplementationSpecificError => 0x83,
+            Self::UnsupportedProtocolVersion => 0x84,
+            Self::ClientIdentifierNotValid => 0x85,
+            Self::BadUserNameOrPassword => 0x86,
+            Self::NotAuthorized => 0x87,
+            Self::ServerUnavailable => 0x88,
+            Self::ServerBusy => 0x89,
+            Self::Banned => 0x8A,
+            Self::ServerShuttingDown => 0x8B,
+            Self::BadAuthenticationMethod => 0x8C,
+            Self::KeepAliveTimeout => 0x8D,
+            Self::SessionTakenOver => 0x8E,
+            Self::TopicFilterInvalid => 0x8F,
+            Self::TopicNameInvalid => 0x90,
+            Self::PacketIdentifierInUse => 0x91,
+            Self::PacketIdentifierNotFound => 0x92,
+            Self::ReceiveMaximumExceeded => 0x93,
+            Self::TopicAliasInvalid => 0x94,
+            Self::PacketTooLarge => 0x95,
+            Self::MessageRateTooHigh => 0x96,
+            Self::QuotaExceeded => 0x97,
+            Self::AdministrativeAction => 0x98,
+            Self::PayloadFormatInvalid => 0x99,
+            Self::RetainNotSupported => 0x9A,
+            Self::QosNotSupported => 0x9B,
+            Self::UseAnotherServer => 0x9C,
+            Self::ServerMoved => 0x9D,
+            Self::SharedSubscriptionsNotSupported => 0x9E,
+            Self::ConnectionRateExceeded => 0x9F,
+            Self::MaximumConnectTime => 0xA0,
+            Self::SubscriptionIdentifiersNotSupported => 0xA1,
+            Self::WildcardSubscriptionsNotSupported => 0xA2,
+            Self::Other(other) => *other,
+        }
+    }
+
+    /// Whether the reason code indicates success (i.e. is less than `0x80`) or not
+    pub const fn is_success(&self) -> bool {
+        self.to_u8() < 0x80
+    }
+}
+impl From<u8> for ReasonCode {
+    fn from(value: u8) -> Self {
+        Self::from_u8(value)
+    }
+}
+impl From<ReasonCode> for u8 {
+    fn from(value: ReasonCode) -> Self {
+        value.to_u8()
+    }
+}
@@ -0,0 +1,50 @@
+//! Session parameters negotiated via the MQTT 5 `CONNECT`/`CONNACK` handshake
+
+use crate::packets::v5::{connack::ConnackV5, connect::ConnectV5};
+
+/// The largest possible packet size in bytes, which applies if no maximum packet size has been specified
+///
+/// # Note
+/// This is the largest packet body length plus the largest fixed header.
+pub const MAX_PACKET_SIZE: u32 = 268_435_460;
+
+/// The effective session parameters of a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NegotiatedSession {
+    /// Whether a previous session is present or not
+    pub session_present: bool,
+    /// The session expiry interval in seconds
+    pub session_expiry_interval: u32,
+    /// The keep alive time in seconds
+    pub keep_alive_secs: u16,
+    /// The maximum amount of concurrent QoS 1 and 2 publications the client is willing to process
+    pub client_receive_maximum: u16,
+    /// The maximum amount of concurrent QoS 1 and 2 publications the server is willing to process
+    pub server_receive_maximum: u16,
+    /// The maximum packet size the client is willing to accept
+    pub client_maximum_packet_size: u32,
+    /// The maximum packet size the server is willing to accept
+    pub server_maximum_packet_size: u32,
+}
+impl NegotiatedSession {
+    /// Combines the parameters requested by the client with the parameters returned by the server
+    ///
+    /// # Note
+    /// The session expiry interval and keep alive time of the server take precedence over the values requested by the
+    /// client.
+    pub fn new<Bytes>(connect: &ConnectV5<Bytes>, connack: &ConnackV5<Bytes>) -> Self
+    where
+        Bytes: AsRef<[u8]>,
+    {
+        Self {
+            session_present: connack.session_present(),
+            session_expiry_interval: connack.session_expiry_interval().unwrap_or(connect.session_expiry_interval()),
+            keep_alive_secs: connack.server_keep_alive().unwrap_or(connect.keep_alive_secs()),
+            client_receive_maximum: connect.receive_maximum(),
+            server_receive_maximum: connack.receive_maximum(),
+            client_maximum_packet_size: connect.maximum_packet_size(),
+            server_maximum_packet_size: connack.maximum_packet_size(),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! MQTT 5 [topic aliases](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901113)
+
+use crate::{anyvec::AnyVec, error::ProtocolError, packets::v5::publish::PublishV5};
+
+/// How to send a topic to the peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AliasDecision {
+    /// The topic is already aliased; send the alias with an empty topic
+    UseAlias(u16),
+    /// A new alias has been assigned to the topic; send the alias together with the full topic
+    AssignNew(u16),
+    /// No alias is available; send the full topic without alias
+    SendFullTopic,
+}
+
+/// A fixed-capacity mapping between topic aliases and topics for one direction of a connection
+///
+/// # Note
+/// Topic aliases are scoped to a single network connection and direction, so senders and receivers need separate maps,
+/// which must be cleared on reconnect. The usable aliases are `1..=maximum`, where `maximum` is the topic alias maximum
+/// advertised by the receiver, but at most `N`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TopicAliasMap<Bytes, const N: usize> {
+    /// The topics where the topic at index `i` belongs to the alias `i + 1`
+    topics: [Option<Bytes>; N],
+    /// The highest usable alias
+    maximum: u16,
+    /// The index of the next alias to replace if all aliases are in use
+    next: usize,
+}
+impl<Bytes, const N: usize> TopicAliasMap<Bytes, N>
+where
+    Bytes: AnyVec<u8>,
+{
+    /// Creates a new, empty map for the given advertised topic alias maximum, which is capped at `N`
+    ///
+    /// # Note
+    /// For received packets, the maximum should be the topic alias maximum that has been advertised to the peer. For
+    /// sent packets, the maximum should be the topic alias maximum that has been advertised by the peer.
+    pub fn new(maximum: u16) -> Self {
+        let maximum = u16::try_from(N).map_or(maximum, |capacity| maximum.min(capacity));
+        Self { topics: core::array::from_fn(|_| None), maximum, next: 0 }
+    }
+
+    /// The highest usable alias
+    pub const fn maximum(&self) -> u16 {
+        self.maximum
+    }
+
+    /// Decides how to send the given topic, assigning a new alias if possible
+    ///
+    /// # Note
+    /// If all aliases are in use, the least recently assigned alias is reassigned. If the topic cannot be stored, the
+    /// full topic is sent instead.
+    pub fn outbound(&mut self, topic: &[u8]) -> AliasDecision {
+        // Use an existing alias
+        if let Some(alias) = self.find(topic) {
+            return AliasDecision::UseAlias(alias);
+        }
+
+        // Assign a free alias, or reassign the next alias in order
+        let limit = self.maximum as usize;
+        if limit == 0 {
+            return AliasDecision::SendFullTopic;
+        }
+        let free = self.topics.iter().take(limit).position(Option::is_none);
+        let index = free.unwrap_or(self.next);
+        let (Some(slot), Ok(topic)) = (self.topics.get_mut(index), Bytes::new(topic)) else {
+            return AliasDecision::SendFullTopic;
+        };
+        *slot = Some(topic);
+        if free.is_none() {
+            // Advance the replacement index
+            self.next = index.saturating_add(1).checked_rem(limit).unwrap_or_default();
+        }
+        Self::alias(index).map_or(AliasDecision::SendFullTopic, AliasDecision::AssignNew)
+    }
+
+    /// Resolves the topic of a received packet with the given alias, and establishes or replaces the alias if the
+    /// packet carries a topic
+    pub fn inbound(&mut self, alias: u16, topic: Option<&[u8]>) -> Result<&[u8], ProtocolError> {
+        // Validate the alias
+        if alias == 0 {
+            return Err(ProtocolError::ZeroTopicAlias);
+        }
+        if alias > self.maximum {
+            return Err(ProtocolError::TopicAliasExceedsMaximum { alias, maximum: self.maximum });
+        }
+        let slot = self.topics.get_mut(alias.saturating_sub(1) as usize);
+        let slot = slot.ok_or(ProtocolError::TopicAliasExceedsMaximum { alias, maximum: self.maximum })?;
+
+        // Establish the alias or resolve the topic
+        match topic.filter(|topic| !topic.is_empty()) {
+            Some(topic) => {
+                let topic = Bytes::new(topic).map_err(|_| ProtocolError::TopicTooLong)?;
+                Ok(Bytes::as_ref(slot.insert(topic)))
+            }
+            None => slot.as_ref().map(Bytes::as_ref).ok_or(ProtocolError::UnknownTopicAlias { alias }),
+        }
+    }
+
+    /// Gets the topic of the given alias
+    pub fn get(&self, alias: u16) -> Option<&[u8]> {
+        let slot = self.topics.get((alias as usize).checked_sub(1)?)?;
+        slot.as_ref().map(Bytes::as_ref)
+    }
+
+    /// Removes all aliases, e.g. on reconnect
+    pub fn clear(&mut self) {
+        self.topics.iter_mut().for_each(|slot| *slot = None);
+        self.next = 0;
+    }
+
+    /// Rewrites an outgoing packet to use a topic alias where possible (see [`Self::outbound`])
+    ///
+    /// # Note
+    /// Packets that already carry a topic alias are passed through as-is.
+    pub fn apply(&mut self, publish: PublishV5<Bytes>) -> Result<PublishV5<Bytes>, &'static str> {
+        if publish.topic_alias().is_some() {
+            return Ok(publish);
+        }
+        match self.outbound(publish.topic()) {
+            AliasDecision::UseAlias(alias) => publish.rewrite_topic(Some(&[]), Some(alias)),
+            AliasDecision::AssignNew(alias) => publish.rewrite_topic(None, Some(alias)),
+            AliasDecision::SendFullTopic => Ok(publish),
+        }
+    }
+
+    /// Rewrites a received packet to carry its full topic and no topic alias (see [`Self::inbound`])
+    pub fn strip(&mut self, publish: PublishV5<Bytes>) -> Result<PublishV5<Bytes>, ProtocolError> {
+        let Some(alias) = publish.topic_alias() else {
+            return Ok(publish);
+        };
+        let topic = self.inbound(alias, Some(publish.topic()))?;
+        publish.rewrite_topic(Some(topic), None).map_err(|_| ProtocolError::TopicTooLong)
+    }
+
+    /// Finds the alias of the given topic
+    fn find(&self, topic: &[u8]) -> Option<u16> {
+        let mut slots = self.topics.iter().take(self.maximum as usize);
+        let index = slots.position(|slot| slot.as_ref().is_some_and(|slot| slot.as_ref() == topic))?;
+        Self::alias(index)
+    }
+
+    /// The alias for the given index
+    fn alias(index: usize) -> Option<u16> {
+        u16::try_from(index.checked_add(1)?).ok()
+    }
+}
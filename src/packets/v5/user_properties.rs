@@ -0,0 +1,180 @@
+//! MQTT 5 [user properties](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901068)
+
+use crate::{
+    anyvec::AnyVec,
+    coding::{encoder::check_field_len, properties},
+    packets::v5::properties::Properties,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+    marker::PhantomData,
+    slice,
+};
+
+/// A list of MQTT 5 user properties (i.e. UTF-8 key/value pairs)
+///
+/// # Note
+/// The pairs are kept in insertion order, and keys may occur multiple times as permitted by the specification. The
+/// list can be shared across packets by reading it from and appending it to a [`Properties`] block.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct UserProperties<Seq, Bytes> {
+    /// The key/value pairs
+    pairs: Seq,
+    /// The byte container type
+    _bytes: PhantomData<Bytes>,
+}
+impl<Seq, Bytes> UserProperties<Seq, Bytes>
+where
+    Seq: AnyVec<(Bytes, Bytes)>,
+    Bytes: AnyVec<u8>,
+{
+    /// Creates a new, empty list
+    pub fn new() -> Self {
+        Self { pairs: Seq::default(), _bytes: PhantomData }
+    }
+
+    /// Appends a key/value pair
+    ///
+    /// # Note
+    /// Key and value must be valid MQTT UTF-8 strings of at most `u16::MAX` bytes. The list is left unchanged if the
+    /// pair cannot be appended.
+    pub fn push<K, V>(&mut self, key: K, value: V) -> Result<(), &'static str>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        // Validate the pair
+        let (key, value) = (key.as_ref(), value.as_ref());
+        check_field_len(&key)?;
+        check_field_len(&value)?;
+        properties::validate_utf8(key)?;
+        properties::validate_utf8(value)?;
+
+        // Copy and append the pair
+        let pair = (Bytes::new(key)?, Bytes::new(value)?);
+        self.pairs.push(pair)
+    }
+    /// Appends a key/value pair (see [`Self::push`])
+    pub fn with<K, V>(mut self, key: K, value: V) -> Result<Self, &'static str>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.push(key, value)?;
+        Ok(self)
+    }
+
+    /// Reads the user properties of the given properties block, preserving their order
+    pub fn from_properties<T>(properties: &Properties<T>) -> Result<Self, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut this = Self::new();
+        for (key, value) in properties.user_properties() {
+            // The pairs have been validated by the properties block already
+            let pair = (Bytes::new(key)?, Bytes::new(value)?);
+            this.pairs.push(pair)?;
+        }
+        Ok(this)
+    }
+}
+impl<Seq, Bytes> UserProperties<Seq, Bytes>
+where
+    Seq: AsRef<[(Bytes, Bytes)]>,
+    Bytes: AsRef<[u8]>,
+{
+    /// The amount of key/value pairs
+    pub fn len(&self) -> usize {
+        self.pairs.as_ref().len()
+    }
+    /// Whether the list is empty or not
+    pub fn is_empty(&self) -> bool {
+        self.pairs.as_ref().is_empty()
+    }
+
+    /// An iterator over the key/value pairs as `(key, value)` tuples
+    pub fn iter(&self) -> Iter<'_, Bytes> {
+        Iter { pairs: self.pairs.as_ref().iter() }
+    }
+    /// An iterator over the values of all pairs with the given key
+    pub fn get<'a>(&'a self, key: &'a [u8]) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.iter().filter_map(move |(key_, value)| (key_ == key).then_some(value))
+    }
+
+    /// Appends all key/value pairs to the given properties block
+    ///
+    /// # Note
+    /// The properties block is left unchanged if not all pairs can be appended.
+    pub fn append_to<T>(&self, properties: &mut Properties<T>) -> Result<(), &'static str>
+    where
+        T: AnyVec<u8>,
+    {
+        properties.push_user_properties(self)
+    }
+}
+impl<Seq, Bytes> Debug for UserProperties<Seq, Bytes>
+where
+    Seq: AsRef<[(Bytes, Bytes)]>,
+    Bytes: AsRef<[u8]>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+#[cfg(feature = "defmt")]
+impl<Seq, Bytes> defmt::Format for UserProperties<Seq, Bytes>
+where
+    Seq: AsRef<[(Bytes, Bytes)]>,
+{
+    fn format(&self, f: defmt::Formatter<'_>) {
+        // Only emit the amount of pairs
+        defmt::write!(f, "UserProperties {{ len: {=usize} }}", self.pairs.as_ref().len())
+    }
+}
+impl<'a, Seq, Bytes> IntoIterator for &'a UserProperties<Seq, Bytes>
+where
+    Seq: AsRef<[(Bytes, Bytes)]>,
+    Bytes: AsRef<[u8]>,
+{
+    type Item = (&'a [u8], &'a [u8]);
+    type IntoIter = Iter<'a, Bytes>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the key/value pairs of [`UserProperties`]
+#[derive(Debug, Clone)]
+pub struct Iter<'a, Bytes> {
+    /// The underlying pairs
+    pairs: slice::Iter<'a, (Bytes, Bytes)>,
+}
+impl<'a, Bytes> Iterator for Iter<'a, Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.pairs.next()?;
+        Some((key.as_ref(), value.as_ref()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pairs.size_hint()
+    }
+}
+impl<Bytes> ExactSizeIterator for Iter<'_, Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    // No members to implement
+}
+impl<Bytes> FusedIterator for Iter<'_, Bytes>
+where
+    Bytes: AsRef<[u8]>,
+{
+    // No members to implement
+}
@@ -0,0 +1,151 @@
+//! [`proptest`] strategies that generate realistic, spec-valid packets
+//!
+//! # Note
+//! The strategies are generic over the container types, so they can be used with custom containers as well as with the
+//! default type aliases (e.g. `publish_strategy::<Bytes>(256)`). Topics and payloads shrink toward short topics and
+//! empty payloads; optional fields shrink toward `None`.
+
+use crate::{
+    anyvec::AnyVec,
+    packets::{
+        connack::Connack, connect::Connect, disconnect::Disconnect, packet::Packet, pingreq::Pingreq,
+        pingresp::Pingresp, puback::Puback, pubcomp::Pubcomp, publish::Publish, pubrec::Pubrec, pubrel::Pubrel,
+        suback::Suback, subscribe::Subscribe, unsuback::Unsuback, unsubscribe::Unsubscribe,
+    },
+};
+use core::fmt::Debug;
+use proptest::{
+    arbitrary::any,
+    collection::vec,
+    option, prop_oneof,
+    strategy::{Just, Strategy},
+};
+
+/// The maximum payload length of the `PUBLISH` packets generated by [`any_packet`]
+const ANY_PACKET_MAX_PAYLOAD: usize = 256;
+
+/// A realistic topic name like `sensors/3/temperature`
+fn topic() -> impl Strategy<Value = std::string::String> {
+    "[a-z0-9]{1,8}(/[a-z0-9]{1,8}){0,3}"
+}
+
+/// A realistic topic filter like `sensors/+/temperature` or `sensors/#`
+fn topic_filter() -> impl Strategy<Value = std::string::String> {
+    "([a-z0-9]{1,8}|\\+)(/([a-z0-9]{1,8}|\\+)){0,3}(/#)?"
+}
+
+/// A non-zero packet ID
+fn packet_id() -> impl Strategy<Value = u16> {
+    1..=u16::MAX
+}
+
+/// A quality-of-service level
+fn qos() -> impl Strategy<Value = u8> {
+    0..=2u8
+}
+
+/// A strategy for [`Connack`] packets
+///
+/// # Note
+/// The session-present flag is only set if the connection has been accepted.
+pub fn connack_strategy() -> impl Strategy<Value = Connack> {
+    (0..=5u8, any::<bool>())
+        .prop_map(|(return_code, session_present)| Connack::new(return_code == 0 && session_present, return_code))
+}
+
+/// A strategy for [`Connect`] packets with an optional last-will and optional login data
+pub fn connect_strategy<Bytes>() -> impl Strategy<Value = Connect<Bytes>>
+where
+    Bytes: AnyVec<u8> + Debug,
+{
+    let will = option::of((topic(), vec(any::<u8>(), 0..=64), qos(), any::<bool>()));
+    let login = option::of(("[a-z0-9]{1,16}", vec(any::<u8>(), 0..=32)));
+    (any::<u16>(), any::<bool>(), "[a-zA-Z0-9]{0,23}", will, login).prop_filter_map(
+        "Packet does not fit into the container types",
+        |(keep_alive_secs, clean_session, client_id, will, login)| {
+            // Assemble the packet
+            let mut connect = Connect::new(keep_alive_secs, clean_session, client_id).ok()?;
+            if let Some((topic, message, qos, retain)) = will {
+                connect = connect.with_will(topic, message, qos, retain).ok()?;
+            }
+            if let Some((username, password)) = login {
+                connect = connect.with_username_password(username, password).ok()?;
+            }
+            Some(connect)
+        },
+    )
+}
+
+/// A strategy for [`Publish`] packets with a payload of at most `max_payload` bytes
+///
+/// # Note
+/// Packets with a QoS greater than `0` always have a packet ID; retransmissions are only generated for these packets.
+pub fn publish_strategy<Bytes>(max_payload: usize) -> impl Strategy<Value = Publish<Bytes>>
+where
+    Bytes: AnyVec<u8> + Debug,
+{
+    let qos = prop_oneof![Just(None), (1..=2u8, packet_id(), any::<bool>()).prop_map(Some)];
+    (topic(), vec(any::<u8>(), 0..=max_payload), any::<bool>(), qos).prop_filter_map(
+        "Packet does not fit into the container types",
+        |(topic, payload, retain, qos)| {
+            // Assemble the packet
+            let publish = Publish::new(topic, payload, retain).ok()?;
+            match qos {
+                Some((qos, packet_id, dup)) => Some(publish.with_qos(qos, packet_id, dup)),
+                None => Some(publish),
+            }
+        },
+    )
+}
+
+/// A strategy for [`Subscribe`] packets with up to 4 topic filters
+pub fn subscribe_strategy<Seq, Bytes>() -> impl Strategy<Value = Subscribe<Seq, Bytes>>
+where
+    Seq: AnyVec<(Bytes, u8)> + Debug,
+    Bytes: AnyVec<u8> + Debug,
+{
+    (packet_id(), vec((topic_filter(), qos()), 1..=4))
+        .prop_filter_map("Packet does not fit into the container types", |(packet_id, topics)| {
+            Subscribe::new(packet_id, topics).ok()
+        })
+}
+
+/// A strategy for [`Unsubscribe`] packets with up to 4 topic filters
+pub fn unsubscribe_strategy<Seq, Bytes>() -> impl Strategy<Value = Unsubscribe<Seq, Bytes>>
+where
+    Seq: AnyVec<Bytes> + Debug,
+    Bytes: AnyVec<u8> + Debug,
+{
+    (packet_id(), vec(topic_filter(), 1..=4))
+        .prop_filter_map("Packet does not fit into the container types", |(packet_id, topics)| {
+            Unsubscribe::new(packet_id, topics).ok()
+        })
+}
+
+/// A strategy for packets of any known type
+///
+/// # Note
+/// Raw packets are not generated since they do not round-trip through the strict decoder.
+pub fn any_packet<TopicsSeq, TopicsQosSeq, Bytes>() -> impl Strategy<Value = Packet<TopicsSeq, TopicsQosSeq, Bytes>>
+where
+    TopicsSeq: AnyVec<Bytes> + Debug + 'static,
+    TopicsQosSeq: AnyVec<(Bytes, u8)> + Debug + 'static,
+    Bytes: AnyVec<u8> + Debug + 'static,
+{
+    prop_oneof![
+        Just(Pingreq::new()).prop_map(Packet::Pingreq),
+        Just(Pingresp::new()).prop_map(Packet::Pingresp),
+        Just(Disconnect::new()).prop_map(Packet::Disconnect),
+        packet_id().prop_map(|packet_id| Packet::Puback(Puback::new(packet_id))),
+        packet_id().prop_map(|packet_id| Packet::Pubcomp(Pubcomp::new(packet_id))),
+        packet_id().prop_map(|packet_id| Packet::Pubrec(Pubrec::new(packet_id))),
+        packet_id().prop_map(|packet_id| Packet::Pubrel(Pubrel::new(packet_id))),
+        packet_id().prop_map(|packet_id| Packet::Suback(Suback::new(packet_id))),
+        packet_id().prop_map(|packet_id| Packet::Unsuback(Unsuback::new(packet_id))),
+        connack_strategy().prop_map(Packet::Connack),
+        connect_strategy().prop_map(Packet::Connect),
+        publish_strategy(ANY_PACKET_MAX_PAYLOAD).prop_map(Packet::Publish),
+        subscribe_strategy().prop_map(Packet::Subscribe),
+        unsubscribe_strategy().prop_map(Packet::Unsubscribe),
+    ]
+}
@@ -0,0 +1,265 @@
+//! Round-trip assertion helpers, a corpus of known-good packets and an in-memory loopback transport for tests
+//!
+//! # Note
+//! The corpus is the same set of encoded/decoded pairs the crate uses for its own tests, so downstream tests can rely
+//! on it staying in sync with the codec.
+
+use crate::{
+    anyvec::AnyVec,
+    packets::{packet, TryFromIterator},
+    Connack, Connect, Disconnect, Packet, Pingreq, Pingresp, Puback, Pubcomp, Publish, Pubrec, Pubrel, Suback,
+    Subscribe, Unsuback, Unsubscribe,
+};
+use core::fmt::Debug;
+
+/// Asserts that the given packet round-trips, i.e. that decoding the encoded packet via [`Packet`] yields an equal
+/// packet which again encodes to the same bytes, and that the encoded length reported by the encoding iterator is exact
+///
+/// # Panics
+/// This function panics if the packet does not round-trip.
+pub fn assert_roundtrip<TopicsSeq, TopicsQosSeq, Bytes>(packet: &packet::Packet<TopicsSeq, TopicsQosSeq, Bytes>)
+where
+    TopicsSeq: AnyVec<Bytes> + Debug,
+    TopicsQosSeq: AnyVec<(Bytes, u8)> + Debug,
+    Bytes: AnyVec<u8> + Debug,
+    packet::Packet<TopicsSeq, TopicsQosSeq, Bytes>: PartialEq,
+{
+    // Validate the encoded length
+    let encoded = packet.into_iter();
+    let encoded_len = encoded.len();
+    assert_eq!(encoded.clone().count(), encoded_len, "Invalid encoded length of {packet:?}");
+
+    // Decode the packet and validate the re-encoded packet
+    let decoded = packet::Packet::try_from_iter(encoded.clone());
+    let Ok(decoded) = decoded else {
+        panic!("Failed to decode encoded packet {packet:?}: {decoded:?}");
+    };
+    assert_eq!(&decoded, packet, "Invalid decoded packet");
+    assert!(decoded.into_iter().eq(encoded), "Invalid re-encoded packet {packet:?}");
+}
+
+/// Asserts that the given bytes are rejected by the decoder
+///
+/// # Panics
+/// This function panics if the bytes can be decoded as [`Packet`].
+pub fn assert_rejects(bytes: &[u8]) {
+    let decoded: Result<Packet, _> = Packet::try_from_iter(bytes.iter().copied());
+    assert!(decoded.is_err(), "Unexpected success when decoding invalid packet {bytes:02x?}: {decoded:?}");
+}
+
+/// The known-good encoded/decoded packet pairs
+///
+/// # Panics
+/// This function panics if the container types cannot hold the corpus packets, which never happens with the default
+/// container types.
+#[rustfmt::skip]
+#[allow(clippy::expect_used, reason = "The corpus packets are valid")]
+pub fn corpus() -> impl ExactSizeIterator<Item = (&'static [u8], Packet)> {
+    // Build the pairs lazily to avoid large stack arrays with stack-allocated containers
+    (0..23).map(|index| -> (&'static [u8], Packet) {
+        match index {
+            // CONNACK packets
+            0 => (b"\x20\x02\x00\x00", Packet::Connack(Connack::new(false, 0))),
+            1 => (b"\x20\x02\x01\x00", Packet::Connack(Connack::new(true, 0))),
+            2 => (b"\x20\x02\x00\x05", Packet::Connack(Connack::new(false, 5))),
+            // A basic CONNECT packet
+            3 => (
+                b"\x10\x10\x00\x04MQTT\x04\x00\x00\x1E\x00\x04test",
+                Packet::Connect(Connect::new(30, false, b"test").expect("failed to create packet")),
+            ),
+            // A CONNECT packet with a last-will
+            4 => (
+                b"\x10\x25\x00\x04MQTT\x04\x04\x00\x1E\x00\x04test\x00\x08lastwill\x00\x09testolope",
+                Packet::Connect(Connect::new(30, false, b"test").expect("failed to create packet")
+                    // Set last will
+                    .with_will(b"lastwill", b"testolope", 0x00, false).expect("failed to configure last will")),
+            ),
+            // A CONNECT packet with login data
+            5 => (
+                b"\x10\x24\x00\x04MQTT\x04\xC0\x00\x1E\x00\x04test\x00\x08username\x00\x08password",
+                Packet::Connect(Connect::new(30, false, b"test").expect("failed to create packet")
+                    // Set login data
+                    .with_username_password(b"username", b"password").expect("failed to configure login data")),
+            ),
+            // A CONNECT packet with everything and clean session
+            6 => (
+                b"\x10\x3D\x00\x04MQTT\x04\xEE\xFF\xFF\x00\x08clientid\x00\x08lastwill\x00\x09testolope\x00\x08username\x00\x08password",
+                Packet::Connect(Connect::new(65535, true, b"clientid").expect("failed to create packet")
+                    // Set last will
+                    .with_will(b"lastwill", b"testolope", 0x01, true).expect("failed to configure last will")
+                    // Set login data
+                    .with_username_password(b"username", b"password").expect("failed to configure login data")),
+            ),
+            // Packets without configurable data
+            7 => (b"\xE0\x00", Packet::Disconnect(Disconnect::new())),
+            8 => (b"\xC0\x00", Packet::Pingreq(Pingreq::new())),
+            9 => (b"\xD0\x00", Packet::Pingresp(Pingresp::new())),
+            // ACK-like packets
+            10 => (b"\x40\x02\x04\x07", Packet::Puback(Puback::new(0x0407))),
+            11 => (b"\x70\x02\x04\x07", Packet::Pubcomp(Pubcomp::new(0x0407))),
+            12 => (b"\x50\x02\x04\x07", Packet::Pubrec(Pubrec::new(0x0407))),
+            13 => (b"\x60\x02\x04\x07", Packet::Pubrel(Pubrel::new(0x0407))),
+            14 => (b"\x90\x02\x04\x07", Packet::Suback(Suback::new(0x0407))),
+            15 => (b"\xB0\x02\x04\x07", Packet::Unsuback(Unsuback::new(0x0407))),
+            // A basic PUBLISH packet
+            16 => (
+                b"\x30\x0B\x00\x04TestOlope",
+                Packet::Publish(Publish::new(b"Test", b"Olope", false).expect("failed to create packet")),
+            ),
+            // A PUBLISH packet with QoS
+            17 => (
+                b"\x34\x0D\x00\x04Test\x04\x07Olope",
+                Packet::Publish(Publish::new(b"Test", b"Olope", false).expect("failed to create packet")
+                    // Set QoS
+                    .with_qos(2, 0x0407, false)),
+            ),
+            // A PUBLISH packet with everything enabled
+            18 => (
+                b"\x3B\x0D\x00\x04Test\x04\x07Olope",
+                Packet::Publish(Publish::new(b"Test", b"Olope", true).expect("failed to create packet")
+                    // Set QoS
+                    .with_qos(1, 0x0407, true)),
+            ),
+            // A single topic subscription
+            19 => (
+                b"\x82\x0E\x04\x07\x00\x09testolope\x01",
+                Packet::Subscribe(Subscribe::new(0x0407, [(b"testolope", 1)]).expect("failed to create packet")),
+            ),
+            // A multiple topic subscription
+            20 => (
+                b"\x82\x11\x04\x07\x00\x04test\x01\x00\x05olope\x02",
+                Packet::Subscribe(Subscribe::new(0x0407, [("test", 1), ("olope", 2)]).expect("failed to create packet")),
+            ),
+            // A single topic unsubscription
+            21 => (
+                b"\xA2\x0D\x04\x07\x00\x09testolope",
+                Packet::Unsubscribe(Unsubscribe::new(0x0407, [b"testolope"]).expect("failed to create packet")),
+            ),
+            // A multiple topic unsubscription
+            _ => (
+                b"\xA2\x0F\x04\x07\x00\x04test\x00\x05olope",
+                Packet::Unsubscribe(Unsubscribe::new(0x0407, ["test", "olope"]).expect("failed to create packet")),
+            ),
+        }
+    })
+}
+
+/// One direction of a [`LoopbackPipe`]
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct Channel {
+    /// The queued bytes
+    queue: std::collections::VecDeque<u8>,
+    /// Whether the writing endpoint has been dropped
+    closed: bool,
+}
+
+/// One endpoint of an in-memory loopback pipe; bytes written to one endpoint can be read from the connected endpoint
+///
+/// # Note
+/// If no bytes are queued, reads fail with [`std::io::ErrorKind::WouldBlock`] as long as the connected endpoint is
+/// alive, and signal end-of-file once it has been dropped. Since the pipe implements [`std::io::Read`] and
+/// [`std::io::Write`], it can also be used as [`crate::transport::PacketSource`] and [`crate::transport::PacketSink`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct LoopbackPipe {
+    /// The channel to read from
+    incoming: std::sync::Arc<std::sync::Mutex<Channel>>,
+    /// The channel to write to
+    outgoing: std::sync::Arc<std::sync::Mutex<Channel>>,
+    /// The maximum amount of bytes to deliver per read
+    max_read: usize,
+    /// Errors to return from the next reads
+    read_errors: std::collections::VecDeque<std::io::ErrorKind>,
+    /// Errors to return from the next writes
+    write_errors: std::collections::VecDeque<std::io::ErrorKind>,
+}
+#[cfg(feature = "std")]
+impl LoopbackPipe {
+    /// Creates a pair of connected endpoints
+    pub fn pair() -> (Self, Self) {
+        let (a, b) = (std::sync::Arc::default(), std::sync::Arc::default());
+        let endpoint = |incoming, outgoing| Self {
+            incoming,
+            outgoing,
+            max_read: usize::MAX,
+            read_errors: std::collections::VecDeque::new(),
+            write_errors: std::collections::VecDeque::new(),
+        };
+        (endpoint(std::sync::Arc::clone(&a), std::sync::Arc::clone(&b)), endpoint(b, a))
+    }
+
+    /// Limits the amount of bytes delivered per read to `max_read` (but at least `1`) to simulate partial reads
+    pub fn with_chunking(mut self, max_read: usize) -> Self {
+        self.max_read = max_read.max(1);
+        self
+    }
+
+    /// Makes the next read from this endpoint fail with the given error kind; multiple errors are returned in order
+    pub fn inject_read_error(&mut self, kind: std::io::ErrorKind) {
+        self.read_errors.push_back(kind);
+    }
+
+    /// Makes the next write to this endpoint fail with the given error kind; multiple errors are returned in order
+    pub fn inject_write_error(&mut self, kind: std::io::ErrorKind) {
+        self.write_errors.push_back(kind);
+    }
+
+    /// The amount of bytes that are queued for reading from this endpoint
+    pub fn pending(&self) -> usize {
+        Self::lock(&self.incoming).queue.len()
+    }
+
+    /// Locks the given channel
+    fn lock(channel: &std::sync::Mutex<Channel>) -> std::sync::MutexGuard<'_, Channel> {
+        // A poisoned channel is still consistent since the queue operations do not panic
+        channel.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+#[cfg(feature = "std")]
+impl std::io::Read for LoopbackPipe {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use std::io::{Error, ErrorKind};
+
+        // Return injected errors first
+        if let Some(kind) = self.read_errors.pop_front() {
+            return Err(Error::new(kind, "Injected read error"));
+        }
+
+        // Deliver the queued bytes or signal the channel state
+        let mut channel = Self::lock(&self.incoming);
+        match channel.queue.is_empty() {
+            true if buf.is_empty() || channel.closed => Ok(0),
+            true => Err(Error::new(ErrorKind::WouldBlock, "No bytes are queued")),
+            false => {
+                let len = buf.len().min(self.max_read).min(channel.queue.len());
+                let read = buf.iter_mut().zip(channel.queue.drain(..len)).map(|(slot, byte)| *slot = byte);
+                Ok(read.count())
+            }
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::io::Write for LoopbackPipe {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Return injected errors first
+        if let Some(kind) = self.write_errors.pop_front() {
+            return Err(std::io::Error::new(kind, "Injected write error"));
+        }
+
+        // Queue the bytes
+        Self::lock(&self.outgoing).queue.extend(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+#[cfg(feature = "std")]
+impl Drop for LoopbackPipe {
+    fn drop(&mut self) {
+        // Signal end-of-file to the connected endpoint
+        Self::lock(&self.outgoing).closed = true;
+    }
+}
@@ -0,0 +1,144 @@
+//! MQTT topic names and topic filters
+
+use crate::{
+    anyvec::AnyVec,
+    error::{DecoderError, TopicError},
+};
+
+/// Validates a topic name, i.e. a non-empty MQTT UTF-8 string of at most 65535 bytes without wildcards
+pub fn validate_name(name: &[u8]) -> Result<(), TopicError> {
+    // Validate the string and ensure that there are no wildcards
+    validate_string(name)?;
+    match name.iter().position(|byte| matches!(byte, b'+' | b'#')) {
+        Some(offset) => Err(TopicError::WildcardInName { offset }),
+        None => Ok(()),
+    }
+}
+
+/// Validates a topic filter, i.e. a non-empty MQTT UTF-8 string of at most 65535 bytes where `+` occupies an entire
+/// level and `#` occupies the entire last level
+pub fn validate_filter(filter: &[u8]) -> Result<(), TopicError> {
+    // Validate the string
+    validate_string(filter)?;
+
+    // Validate the wildcard placement for each level
+    let (mut levels, mut start) = (filter.split(|byte| *byte == b'/').peekable(), 0usize);
+    while let Some(level) = levels.next() {
+        // `+` must be the only character within the level
+        if let Some(position) = level.iter().position(|byte| *byte == b'+') {
+            if level.len() != 1 {
+                return Err(TopicError::InvalidSingleLevelWildcard { offset: start.saturating_add(position) });
+            }
+        }
+        // `#` must be the only character within the last level
+        if let Some(position) = level.iter().position(|byte| *byte == b'#') {
+            if level.len() != 1 || levels.peek().is_some() {
+                return Err(TopicError::InvalidMultiLevelWildcard { offset: start.saturating_add(position) });
+            }
+        }
+        start = start.saturating_add(level.len()).saturating_add(1);
+    }
+    Ok(())
+}
+
+/// Validates the topic filters of a decoded sequence that starts at the given packet offset, where each length-prefixed
+/// filter is followed by `trailer` bytes
+pub(crate) fn validate_filters<'a, I>(filters: I, offset: usize, trailer: usize) -> Result<(), DecoderError>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut offset = offset;
+    for filter in filters {
+        // Validate the filter and translate the offset into the packet
+        let start = offset.saturating_add(2);
+        if let Err(e) = validate_filter(filter) {
+            let error = err!(SpecViolation, e.description(), rule e.rule());
+            return Err(error.with_offset(start.saturating_add(e.offset())));
+        }
+        offset = start.saturating_add(filter.len()).saturating_add(trailer);
+    }
+    Ok(())
+}
+
+/// Validates the common string rules of topic names and topic filters
+fn validate_string(topic: &[u8]) -> Result<(), TopicError> {
+    // Validate the length
+    match topic.len() {
+        0 => return Err(TopicError::Empty),
+        len if len > TopicError::MAX_LEN => return Err(TopicError::TooLong),
+        _ => (),
+    }
+
+    // Validate the encoding
+    if let Err(e) = core::str::from_utf8(topic) {
+        return Err(TopicError::InvalidUtf8 { offset: e.valid_up_to() });
+    }
+    match topic.iter().position(|byte| *byte == 0) {
+        Some(offset) => Err(TopicError::NullCharacter { offset }),
+        None => Ok(()),
+    }
+}
+
+/// A [shared subscription](https://docs.oasis-open.org/mqtt/mqtt/v5.0/os/mqtt-v5.0-os.html#_Toc3901250) filter of the
+/// form `$share/{group}/{filter}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SharedFilter<'a> {
+    /// The share name
+    group: &'a [u8],
+    /// The inner topic filter
+    filter: &'a [u8],
+}
+impl<'a> SharedFilter<'a> {
+    /// The prefix of shared subscription filters
+    pub const PREFIX: &'static [u8] = b"$share/";
+
+    /// Creates a new shared subscription filter from the given share name and inner topic filter
+    ///
+    /// # Note
+    /// The share name must not be empty and must not contain `/`, `+` or `#`; the inner topic filter must be a valid
+    /// topic filter (see [`validate_filter`]).
+    pub fn new(group: &'a [u8], filter: &'a [u8]) -> Result<Self, &'static str> {
+        // Validate the share name and the inner filter
+        if group.is_empty() || group.iter().any(|byte| matches!(byte, b'/' | b'+' | b'#')) {
+            return Err("Invalid share name");
+        }
+        validate_filter(filter)?;
+        Ok(Self { group, filter })
+    }
+
+    /// Parses a shared subscription filter, or returns `None` if the filter is not a valid shared subscription filter
+    pub fn parse(filter: &'a [u8]) -> Option<Self> {
+        // Split the share name from the inner filter at the first slash after the prefix
+        let shared = filter.strip_prefix(Self::PREFIX)?;
+        let separator = shared.iter().position(|byte| *byte == b'/')?;
+        let (group, filter) = shared.split_at(separator);
+        let filter = filter.get(1..)?;
+        Self::new(group, filter).ok()
+    }
+
+    /// The share name
+    pub const fn group(&self) -> &'a [u8] {
+        self.group
+    }
+    /// The inner topic filter
+    pub const fn filter(&self) -> &'a [u8] {
+        self.filter
+    }
+
+    /// Builds the combined `$share/{group}/{filter}` topic filter
+    pub fn to_vec<T>(&self) -> Result<T, &'static str>
+    where
+        T: AnyVec<u8>,
+    {
+        // Reserve the capacity once and copy the parts
+        let mut combined = T::default();
+        let len =
+            Self::PREFIX.len().saturating_add(self.group.len()).saturating_add(1).saturating_add(self.filter.len());
+        combined.reserve(len)?;
+        for part in [Self::PREFIX, self.group, b"/", self.filter] {
+            combined.extend(part)?;
+        }
+        Ok(combined)
+    }
+}
@@ -0,0 +1,107 @@
+//! Transport-agnostic packet sources and sinks
+//!
+//! # Note
+//! Protocol logic can be written once against [`PacketSource`] and [`PacketSink`] and then be used over any transport;
+//! implementations are provided for `std::io` readers/writers and for `embedded-io` streams via `EmbeddedTransport`.
+
+/// A source of packets
+pub trait PacketSource<T> {
+    /// The transport error type
+    type Error;
+
+    /// Receives the next packet, or returns `None` if the transport has been closed cleanly at a packet boundary
+    fn recv(&mut self) -> Result<Option<T>, Self::Error>;
+}
+#[cfg(feature = "std")]
+impl<T, R> PacketSource<T> for R
+where
+    T: crate::packets::TryFromIterator,
+    R: std::io::Read,
+{
+    type Error = std::io::Error;
+
+    fn recv(&mut self) -> Result<Option<T>, Self::Error> {
+        use crate::packets::TryFromReader;
+        T::try_read_opt(self)
+    }
+}
+
+/// A sink for packets
+pub trait PacketSink<T> {
+    /// The transport error type
+    type Error;
+
+    /// Sends a packet
+    fn send(&mut self, packet: T) -> Result<(), Self::Error>;
+}
+#[cfg(feature = "std")]
+impl<T, W> PacketSink<T> for W
+where
+    T: IntoIterator<Item = u8>,
+    W: std::io::Write,
+{
+    type Error = std::io::Error;
+
+    fn send(&mut self, packet: T) -> Result<(), Self::Error> {
+        use crate::packets::ToWriter;
+        packet.write(self)
+    }
+}
+
+/// A packet transport over an [`embedded_io`] stream that buffers incoming packet frames in a caller-provided scratch
+/// buffer (see [`crate::packets::embedded::TryFromEmbeddedReader`])
+#[cfg(feature = "embedded-io")]
+#[derive(Debug)]
+pub struct EmbeddedTransport<'a, S> {
+    /// The underlying stream
+    stream: S,
+    /// The scratch buffer for incoming packet frames
+    scratch: &'a mut [u8],
+}
+#[cfg(feature = "embedded-io")]
+impl<'a, S> EmbeddedTransport<'a, S> {
+    /// Creates a new transport over the given stream
+    pub fn new(stream: S, scratch: &'a mut [u8]) -> Self {
+        Self { stream, scratch }
+    }
+
+    /// Returns the underlying stream
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+#[cfg(feature = "embedded-io")]
+impl<T, S> PacketSource<T> for EmbeddedTransport<'_, S>
+where
+    T: crate::packets::TryFromIterator,
+    S: embedded_io::Read,
+{
+    type Error = crate::error::SourceOrDecode<S::Error>;
+
+    fn recv(&mut self) -> Result<Option<T>, Self::Error> {
+        use crate::{error::SourceOrDecode, packets::embedded};
+
+        // Read the frame and check for a clean end-of-file
+        let frame = embedded::read_frame(&mut self.stream, self.scratch)?;
+        if frame.is_empty() {
+            return Ok(None);
+        }
+
+        // Try to build the packet from the frame
+        let packet = T::try_from_iter(frame.iter().copied()).map_err(SourceOrDecode::Decode)?;
+        Ok(Some(packet))
+    }
+}
+#[cfg(feature = "embedded-io")]
+impl<T, S> PacketSink<T> for EmbeddedTransport<'_, S>
+where
+    T: IntoIterator<Item = u8>,
+    S: embedded_io::Write,
+{
+    type Error = S::Error;
+
+    fn send(&mut self, packet: T) -> Result<(), Self::Error> {
+        use crate::packets::embedded::ToEmbeddedWriter;
+        packet.write_embedded(&mut self.stream)
+    }
+}
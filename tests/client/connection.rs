@@ -0,0 +1,49 @@
+#![cfg(feature = "std")]
+
+use mqtt_tiny::{
+    client::MqttConnection, test_util::LoopbackPipe, Connack, Connect, Disconnect, Packet, Puback, Publish,
+};
+use std::io::{ErrorKind, Read, Write};
+
+/// Tests a connection lifecycle over an in-memory loopback pipe
+#[test]
+pub fn lifecycle() {
+    // Prepare the server responses
+    let (client, mut server) = LoopbackPipe::pair();
+    let connack = Connack::new(false, 0);
+    let puback = Puback::new(7);
+    server.write_all(&(&connack).into_iter().collect::<Vec<u8>>()).expect("Failed to write response");
+    server.write_all(&(&puback).into_iter().collect::<Vec<u8>>()).expect("Failed to write response");
+
+    // Connect, publish and disconnect
+    let connect = Connect::new(30, true, b"mqtttinytestsclient").expect("Failed to create packet");
+    let (mut connection, received) =
+        MqttConnection::connect(client.with_chunking(3), connect.clone()).expect("Failed to connect");
+    assert_eq!(received, connack, "Invalid received CONNACK packet");
+    let publish = Publish::new(b"test", b"Olope", false).expect("Failed to create packet").with_qos(1, 7, false);
+    connection.send(&publish).expect("Failed to send packet");
+    let received = connection.recv().expect("Failed to receive packet");
+    assert_eq!(received, Packet::Puback(puback), "Invalid received PUBACK packet");
+    drop(connection.disconnect().expect("Failed to disconnect"));
+
+    // Validate the output
+    let mut expected: Vec<u8> = connect.into_iter().collect();
+    expected.extend(publish);
+    expected.extend(Disconnect::new());
+    let mut output = Vec::new();
+    server.read_to_end(&mut output).expect("Failed to read output");
+    assert_eq!(output, expected, "Invalid output");
+}
+
+/// Tests that transport errors are propagated by the connection
+#[test]
+pub fn error() {
+    // Fail the CONNECT write
+    let (mut client, _server) = LoopbackPipe::pair();
+    client.inject_write_error(ErrorKind::BrokenPipe);
+    let connect = Connect::new(30, true, b"mqtttinytestsclient").expect("Failed to create packet");
+    let Err(e) = MqttConnection::connect(client, connect) else {
+        panic!("Unexpected success when connecting over failing transport");
+    };
+    assert_eq!(e.kind(), ErrorKind::BrokenPipe, "Invalid error kind");
+}
@@ -0,0 +1,58 @@
+#![cfg(all(feature = "std", feature = "embedded-nal"))]
+
+use embedded_nal::nb;
+use mqtt_tiny::{
+    nal::NalSession,
+    packets::{ToWriter, TryFromReader},
+    Connack, Connect, Disconnect, Packet, Puback, Publish,
+};
+use std::{net::TcpListener, thread};
+
+/// Tests a session lifecycle against a broker over the `std` embedded-nal stack
+#[test]
+pub fn lifecycle() {
+    // Start the broker
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind listener");
+    let remote = listener.local_addr().expect("Failed to get listener address");
+    let broker = thread::spawn(move || {
+        // Acknowledge the connection and the publication
+        let (mut stream, _) = listener.accept().expect("Failed to accept connection");
+        let connect = Packet::try_read(&mut stream).expect("Failed to read CONNECT packet");
+        Connack::new(false, 0).write(&mut stream).expect("Failed to write CONNACK packet");
+        let publish = Packet::try_read(&mut stream).expect("Failed to read PUBLISH packet");
+        Puback::new(7).write(&mut stream).expect("Failed to write PUBACK packet");
+        let disconnect = Packet::try_read(&mut stream).expect("Failed to read DISCONNECT packet");
+        (connect, publish, disconnect)
+    });
+
+    // Connect, publish and disconnect
+    let mut stack = std_embedded_nal::Stack;
+    let mut tx = [0; 64];
+    let mut session: NalSession<_, Vec<u8>> = NalSession::new(&mut stack, &mut tx).expect("Failed to create session");
+    let connect = Connect::new(30, true, b"mqtttinytestsclient").expect("Failed to create packet");
+    let connack = nb::block!(session.connect(&mut stack, remote, &connect)).expect("Failed to connect");
+    assert_eq!(connack, Connack::new(false, 0), "Invalid received CONNACK packet");
+    let publish = Publish::new(b"test", b"Olope", false).expect("Failed to create packet").with_qos(1, 7, false);
+    nb::block!(session.send_packet(&mut stack, &publish)).expect("Failed to send packet");
+    let puback: Packet = nb::block!(session.recv_packet(&mut stack)).expect("Failed to receive packet");
+    assert_eq!(puback, Packet::Puback(Puback::new(7)), "Invalid received PUBACK packet");
+    nb::block!(session.send_packet(&mut stack, Disconnect::new())).expect("Failed to send packet");
+    session.close(&mut stack).expect("Failed to close session");
+
+    // Validate the packets received by the broker
+    let (received_connect, received_publish, received_disconnect) = broker.join().expect("Broker has panicked");
+    assert_eq!(received_connect, Packet::Connect(connect), "Invalid CONNECT packet");
+    assert_eq!(received_publish, Packet::Publish(publish), "Invalid PUBLISH packet");
+    assert_eq!(received_disconnect, Packet::Disconnect(Disconnect::new()), "Invalid DISCONNECT packet");
+}
+
+/// Tests that packets which do not fit into the transmit buffer are rejected
+#[test]
+pub fn too_large() {
+    let mut stack = std_embedded_nal::Stack;
+    let mut tx = [0; 8];
+    let mut session: NalSession<_, Vec<u8>> = NalSession::new(&mut stack, &mut tx).expect("Failed to create session");
+    let publish = Publish::new(b"test", b"Olope", false).expect("Failed to create packet");
+    let result = session.send_packet(&mut stack, &publish);
+    assert!(matches!(result, Err(nb::Error::Other(_))), "Unexpected success when sending too large packet");
+}
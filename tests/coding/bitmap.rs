@@ -1,12 +1,12 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use mqtt_tiny::coding::{length::Length, Decoder, Encoder};
 use std::ops::Deref;
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
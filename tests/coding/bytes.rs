@@ -1,15 +1,16 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use mqtt_tiny::{
     anyvec::AnyVec,
     coding::{length::Length, Decoder, Encoder},
+    error::EncodeError,
 };
 use std::ops::Deref;
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, { 65 * 1024 }>;
 
 /// A test vector for known-good encoded/decoded pairs
@@ -84,6 +85,18 @@ pub fn decode() {
     }
 }
 
+/// Tests successful decoding into the same container
+#[test]
+pub fn decode_into() {
+    let mut decoded = Vec::default();
+    for test_vector in Good::all().iter().chain(Good::all().iter()) {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        Decoder::new(encoded).bytes_into(&mut decoded).expect("Failed to decode valid byte field");
+        assert_eq!(decoded.deref(), test_vector.decoded.as_slice(), "Invalid decoded byte field")
+    }
+}
+
 /// Tests successful decoding
 #[test]
 pub fn decode_optional() {
@@ -145,3 +158,50 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid byte field");
     }
 }
+
+/// Tests failing decoding of byte fields that are truncated at or around a chunk boundary
+#[test]
+pub fn decode_truncated() {
+    for test_vector in Good::all() {
+        for cut in [2, 3, 257, 258, 259, test_vector.encoded.len()] {
+            // Truncate the field by one byte after the cut
+            let Some(truncated) = test_vector.encoded.get(..cut.saturating_sub(1)) else {
+                continue;
+            };
+            if truncated.len() < 2 || truncated.len() == test_vector.encoded.len() {
+                // Skip truncated headers and untruncated fields
+                continue;
+            }
+
+            // Decode and validate
+            let decoded: Result<Vec, _> = Decoder::new(truncated.iter().copied()).bytes();
+            assert!(decoded.is_err(), "Unexpected success when decoding truncated byte field");
+        }
+    }
+}
+
+/// Tests fallible encoding at the field length boundary
+#[test]
+pub fn encode_try() {
+    // Encode a max-length byte field
+    let max: Vec = AnyVec::new(&[0x07; 65_535]).expect("Failed to create test vector");
+    let length: usize = Length::new().try_bytes(&max).expect("Failed to compute valid length").into();
+    let encoded = Encoder::default().try_bytes(max.clone()).expect("Failed to encode valid byte field");
+    let encoded: Vec = encoded.into_iter().collect();
+    assert_eq!(encoded.len(), 65_537, "Invalid encoded byte field");
+    assert_eq!(length, 65_537, "Invalid encoded length");
+
+    // Encode a max-length optional byte field
+    let encoded = Encoder::default().try_optional_bytes(Some(max)).expect("Failed to encode valid byte field");
+    let encoded: Vec = encoded.into_iter().collect();
+    assert_eq!(encoded.len(), 65_537, "Invalid encoded byte field");
+
+    // Encode a too-long byte field
+    let too_long: Vec = AnyVec::new(&[0x07; 65_536]).expect("Failed to create test vector");
+    let error = EncodeError::FieldTooLong { len: 65_536 };
+    assert_eq!(error.excess(), 1, "Invalid excess length");
+    assert_eq!(Length::new().try_bytes(&too_long).err(), Some(error), "Unexpected success when computing length");
+    assert_eq!(Length::new().try_optional_bytes(&Some(too_long.clone())).err(), Some(error), "Invalid error");
+    assert!(Encoder::default().try_bytes(too_long.clone()).is_err(), "Unexpected success when encoding");
+    assert!(Encoder::default().try_optional_bytes(Some(too_long)).is_err(), "Unexpected success when encoding");
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "arrayvec")]
+
+use arrayvec::ArrayVec;
+use core::ops::Deref;
+use mqtt_tiny::coding::Encoder;
+
+/// Tests collecting into a vector with enough capacity
+#[test]
+pub fn collect_into() {
+    // Collect into a vector that can exactly hold the encoded bytes
+    let encoded = Encoder::default().u8(0x04).u16(0x0707);
+    let collected: ArrayVec<u8, 3> = encoded.collect_into().expect("Failed to collect encoded bytes");
+    assert_eq!(collected.deref(), b"\x04\x07\x07", "Invalid collected bytes");
+}
+
+/// Tests collecting into a vector with insufficient capacity
+#[test]
+pub fn collect_into_invalid() {
+    // Collect into a vector that is one byte too small
+    let encoded = Encoder::default().u8(0x04).u16(0x0707);
+    let collected: Result<ArrayVec<u8, 2>, _> = encoded.collect_into();
+    assert!(collected.is_err(), "Unexpected success when collecting into a too-small vector");
+}
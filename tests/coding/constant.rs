@@ -0,0 +1,80 @@
+use mqtt_tiny::coding::Decoder;
+
+/// A test vector for expected constants
+#[derive(Debug, Clone, Copy)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The expected constant
+    expected: &'static [u8],
+}
+impl Good {
+    /// Good encoded/expected pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self { encoded: b"", expected: b"" },
+            Self { encoded: b"\x00\x04MQTT", expected: b"\x00\x04MQTT" },
+            Self { encoded: b"\x00\x04MQTT\x04", expected: b"\x00\x04MQTT" },
+        ]
+    }
+}
+
+/// A test vector for known-bad encoded constants
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The expected constant
+    expected: &'static [u8],
+    /// The position after the offending byte
+    position: usize,
+}
+impl BadEncoded {
+    /// Bad encoded/expected pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // A truncated constant
+            Self { encoded: b"\x00\x04MQ", expected: b"\x00\x04MQTT", position: 4 },
+            // A mismatching constant
+            Self { encoded: b"\x00\x04MQTP", expected: b"\x00\x04MQTT", position: 6 },
+            Self { encoded: b"\x00\x06MQIsdp", expected: b"\x00\x04MQTT", position: 2 },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let mut decoder = Decoder::new(test_vector.encoded.iter().copied());
+        decoder.constant(test_vector.expected).expect("Failed to decode valid constant");
+        assert_eq!(decoder.position(), test_vector.expected.len(), "Invalid position");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let mut decoder = Decoder::new(test_vector.encoded.iter().copied());
+        let decoded = decoder.constant(test_vector.expected);
+        assert!(decoded.is_err(), "Unexpected success when decoding invalid constant");
+        assert_eq!(decoder.position(), test_vector.position, "Invalid position");
+    }
+}
+
+/// Tests decoding protocol versions
+#[test]
+pub fn decode_version() {
+    // Decode a supported version
+    let mut decoder = Decoder::new([0x04].iter().copied());
+    decoder.version_constant(0x04).expect("Failed to decode supported version");
+
+    // Decode an unsupported and a missing version
+    let mut decoder = Decoder::new([0x05].iter().copied());
+    assert!(decoder.version_constant(0x04).is_err(), "Unexpected success when decoding unsupported version");
+    let mut decoder = Decoder::new([].iter().copied());
+    assert!(decoder.version_constant(0x04).is_err(), "Unexpected success when decoding missing version");
+}
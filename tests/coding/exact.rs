@@ -0,0 +1,46 @@
+use mqtt_tiny::{
+    coding::Decoder,
+    error::{DecoderError, Decoding},
+};
+
+/// Tests finalizing a decoder that consumed exactly the expected amount of bytes
+#[test]
+pub fn exact() {
+    // Decode exactly the limited bytes
+    let mut decoder = Decoder::new(b"\x01\x02\x03".iter().copied()).take_exact(2);
+    assert_eq!(decoder.u16().expect("Failed to decode u16"), 0x0102, "Invalid value");
+    decoder.finish().expect("Failed to finalize exact decoder");
+}
+
+/// Tests finalizing a decoder whose source ends before the expected amount of bytes
+#[test]
+pub fn under() {
+    // Limit to more bytes than available
+    let mut decoder = Decoder::new(b"\x01\x02".iter().copied()).take_exact(4);
+    decoder.u16().expect("Failed to decode u16");
+    assert_eq!(
+        decoder.finish(),
+        Err(DecoderError::new(Decoding::Truncated, "Truncated input").with_offset(2)),
+        "Unexpected result for truncated input"
+    );
+}
+
+/// Tests finalizing a decoder that has not consumed all expected bytes
+#[test]
+pub fn over() {
+    // Consume less bytes than limited
+    let mut decoder = Decoder::new(b"\x01\x02\x03\x04".iter().copied()).take_exact(3);
+    decoder.u16().expect("Failed to decode u16");
+    assert_eq!(
+        decoder.finish(),
+        Err(DecoderError::new(Decoding::SpecViolation, "Trailing bytes").with_offset(2)),
+        "Unexpected result for trailing bytes"
+    );
+}
+
+/// Tests that finalizing a decoder without exact limit is a no-op
+#[test]
+pub fn unlimited() {
+    let decoder = Decoder::new(b"\x01\x02".iter().copied()).limit(4);
+    decoder.finish().expect("Failed to finalize unlimited decoder");
+}
@@ -0,0 +1,142 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::anyvec::AnyVec;
+
+/// Tests extending heap-backed vectors from iterators
+#[test]
+#[cfg(feature = "alloc")]
+pub fn extend_from_iter() {
+    // Extend from an iterator with an exact size hint
+    let mut vec: std::vec::Vec<u8> = AnyVec::new(b"Test").expect("Failed to create vector");
+    vec.extend_from_iter(b"olope".iter().copied()).expect("Failed to extend vector");
+    assert_eq!(vec, b"Testolope", "Invalid extended vector");
+
+    // Extend from an iterator with a too small size hint
+    let mut vec = std::vec::Vec::new();
+    AnyVec::extend_from_iter(&mut vec, (0..1024u16).filter(|value| value % 2 == 0)).expect("Failed to extend vector");
+    assert_eq!(vec.len(), 512, "Invalid extended vector");
+}
+
+/// Tests extending fixed-capacity vectors from iterators
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn extend_from_iter_fixed() {
+    // Extend to the exact capacity
+    let mut vec: arrayvec::ArrayVec<u8, 9> = AnyVec::new(b"Test").expect("Failed to create vector");
+    vec.extend_from_iter(b"olope".iter().copied()).expect("Failed to extend vector");
+    assert_eq!(vec.as_slice(), b"Testolope", "Invalid extended vector");
+
+    // Exceed the capacity with an exact size hint
+    let mut vec: arrayvec::ArrayVec<u8, 8> = AnyVec::new(b"Test").expect("Failed to create vector");
+    let result = vec.extend_from_iter(b"olope".iter().copied());
+    assert_eq!(result, Err("Not enough memory"), "Unexpected success when exceeding capacity");
+    assert_eq!(vec.as_slice(), b"Test", "Unexpected elements after failing early");
+
+    // Exceed the capacity without size hint
+    let mut vec: arrayvec::ArrayVec<u8, 8> = AnyVec::new(b"Test").expect("Failed to create vector");
+    let result = vec.extend_from_iter(b"olope".iter().copied().filter(|_| true));
+    assert_eq!(result, Err("Not enough memory"), "Unexpected success when exceeding capacity");
+    assert_eq!(vec.as_slice(), b"Testolop", "Invalid retained elements");
+}
+
+/// Tests that fixed-capacity vectors fail before any bytes are copied if the reserved capacity is too large
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn reserve_fixed() {
+    use mqtt_tiny::{
+        coding::Decoder,
+        error::{DecoderError, Decoding, Detail},
+    };
+    type Vec = arrayvec::ArrayVec<u8, 8>;
+
+    // Decode a length-prefixed field that exceeds the capacity
+    let mut decoder = Decoder::new(b"\x00\x09Testolope".iter().copied());
+    let decoded = decoder.bytes::<Vec>();
+    let expected = DecoderError::new(Decoding::Memory, "Not enough memory");
+    let expected = expected.with_detail(Detail::Mismatch { expected: 9, found: 8 });
+    assert_eq!(decoded, Err(expected), "Unexpected success when exceeding capacity");
+    assert_eq!(decoder.position(), 2, "Unexpected bytes consumed after the length prefix");
+
+    // Decode the remainder of a limited decoder that exceeds the capacity
+    let mut decoder = Decoder::new(b"Testolope".iter().copied()).limit(9);
+    let decoded = decoder.raw_remainder::<Vec>();
+    assert_eq!(decoded, Err(expected), "Unexpected success when exceeding capacity");
+    assert_eq!(decoder.position(), 0, "Unexpected bytes consumed");
+
+    // Create a vector that exceeds the capacity
+    assert_eq!(
+        <Vec as AnyVec<u8>>::new(b"Testolope"),
+        Err("Not enough memory"),
+        "Unexpected success when exceeding capacity"
+    );
+    let mut vec: Vec = AnyVec::new(b"Test").expect("Failed to create vector");
+    assert_eq!(vec.reserve(5), Err("Not enough memory"), "Unexpected success when exceeding capacity");
+    assert_eq!(vec.reserve(4), Ok(()), "Failed to reserve exact capacity");
+}
+
+/// Tests clearing and truncating heap-backed vectors
+#[test]
+#[cfg(feature = "alloc")]
+pub fn truncate() {
+    let mut vec: std::vec::Vec<u8> = AnyVec::new(b"Testolope").expect("Failed to create vector");
+    AnyVec::truncate(&mut vec, 16);
+    assert_eq!(vec, b"Testolope", "Unexpected truncation");
+    AnyVec::truncate(&mut vec, 4);
+    assert_eq!(vec, b"Test", "Invalid truncated vector");
+    AnyVec::clear(&mut vec);
+    assert!(vec.is_empty(), "Invalid cleared vector");
+    assert!(vec.capacity() >= 9, "Unexpected deallocation");
+}
+
+/// Tests clearing and truncating fixed-capacity vectors
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn truncate_fixed() {
+    let mut vec: arrayvec::ArrayVec<u8, 9> = AnyVec::new(b"Testolope").expect("Failed to create vector");
+    AnyVec::truncate(&mut vec, 16);
+    assert_eq!(vec.as_slice(), b"Testolope", "Unexpected truncation");
+    AnyVec::truncate(&mut vec, 4);
+    assert_eq!(vec.as_slice(), b"Test", "Invalid truncated vector");
+    AnyVec::clear(&mut vec);
+    assert!(vec.is_empty(), "Invalid cleared vector");
+}
+
+/// A vector that only implements the required methods
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MinimalVec(std::vec::Vec<u8>);
+impl AsRef<[u8]> for MinimalVec {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+impl IntoIterator for MinimalVec {
+    type Item = u8;
+    type IntoIter = std::vec::IntoIter<u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl AnyVec<u8> for MinimalVec {
+    fn extend(&mut self, elements: &[u8]) -> Result<(), &'static str> {
+        self.0.extend_from_slice(elements);
+        Ok(())
+    }
+
+    fn insert(&mut self, index: usize, element: u8) -> Result<(), &'static str> {
+        self.0.insert(index, element);
+        Ok(())
+    }
+}
+
+/// Tests the default implementations of clearing and truncating
+#[test]
+pub fn truncate_default() {
+    let mut vec = MinimalVec::new(b"Testolope").expect("Failed to create vector");
+    vec.truncate(16);
+    assert_eq!(vec.as_ref(), b"Testolope", "Unexpected truncation");
+    vec.truncate(4);
+    assert_eq!(vec.as_ref(), b"Test", "Invalid truncated vector");
+    vec.clear();
+    assert!(vec.as_ref().is_empty(), "Invalid cleared vector");
+}
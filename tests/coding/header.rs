@@ -1,12 +1,16 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
-use mqtt_tiny::coding::{length::Length, Decoder, Encoder};
+use mqtt_tiny::{
+    coding::{length::Length, Decoder, Encoder},
+    error::EncodeError,
+    packets::packet::PacketType,
+};
 use std::ops::Deref;
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -69,6 +73,68 @@ pub fn encode() {
     }
 }
 
+/// Tests fallible encoding at the packet type boundary
+#[test]
+pub fn encode_try() {
+    // Encode the max packet type
+    let length: usize = Length::new().try_header(&15, &[true; 4]).expect("Failed to compute valid length").into();
+    let encoded = Encoder::default().try_header(15, [true; 4]).expect("Failed to encode valid header");
+    let encoded: Vec = encoded.into_iter().collect();
+    assert_eq!(encoded.deref(), &[0xFF], "Invalid encoded header");
+    assert_eq!(length, 1, "Invalid encoded length");
+
+    // Encode a too-large packet type
+    let error = EncodeError::InvalidPacketType { type_: 16 };
+    assert_eq!(error.excess(), 1, "Invalid excess");
+    assert_eq!(
+        Length::new().try_header(&16, &[false; 4]).err(),
+        Some(error),
+        "Unexpected success when computing length"
+    );
+    assert!(Encoder::default().try_header(16, [false; 4]).is_err(), "Unexpected success when encoding");
+}
+
+/// Tests that typed and raw header encoding produce identical bytes
+#[test]
+pub fn encode_typed() {
+    for type_ in PacketType::ALL {
+        for flags in [[false; 4], [true, false, true, false], [false, true, false, true], [true; 4]] {
+            // Encode both paths and compare
+            let raw: Vec = Encoder::default().header(type_ as u8, flags).collect();
+            let typed: Vec = Encoder::default().typed_header(type_, flags).collect();
+            assert_eq!(typed.deref(), raw.deref(), "Invalid typed header");
+        }
+    }
+}
+
+/// Tests that typed and raw header decoding produce identical results
+#[test]
+pub fn decode_typed() {
+    for test_vector in Good::all() {
+        // Decode both paths and compare
+        let encoded = test_vector.encoded.iter().copied();
+        let (type_, flags) = Decoder::new(encoded).header_typed().expect("Failed to decode valid header");
+        assert_eq!(type_, test_vector.decoded.0, "Invalid decoded header");
+        assert_eq!(flags.to_bitmap(), test_vector.decoded.1, "Invalid decoded header");
+
+        // Validate accessors
+        let [dup, qos1, qos0, retain] = test_vector.decoded.1;
+        assert_eq!(flags.dup(), dup, "Invalid dup flag");
+        assert_eq!(flags.qos(), ((qos1 as u8) << 1) | (qos0 as u8), "Invalid QoS");
+        assert_eq!(flags.retain(), retain, "Invalid retain flag");
+        assert_eq!(flags.is_reserved_zero(), test_vector.decoded.1 == [false; 4], "Invalid reserved flags");
+        assert_eq!([flags.bit(3), flags.bit(2), flags.bit(1), flags.bit(0)], test_vector.decoded.1, "Invalid bits");
+        assert!(!flags.bit(4), "Invalid out-of-range bit");
+
+        // Encode via typed flags and compare
+        for type_ in PacketType::ALL {
+            let raw: Vec = Encoder::default().typed_header(type_, test_vector.decoded.1).collect();
+            let typed: Vec = Encoder::default().typed_header(type_, flags).collect();
+            assert_eq!(typed.deref(), raw.deref(), "Invalid typed header");
+        }
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -79,3 +145,23 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid header");
     }
 }
+
+/// Tests peeking at the header without consuming it
+#[test]
+pub fn peek() {
+    for test_vector in Good::all() {
+        // Peek and validate
+        let mut decoder = Decoder::new(test_vector.encoded.iter().copied()).peekable();
+        let peeked = decoder.peek_header().expect("Failed to peek valid header");
+        assert_eq!(peeked, test_vector.decoded, "Invalid peeked header");
+
+        // Ensure that the header can still be decoded
+        let decoded = decoder.header().expect("Failed to decode valid header after peeking");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded header");
+        assert_eq!(decoder.position(), 1, "Invalid position");
+    }
+
+    // Peek at an empty source
+    let mut decoder = Decoder::new([].iter().copied()).peekable();
+    assert!(decoder.peek_header().is_err(), "Unexpected success when peeking empty source");
+}
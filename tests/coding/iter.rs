@@ -0,0 +1,32 @@
+use mqtt_tiny::coding::Encoder;
+
+/// Tests consuming an encoder directly and extending it afterwards
+#[test]
+pub fn encoder() {
+    // Consume the first byte directly
+    let mut encoder = Encoder::default().u8(1).u16(2);
+    assert_eq!(encoder.len_hint(), Some(3), "Invalid length hint");
+    assert_eq!(encoder.next(), Some(1), "Invalid encoded byte");
+    assert_eq!(encoder.len_hint(), Some(2), "Invalid length hint");
+
+    // Extend the partially drained encoder and validate the remaining bytes
+    let encoder = encoder.u8(3);
+    assert!(encoder.eq([0x00, 0x02, 0x03]), "Invalid encoded bytes");
+}
+
+/// Tests passing an encoder to APIs that expect an iterator
+#[test]
+pub fn encoder_as_iter() {
+    /// Sums up all bytes of an iterator
+    fn sum(iter: impl Iterator<Item = u8>) -> usize {
+        iter.map(usize::from).sum()
+    }
+
+    // Sum up the encoded bytes
+    let encoder = Encoder::default().u8(1).u16(0x0203).raw([4, 5]);
+    assert_eq!(sum(encoder), 15, "Invalid sum");
+
+    // Ensure that unknown lengths are not reported
+    let encoder = Encoder::default().raw((0..).take_while(|byte| *byte < 4));
+    assert_eq!(encoder.len_hint(), None, "Invalid length hint");
+}
@@ -0,0 +1,36 @@
+use mqtt_tiny::coding::length::Length;
+
+/// Tests composing lengths arithmetically
+#[test]
+pub fn arithmetic() {
+    // Compose lengths via the builder and via arithmetic
+    let fluent: usize = Length::new().u8(&0).u16(&0).raw(b"Testolope").into();
+    let composed: usize = (Length::new().u8(&0) + Length::new().u16(&0) + Length::from(9)).into();
+    assert_eq!(composed, fluent, "Invalid composed length");
+
+    // Accumulate lengths
+    let mut accumulated = Length::new();
+    accumulated += Length::new().bytes(b"Test");
+    accumulated += Length::from(5);
+    assert_eq!(usize::from(accumulated), 11, "Invalid accumulated length");
+
+    // Sum-up lengths
+    let summed: Length = [b"Test".as_slice(), b"Olope"].iter().map(|bytes| Length::new().bytes(bytes)).sum();
+    assert_eq!(usize::from(summed), 13, "Invalid summed length");
+}
+
+/// Tests the frame length at each packet length boundary
+#[test]
+pub fn total_frame() {
+    #[rustfmt::skip]
+    let test_vectors = [
+        (0, 2), (127, 129),
+        (128, 131), (16_383, 16_386),
+        (16_384, 16_388), (2_097_151, 2_097_155),
+        (2_097_152, 2_097_157), (268_435_455, 268_435_460),
+    ];
+    for (body_len, frame_len) in test_vectors {
+        // Compute and validate the frame length
+        assert_eq!(Length::total_frame(body_len), frame_len, "Invalid frame length");
+    }
+}
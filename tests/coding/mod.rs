@@ -1,9 +1,21 @@
 pub mod bitmap;
 pub mod bytes;
+pub mod collect;
+pub mod constant;
+pub mod exact;
+pub mod extend;
 pub mod header;
+pub mod iter;
+pub mod length;
 pub mod packetlen;
+pub mod position;
+pub mod properties;
+pub mod property;
 pub mod raw;
+pub mod remaining;
+pub mod skip;
 pub mod topics;
 pub mod topicsqos;
 pub mod u16;
 pub mod u8;
+pub mod utf8;
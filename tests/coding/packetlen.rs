@@ -1,12 +1,15 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
-use mqtt_tiny::coding::{length::Length, Decoder, Encoder};
+use mqtt_tiny::{
+    coding::{length::Length, varint, Decoder, Encoder},
+    error::EncodeError,
+};
 use std::ops::Deref;
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -98,6 +101,25 @@ pub fn encode() {
     }
 }
 
+/// Tests fallible encoding at the packet length boundary
+#[test]
+pub fn encode_try() {
+    // Encode the max packet length
+    const MAX: usize = 0b1111111_1111111_1111111_1111111;
+    let length: usize = Length::new().try_packetlen(&MAX).expect("Failed to compute valid length").into();
+    let encoded = Encoder::default().try_packetlen(MAX).expect("Failed to encode valid packet length");
+    let encoded: Vec = encoded.into_iter().collect();
+    assert_eq!(encoded.deref(), &[0b1_1111111, 0b1_1111111, 0b1_1111111, 0b0_1111111], "Invalid encoded length");
+    assert_eq!(length, 4, "Invalid encoded length");
+
+    // Encode a too-large packet length
+    let error = EncodeError::PacketTooLarge { len: MAX + 1 };
+    assert_eq!(error.limit(), MAX, "Invalid limit");
+    assert_eq!(error.excess(), 1, "Invalid excess length");
+    assert_eq!(Length::new().try_packetlen(&(MAX + 1)).err(), Some(error), "Unexpected success when computing length");
+    assert!(Encoder::default().try_packetlen(MAX + 1).is_err(), "Unexpected success when encoding");
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -108,3 +130,29 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid flags");
     }
 }
+
+/// Tests successful en-/decoding via the variable-byte-integer helpers
+#[test]
+pub fn varint() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let (encoded, size) = varint::encode(test_vector.decoded);
+        assert_eq!(&encoded[..size], test_vector.encoded, "Invalid encoded varint");
+
+        // Decode and validate
+        let trailing = test_vector.encoded.iter().chain(&[0xFF]).copied();
+        let (decoded, consumed) = varint::decode(trailing).expect("Failed to decode valid varint");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded varint");
+        assert_eq!(consumed, test_vector.encoded.len(), "Invalid consumed length");
+    }
+}
+
+/// Tests failing decoding via the variable-byte-integer helpers
+#[test]
+pub fn varint_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        assert!(varint::decode(encoded).is_err(), "Unexpected success when decoding invalid varint");
+    }
+}
@@ -0,0 +1,88 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::{coding::Decoder, error::DecoderError};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for known-bad encoded fields and the position where decoding stopped
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The position after the failed read
+    position: usize,
+}
+impl BadEncoded {
+    /// Bad encoded CONNECT packets with the expected position
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Packet with truncated client ID
+            Self { encoded: b"\x10\x10\x00\x04MQTT\x04\x00\x00\x1E\x00\x04te", position: 16 },
+            // Packet with indicated last will but missing message
+            Self { encoded: b"\x10\x1A\x00\x04MQTT\x04\x04\x00\x1E\x00\x04test\x00\x08lastwill", position: 28 },
+            // Packet with truncated packet length
+            Self { encoded: b"\x10\x80", position: 2 },
+        ]
+    }
+}
+
+/// Tests the position while reading
+#[test]
+pub fn position() {
+    let mut decoder = Decoder::new(b"\x30\x0E\x00\x04testTestolope".iter().copied());
+    assert_eq!(decoder.position(), 0, "Invalid initial position");
+
+    // Read header and length
+    decoder.header().expect("Failed to decode valid header");
+    assert_eq!(decoder.position(), 1, "Invalid position after header");
+    let len = decoder.packetlen().expect("Failed to decode valid packet length");
+    assert_eq!(decoder.position(), 2, "Invalid position after packet length");
+
+    // Read body with a limited and peekable decoder
+    let mut decoder = decoder.limit(len).peekable();
+    let _: Vec = decoder.bytes().expect("Failed to decode valid byte field");
+    assert_eq!(decoder.position(), 8, "Invalid position after byte field");
+    assert_eq!(decoder.peek_u8(), Some(b'T'), "Invalid next byte");
+    assert_eq!(decoder.position(), 8, "Invalid position after peek");
+    decoder.skip_remaining();
+    assert_eq!(decoder.position(), 16, "Invalid position after remainder");
+}
+
+/// Decodes a CONNECT packet step by step and returns the position where decoding stopped
+fn decode_connect(encoded: &[u8]) -> (Result<(), DecoderError>, usize) {
+    // Read header and packet length
+    let mut decoder = Decoder::new(encoded.iter().copied());
+    let len = match decoder.header().and_then(|_| decoder.packetlen()) {
+        Ok(len) => len,
+        Err(e) => return (Err(e), decoder.position()),
+    };
+
+    // Read body
+    let mut decoder = decoder.limit(len);
+    let mut read_body = || {
+        let _name: [u8; 6] = decoder.raw()?;
+        let _level = decoder.u8()?;
+        let [_, _, _, _, _, f_will, _, _] = decoder.bitmap()?;
+        let _keep_alive = decoder.u16()?;
+        let _client_id: Vec = decoder.bytes()?;
+        let _will_topic: Option<Vec> = decoder.optional_bytes(f_will)?;
+        let _will_message: Option<Vec> = decoder.optional_bytes(f_will)?;
+        Ok(())
+    };
+    (read_body(), decoder.position())
+}
+
+/// Tests the position after failing decoding
+#[test]
+pub fn position_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate error and position
+        let (result, position) = decode_connect(test_vector.encoded);
+        assert!(result.is_err(), "Unexpected success when decoding invalid packet");
+        assert_eq!(position, test_vector.position, "Invalid position after failed read");
+    }
+}
@@ -0,0 +1,153 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::{
+    anyvec::AnyVec,
+    coding::{length::Length, Decoder, Encoder},
+};
+use std::ops::Deref;
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 256>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone, Copy)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: &'static [u8],
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // An empty properties block
+            Self { encoded: b"\x00", decoded: b"" },
+            // A properties block with a single property
+            Self { encoded: b"\x03\x21\x00\x0A", decoded: b"\x21\x00\x0A" },
+            // A properties block with multiple properties
+            Self { encoded: b"\x07\x21\x00\x0A\x1F\x00\x01x", decoded: b"\x21\x00\x0A\x1F\x00\x01x" },
+        ]
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // A missing length
+            Self { encoded: b"" },
+            // A truncated length
+            Self { encoded: b"\x81" },
+            // A truncated properties block
+            Self { encoded: b"\x03\x21\x00" },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    let mut decoded = Vec::default();
+    for test_vector in Good::all().iter().chain(Good::all()) {
+        // Decode and validate
+        let mut decoder = Decoder::new(test_vector.encoded.iter().copied());
+        decoder.properties_into(&mut decoded).expect("Failed to decode valid properties block");
+        assert_eq!(decoded.deref(), test_vector.decoded, "Invalid decoded properties block");
+        assert_eq!(decoder.position(), test_vector.encoded.len(), "Invalid decoder position");
+    }
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode length
+        let length: usize = Length::new().properties(&test_vector.decoded).into();
+
+        // Encode and validate
+        let properties: Vec = AnyVec::new(test_vector.decoded).expect("Failed to create test vector");
+        let encoded = Encoder::default().properties(properties);
+        let encoded: Vec = encoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded properties block");
+        assert_eq!(length, test_vector.encoded.len(), "Invalid encoded length");
+    }
+}
+
+/// Tests successful encoding of optional properties blocks
+#[test]
+pub fn encode_optional() {
+    for test_vector in Good::all() {
+        // Encode and validate None
+        let encoded = Encoder::default().optional_properties(Option::<Vec>::None);
+        let encoded: Vec = encoded.into_iter().collect();
+        let length: usize = Length::new().optional_properties(&Option::<Vec>::None).into();
+        assert_eq!(encoded.deref(), b"", "Invalid encoded properties block");
+        assert_eq!(length, 0, "Invalid encoded length");
+
+        // Encode and validate Some
+        let properties: Vec = AnyVec::new(test_vector.decoded).expect("Failed to create test vector");
+        let length: usize = Length::new().optional_properties(&Some(&properties)).into();
+        let encoded = Encoder::default().optional_properties(Some(properties));
+        let encoded: Vec = encoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded properties block");
+        assert_eq!(length, test_vector.encoded.len(), "Invalid encoded length");
+    }
+}
+
+/// Tests variable byte integer en-/decoding
+#[test]
+pub fn varint() {
+    for value in [0, 1, 127, 128, 16_383, 16_384, 2_097_151, 2_097_152, 268_435_455] {
+        // Encode, decode and validate
+        let length: usize = Length::new().varint(&value).into();
+        let encoded = Encoder::default().varint(value);
+        let encoded: Vec = encoded.into_iter().collect();
+        let decoded = Decoder::new(encoded.iter().copied()).varint().expect("Failed to decode valid integer");
+        assert_eq!(decoded, value, "Invalid decoded integer");
+        assert_eq!(length, encoded.len(), "Invalid encoded length");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let mut decoded = Vec::default();
+        let mut decoder = Decoder::new(test_vector.encoded.iter().copied());
+        let error = decoder.properties_into(&mut decoded).expect_err("Unexpected success");
+        assert!(error.is_truncated(), "Invalid error variant");
+    }
+}
+
+/// Tests that a huge declared length is rejected before any capacity is reserved
+#[test]
+pub fn decode_huge_length() {
+    // A properties block that declares the maximum length but only carries three bytes
+    let encoded = b"\xFF\xFF\xFF\x7F\x21\x00\x0A";
+    let mut decoded = Vec::default();
+    let mut decoder = Decoder::new(encoded.iter().copied());
+    let error = decoder.properties_into(&mut decoded).expect_err("Unexpected success");
+    assert!(error.is_truncated(), "Invalid error variant");
+    assert_eq!(error.offset(), 7, "Invalid error offset");
+    #[cfg(feature = "alloc")]
+    assert!(decoded.capacity() < 256, "Unexpected capacity reservation");
+
+    // A source of unknown length must not reserve the declared length either
+    let mut encoded = encoded.iter().copied();
+    let mut decoder = Decoder::new(core::iter::from_fn(|| encoded.next()));
+    let error = decoder.properties_into(&mut decoded).expect_err("Unexpected success");
+    assert!(error.is_truncated(), "Invalid error variant");
+    #[cfg(feature = "alloc")]
+    assert!(decoded.capacity() < 256, "Unexpected capacity reservation");
+}
@@ -0,0 +1,264 @@
+#![cfg(all(feature = "v5", any(feature = "alloc", feature = "arrayvec")))]
+
+use mqtt_tiny::coding::{
+    properties::{id, PropertyDecoder, PropertyEncoder, PropertyType, Value},
+    Decoder,
+};
+use std::ops::Deref;
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone, Copy)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: &'static [(u8, Value<'static>)],
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // An empty properties block
+            Self { encoded: b"", decoded: &[] },
+            // A byte
+            Self { encoded: b"\x01\x01", decoded: &[(id::PAYLOAD_FORMAT_INDICATOR, Value::Byte(1))] },
+            // A two byte integer
+            Self { encoded: b"\x21\x01\x02", decoded: &[(id::RECEIVE_MAXIMUM, Value::U16(0x0102))] },
+            // A four byte integer
+            Self {
+                encoded: b"\x02\x01\x02\x03\x04",
+                decoded: &[(id::MESSAGE_EXPIRY_INTERVAL, Value::U32(0x01020304))],
+            },
+            // A variable byte integer
+            Self { encoded: b"\x0B\x7F", decoded: &[(id::SUBSCRIPTION_IDENTIFIER, Value::Varint(127))] },
+            // Binary data
+            Self { encoded: b"\x09\x00\x02\x00\xFF", decoded: &[(id::CORRELATION_DATA, Value::Binary(b"\x00\xFF"))] },
+            // Empty binary data
+            Self { encoded: b"\x16\x00\x00", decoded: &[(id::AUTHENTICATION_DATA, Value::Binary(b""))] },
+            // A UTF-8 string
+            Self { encoded: b"\x03\x00\x04text", decoded: &[(id::CONTENT_TYPE, Value::Utf8(b"text"))] },
+            // A UTF-8 string pair
+            Self { encoded: b"\x26\x00\x01k\x00\x01v", decoded: &[(id::USER_PROPERTY, Value::Pair(b"k", b"v"))] },
+            // Repeatable properties
+            Self {
+                encoded: b"\x0B\x01\x26\x00\x01k\x00\x01v\x0B\x02\x26\x00\x01k\x00\x01w",
+                decoded: &[
+                    (id::SUBSCRIPTION_IDENTIFIER, Value::Varint(1)),
+                    (id::USER_PROPERTY, Value::Pair(b"k", b"v")),
+                    (id::SUBSCRIPTION_IDENTIFIER, Value::Varint(2)),
+                    (id::USER_PROPERTY, Value::Pair(b"k", b"w")),
+                ],
+            },
+        ]
+    }
+}
+
+/// A test vector for known-bad encoded properties blocks
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The offset of the invalid property
+    offset: usize,
+}
+impl BadEncoded {
+    /// Known-bad encoded properties blocks
+    pub const fn all() -> &'static [Self] {
+        &[
+            // A truncated byte
+            Self { encoded: b"\x01", offset: 0 },
+            // A truncated two byte integer
+            Self { encoded: b"\x21\x00", offset: 0 },
+            // A truncated four byte integer
+            Self { encoded: b"\x02\x00\x00\x00", offset: 0 },
+            // A truncated variable byte integer
+            Self { encoded: b"\x0B\x81", offset: 0 },
+            // An overlong variable byte integer
+            Self { encoded: b"\x0B\xFF\xFF\xFF\xFF\x01", offset: 0 },
+            // A truncated binary data length
+            Self { encoded: b"\x09\x00", offset: 0 },
+            // A binary data length that exceeds the block
+            Self { encoded: b"\x09\x00\x05ab", offset: 0 },
+            // A UTF-8 string length that exceeds the block
+            Self { encoded: b"\x21\x00\x0A\x03\x00\x05text", offset: 3 },
+            // An invalid UTF-8 string
+            Self { encoded: b"\x03\x00\x02\xC3\x28", offset: 0 },
+            // A truncated UTF-8 string pair
+            Self { encoded: b"\x26\x00\x01k\x00", offset: 0 },
+            // A UTF-8 string pair value length that exceeds the block
+            Self { encoded: b"\x26\x00\x01k\x00\x02v", offset: 0 },
+            // A duplicate property
+            Self { encoded: b"\x21\x00\x0A\x01\x01\x21\x00\x0B", offset: 5 },
+        ]
+    }
+}
+
+/// Tests successful decoding of all value types
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let decoded = PropertyDecoder::new(test_vector.encoded, 0);
+        let decoded: std::vec::Vec<_> = decoded.collect::<Result<_, _>>().expect("Failed to decode valid properties");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded properties");
+    }
+}
+
+/// Tests successful encoding of all value types
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let mut encoded = Vec::default();
+        let mut encoder = PropertyEncoder::new(&mut encoded);
+        for (id, value) in test_vector.decoded {
+            encoder.push(*id, *value).expect("Failed to encode valid property");
+        }
+        assert_eq!(encoder.len(), test_vector.encoded.len(), "Invalid encoded length");
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded properties");
+    }
+}
+
+/// Tests that unknown properties are preserved as raw remainder and can be re-encoded verbatim
+#[test]
+pub fn unknown() {
+    // Decode and validate
+    let encoded = b"\x21\x00\x0A\x7F\x01\x02\x21";
+    let decoded = PropertyDecoder::new(encoded, 0);
+    let decoded: std::vec::Vec<_> = decoded.collect::<Result<_, _>>().expect("Failed to decode valid properties");
+    let expected = [(id::RECEIVE_MAXIMUM, Value::U16(10)), (0x7F, Value::Raw(b"\x7F\x01\x02\x21"))];
+    assert_eq!(decoded, expected, "Invalid decoded properties");
+
+    // Re-encode and validate
+    let mut reencoded = Vec::default();
+    let mut encoder = PropertyEncoder::new(&mut reencoded);
+    encoder.push(id::RECEIVE_MAXIMUM, Value::U16(10)).expect("Failed to encode valid property");
+    encoder.push_unknown(b"\x7F\x01\x02\x21").expect("Failed to encode unknown property");
+    assert!(
+        encoder.push(id::CONTENT_TYPE, Value::Utf8(b"text")).is_err(),
+        "Unexpected property after unknown property"
+    );
+    assert!(encoder.push_unknown(b"\x7F").is_err(), "Unexpected second unknown property");
+    assert_eq!(reencoded.deref(), encoded, "Invalid re-encoded properties");
+}
+
+/// Tests the wire types of the property identifiers
+#[test]
+pub fn property_type() {
+    assert_eq!(PropertyType::of(id::MAXIMUM_QOS), Some(PropertyType::Byte), "Invalid property type");
+    assert_eq!(PropertyType::of(id::TOPIC_ALIAS), Some(PropertyType::U16), "Invalid property type");
+    assert_eq!(PropertyType::of(id::MAXIMUM_PACKET_SIZE), Some(PropertyType::U32), "Invalid property type");
+    assert_eq!(PropertyType::of(id::SUBSCRIPTION_IDENTIFIER), Some(PropertyType::Varint), "Invalid property type");
+    assert_eq!(PropertyType::of(id::AUTHENTICATION_DATA), Some(PropertyType::Binary), "Invalid property type");
+    assert_eq!(PropertyType::of(id::REASON_STRING), Some(PropertyType::Utf8), "Invalid property type");
+    assert_eq!(PropertyType::of(id::USER_PROPERTY), Some(PropertyType::Pair), "Invalid property type");
+    assert_eq!(PropertyType::of(0x00), None, "Invalid property type");
+    assert_eq!(PropertyType::of(0x7F), None, "Invalid property type");
+}
+
+/// Tests caller-supplied duplicate policies
+#[test]
+pub fn policy() {
+    // Accept duplicates of all properties
+    let encoded = b"\x21\x00\x0A\x21\x00\x0B";
+    let decoder = PropertyDecoder::new(encoded, 0).with_policy(|_| true);
+    decoder.validate().expect("Failed to decode duplicate properties");
+
+    // Reject duplicates of all properties
+    let encoded = b"\x26\x00\x01k\x00\x01v\x26\x00\x01k\x00\x01w";
+    let decoder = PropertyDecoder::new(encoded, 0).with_policy(|_| false);
+    let error = decoder.validate().expect_err("Unexpected success");
+    assert!(error.is_spec_violation(), "Invalid error variant");
+    assert_eq!(error.offset(), 7, "Invalid error offset");
+
+    // Apply the policies when encoding
+    let mut encoded = Vec::default();
+    let mut encoder = PropertyEncoder::new(&mut encoded).with_policy(|id| id == id::RECEIVE_MAXIMUM);
+    encoder.push(id::RECEIVE_MAXIMUM, Value::U16(10)).expect("Failed to encode valid property");
+    encoder.push(id::RECEIVE_MAXIMUM, Value::U16(11)).expect("Failed to encode duplicate property");
+    encoder.push(id::USER_PROPERTY, Value::Pair(b"k", b"v")).expect("Failed to encode valid property");
+    assert!(encoder.push(id::USER_PROPERTY, Value::Pair(b"k", b"w")).is_err(), "Unexpected duplicate property");
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let mut decoder = PropertyDecoder::new(test_vector.encoded, 7);
+        let error = decoder.find_map(Result::err).expect("Unexpected success");
+        assert!(error.is_spec_violation(), "Invalid error variant");
+        assert_eq!(error.offset(), test_vector.offset + 7, "Invalid error offset");
+        assert!(decoder.next().is_none(), "Unexpected property after error");
+    }
+}
+
+/// Tests failing encoding
+#[test]
+pub fn encode_invalid() {
+    let mut encoded = Vec::default();
+    let mut encoder = PropertyEncoder::new(&mut encoded);
+    encoder.push(id::RECEIVE_MAXIMUM, Value::U16(10)).expect("Failed to encode valid property");
+
+    // Validate that invalid properties are rejected and leave the block unchanged
+    assert!(encoder.push(id::RECEIVE_MAXIMUM, Value::U16(11)).is_err(), "Unexpected duplicate property");
+    assert!(encoder.push(id::RECEIVE_MAXIMUM, Value::U32(11)).is_err(), "Unexpected mismatching value type");
+    assert!(encoder.push(0x7F, Value::Byte(1)).is_err(), "Unexpected unknown property");
+    assert!(encoder.push(0x7F, Value::Raw(b"\x7F")).is_err(), "Unexpected raw property");
+    assert!(encoder.push(id::CONTENT_TYPE, Value::Utf8(b"\xC3\x28")).is_err(), "Unexpected invalid UTF-8 string");
+    assert!(encoder.push(id::USER_PROPERTY, Value::Pair(b"k", b"\0")).is_err(), "Unexpected invalid UTF-8 pair");
+    assert!(encoder.push(id::SUBSCRIPTION_IDENTIFIER, Value::Varint(1 << 28)).is_err(), "Unexpected overlong varint");
+    assert!(encoder.push_unknown(b"\x21\x00\x0A").is_err(), "Unexpected known property as unknown property");
+    assert!(encoder.push_unknown(b"").is_err(), "Unexpected empty unknown property");
+    assert_eq!(encoded.deref(), b"\x21\x00\x0A", "Invalid encoded properties");
+}
+
+/// Tests reading the length-prefixed properties block
+#[test]
+pub fn read() {
+    // Read a properties block that is followed by other fields
+    let encoded = b"\x00\x07\x05\x21\x00\x0A\x01\x01\xFF";
+    let mut decoder = Decoder::new(encoded.iter().copied());
+    decoder.u16().expect("Failed to decode valid field");
+
+    // Decode and validate
+    let mut raw = Vec::default();
+    let properties = PropertyDecoder::read(&mut decoder, &mut raw).expect("Failed to read valid properties block");
+    let decoded: std::vec::Vec<_> = properties.collect::<Result<_, _>>().expect("Failed to decode valid properties");
+    let expected = [(id::RECEIVE_MAXIMUM, Value::U16(10)), (id::PAYLOAD_FORMAT_INDICATOR, Value::Byte(1))];
+    assert_eq!(decoded, expected, "Invalid decoded properties");
+    assert_eq!(decoder.position(), 8, "Invalid decoder position");
+}
+
+/// Tests reading properties blocks with truncated or overlong property lengths
+#[test]
+pub fn read_invalid() {
+    // A property length that exceeds the input
+    let mut raw = Vec::default();
+    let mut decoder = Decoder::new(b"\x05\x21\x00\x0A".iter().copied());
+    let error = PropertyDecoder::read(&mut decoder, &mut raw).expect_err("Unexpected success");
+    assert!(error.is_truncated(), "Invalid error variant");
+    assert_eq!(error.offset(), 4, "Invalid error offset");
+
+    // A truncated property length
+    let mut decoder = Decoder::new(b"\x81".iter().copied());
+    let error = PropertyDecoder::read(&mut decoder, &mut raw).expect_err("Unexpected success");
+    assert!(error.is_truncated(), "Invalid error variant");
+
+    // An overlong property length
+    let mut decoder = Decoder::new(b"\xFF\xFF\xFF\xFF\x01".iter().copied());
+    assert!(PropertyDecoder::read(&mut decoder, &mut raw).is_err(), "Unexpected success");
+
+    // A property length that splits a property
+    let mut decoder = Decoder::new(b"\x02\x21\x00\x0A".iter().copied());
+    let properties = PropertyDecoder::read(&mut decoder, &mut raw).expect("Failed to read properties block");
+    let error = properties.validate().expect_err("Unexpected success");
+    assert!(error.is_spec_violation(), "Invalid error variant");
+    assert_eq!(error.offset(), 1, "Invalid error offset");
+}
@@ -1,4 +1,4 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
 use mqtt_tiny::{
@@ -7,9 +7,9 @@ use mqtt_tiny::{
 };
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, { 65 * 1024 }>;
 
 /// A test vector for known-good encoded/decoded pairs
@@ -67,3 +67,38 @@ pub fn encode() {
         assert_eq!(encoded.deref(), test_vector.raw.as_slice(), "Invalid encoded raw data")
     }
 }
+
+/// Tests successful encoding of borrowed slices
+#[test]
+pub fn encode_slice() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded = Encoder::default().raw_slice(&test_vector.raw);
+        let encoded: Vec = encoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.raw.as_slice(), "Invalid encoded raw data")
+    }
+}
+
+/// Tests successful en-/decoding of optional fixed-size arrays
+#[test]
+pub fn optional_array() {
+    for array in [[0x00; 4], [0x01, 0x02, 0x03, 0x04], [0xFF; 4]] {
+        // Decode and validate None and Some
+        let decoded = Decoder::new(array).optional_raw::<4>(false).expect("Failed to decode valid array");
+        assert!(decoded.is_none(), "Invalid decoded array");
+        let decoded = Decoder::new(array).optional_raw::<4>(true).expect("Failed to decode valid array");
+        assert_eq!(decoded, Some(array), "Invalid decoded array");
+
+        // Encode and validate None and Some
+        let encoded: Vec = Encoder::default().optional_raw::<4>(None).collect();
+        assert_eq!(encoded.deref(), b"", "Invalid encoded array");
+        assert_eq!(usize::from(Length::new().optional_raw::<4>(&None)), 0, "Invalid encoded length");
+        let encoded: Vec = Encoder::default().optional_raw(Some(array)).collect();
+        assert_eq!(encoded.deref(), array.as_slice(), "Invalid encoded array");
+        assert_eq!(usize::from(Length::new().optional_raw(&Some(array))), 4, "Invalid encoded length");
+    }
+
+    // Decode a truncated array
+    let decoded = Decoder::new([0x01, 0x02, 0x03]).optional_raw::<4>(true);
+    assert!(decoded.is_err(), "Unexpected success when decoding truncated array");
+}
@@ -0,0 +1,48 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::coding::Decoder;
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// Tests the remaining length while reading from a limited decoder
+#[test]
+pub fn remaining() {
+    let mut decoder = Decoder::new(b"\x00\x04test\x00\x07Testolope".iter().copied()).limit(10);
+    assert_eq!(decoder.remaining(), 10, "Invalid initial remaining length");
+
+    // Interleave reads and checks
+    let _: Vec = decoder.bytes().expect("Failed to decode valid byte field");
+    assert_eq!(decoder.remaining(), 4, "Invalid remaining length after byte field");
+    decoder.u16().expect("Failed to decode valid u16");
+    assert_eq!(decoder.remaining(), 2, "Invalid remaining length after u16");
+    decoder.skip_remaining();
+    assert_eq!(decoder.remaining(), 0, "Invalid remaining length after remainder");
+    assert!(decoder.u8().is_err(), "Unexpected success when reading past the limit");
+}
+
+/// Tests the remaining length while reading from a limited and peekable decoder
+#[test]
+pub fn remaining_peekable() {
+    let mut decoder = Decoder::new(b"\x00\x04test\x00\x07Testolope".iter().copied()).limit(10).peekable();
+    assert_eq!(decoder.remaining(), 10, "Invalid initial remaining length");
+
+    // Interleave reads, peeks and checks
+    let _: Vec = decoder.bytes().expect("Failed to decode valid byte field");
+    assert_eq!(decoder.peek_u8(), Some(0x00), "Invalid next byte");
+    assert_eq!(decoder.remaining(), 4, "Invalid remaining length after peek");
+    decoder.u8().expect("Failed to decode valid u8");
+    assert_eq!(decoder.remaining(), 3, "Invalid remaining length after u8");
+}
+
+/// Tests the remaining length if the source is shorter than the limit
+#[test]
+pub fn remaining_truncated() {
+    let mut decoder = Decoder::new(b"test".iter().copied()).limit(10);
+    assert_eq!(decoder.remaining(), 4, "Invalid remaining length of truncated source");
+    decoder.skip(4).expect("Failed to skip available bytes");
+    assert_eq!(decoder.remaining(), 0, "Invalid remaining length after truncated source");
+}
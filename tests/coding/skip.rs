@@ -0,0 +1,45 @@
+use mqtt_tiny::coding::Decoder;
+
+/// Tests skipping the exact amount of available bytes
+#[test]
+pub fn skip() {
+    let mut decoder = Decoder::new(b"Testolope".iter().copied());
+    decoder.skip(4).expect("Failed to skip available bytes");
+    assert_eq!(decoder.u8(), Ok(b'o'), "Invalid byte after skipped bytes");
+
+    // Skip the rest of the input
+    decoder.skip(4).expect("Failed to skip available bytes");
+    assert!(decoder.u8().is_err(), "Unexpected remaining byte");
+}
+
+/// Tests skipping zero bytes
+#[test]
+pub fn skip_empty() {
+    let mut decoder = Decoder::new(b"".iter().copied());
+    decoder.skip(0).expect("Failed to skip zero bytes");
+
+    // Skipping nothing must not consume anything
+    let mut decoder = Decoder::new(b"T".iter().copied());
+    decoder.skip(0).expect("Failed to skip zero bytes");
+    assert_eq!(decoder.u8(), Ok(b'T'), "Invalid byte after skipped bytes");
+}
+
+/// Tests skipping the remainder of a limited source
+#[test]
+pub fn skip_remaining() {
+    let mut source = b"Testolope".iter().copied();
+    let mut decoder = Decoder::new(source.by_ref()).limit(4);
+    decoder.skip_remaining();
+    assert!(decoder.u8().is_err(), "Unexpected remaining byte");
+
+    // Ensure that only the limited range has been consumed
+    assert_eq!(source.next(), Some(b'o'), "Invalid byte after skipped bytes");
+}
+
+/// Tests skipping more bytes than available
+#[test]
+pub fn skip_invalid() {
+    let mut decoder = Decoder::new(b"Test".iter().copied());
+    let skipped = decoder.skip(5);
+    assert!(skipped.is_err(), "Unexpected success when skipping truncated input");
+}
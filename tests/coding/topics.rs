@@ -1,4 +1,4 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use mqtt_tiny::{
     anyvec::AnyVec,
@@ -7,9 +7,9 @@ use mqtt_tiny::{
 use std::ops::Deref;
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec<T> = std::vec::Vec<T>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec<T> = arrayvec::ArrayVec<T, 64>;
 
 /// A test vector for known-good encoded/decoded pairs
@@ -83,6 +83,26 @@ pub fn decode() {
     }
 }
 
+/// Tests decoding with a limited amount of entries
+#[test]
+pub fn decode_capped() {
+    for test_vector in Good::all() {
+        // Decode with the exact amount of entries as limit
+        let entries = test_vector.decoded.len();
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded: Vec<Vec<u8>> =
+            Decoder::new(encoded).peekable().topics_capped(entries).expect("Failed to decode valid topics sequence");
+        assert_eq!(decoded.deref(), test_vector.decoded.as_slice(), "Invalid decoded topics sequence");
+
+        // Decode with a too small limit
+        if let Some(limit) = entries.checked_sub(1) {
+            let encoded = test_vector.encoded.iter().copied();
+            let decoded: Result<Vec<Vec<u8>>, _> = Decoder::new(encoded).peekable().topics_capped(limit);
+            assert!(decoded.is_err(), "Unexpected success when decoding too many entries");
+        }
+    }
+}
+
 /// Tests successful encoding
 #[test]
 pub fn encode() {
@@ -108,3 +128,21 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid topics sequence");
     }
 }
+
+/// Tests fallible encoding at the topic length boundary
+#[test]
+#[cfg(feature = "alloc")]
+pub fn encode_try() {
+    // Encode a max-length topic
+    let max: Vec<_> = vec![vec![0x07; 65_535]];
+    let length: usize = Length::new().try_topics(&max).expect("Failed to compute valid length").into();
+    let encoded = Encoder::default().try_topics(max).expect("Failed to encode valid topics sequence");
+    let encoded: Vec<u8> = encoded.into_iter().collect();
+    assert_eq!(encoded.len(), 65_537, "Invalid encoded topics sequence");
+    assert_eq!(length, 65_537, "Invalid encoded length");
+
+    // Encode a too-long topic
+    let too_long: Vec<_> = vec![vec![0x07; 65_536]];
+    assert!(Length::new().try_topics(&too_long).is_err(), "Unexpected success when computing length");
+    assert!(Encoder::default().try_topics(too_long).is_err(), "Unexpected success when encoding");
+}
@@ -1,12 +1,12 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use mqtt_tiny::coding::{length::Length, Decoder, Encoder};
 use std::ops::Deref;
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -68,6 +68,43 @@ pub fn encode() {
     }
 }
 
+/// Tests successful decoding of optional bytes
+#[test]
+pub fn decode_optional() {
+    for test_vector in Good::all() {
+        // Decode and validate None
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = Decoder::new(encoded).optional_u8(false).expect("Failed to decode valid byte");
+        assert!(decoded.is_none(), "Invalid decoded byte");
+
+        // Decode and validate Some
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = Decoder::new(encoded)
+            .optional_u8(true)
+            .expect("Failed to decode valid byte")
+            .expect("Failed to unwrap valid byte");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded byte")
+    }
+}
+
+/// Tests successful encoding of optional bytes
+#[test]
+pub fn encode_optional() {
+    for test_vector in Good::all() {
+        // Encode and validate None
+        let length: usize = Length::new().optional_u8(&None).into();
+        let encoded: Vec = Encoder::default().optional_u8(None).collect();
+        assert_eq!(encoded.deref(), b"", "Invalid encoded byte");
+        assert_eq!(length, 0, "Invalid encoded length");
+
+        // Encode and validate Some
+        let length: usize = Length::new().optional_u8(&Some(test_vector.decoded)).into();
+        let encoded: Vec = Encoder::default().optional_u8(Some(test_vector.decoded)).collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded byte");
+        assert_eq!(length, test_vector.encoded.len(), "Invalid encoded length");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
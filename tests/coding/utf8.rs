@@ -0,0 +1,109 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::coding::Decoder;
+use std::ops::Deref;
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for known-good encoded/decoded pairs
+#[derive(Debug, Clone, Copy)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: &'static str,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // An empty string
+            Self { encoded: b"\x00\x00", decoded: "" },
+            // An ASCII string
+            Self { encoded: b"\x00\x09Testolope", decoded: "Testolope" },
+            // Two-byte sequences
+            Self { encoded: b"\x00\x06\xC3\xA4\xC3\xB6\xC3\xBC", decoded: "äöü" },
+            // A three-byte sequence
+            Self { encoded: b"\x00\x03\xE2\x82\xAC", decoded: "€" },
+            // A four-byte sequence
+            Self { encoded: b"\x00\x04\xF0\x9D\x84\x9E", decoded: "𝄞" },
+            // The highest valid code point
+            Self { encoded: b"\x00\x04\xF4\x8F\xBF\xBF", decoded: "\u{10FFFF}" },
+        ]
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // A truncated data field
+            Self { encoded: b"\x00\x02T" },
+            // A null character
+            Self { encoded: b"\x00\x03T\x00T" },
+            // A lone continuation byte
+            Self { encoded: b"\x00\x01\x80" },
+            // An overlong encoding
+            Self { encoded: b"\x00\x02\xC0\x80" },
+            // An overlong three-byte encoding
+            Self { encoded: b"\x00\x03\xE0\x80\x80" },
+            // A surrogate code point
+            Self { encoded: b"\x00\x03\xED\xA0\x80" },
+            // A code point above `U+10FFFF`
+            Self { encoded: b"\x00\x04\xF4\x90\x80\x80" },
+            // A multi-byte sequence that is truncated by the field length
+            Self { encoded: b"\x00\x02\xE2\x82\xAC" },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded: Vec = Decoder::new(encoded).utf8().expect("Failed to decode valid string");
+        assert_eq!(decoded.deref(), test_vector.decoded.as_bytes(), "Invalid decoded string")
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode_optional() {
+    for test_vector in Good::all() {
+        // Decode and validate None
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded: Option<Vec> = Decoder::new(encoded).optional_utf8(false).expect("Failed to decode valid string");
+        assert!(decoded.is_none(), "Invalid decoded string");
+
+        // Decode and validate Some
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded: Vec = Decoder::new(encoded)
+            .optional_utf8(true)
+            .expect("Failed to decode valid string")
+            .expect("Failed to unwrap valid string");
+        assert_eq!(decoded.deref(), test_vector.decoded.as_bytes(), "Invalid decoded string")
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded: Result<Vec, _> = Decoder::new(encoded).utf8();
+        assert!(decoded.is_err(), "Unexpected success when decoding invalid string");
+    }
+}
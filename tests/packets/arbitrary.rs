@@ -0,0 +1,44 @@
+#![cfg(all(feature = "arbitrary", feature = "std"))]
+
+use arbitrary::{Arbitrary, Unstructured};
+use mqtt_tiny::{packets, Packet, Raw};
+use std::collections::BTreeSet;
+
+/// Generates deterministic pseudo-random entropy from the given seed
+fn entropy(seed: u64) -> std::vec::Vec<u8> {
+    // Use a simple xorshift generator
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let mut bytes = std::vec::Vec::with_capacity(512);
+    while bytes.len() < 512 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        bytes.extend_from_slice(&state.to_le_bytes());
+    }
+    bytes
+}
+
+/// Tests that arbitrary packets round-trip
+#[test]
+pub fn roundtrip() {
+    let mut names = BTreeSet::new();
+    for seed in 0..512 {
+        // Generate and round-trip packet
+        let entropy = entropy(seed);
+        let packet: Packet = Packet::arbitrary(&mut Unstructured::new(&entropy)).expect("Failed to generate packet");
+        packets::roundtrip::<Packet>(&packet).expect("Failed to round-trip packet");
+        names.insert(packet.name());
+    }
+    assert_eq!(names.len(), 14, "Not all packet types have been generated");
+}
+
+/// Tests that arbitrary raw packets round-trip
+#[test]
+pub fn roundtrip_raw() {
+    for seed in 0..256 {
+        // Generate and round-trip packet
+        let entropy = entropy(seed);
+        let packet: Raw = Raw::arbitrary(&mut Unstructured::new(&entropy)).expect("Failed to generate packet");
+        packets::roundtrip::<Raw>(&packet).expect("Failed to round-trip packet");
+    }
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "arrayvec")]
+
+use mqtt_tiny::{
+    anyvec::AnyVec,
+    coding::Decoder,
+    error::{DecoderError, Detail, Field},
+    packets::{
+        connect::Connect, publish::Publish, raw::Raw, subscribe::Subscribe, unsubscribe::Unsubscribe, TryFromIterator,
+    },
+};
+
+/// A tiny byte container
+type Bytes = arrayvec::ArrayVec<u8, 8>;
+/// A tiny topic list
+type TopicsQos = arrayvec::ArrayVec<(Bytes, u8), 1>;
+/// A tiny topic list without quality-of-service levels
+type Topics = arrayvec::ArrayVec<Bytes, 1>;
+
+/// Maps a decoding error to its description
+fn description<T>(result: Result<T, DecoderError>) -> Result<T, &'static str> {
+    result.map_err(|e| e.description())
+}
+
+/// Tests that memory errors are tagged with the overflowing field
+#[test]
+pub fn capacity() {
+    // Overflow the topic and the payload of a PUBLISH packet
+    let decoded = Publish::<Bytes>::try_from_iter(*b"\x30\x0B\x00\x09TestolopeX");
+    assert_eq!(description(decoded), Err(Field::Topic.capacity_error()), "Invalid error for overflowing topic");
+    let decoded = Publish::<Bytes>::try_from_iter(*b"\x30\x0F\x00\x04TestOlopeOlope");
+    assert_eq!(description(decoded), Err(Field::Payload.capacity_error()), "Invalid error for overflowing payload");
+
+    // Overflow the client ID and the password of a CONNECT packet
+    let decoded = Connect::<Bytes>::try_from_iter(*b"\x10\x15\x00\x04MQTT\x04\x00\x00\x1E\x00\x09testolope");
+    assert_eq!(description(decoded), Err(Field::ClientId.capacity_error()), "Invalid error for overflowing client ID");
+    let encoded = *b"\x10\x27\x00\x04MQTT\x04\xC0\x00\x1E\x00\x04test\x00\x08username\x00\x09passwords";
+    let decoded = Connect::<Bytes>::try_from_iter(encoded);
+    assert_eq!(description(decoded), Err(Field::Password.capacity_error()), "Invalid error for overflowing password");
+
+    // Overflow the topic list of a SUBSCRIBE packet
+    let decoded = Subscribe::<TopicsQos, Bytes>::try_from_iter(*b"\x82\x0E\x04\x07\x00\x09testolope\x01");
+    assert_eq!(description(decoded), Err(Field::Topics.capacity_error()), "Invalid error for overflowing topic");
+    let decoded = Subscribe::<TopicsQos, Bytes>::try_from_iter(*b"\x82\x0E\x04\x07\x00\x02te\x01\x00\x02st\x01");
+    assert_eq!(description(decoded), Err(Field::Topics.capacity_error()), "Invalid error for overflowing topic list");
+
+    // Overflow the body of a raw packet
+    let decoded = Raw::<Bytes>::try_from_iter(*b"\xF0\x09Testolope");
+    assert_eq!(description(decoded), Err(Field::Body.capacity_error()), "Invalid error for overflowing body");
+
+    // Pass other errors through
+    let decoded = Publish::<Bytes>::try_from_iter(*b"\x30\x0B\x00\x04Te");
+    assert!(decoded.is_err_and(|e| e.is_truncated()), "Invalid error for truncated topic");
+}
+
+/// Tests that memory errors name each optional field of multi-field packets
+#[test]
+pub fn capacity_optional_fields() {
+    // Overflow the last-will topic, the last-will message and the username of a CONNECT packet
+    let encoded = *b"\x10\x1F\x00\x04MQTT\x04\x04\x00\x1E\x00\x04test\x00\x09testolope\x00\x02hi";
+    let decoded = Connect::<Bytes>::try_from_iter(encoded);
+    assert_eq!(
+        description(decoded),
+        Err(Field::WillTopic.capacity_error()),
+        "Invalid error for overflowing last-will topic"
+    );
+    let encoded = *b"\x10\x1F\x00\x04MQTT\x04\x04\x00\x1E\x00\x04test\x00\x02hi\x00\x09testolope";
+    let decoded = Connect::<Bytes>::try_from_iter(encoded);
+    assert_eq!(
+        description(decoded),
+        Err(Field::WillMessage.capacity_error()),
+        "Invalid error for overflowing last-will message"
+    );
+    let encoded = *b"\x10\x1B\x00\x04MQTT\x04\x80\x00\x1E\x00\x04test\x00\x09testolope";
+    let decoded = Connect::<Bytes>::try_from_iter(encoded);
+    assert_eq!(description(decoded), Err(Field::Username.capacity_error()), "Invalid error for overflowing username");
+
+    // Overflow a topic and the topic list of an UNSUBSCRIBE packet
+    let decoded = Unsubscribe::<Topics, Bytes>::try_from_iter(*b"\xA2\x0D\x04\x07\x00\x09testolope");
+    assert_eq!(description(decoded), Err(Field::Topics.capacity_error()), "Invalid error for overflowing topic");
+    let decoded = Unsubscribe::<Topics, Bytes>::try_from_iter(*b"\xA2\x0A\x04\x07\x00\x02te\x00\x02st");
+    assert_eq!(description(decoded), Err(Field::Topics.capacity_error()), "Invalid error for overflowing topic list");
+}
+
+/// Tests that memory errors report the needed and the available capacity
+#[test]
+pub fn capacity_detail() {
+    // Overflow the topic and the payload of a PUBLISH packet
+    let error = Publish::<Bytes>::try_from_iter(*b"\x30\x0B\x00\x09TestolopeX").expect_err("Unexpected success");
+    assert!(error.is_memory(), "Invalid error variant");
+    assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 9, found: 8 }), "Invalid error detail");
+    let error = Publish::<Bytes>::try_from_iter(*b"\x30\x10\x00\x04TestOlopeOlope").expect_err("Unexpected success");
+    assert!(error.is_memory(), "Invalid error variant");
+    assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 10, found: 8 }), "Invalid error detail");
+
+    // Overflow the topic list of a SUBSCRIBE packet
+    let encoded = *b"\x82\x0E\x04\x07\x00\x02te\x01\x00\x02st\x01";
+    let error = Subscribe::<TopicsQos, Bytes>::try_from_iter(encoded).expect_err("Unexpected success");
+    assert!(error.is_memory(), "Invalid error variant");
+    assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 2, found: 1 }), "Invalid error detail");
+
+    // Overflow the body of a raw packet
+    let error = Raw::<Bytes>::try_from_iter(*b"\xF0\x09Testolope").expect_err("Unexpected success");
+    assert!(error.is_memory(), "Invalid error variant");
+    assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 9, found: 8 }), "Invalid error detail");
+}
+
+/// A tiny byte container with a custom error message
+#[derive(Debug, Default)]
+struct CustomBytes(Bytes);
+impl AsRef<[u8]> for CustomBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+impl IntoIterator for CustomBytes {
+    type Item = u8;
+    type IntoIter = <Bytes as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+impl AnyVec<u8> for CustomBytes {
+    fn extend(&mut self, elements: &[u8]) -> Result<(), &'static str> {
+        self.0.try_extend_from_slice(elements).map_err(|_| "Custom container is full")
+    }
+
+    fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    fn insert(&mut self, index: usize, element: u8) -> Result<(), &'static str> {
+        self.0.try_insert(index, element).map_err(|_| "Custom container is full")
+    }
+}
+
+/// Tests that errors of custom containers are reported as memory errors
+#[test]
+pub fn capacity_custom() {
+    let mut decoder = Decoder::new(b"\x00\x09Testolope".iter().copied());
+    let error = decoder.bytes::<CustomBytes>().expect_err("Unexpected success");
+    assert!(error.is_memory(), "Invalid error variant");
+    assert_eq!(error.description(), "Custom container is full", "Invalid error description");
+    assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 9, found: 8 }), "Invalid error detail");
+}
@@ -1,12 +1,12 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
-use mqtt_tiny::{packets::TryFromIterator, Connack};
+use mqtt_tiny::{packets::TryFromIterator, test_util, Connack, Packet};
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -18,13 +18,12 @@ pub struct Good {
     decoded: Connack,
 }
 impl Good {
-    /// Good encoded/decoded pairs
-    pub const fn all() -> [Self; 3] {
-        [
-            Self { encoded: b"\x20\x02\x00\x00", decoded: Connack::new(false, 0) },
-            Self { encoded: b"\x20\x02\x01\x00", decoded: Connack::new(true, 0) },
-            Self { encoded: b"\x20\x02\x00\x05", decoded: Connack::new(false, 5) },
-        ]
+    /// Good encoded/decoded pairs from the shared corpus
+    pub fn all() -> impl Iterator<Item = Self> {
+        test_util::corpus().filter_map(|(encoded, packet)| match packet {
+            Packet::Connack(decoded) => Some(Self { encoded, decoded }),
+            _ => None,
+        })
     }
 }
 
@@ -68,6 +67,16 @@ pub fn encode() {
     }
 }
 
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -1,12 +1,16 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
-use mqtt_tiny::{packets::TryFromIterator, Connect};
+use mqtt_tiny::{
+    error::{Decoding, Detail},
+    packets::{connect, packet::ProtocolVersion, TryFromIterator},
+    test_util, Connect, ConnectRef, Packet,
+};
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -18,38 +22,12 @@ pub struct Good {
     decoded: Connect,
 }
 impl Good {
-    /// Good encoded/decoded pairs
-    pub fn all() -> [Self; 4] {
-        [
-            // A basic packet
-            Self {
-                encoded: b"\x10\x10\x00\x04MQTT\x04\x00\x00\x1E\x00\x04test",
-                decoded: Connect::new(30, false, b"test").expect("failed to create packet"),
-            },
-            // A packet with a last-will
-            Self {
-                encoded: b"\x10\x25\x00\x04MQTT\x04\x04\x00\x1E\x00\x04test\x00\x08lastwill\x00\x09testolope",
-                decoded: Connect::new(30, false, b"test").expect("failed to create packet")
-                    // Set last will
-                    .with_will(b"lastwill", b"testolope", 0x00, false).expect("failed to configure last will"),
-            },
-            // A packet with login data
-            Self {
-                encoded: b"\x10\x24\x00\x04MQTT\x04\xC0\x00\x1E\x00\x04test\x00\x08username\x00\x08password",
-                decoded: Connect::new(30, false, b"test").expect("failed to create packet")
-                    // Set login data
-                    .with_username_password(b"username", b"password").expect("failed to configure login data"),
-            },
-            // A packet with everything and clean session
-            Self {
-                encoded: b"\x10\x3D\x00\x04MQTT\x04\xEE\xFF\xFF\x00\x08clientid\x00\x08lastwill\x00\x09testolope\x00\x08username\x00\x08password",
-                decoded: Connect::new(65535, true, b"clientid").expect("failed to create packet")
-                    // Set last will
-                    .with_will(b"lastwill", b"testolope", 0x01, true).expect("failed to configure last will")
-                    // Set login data
-                    .with_username_password(b"username", b"password").expect("failed to configure login data"),
-            },
-        ]
+    /// Good encoded/decoded pairs from the shared corpus
+    pub fn all() -> impl Iterator<Item = Self> {
+        test_util::corpus().filter_map(|(encoded, packet)| match packet {
+            Packet::Connect(decoded) => Some(Self { encoded, decoded }),
+            _ => None,
+        })
     }
 }
 
@@ -58,23 +36,57 @@ impl Good {
 pub struct BadEncoded {
     /// The invalid encoded representation
     encoded: &'static [u8],
+    /// The offset of the offending byte
+    offset: usize,
+    /// The expected/found or rule detail, if any
+    detail: Option<Detail>,
 }
 impl BadEncoded {
     /// Good encoded/decoded pairs
     pub const fn all() -> &'static [Self] {
         &[
             // Packet with invalid packet type
-            Self { encoded: b"\x20\x10\x00\x04MQTT\x04\x00\x00\x1E\x00\x04test" },
+            Self {
+                encoded: b"\x20\x10\x00\x04MQTT\x04\x00\x00\x1E\x00\x04test",
+                offset: 0,
+                detail: Some(Detail::Mismatch { expected: 1, found: 2 }),
+            },
             // Packet with invalid protocol name
-            Self { encoded: b"\x10\x10\x00\x04MQTP\x04\x00\x00\x1E\x00\x04test" },
+            Self {
+                encoded: b"\x10\x10\x00\x04MQTP\x04\x00\x00\x1E\x00\x04test",
+                offset: 7,
+                detail: Some(Detail::Mismatch { expected: b'T' as u32, found: b'P' as u32 }),
+            },
             // Packet with invalid protocol version
-            Self { encoded: b"\x10\x10\x00\x04MQTT\x05\x00\x00\x1E\x00\x04test" },
+            Self {
+                encoded: b"\x10\x10\x00\x04MQTT\x05\x00\x00\x1E\x00\x04test",
+                offset: 8,
+                detail: Some(Detail::Mismatch { expected: 4, found: 5 }),
+            },
             // Packet with indicated last will but missing topic/message
-            Self { encoded: b"\x10\x1A\x00\x04MQTT\x04\x04\x00\x1E\x00\x04test\x00\x08lastwill" },
+            Self {
+                encoded: b"\x10\x1A\x00\x04MQTT\x04\x04\x00\x1E\x00\x04test\x00\x08lastwill",
+                offset: 28,
+                detail: None,
+            },
             // Packet with indicated but missing username
-            Self { encoded: b"\x10\x10\x00\x04MQTT\x04\xC0\x00\x1E\x00\x04test" },
+            Self { encoded: b"\x10\x10\x00\x04MQTT\x04\xC0\x00\x1E\x00\x04test", offset: 18, detail: None },
             // Packet with indicated but missing password
-            Self { encoded: b"\x10\x10\x00\x04MQTT\x04\xC0\x00\x1E\x00\x04test" },
+            Self { encoded: b"\x10\x10\x00\x04MQTT\x04\xC0\x00\x1E\x00\x04test", offset: 18, detail: None },
+            // Packet with the reserved flag set
+            Self {
+                encoded: b"\x10\x10\x00\x04MQTT\x04\x01\x00\x1E\x00\x04test",
+                offset: 9,
+                detail: Some(Detail::Rule("MQTT-3.1.2-3")),
+            },
+            // Packet with invalid last-will QoS
+            Self {
+                encoded: b"\x10\x25\x00\x04MQTT\x04\x1C\x00\x1E\x00\x04test\x00\x08lastwill\x00\x09testolope",
+                offset: 9,
+                detail: Some(Detail::Rule("MQTT-3.1.2-14")),
+            },
+            // Packet with trailing bytes after the last field
+            Self { encoded: b"\x10\x11\x00\x04MQTT\x04\x00\x00\x1E\x00\x04test\x00", offset: 18, detail: None },
         ]
     }
 }
@@ -90,6 +102,22 @@ pub fn decode() {
     }
 }
 
+/// Tests that zero-copy decoding is equivalent to owned decoding
+#[test]
+pub fn decode_ref() {
+    for test_vector in Good::all() {
+        // Parse and validate the borrowed view and the consumed length
+        let (decoded, consumed) = ConnectRef::parse(test_vector.encoded).expect("Failed to decode valid packet");
+        assert_eq!(consumed, test_vector.encoded.len(), "Invalid consumed length");
+
+        // Convert the view and compare it against the owned decoder
+        let decoded: Connect = decoded.to_owned().expect("Failed to copy valid packet");
+        let owned = Connect::try_from_iter(test_vector.encoded.iter().copied()).expect("Failed to decode valid packet");
+        assert_eq!(decoded, owned, "Invalid decoded packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet");
+    }
+}
+
 /// Tests successful encoding
 #[test]
 pub fn encode() {
@@ -101,6 +129,16 @@ pub fn encode() {
     }
 }
 
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -108,6 +146,145 @@ pub fn decode_invalid() {
         // Decode and validate
         let encoded = test_vector.encoded.iter().copied();
         let decoded = Connect::try_from_iter(encoded);
-        assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
+        let error = decoded.expect_err("Unexpected success when decoding invalid packet");
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset");
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail");
     }
 }
+
+/// Tests failing zero-copy decoding
+#[test]
+pub fn decode_ref_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let decoded = ConnectRef::parse(test_vector.encoded);
+        let error = decoded.expect_err("Unexpected success when decoding invalid packet");
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset");
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail");
+    }
+}
+
+/// Tests that an unsupported protocol level is distinguished from an invalid protocol name
+#[test]
+pub fn decode_unsupported_version() {
+    // Valid protocol name with unsupported protocol level
+    let encoded = b"\x10\x10\x00\x04MQTT\x05\x00\x00\x1E\x00\x04test";
+    let error = Connect::try_from_iter(encoded.iter().copied()).expect_err("Unexpected success when decoding packet");
+    assert_eq!(*error.variant(), Decoding::UnsupportedVersion, "Invalid error variant");
+    let error = ConnectRef::parse(encoded).expect_err("Unexpected success when decoding packet");
+    assert_eq!(*error.variant(), Decoding::UnsupportedVersion, "Invalid error variant");
+
+    // Invalid protocol name with supported protocol level
+    let encoded = b"\x10\x10\x00\x04MQTP\x04\x00\x00\x1E\x00\x04test";
+    let error = Connect::try_from_iter(encoded.iter().copied()).expect_err("Unexpected success when decoding packet");
+    assert_eq!(*error.variant(), Decoding::SpecViolation, "Invalid error variant");
+    let error = ConnectRef::parse(encoded).expect_err("Unexpected success when decoding packet");
+    assert_eq!(*error.variant(), Decoding::SpecViolation, "Invalid error variant");
+}
+
+/// Tests that the will message and password are redacted in the debug representation
+#[test]
+#[cfg(not(feature = "debug-credentials"))]
+pub fn debug_redacted() {
+    // Create packet and format it
+    let packet = Connect::new(30, false, b"test").expect("failed to create packet")
+        // Set last will
+        .with_will(b"lastwill", b"testolope", 0x00, false).expect("failed to configure last will")
+        // Set login data
+        .with_username_password(b"username", b"password").expect("failed to configure login data");
+    let debug = format!("{packet:?}");
+
+    // Validate redacted fields
+    assert!(debug.contains("will_message: Some(<redacted, 9 bytes>)"), "Will message is not redacted");
+    assert!(debug.contains("password: Some(<redacted, 8 bytes>)"), "Password is not redacted");
+    assert!(!debug.contains(&format!("{:?}", b"password".as_slice())), "Password is leaked");
+
+    // Validate unredacted fields
+    let username = format!("username: Some({:?})", b"username".as_slice());
+    assert!(debug.contains(&username), "Username is not displayed");
+}
+
+/// Tests that encode-only packets are byte-equal to owned packets
+#[test]
+pub fn encode_new_ref() {
+    for test_vector in Good::all() {
+        // Create the encode-only packet from the owned packet's fields
+        let owned = &test_vector.decoded;
+        let mut packet = ConnectRef::new_ref(owned.keep_alive_secs(), owned.clean_session(), owned.client_id())
+            .expect("Failed to create packet");
+        if let (Some(topic), Some(message)) = (owned.will_topic(), owned.will_message()) {
+            packet = packet
+                .with_will_ref(topic, message, owned.will_qos(), owned.will_retain())
+                .expect("Failed to configure last-will");
+        }
+        if let (Some(username), Some(password)) = (owned.username(), owned.password()) {
+            packet = packet.with_username_password_ref(username, password).expect("Failed to configure login data");
+        }
+
+        // Encode and validate
+        let encoded: Vec = packet.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests that encode-only packets with a too-long client identifier are rejected
+#[test]
+pub fn new_ref_invalid() {
+    let client_id = [0x07; 65_536];
+    let packet = ConnectRef::new_ref(30, true, &client_id);
+    assert!(packet.is_err(), "Unexpected success when creating packet with too-long client identifier");
+}
+
+/// Tests protocol version detection
+#[test]
+pub fn detect_version() {
+    let test_vectors: [(&[u8], ProtocolVersion); 4] = [
+        (b"\x10\x12\x00\x06MQIsdp\x03\x02\x00\x1E\x00\x04test", ProtocolVersion::V3_1),
+        (b"\x10\x10\x00\x04MQTT\x04\x02\x00\x1E\x00\x04test", ProtocolVersion::V3_1_1),
+        (b"\x10\x11\x00\x04MQTT\x05\x02\x00\x1E\x00\x00\x04test", ProtocolVersion::V5),
+        // Only the fields up to the protocol level are required
+        (b"\x10\x10\x00\x04MQTT\x04", ProtocolVersion::V3_1_1),
+    ];
+    for (encoded, version) in test_vectors {
+        // Detect and validate
+        let detected = connect::detect_version(encoded).expect("Failed to detect protocol version");
+        assert_eq!(detected, version, "Invalid protocol version");
+    }
+
+    // Detect without consuming more than necessary
+    let mut iter = b"\x10\x10\x00\x04MQTT\x04\x02\x00\x1E\x00\x04test".iter().copied();
+    let detected = connect::detect_version_iter(&mut iter).expect("Failed to detect protocol version");
+    assert_eq!(detected, ProtocolVersion::V3_1_1, "Invalid protocol version");
+    assert_eq!(iter.next(), Some(0x02), "Invalid remaining input");
+}
+
+/// Tests failing protocol version detection
+#[test]
+pub fn detect_version_invalid() {
+    let test_vectors: [(&[u8], Decoding); 11] = [
+        // Truncated input
+        (b"", Decoding::Truncated),
+        (b"\x10", Decoding::Truncated),
+        (b"\x10\x10\x00", Decoding::Truncated),
+        (b"\x10\x10\x00\x04MQ", Decoding::Truncated),
+        (b"\x10\x10\x00\x06MQIsdp", Decoding::Truncated),
+        // Garbage
+        (b"\x20\x02\x00\x00", Decoding::SpecViolation),
+        (b"GET / HTTP/1.1\r\n", Decoding::SpecViolation),
+        (b"\x10\x10\x00\x05MQTTT\x04", Decoding::SpecViolation),
+        (b"\x10\x10\x00\x04MQIs\x04", Decoding::SpecViolation),
+        (b"\x10\x02\x00\x04MQTT\x04", Decoding::SpecViolation),
+        // Unsupported protocol level
+        (b"\x10\x10\x00\x06MQIsdp\x04", Decoding::UnsupportedVersion),
+    ];
+    for (encoded, variant) in test_vectors {
+        // Detect and validate
+        let error = connect::detect_version(encoded).expect_err("Unexpected success when detecting protocol version");
+        assert_eq!(*error.variant(), variant, "Invalid error variant");
+    }
+
+    // Validate the error offset
+    let error = connect::detect_version(b"\x10\x10\x00\x04MQTT\x06").expect_err("Unexpected success");
+    assert_eq!(*error.variant(), Decoding::UnsupportedVersion, "Invalid error variant");
+    assert_eq!(error.offset(), 8, "Invalid error offset");
+}
@@ -0,0 +1,95 @@
+use mqtt_tiny::error::{DecoderError, Decoding, Detail, EncodeError, SourceOrDecode, MAX_CONTEXT};
+
+/// Asserts that `T` implements `core::error::Error`
+const fn assert_error<T>()
+where
+    T: core::error::Error,
+{
+    // No-op
+}
+
+/// Tests that the error types implement `core::error::Error`, regardless of the `std` feature
+#[test]
+pub fn error() {
+    assert_error::<EncodeError>();
+    assert_error::<DecoderError>();
+    assert_error::<SourceOrDecode<EncodeError>>();
+}
+
+/// Tests that `SourceOrDecode` exposes the underlying error as source
+#[test]
+pub fn source() {
+    use core::error::Error;
+
+    let error: SourceOrDecode<EncodeError> = SourceOrDecode::Source(EncodeError::FieldTooLong { len: 65536 });
+    assert!(error.source().is_some());
+
+    let error: SourceOrDecode<EncodeError> =
+        SourceOrDecode::Decode(DecoderError::new(Decoding::SpecViolation, "Invalid packet"));
+    assert!(error.source().is_none());
+}
+
+/// Tests that the expected/found or rule detail is rendered in the display representation
+#[test]
+pub fn detail() {
+    let mismatch = Detail::Mismatch { expected: 4, found: 5 };
+    let error = DecoderError::new(Decoding::UnsupportedVersion, "Unsupported protocol version").with_detail(mismatch);
+    assert_eq!(
+        error.with_offset(8).to_string(),
+        "MQTT error: UnsupportedVersion (Unsupported protocol version; expected 4, found 5) at offset 8"
+    );
+
+    let error =
+        DecoderError::new(Decoding::SpecViolation, "Invalid QoS level").with_detail(Detail::Rule("MQTT-3.3.1-4"));
+    assert_eq!(error.to_string(), "MQTT error: SpecViolation (Invalid QoS level; violates MQTT-3.3.1-4) at offset 0");
+}
+
+/// Tests that errors compare by variant, offset and detail, but not by description
+#[test]
+pub fn equality() {
+    let error = DecoderError::new(Decoding::Truncated, "Truncated input").with_offset(4);
+    assert_eq!(error, DecoderError::new(Decoding::Truncated, "Truncated packet").with_offset(4));
+    assert_ne!(error, DecoderError::new(Decoding::Truncated, "Truncated input").with_offset(5));
+    assert_ne!(error, DecoderError::new(Decoding::Memory, "Truncated input").with_offset(4));
+    assert_ne!(error, error.with_detail(Detail::Mismatch { expected: 1, found: 2 }));
+
+    assert!(error.is_truncated() && !error.is_spec_violation() && !error.is_memory());
+}
+
+/// Tests that the context chain holds at most `MAX_CONTEXT` frames
+#[test]
+pub fn context() {
+    let error = DecoderError::new(Decoding::Truncated, "Truncated input").context("a").context("b").context("c");
+    assert_eq!(error.to_string(), "MQTT error: Truncated (Truncated input) at offset 0 in c > b > a");
+
+    let error = error.context("d");
+    assert_eq!(error.context_chain().count(), MAX_CONTEXT, "Invalid context chain length");
+    assert_eq!(error.to_string(), "MQTT error: Truncated (Truncated input) at offset 0 in c > b > a");
+}
+
+/// Tests the display representation without the `location` feature
+#[test]
+#[cfg(not(feature = "location"))]
+pub fn display() {
+    let error = DecoderError::new(Decoding::SpecViolation, "Invalid protocol name").with_offset(4);
+    assert_eq!(format!("{error}"), "MQTT error: SpecViolation (Invalid protocol name) at offset 4");
+    assert_eq!(format!("{error:#}"), "MQTT error: SpecViolation (Invalid protocol name) at offset 4");
+
+    let error: SourceOrDecode<EncodeError> = SourceOrDecode::Decode(error);
+    assert_eq!(format!("{error:#}"), "MQTT error: SpecViolation (Invalid protocol name) at offset 4");
+}
+
+/// Tests the display representation with the `location` feature
+#[test]
+#[cfg(feature = "location")]
+pub fn display() {
+    let (error, line) = (DecoderError::new(Decoding::SpecViolation, "Invalid protocol name").with_offset(4), line!());
+    assert_eq!(format!("{error}"), "MQTT error: SpecViolation (Invalid protocol name) at offset 4");
+
+    // The alternate form renders the location on a separate line
+    let expected = format!("MQTT error: SpecViolation (Invalid protocol name) at offset 4\n  at {}:{line}:", file!());
+    assert!(format!("{error:#}").starts_with(&expected), "Invalid alternate display representation");
+
+    let wrapped: SourceOrDecode<EncodeError> = SourceOrDecode::Decode(error);
+    assert_eq!(format!("{wrapped:#}"), format!("{error:#}"), "Invalid alternate display representation");
+}
@@ -0,0 +1,40 @@
+use mqtt_tiny::packets::flags::{ConnectFlags, PublishFlags};
+
+/// Tests the round-trip of all connect flag bit patterns
+#[test]
+pub fn connect_flags() {
+    for bits in 0..=u8::MAX {
+        // Decode and validate
+        let reserved = bits & 0b0000_0001 != 0;
+        let will_qos = (bits >> 3) & 0b11;
+        match ConnectFlags::from_bits(bits) {
+            Ok(flags) => {
+                assert!(!reserved && will_qos < 3, "Unexpected success when decoding invalid flags");
+                assert_eq!(flags.will_qos, will_qos, "Invalid decoded QoS");
+                assert_eq!(flags.to_bits(), bits, "Invalid round-trip");
+            }
+            Err(_) => assert!(reserved || will_qos == 3, "Failed to decode valid flags"),
+        }
+    }
+}
+
+/// Tests the round-trip of all publish flag bit patterns
+#[test]
+pub fn publish_flags() {
+    for bits in 0..=u8::MAX {
+        // Decode and validate
+        let qos = (bits >> 1) & 0b11;
+        match PublishFlags::from_bits(bits) {
+            Ok(flags) => {
+                assert!(bits <= 0b1111 && qos < 3, "Unexpected success when decoding invalid flags");
+                assert_eq!(flags.qos, qos, "Invalid decoded QoS");
+                assert_eq!(flags.to_bits(), bits, "Invalid round-trip");
+
+                // Validate the bitmap representation
+                let bitmap = [bits & 0b1000 != 0, bits & 0b0100 != 0, bits & 0b0010 != 0, bits & 0b0001 != 0];
+                assert_eq!(flags.to_bitmap(), bitmap, "Invalid bitmap");
+            }
+            Err(_) => assert!(bits > 0b1111 || qos == 3, "Failed to decode valid flags"),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+#![cfg(all(feature = "defmt", any(feature = "alloc", feature = "arrayvec")))]
+
+use mqtt_tiny::{
+    error::{EncodeError, SourceOrDecode},
+    packets::{
+        flags::{ConnectFlags, HeaderFlags, PublishFlags},
+        packet::PacketType,
+    },
+    Connack, Connect, Disconnect, Packet, Pingreq, Pingresp, Puback, Pubcomp, Publish, Pubrec, Pubrel, Raw, Suback,
+    Subscribe, Unsuback, Unsubscribe,
+};
+
+/// Asserts that `T` implements `defmt::Format`
+const fn assert_format<T>()
+where
+    T: defmt::Format,
+{
+    // No-op
+}
+
+/// Tests that the default packet types and errors implement `defmt::Format`
+#[test]
+pub fn format() {
+    // Packets
+    assert_format::<Connack>();
+    assert_format::<Connect>();
+    assert_format::<Disconnect>();
+    assert_format::<Pingreq>();
+    assert_format::<Pingresp>();
+    assert_format::<Puback>();
+    assert_format::<Pubcomp>();
+    assert_format::<Publish>();
+    assert_format::<Pubrec>();
+    assert_format::<Pubrel>();
+    assert_format::<Raw>();
+    assert_format::<Suback>();
+    assert_format::<Subscribe>();
+    assert_format::<Unsuback>();
+    assert_format::<Unsubscribe>();
+    assert_format::<Packet>();
+
+    // Packet metadata and errors
+    assert_format::<PacketType>();
+    assert_format::<HeaderFlags>();
+    assert_format::<ConnectFlags>();
+    assert_format::<PublishFlags>();
+    assert_format::<EncodeError>();
+    assert_format::<SourceOrDecode<EncodeError>>();
+}
@@ -0,0 +1,38 @@
+#![cfg(all(feature = "location", feature = "alloc"))]
+
+use mqtt_tiny::{packets::TryFromIterator, Connect, ConnectRef, Publish, Subscribe};
+
+/// Asserts that the error location points to the given packet decoder
+fn assert_location(location: &core::panic::Location, file: &str) {
+    let path = location.file().replace('\\', "/");
+    assert!(path.ends_with(file), "Invalid error location: {location}");
+}
+
+/// Tests that errors of the generic decoder helpers report the location of the packet decoder
+#[test]
+pub fn location() {
+    // Invalid protocol name
+    let error = Connect::try_from_iter(*b"\x10\x10\x00\x04MQTP\x04\x00\x00\x1E\x00\x04test")
+        .expect_err("Unexpected success when decoding invalid packet");
+    assert_location(error.location(), "src/packets/connect.rs");
+
+    // Invalid protocol name via the zero-copy decoder
+    let error = ConnectRef::parse(b"\x10\x10\x00\x04MQTP\x04\x00\x00\x1E\x00\x04test")
+        .expect_err("Unexpected success when decoding invalid packet");
+    assert_location(error.location(), "src/packets/connect.rs");
+
+    // Truncated topic
+    let error =
+        Publish::try_from_iter(*b"\x30\x04\x00\x04te").expect_err("Unexpected success when decoding invalid packet");
+    assert_location(error.location(), "src/packets/publish.rs");
+
+    // Invalid QoS level
+    let error =
+        Publish::try_from_iter(*b"\x36\x03\x00\x01a").expect_err("Unexpected success when decoding invalid packet");
+    assert_location(error.location(), "src/packets/publish.rs");
+
+    // Truncated topic sequence
+    let error = Subscribe::try_from_iter(*b"\x82\x05\x00\x01\x00\x01a")
+        .expect_err("Unexpected success when decoding invalid packet");
+    assert_location(error.location(), "src/packets/subscribe.rs");
+}
@@ -0,0 +1,88 @@
+#![cfg(all(feature = "log", feature = "std"))]
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use mqtt_tiny::{
+    packets::{ToWriter, TryFromIterator},
+    Packet, Publish,
+};
+use std::{cell::RefCell, string::String, sync::Once};
+
+std::thread_local! {
+    /// The messages that have been logged by the current thread
+    static MESSAGES: RefCell<std::vec::Vec<(Level, String)>> = const { RefCell::new(std::vec::Vec::new()) };
+}
+
+/// A logger that captures the messages per thread
+struct CapturingLogger;
+impl CapturingLogger {
+    /// Installs the logger and returns the messages that are logged by the current thread while `f` is executed
+    fn capture<F>(f: F) -> std::vec::Vec<(Level, String)>
+    where
+        F: FnOnce(),
+    {
+        // Install the logger once
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            log::set_logger(&CapturingLogger).expect("Failed to install logger");
+            log::set_max_level(LevelFilter::Trace);
+        });
+
+        // Capture the messages
+        MESSAGES.with_borrow_mut(|messages| messages.clear());
+        f();
+        MESSAGES.with_borrow_mut(std::mem::take)
+    }
+}
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let message = (record.level(), record.args().to_string());
+        MESSAGES.with_borrow_mut(|messages| messages.push(message));
+    }
+
+    fn flush(&self) {
+        // No-op
+    }
+}
+
+/// Tests that successful decoding and encoding is logged without dumping the payload
+#[test]
+pub fn log_success() {
+    let messages = CapturingLogger::capture(|| {
+        // Decode and write a packet
+        let packet = Packet::try_from_iter(*b"\x30\x0B\x00\x04TestOlope").expect("Failed to decode valid packet");
+        packet.write(std::vec::Vec::new()).expect("Failed to write packet");
+    });
+
+    // Validate the messages
+    let expected = [
+        (Level::Trace, "decoded PUBLISH (topic: 4 bytes, packet ID: None, payload: 5 bytes)"),
+        (Level::Trace, "decoded PUBLISH packet (13 bytes)"),
+        (Level::Trace, "wrote packet (13 bytes)"),
+    ];
+    let messages: std::vec::Vec<_> = messages.iter().map(|(level, message)| (*level, message.as_str())).collect();
+    assert_eq!(messages, expected, "Invalid log messages");
+}
+
+/// Tests that decoding errors are logged with the offset
+#[test]
+pub fn log_failure() {
+    let messages = CapturingLogger::capture(|| {
+        // Decode an invalid packet; the per-packet decoders only log successfully decoded packets
+        let packet = Packet::try_from_iter(*b"\x36\x0D\x00\x04Test\x04\x07Olope");
+        assert!(packet.is_err(), "Unexpected success when decoding invalid packet");
+        let packet = Publish::try_from_iter(*b"\x36\x0D\x00\x04Test\x04\x07Olope");
+        assert!(packet.is_err(), "Unexpected success when decoding invalid packet");
+    });
+
+    // Validate the messages
+    let expected = [(
+        Level::Debug,
+        "failed to decode packet: MQTT error: SpecViolation (Invalid QoS level; violates MQTT-3.3.1-4) at offset 0",
+    )];
+    let messages: std::vec::Vec<_> = messages.iter().map(|(level, message)| (*level, message.as_str())).collect();
+    assert_eq!(messages, expected, "Invalid log messages");
+}
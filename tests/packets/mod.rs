@@ -1,6 +1,14 @@
+pub mod arbitrary;
+pub mod capacity;
 pub mod connack;
 pub mod connect;
 pub mod disconnect;
+pub mod error;
+pub mod flags;
+pub mod format;
+pub mod location;
+pub mod logging;
+pub mod packet;
 pub mod pingreq;
 pub mod pingresp;
 pub mod puback;
@@ -8,7 +16,12 @@ pub mod pubcomp;
 pub mod publish;
 pub mod pubrec;
 pub mod pubrel;
+pub mod raw;
+pub mod strategies;
+pub mod stream;
 pub mod suback;
 pub mod subscribe;
 pub mod unsuback;
 pub mod unsubscribe;
+pub mod utf8;
+pub mod v5;
@@ -0,0 +1,1194 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    coding::Decoder,
+    error::{Detail, EncodeError, SourceOrDecode},
+    packets::{self, packet::PacketType, FrameStatus, ToSlice, TryFromFallibleIterator, TryFromIterator},
+    Connack, Connect, Disconnect, Packet, Pingreq, Pingresp, Puback, Pubcomp, Publish, Pubrec, Pubrel, Suback,
+    Subscribe, Unsuback, Unsubscribe,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for packet types
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The packet type
+    type_: PacketType,
+    /// The type constant
+    constant: u8,
+    /// The canonical name
+    name: &'static str,
+    /// An example packet
+    pub packet: Packet,
+}
+impl Good {
+    /// All packet types
+    pub fn all() -> [Self; 14] {
+        [
+            Self {
+                type_: PacketType::Connack,
+                constant: Connack::TYPE,
+                name: "CONNACK",
+                packet: Packet::Connack(Connack::new(false, 0)),
+            },
+            Self {
+                type_: PacketType::Connect,
+                constant: Connect::TYPE,
+                name: "CONNECT",
+                packet: Packet::Connect(Connect::new(30, false, b"test").expect("failed to create packet")),
+            },
+            Self {
+                type_: PacketType::Disconnect,
+                constant: Disconnect::TYPE,
+                name: "DISCONNECT",
+                packet: Packet::Disconnect(Disconnect::new()),
+            },
+            Self {
+                type_: PacketType::Pingreq,
+                constant: Pingreq::TYPE,
+                name: "PINGREQ",
+                packet: Packet::Pingreq(Pingreq::new()),
+            },
+            Self {
+                type_: PacketType::Pingresp,
+                constant: Pingresp::TYPE,
+                name: "PINGRESP",
+                packet: Packet::Pingresp(Pingresp::new()),
+            },
+            Self {
+                type_: PacketType::Puback,
+                constant: Puback::TYPE,
+                name: "PUBACK",
+                packet: Packet::Puback(Puback::new(0x0407)),
+            },
+            Self {
+                type_: PacketType::Pubcomp,
+                constant: Pubcomp::TYPE,
+                name: "PUBCOMP",
+                packet: Packet::Pubcomp(Pubcomp::new(0x0407)),
+            },
+            Self {
+                type_: PacketType::Publish,
+                constant: Publish::TYPE,
+                name: "PUBLISH",
+                packet: Packet::Publish(Publish::new(b"Test", b"Olope", false).expect("failed to create packet")),
+            },
+            Self {
+                type_: PacketType::Pubrec,
+                constant: Pubrec::TYPE,
+                name: "PUBREC",
+                packet: Packet::Pubrec(Pubrec::new(0x0407)),
+            },
+            Self {
+                type_: PacketType::Pubrel,
+                constant: Pubrel::TYPE,
+                name: "PUBREL",
+                packet: Packet::Pubrel(Pubrel::new(0x0407)),
+            },
+            Self {
+                type_: PacketType::Suback,
+                constant: Suback::TYPE,
+                name: "SUBACK",
+                packet: Packet::Suback(Suback::new(0x0407)),
+            },
+            Self {
+                type_: PacketType::Subscribe,
+                constant: Subscribe::TYPE,
+                name: "SUBSCRIBE",
+                packet: Packet::Subscribe(Subscribe::new(0x0407, [(b"Test", 1)]).expect("failed to create packet")),
+            },
+            Self {
+                type_: PacketType::Unsuback,
+                constant: Unsuback::TYPE,
+                name: "UNSUBACK",
+                packet: Packet::Unsuback(Unsuback::new(0x0407)),
+            },
+            Self {
+                type_: PacketType::Unsubscribe,
+                constant: Unsubscribe::TYPE,
+                name: "UNSUBSCRIBE",
+                packet: Packet::Unsubscribe(Unsubscribe::new(0x0407, [b"Test"]).expect("failed to create packet")),
+            },
+        ]
+    }
+}
+
+/// Tests the packet type names
+#[test]
+pub fn name() {
+    for test_vector in Good::all() {
+        // Validate type and packet names
+        assert_eq!(test_vector.type_.name(), test_vector.name, "Invalid packet type name");
+        assert_eq!(test_vector.type_.to_string(), test_vector.name, "Invalid displayed packet type name");
+        assert_eq!(test_vector.packet.name(), test_vector.name, "Invalid packet name");
+        assert_eq!(test_vector.packet.packet_type(), Some(test_vector.type_), "Invalid packet type");
+    }
+}
+
+/// Tests the packet type constants
+#[test]
+pub fn type_constant() {
+    for test_vector in Good::all() {
+        // Convert type to constant and back
+        assert_eq!(u8::from(test_vector.type_), test_vector.constant, "Invalid packet type constant");
+        let type_ = PacketType::try_from(test_vector.constant).expect("failed to convert valid packet type constant");
+        assert_eq!(type_, test_vector.type_, "Invalid packet type");
+    }
+
+    // Test invalid packet type constants
+    for constant in [0, 15] {
+        let type_ = PacketType::try_from(constant);
+        assert!(type_.is_err(), "Unexpected success when converting invalid packet type constant");
+    }
+}
+
+/// Tests that encoding a borrowed packet yields the same bytes as encoding an owned packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode both representations and compare
+        let borrowed: Vec = (&test_vector.packet).into_iter().collect();
+        let owned: Vec = test_vector.packet.into_iter().collect();
+        assert_eq!(borrowed.deref(), owned.deref(), "Invalid encoded packet");
+    }
+}
+
+/// Tests that the encoding iterators report their exact remaining length
+#[test]
+pub fn size_hint() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+
+        // Validate the iterator while consuming it
+        let mut iter = test_vector.packet.clone().into_iter();
+        assert_eq!(iter.size_hint(), (encoded.len(), Some(encoded.len())), "Invalid size hint");
+        for remaining in (0..encoded.len()).rev() {
+            iter.next().expect("Failed to read encoded byte");
+            assert_eq!(iter.len(), remaining, "Invalid size hint");
+        }
+
+        // Validate the borrowed iterator
+        let iter = (&test_vector.packet).into_iter();
+        assert_eq!(iter.size_hint(), (encoded.len(), Some(encoded.len())), "Invalid size hint");
+    }
+}
+
+/// Tests that the encoding iterators keep returning `None` after they are exhausted
+#[test]
+pub fn fused() {
+    for test_vector in Good::all() {
+        // Exhaust the owned and borrowed iterator
+        let mut iter_ref = (&test_vector.packet).into_iter();
+        let mut iter = test_vector.packet.clone().into_iter();
+        iter_ref.by_ref().for_each(drop);
+        iter.by_ref().for_each(drop);
+
+        // Poll past the end
+        for _ in 0..4 {
+            assert_eq!(iter.next(), None, "Unexpected byte after end of packet");
+            assert_eq!(iter_ref.next(), None, "Unexpected byte after end of packet");
+        }
+    }
+}
+
+/// Tests that cloning a partially consumed encoding iterator yields the same remaining bytes
+#[test]
+pub fn clone_halfway() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let (head, tail) = encoded.split_at(encoded.len() / 2);
+
+        // Consume the first half of the owned and borrowed iterator
+        let mut iter_ref = (&test_vector.packet).into_iter();
+        let mut iter = test_vector.packet.clone().into_iter();
+        let head_ref: Vec = iter_ref.by_ref().take(head.len()).collect();
+        let head_owned: Vec = iter.by_ref().take(head.len()).collect();
+        assert_eq!(head_ref.deref(), head, "Invalid encoded packet");
+        assert_eq!(head_owned.deref(), head, "Invalid encoded packet");
+
+        // Clone the iterators and validate both halves
+        let tail_ref: Vec = iter_ref.clone().collect();
+        let tail_owned: Vec = iter.clone().collect();
+        assert_eq!(tail_ref.deref(), tail, "Invalid cloned packet");
+        assert_eq!(tail_owned.deref(), tail, "Invalid cloned packet");
+        assert_eq!(iter_ref.collect::<Vec>().deref(), tail, "Invalid encoded packet");
+        assert_eq!(iter.collect::<Vec>().deref(), tail, "Invalid encoded packet");
+    }
+}
+
+/// Tests that collecting into a fixed-capacity vector is unaffected by the size hint
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn collect_arrayvec() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let collected: arrayvec::ArrayVec<u8, 64> = test_vector.packet.into_iter().collect();
+        assert_eq!(collected.deref(), encoded.deref(), "Invalid encoded packet");
+    }
+}
+
+/// Tests encoding into a fixed-capacity vector that exactly fits the packet or is one byte too small
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn to_bytes() {
+    let packet: Packet = Packet::Puback(Puback::new(7));
+
+    // Encode into a vector that exactly fits and one that is too small
+    let encoded: arrayvec::ArrayVec<u8, 4> = packet.to_bytes().expect("Failed to encode valid packet");
+    assert_eq!(encoded.deref(), b"\x40\x02\x00\x07", "Invalid encoded packet");
+    let encoded: Result<arrayvec::ArrayVec<u8, 3>, _> = packet.to_bytes();
+    assert!(encoded.is_err(), "Unexpected success when encoding into a too-small vector");
+}
+
+/// Tests encoding into a caller-provided buffer
+#[test]
+pub fn encode_into() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+
+        // Encode the type-erased and the concrete packet
+        let mut buf = [0; 64];
+        let written = test_vector.packet.encode_into(&mut buf).expect("Failed to encode valid packet");
+        assert_eq!(&buf[..written], encoded.deref(), "Invalid encoded packet");
+        if let Packet::Publish(publish) = &test_vector.packet {
+            // Encode the concrete packet
+            let written = publish.encode_into(&mut buf).expect("Failed to encode valid packet");
+            assert_eq!(&buf[..written], encoded.deref(), "Invalid encoded packet");
+        }
+    }
+}
+
+/// Tests encoding into a too-small caller-provided buffer
+#[test]
+pub fn encode_into_invalid() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+
+        // Encode into a buffer that is one byte too small
+        let mut buf = [0; 64];
+        let buf = &mut buf[..encoded.len() - 1];
+        let error = EncodeError::BufferTooSmall { required: encoded.len(), capacity: encoded.len() - 1 };
+        assert_eq!(test_vector.packet.encode_into(buf), Err(error), "Unexpected success when encoding");
+        assert_eq!(error.excess(), 1, "Invalid excess length");
+    }
+}
+
+/// Tests encoding into a fixed-size array
+#[test]
+pub fn encode_into_array() {
+    // Encode the signal packets into exactly fitting arrays
+    let (buf, written): ([u8; 2], usize) = Pingreq::new().encode_into_array().expect("Failed to encode valid packet");
+    assert_eq!(&buf[..written], b"\xC0\x00", "Invalid encoded packet");
+    let (buf, written): ([u8; 2], usize) = Pingresp::new().encode_into_array().expect("Failed to encode valid packet");
+    assert_eq!(&buf[..written], b"\xD0\x00", "Invalid encoded packet");
+    let (buf, written): ([u8; 2], usize) =
+        Disconnect::new().encode_into_array().expect("Failed to encode valid packet");
+    assert_eq!(&buf[..written], b"\xE0\x00", "Invalid encoded packet");
+
+    // Encode an ACK-like packet into a larger array
+    let (buf, written): ([u8; 8], usize) =
+        Puback::new(0x0407).encode_into_array().expect("Failed to encode valid packet");
+    assert_eq!(&buf[..written], b"\x40\x02\x04\x07", "Invalid encoded packet");
+
+    // Encode all type-erased packets
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let (buf, written): ([u8; 64], usize) =
+            test_vector.packet.encode_into_array().expect("Failed to encode valid packet");
+        assert_eq!(&buf[..written], encoded.deref(), "Invalid encoded packet");
+    }
+}
+
+/// Tests encoding into a too-small fixed-size array
+#[test]
+pub fn encode_into_array_invalid() {
+    let error = EncodeError::BufferTooSmall { required: 4, capacity: 3 };
+    let encoded: Result<([u8; 3], usize), _> = Puback::new(0x0407).encode_into_array();
+    assert_eq!(encoded, Err(error), "Unexpected success when encoding");
+    let error = EncodeError::BufferTooSmall { required: 2, capacity: 0 };
+    let encoded: Result<([u8; 0], usize), _> = Packet::Pingreq(Pingreq::new()).encode_into_array();
+    assert_eq!(encoded, Err(error), "Unexpected success when encoding");
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Encode, decode and validate
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let decoded = Packet::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+    }
+}
+
+/// Tests peeking at the packet type before decoding
+#[test]
+pub fn decode_peeked() {
+    for test_vector in Good::all() {
+        // Peek at the header and validate the type
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let mut decoder = Decoder::new(encoded).peekable();
+        let (type_, _) = decoder.peek_header().expect("Failed to peek valid header");
+        assert_eq!(type_, test_vector.constant, "Invalid peeked packet type");
+
+        // Ensure that the full packet can still be decoded
+        let decoded = Packet::try_from_iter(decoder).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+    }
+}
+
+/// A reader that yields at most three bytes per read call
+#[cfg(feature = "std")]
+struct ShortReader<'a>(&'a [u8]);
+#[cfg(feature = "std")]
+impl std::io::Read for ShortReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.0.len()).min(3);
+        let (chunk, rest) = self.0.split_at(len);
+        buf[..len].copy_from_slice(chunk);
+        self.0 = rest;
+        Ok(len)
+    }
+}
+
+/// A reader that counts its read calls
+#[cfg(feature = "std")]
+struct CountingReader<'a> {
+    /// The underlying reader
+    reader: &'a [u8],
+    /// The amount of read calls
+    calls: usize,
+}
+#[cfg(feature = "std")]
+impl std::io::Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.calls += 1;
+        self.reader.read(buf)
+    }
+}
+
+/// A buffered reader that only supports reads via its buffer
+#[cfg(feature = "std")]
+struct BufferOnlyReader<'a>(&'a [u8]);
+#[cfg(feature = "std")]
+impl std::io::Read for BufferOnlyReader<'_> {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        panic!("Unexpected read call")
+    }
+}
+#[cfg(feature = "std")]
+impl std::io::BufRead for BufferOnlyReader<'_> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.0)
+    }
+    fn consume(&mut self, amt: usize) {
+        self.0 = &self.0[amt..];
+    }
+}
+
+/// Tests that the packet body is read in bulk
+#[test]
+#[cfg(feature = "std")]
+pub fn read_bulk() {
+    use mqtt_tiny::packets::TryFromReader;
+
+    // Decode a large packet
+    let payload = vec![0x2A; 64 * 1024];
+    let packet = Packet::Publish(Publish::new(b"test", &payload, false).expect("Failed to create packet"));
+    let encoded: Vec = (&packet).into_iter().collect();
+    let mut reader = CountingReader { reader: &encoded, calls: 0 };
+    let decoded = Packet::try_read(&mut reader).expect("Failed to decode valid packet");
+    assert_eq!(decoded, packet, "Invalid decoded packet");
+
+    // Validate that the body is not read byte-by-byte
+    assert!(reader.calls < 64, "Too many read calls: {}", reader.calls);
+    assert!(reader.reader.is_empty(), "Packet has not been read completely");
+}
+
+/// Tests distinguishing a clean end-of-file from a truncated packet
+#[test]
+#[cfg(feature = "std")]
+pub fn read_opt() {
+    use mqtt_tiny::packets::TryFromReader;
+    use std::io::Cursor;
+
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+
+        // Read from an empty cursor
+        let mut reader = Cursor::new(Vec::new());
+        let decoded = Packet::try_read_opt(&mut reader).expect("Failed to read from empty reader");
+        assert!(decoded.is_none(), "Unexpected packet from empty reader");
+
+        // Read one packet followed by a clean end-of-file
+        let mut reader = Cursor::new(encoded.clone());
+        let decoded = Packet::try_read_opt(&mut reader).expect("Failed to decode valid packet");
+        assert_eq!(decoded.as_ref(), Some(&test_vector.packet), "Invalid decoded packet");
+        let decoded = Packet::try_read_opt(&mut reader).expect("Failed to read clean end-of-file");
+        assert!(decoded.is_none(), "Unexpected packet after clean end-of-file");
+
+        // Read one-and-a-half packets
+        let mut concatenated = encoded.clone();
+        concatenated.extend(encoded.iter().copied().take(encoded.len().div_ceil(2)));
+        let mut reader = Cursor::new(concatenated);
+        let decoded = Packet::try_read_opt(&mut reader).expect("Failed to decode valid packet");
+        assert_eq!(decoded.as_ref(), Some(&test_vector.packet), "Invalid decoded packet");
+        let decoded = Packet::try_read_opt(&mut reader);
+        assert!(decoded.is_err(), "Unexpected success when reading truncated packet");
+    }
+}
+
+/// Tests successful decoding from a buffered reader with short reads
+#[test]
+#[cfg(feature = "std")]
+pub fn read_buffered() {
+    use mqtt_tiny::packets::TryFromReader;
+    use std::io::BufReader;
+
+    for test_vector in Good::all() {
+        // Decode from a short-reading buffered reader and validate
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let reader = BufReader::with_capacity(4, ShortReader(&encoded));
+        let decoded = Packet::try_read_buffered(reader).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+    }
+
+    // Decode a large packet
+    let payload = vec![0x2A; 64 * 1024];
+    let packet = Packet::Publish(Publish::new(b"test", &payload, false).expect("Failed to create packet"));
+    let encoded: Vec = (&packet).into_iter().collect();
+    let decoded = Packet::try_read_buffered(encoded.as_slice()).expect("Failed to decode valid packet");
+    assert_eq!(decoded, packet, "Invalid decoded packet");
+}
+
+/// Tests that buffered decoding is served from the reader buffer and consumes the packet only
+#[test]
+#[cfg(feature = "std")]
+pub fn read_buffered_consume() {
+    use mqtt_tiny::packets::TryFromReader;
+
+    for test_vector in Good::all() {
+        // Decode two consecutive packets from the reader buffer
+        let encoded: Vec = (&test_vector.packet).into_iter().chain(&test_vector.packet).collect();
+        let mut reader = BufferOnlyReader(&encoded);
+        for _ in 0..2 {
+            let decoded = Packet::try_read_buffered(&mut reader).expect("Failed to decode valid packet");
+            assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+        }
+        assert!(reader.0.is_empty(), "Packet has not been read completely");
+    }
+}
+
+/// Tests that buffered and unbuffered decoding behave identically if the reader ends mid-packet
+#[test]
+#[cfg(feature = "std")]
+pub fn read_buffered_truncated() {
+    use mqtt_tiny::packets::TryFromReader;
+    use std::io::BufReader;
+
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        for len in 0..encoded.len() {
+            // Decode the truncated packet via both paths
+            let truncated = &encoded[..len];
+            let buffered = Packet::try_read_buffered(BufReader::new(ShortReader(truncated)));
+            let unbuffered = Packet::try_read(ShortReader(truncated));
+
+            // Validate that both paths behave identically
+            let buffered = buffered.map_err(|e| (e.kind(), e.to_string()));
+            let unbuffered = unbuffered.map_err(|e| (e.kind(), e.to_string()));
+            assert_eq!(buffered, unbuffered, "Buffered and unbuffered decoding differ");
+        }
+    }
+}
+
+/// Tests the I/O error kinds and that the original decoding error can be recovered from them
+#[test]
+#[cfg(feature = "std")]
+pub fn read_error_kind() {
+    use mqtt_tiny::{
+        error::{DecoderError, Decoding},
+        packets::TryFromReader,
+    };
+    use std::io::ErrorKind;
+
+    let test_vectors: [(&[u8], ErrorKind, Decoding); 4] = [
+        // Reserved packet type
+        (b"\xF0\x00", ErrorKind::Unsupported, Decoding::UnknownPacketType),
+        // Unsupported protocol level
+        (b"\x10\x10\x00\x04MQTT\x05\x00\x00\x1E\x00\x04test", ErrorKind::Unsupported, Decoding::UnsupportedVersion),
+        // Invalid QoS level
+        (b"\x36\x03\x00\x01a", ErrorKind::InvalidData, Decoding::SpecViolation),
+        // Truncated packet
+        (b"\xE0", ErrorKind::UnexpectedEof, Decoding::Truncated),
+    ];
+    for (encoded, kind, variant) in test_vectors {
+        // Decode and validate the kind
+        let error = Packet::try_read(encoded).expect_err("Unexpected success when decoding invalid packet");
+        assert_eq!(error.kind(), kind, "Invalid error kind");
+
+        // Recover the original error
+        let error = error.get_ref().and_then(|e| e.downcast_ref::<DecoderError>()).expect("Failed to downcast error");
+        assert_eq!(*error.variant(), variant, "Invalid error variant");
+    }
+}
+
+/// Tests the frame check over all prefixes of valid packets
+#[test]
+pub fn check() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        for len in 0..encoded.len() {
+            // Check the truncated frame
+            let status = packets::check(&encoded[..len]).expect("Failed to check truncated frame");
+            let FrameStatus::Incomplete { needed_hint } = status else {
+                panic!("Unexpected complete status for truncated frame");
+            };
+            if let Some(needed) = needed_hint {
+                // The hint must be exact
+                assert_eq!(len + needed, encoded.len(), "Invalid needed hint");
+            }
+        }
+
+        // Check the complete frame followed by trailing bytes
+        let mut extended = encoded.clone();
+        extended.extend([0xFF; 4]);
+        let status = packets::check(&extended).expect("Failed to check complete frame");
+        assert_eq!(status, FrameStatus::Complete { total_len: encoded.len() }, "Invalid complete status");
+    }
+
+    // Check a packet length that is too long
+    assert!(packets::check(&[0x30, 0x80, 0x80, 0x80, 0x80, 0x00]).is_err(), "Unexpected success for invalid frame");
+}
+
+/// Tests that the amount of consumed bytes equals the encoded length
+#[test]
+pub fn try_from_iter_counted() {
+    for test_vector in Good::all() {
+        // Decode with trailing bytes and validate
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let source = encoded.iter().copied().chain([0xFF; 4]);
+        let (decoded, consumed) = Packet::try_from_iter_counted(source).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+        assert_eq!(consumed, encoded.len(), "Invalid amount of consumed bytes");
+    }
+}
+
+/// Tests that the packet size limit is enforced before the body is read
+#[test]
+pub fn try_from_iter_with_limit() {
+    for test_vector in Good::all() {
+        // Decode within the limit
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let decoded = Packet::try_from_iter_with_limit(encoded.iter().copied(), 16).expect("Failed to decode packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+    }
+
+    // Encode a packet that is too large
+    let packet = Packet::Publish(Publish::new(b"test", [0x2A; 32], false).expect("Failed to create packet"));
+    let encoded: Vec = (&packet).into_iter().collect();
+
+    // Decode and ensure that only the header and packet length have been read
+    let mut pulled = 0;
+    let source = encoded.iter().copied().inspect(|_| pulled += 1);
+    let error = Packet::try_from_iter_with_limit(source, 16).expect_err("Unexpected success");
+    assert!(error.is_memory(), "Invalid error variant");
+    assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 38, found: 16 }), "Invalid error detail");
+    assert_eq!(pulled, 2, "Unexpected body bytes read");
+}
+
+/// Tests that the packet size limit is enforced when reading
+#[test]
+#[cfg(feature = "std")]
+pub fn try_read_with_limit() {
+    use mqtt_tiny::packets::TryFromReader;
+    use std::io::ErrorKind;
+
+    // Encode a packet that is too large
+    let packet = Packet::Publish(Publish::new(b"test", [0x2A; 32], false).expect("Failed to create packet"));
+    let encoded: Vec = (&packet).into_iter().collect();
+
+    // Decode with and without limit
+    let error = Packet::try_read_with_limit(encoded.as_slice(), 16).expect_err("Unexpected success");
+    assert_eq!(error.kind(), ErrorKind::OutOfMemory, "Invalid error kind");
+    let decoded = Packet::try_read_with_limit(encoded.as_slice(), 38).expect("Failed to decode packet");
+    assert_eq!(decoded, packet, "Invalid decoded packet");
+}
+
+/// Tests decoding from a fallible source that fails mid-packet
+#[test]
+pub fn try_from_fallible_iter() {
+    for test_vector in Good::all() {
+        // Decode from an infallible source
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let source = encoded.iter().copied().map(Ok::<u8, &str>);
+        let decoded = Packet::try_from_fallible_iter(source).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+
+        for fail_at in 0..encoded.len() {
+            // Inject a source error
+            let source = encoded.iter().copied().enumerate().map(|(index, byte)| match index {
+                index if index == fail_at => Err("Source failed"),
+                _ => Ok(byte),
+            });
+            let decoded = Packet::try_from_fallible_iter(source);
+            assert_eq!(decoded, Err(SourceOrDecode::Source("Source failed")), "Invalid source error");
+        }
+    }
+
+    // Decode an invalid packet
+    let source = [0xFF, 0x00].into_iter().map(Ok::<u8, &str>);
+    let decoded = Packet::try_from_fallible_iter(source);
+    assert!(matches!(decoded, Err(SourceOrDecode::Decode(_))), "Invalid decode error");
+}
+
+/// Tests decoding back-to-back packets from a single iterator
+#[test]
+pub fn try_from_iter_partial() {
+    let test_vectors = Good::all();
+    for (first, second) in test_vectors.iter().zip(test_vectors.iter().cycle().skip(1)) {
+        // Concatenate both packets
+        let source = (&first.packet).into_iter().chain(&second.packet);
+
+        // Decode both packets and validate
+        let (decoded, source) = Packet::try_from_iter_partial(source).expect("Failed to decode valid packet");
+        assert_eq!(decoded, first.packet, "Invalid decoded packet");
+        let (decoded, mut source) = Packet::try_from_iter_partial(source).expect("Failed to decode valid packet");
+        assert_eq!(decoded, second.packet, "Invalid decoded packet");
+        assert!(source.next().is_none(), "Unexpected bytes after the last packet");
+    }
+}
+
+/// Tests decoding from a slice that contains one-and-a-half packets
+#[test]
+pub fn try_from_slice() {
+    let test_vectors = Good::all();
+    for (test_vector, next) in test_vectors.iter().zip(test_vectors.iter().cycle().skip(1)) {
+        // Concatenate the packet and the first half of the next packet
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let next: Vec = (&next.packet).into_iter().collect();
+        let mut concatenated = encoded.clone();
+        concatenated.extend(next.iter().copied().take(next.len() / 2));
+
+        // Decode and validate packet and boundary
+        let (decoded, consumed) = Packet::try_from_slice(&concatenated).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+        assert_eq!(consumed, encoded.len(), "Invalid packet boundary");
+
+        // Ensure that the remaining half packet is rejected
+        let remainder = &concatenated[consumed..];
+        assert!(Packet::try_from_slice(remainder).is_err(), "Unexpected success when decoding truncated packet");
+    }
+}
+
+/// A writer that records the length of each write call and the amount of flushes, and fails after a given amount of
+/// bytes
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct RecordingWriter {
+    /// The written bytes
+    written: Vec,
+    /// The length of each write call
+    calls: std::vec::Vec<usize>,
+    /// The amount of flushes
+    flushes: usize,
+    /// The amount of bytes after which the writer fails
+    fail_after: Option<usize>,
+}
+#[cfg(feature = "std")]
+impl std::io::Write for RecordingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Fail if the writer is exhausted
+        let capacity = self.fail_after.map(|limit| limit - self.written.len()).unwrap_or(usize::MAX);
+        if capacity == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "Writer is exhausted"));
+        }
+
+        // Record the call
+        let len = buf.len().min(capacity);
+        self.written.extend_from_slice(&buf[..len]);
+        self.calls.push(buf.len());
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+/// Tests that packets are written chunk-wise and flushed once
+#[test]
+#[cfg(feature = "std")]
+pub fn write_chunked() {
+    use mqtt_tiny::packets::ToWriter;
+
+    for test_vector in Good::all() {
+        // Write and validate
+        let mut writer = RecordingWriter::default();
+        (&test_vector.packet).write(&mut writer).expect("Failed to write packet");
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        assert_eq!(writer.written, encoded, "Invalid written packet");
+        assert_eq!(writer.calls, [encoded.len()], "Invalid write call granularity");
+        assert_eq!(writer.flushes, 1, "Invalid amount of flushes");
+    }
+
+    // Write a large packet
+    let payload = vec![0x2A; 64 * 1024];
+    let packet = Packet::Publish(Publish::new(b"test", &payload, false).expect("Failed to create packet"));
+    let mut writer = RecordingWriter::default();
+    (&packet).write(&mut writer).expect("Failed to write packet");
+    let encoded: Vec = (&packet).into_iter().collect();
+    assert_eq!(writer.written, encoded, "Invalid written packet");
+    assert_eq!(writer.calls.len(), encoded.len().div_ceil(128), "Invalid amount of write calls");
+    assert!(writer.calls.iter().rev().skip(1).all(|len| *len == 128), "Invalid write call granularity");
+    assert_eq!(writer.flushes, 1, "Invalid amount of flushes");
+}
+
+/// Tests that an error of the writer mid-packet is propagated
+#[test]
+#[cfg(feature = "std")]
+pub fn write_failing() {
+    use mqtt_tiny::packets::ToWriter;
+
+    // Write a large packet into a writer that fails mid-packet
+    let payload = vec![0x2A; 1024];
+    let packet = Packet::Publish(Publish::new(b"test", &payload, false).expect("Failed to create packet"));
+    let mut writer = RecordingWriter { fail_after: Some(300), ..Default::default() };
+    let error = (&packet).write(&mut writer).expect_err("Unexpected success when writing into failing writer");
+    assert_eq!(error.kind(), std::io::ErrorKind::BrokenPipe, "Invalid error kind");
+    assert_eq!(writer.written.len(), 300, "Invalid amount of written bytes");
+    assert_eq!(writer.flushes, 0, "Unexpected flush after error");
+}
+
+/// Tests that the amount of written bytes matches the reference vectors
+#[test]
+#[cfg(feature = "std")]
+pub fn write_counted() {
+    use mqtt_tiny::{packets::ToWriter, test_util};
+
+    for (encoded, packet) in test_util::corpus() {
+        // Write and validate
+        let mut writer = RecordingWriter::default();
+        let written = (&packet).write_counted(&mut writer).expect("Failed to write packet");
+        assert_eq!(written, encoded.len(), "Invalid amount of written bytes");
+        assert_eq!(writer.written, encoded, "Invalid written packet");
+    }
+
+    // Write a large packet
+    let payload = vec![0x2A; 64 * 1024];
+    let packet = Packet::Publish(Publish::new(b"test", &payload, false).expect("Failed to create packet"));
+    let written = (&packet).write_counted(&mut RecordingWriter::default()).expect("Failed to write packet");
+    assert_eq!(written, (&packet).into_iter().len(), "Invalid amount of written bytes");
+}
+
+/// An asynchronous reader that is pending on every other poll and yields at most three bytes per read
+#[cfg(feature = "futures-io")]
+struct ThrottledReader<R> {
+    /// The underlying reader
+    reader: R,
+    /// Whether the next poll is pending or not
+    pending: bool,
+}
+#[cfg(feature = "futures-io")]
+impl<R> futures::io::AsyncRead for ThrottledReader<R>
+where
+    R: futures::io::AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut [u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        // Be pending on every other poll
+        self.pending = !self.pending;
+        if self.pending {
+            cx.waker().wake_by_ref();
+            return std::task::Poll::Pending;
+        }
+
+        // Read at most three bytes
+        let len = buf.len().min(3);
+        std::pin::Pin::new(&mut self.reader).poll_read(cx, &mut buf[..len])
+    }
+}
+
+/// Tests asynchronous round-trips over an in-memory reader/writer
+#[test]
+#[cfg(feature = "futures-io")]
+pub fn roundtrip_async() {
+    use futures::{executor::block_on, io::Cursor};
+    use mqtt_tiny::packets::asyncio::{ToAsyncWriter, TryFromAsyncReader};
+
+    for test_vector in Good::all() {
+        // Write all packets into the same buffer
+        let mut writer = Cursor::new(Vec::new());
+        block_on((&test_vector.packet).write_async(&mut writer)).expect("Failed to write packet");
+        block_on((&test_vector.packet).write_async(&mut writer)).expect("Failed to write packet");
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        assert_eq!(writer.get_ref().len(), encoded.len() * 2, "Invalid written packets");
+
+        // Read both packets back via a throttled reader
+        let mut reader = ThrottledReader { reader: Cursor::new(writer.into_inner()), pending: false };
+        for _ in 0..2 {
+            let decoded = block_on(Packet::try_read_async(&mut reader)).expect("Failed to decode valid packet");
+            assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+        }
+    }
+}
+
+/// Tests that the amount of asynchronously written bytes matches the reference vectors
+#[test]
+#[cfg(feature = "futures-io")]
+pub fn write_async_counted() {
+    use futures::{executor::block_on, io::Cursor};
+    use mqtt_tiny::{packets::asyncio::ToAsyncWriter, test_util};
+
+    for (encoded, packet) in test_util::corpus() {
+        let mut writer = Cursor::new(Vec::new());
+        let written = block_on((&packet).write_async_counted(&mut writer)).expect("Failed to write packet");
+        assert_eq!(written, encoded.len(), "Invalid amount of written bytes");
+        assert_eq!(writer.get_ref(), encoded, "Invalid written packet");
+    }
+}
+
+/// Tests that asynchronous and synchronous decoding behave identically if the reader ends mid-packet
+#[test]
+#[cfg(feature = "futures-io")]
+pub fn read_async_truncated() {
+    use futures::{executor::block_on, io::Cursor};
+    use mqtt_tiny::packets::{asyncio::TryFromAsyncReader, TryFromReader};
+
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        for len in 0..encoded.len() {
+            // Decode the truncated packet via both paths
+            let truncated = &encoded[..len];
+            let reader = ThrottledReader { reader: Cursor::new(truncated), pending: false };
+            let asynchronous = block_on(Packet::try_read_async(reader));
+            let synchronous = Packet::try_read(truncated);
+
+            // Validate that both paths behave identically
+            let asynchronous = asynchronous.map_err(|e| (e.kind(), e.to_string()));
+            let synchronous = synchronous.map_err(|e| (e.kind(), e.to_string()));
+            assert_eq!(asynchronous, synchronous, "Asynchronous and synchronous decoding differ");
+        }
+    }
+}
+
+/// Tests round-trips over `embedded-io` adapters
+#[test]
+#[cfg(all(feature = "embedded-io", feature = "std"))]
+pub fn roundtrip_embedded() {
+    use embedded_io_adapters::std::FromStd;
+    use mqtt_tiny::packets::embedded::{ToEmbeddedWriter, TryFromEmbeddedReader};
+    use std::io::Cursor;
+
+    for test_vector in Good::all() {
+        // Write two packets into the same buffer
+        let mut writer = FromStd::new(Cursor::new(Vec::new()));
+        (&test_vector.packet).write_embedded(&mut writer).expect("Failed to write packet");
+        (&test_vector.packet).write_embedded(&mut writer).expect("Failed to write packet");
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        assert_eq!(writer.inner().get_ref().len(), encoded.len() * 2, "Invalid written packets");
+
+        // Read both packets back with a scratch buffer that fits the frame exactly
+        let mut scratch = vec![0; encoded.len()];
+        let mut reader = FromStd::new(ShortReader(writer.inner().get_ref()));
+        for _ in 0..2 {
+            let decoded = Packet::try_read_embedded(&mut reader, &mut scratch).expect("Failed to decode valid packet");
+            assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+        }
+    }
+}
+
+/// Tests that the amount of written bytes matches the reference vectors over `embedded-io` adapters
+#[test]
+#[cfg(all(feature = "embedded-io", feature = "std"))]
+pub fn write_embedded_counted() {
+    use embedded_io_adapters::std::FromStd;
+    use mqtt_tiny::{packets::embedded::ToEmbeddedWriter, test_util};
+
+    for (encoded, packet) in test_util::corpus() {
+        let mut writer = FromStd::new(Vec::new());
+        let written = (&packet).write_embedded_counted(&mut writer).expect("Failed to write packet");
+        assert_eq!(written, encoded.len(), "Invalid amount of written bytes");
+        assert_eq!(writer.inner(), encoded, "Invalid written packet");
+    }
+}
+
+/// Tests failing decoding over `embedded-io` adapters
+#[test]
+#[cfg(all(feature = "embedded-io", feature = "std"))]
+pub fn read_embedded_invalid() {
+    use embedded_io_adapters::std::FromStd;
+    use mqtt_tiny::{
+        error::SourceOrDecode,
+        packets::{embedded::TryFromEmbeddedReader, TryFromReader},
+    };
+
+    for test_vector in Good::all() {
+        // Decode with a scratch buffer that is too small
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let mut scratch = vec![0; encoded.len() - 1];
+        let decoded = Packet::try_read_embedded(FromStd::new(encoded.as_slice()), &mut scratch);
+        assert!(
+            matches!(decoded, Err(SourceOrDecode::Decode(e)) if e.is_memory()),
+            "Unexpected result for too small scratch buffer"
+        );
+
+        // Decode truncated packets and validate that the synchronous path behaves identically
+        let mut scratch = vec![0; encoded.len()];
+        for len in 0..encoded.len() {
+            let truncated = &encoded[..len];
+            let decoded = Packet::try_read_embedded(FromStd::new(truncated), &mut scratch);
+            let synchronous = Packet::try_read(truncated);
+            assert_eq!(decoded.ok(), synchronous.ok(), "Embedded and synchronous decoding differ");
+        }
+    }
+}
+
+/// Tests round-trips over `embedded-io-async` adapters
+#[test]
+#[cfg(all(feature = "embedded-io-async", feature = "futures-io"))]
+pub fn roundtrip_embedded_async() {
+    use embedded_io_adapters::futures_03::FromFutures;
+    use futures::{executor::block_on, io::Cursor};
+    use mqtt_tiny::packets::embedded_async::{ToEmbeddedAsyncWriter, TryFromEmbeddedAsyncReader};
+
+    for test_vector in Good::all() {
+        // Write two packets into the same buffer
+        let mut writer = FromFutures::new(Cursor::new(Vec::new()));
+        block_on((&test_vector.packet).write_embedded_async(&mut writer)).expect("Failed to write packet");
+        block_on((&test_vector.packet).write_embedded_async(&mut writer)).expect("Failed to write packet");
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        assert_eq!(writer.inner().get_ref().len(), encoded.len() * 2, "Invalid written packets");
+
+        // Read both packets back via a throttled reader with a scratch buffer that fits the frame exactly
+        let mut scratch = vec![0; encoded.len()];
+        let written = writer.into_inner().into_inner();
+        let mut reader = FromFutures::new(ThrottledReader { reader: Cursor::new(written), pending: false });
+        for _ in 0..2 {
+            let decoded = block_on(Packet::try_read_embedded_async(&mut reader, &mut scratch))
+                .expect("Failed to decode valid packet");
+            assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+        }
+    }
+}
+
+/// Tests that the amount of written bytes matches the reference vectors over `embedded-io-async` adapters
+#[test]
+#[cfg(all(feature = "embedded-io-async", feature = "futures-io"))]
+pub fn write_embedded_async_counted() {
+    use embedded_io_adapters::futures_03::FromFutures;
+    use futures::{executor::block_on, io::Cursor};
+    use mqtt_tiny::{packets::embedded_async::ToEmbeddedAsyncWriter, test_util};
+
+    for (encoded, packet) in test_util::corpus() {
+        let mut writer = FromFutures::new(Cursor::new(Vec::new()));
+        let written = block_on((&packet).write_embedded_async_counted(&mut writer)).expect("Failed to write packet");
+        assert_eq!(written, encoded.len(), "Invalid amount of written bytes");
+        assert_eq!(writer.inner().get_ref(), encoded, "Invalid written packet");
+    }
+}
+
+/// Tests that a too small scratch buffer is rejected over `embedded-io-async` adapters
+#[test]
+#[cfg(all(feature = "embedded-io-async", feature = "futures-io"))]
+pub fn read_embedded_async_scratch() {
+    use embedded_io_adapters::futures_03::FromFutures;
+    use futures::{executor::block_on, io::Cursor};
+    use mqtt_tiny::{error::SourceOrDecode, packets::embedded_async::TryFromEmbeddedAsyncReader};
+
+    for test_vector in Good::all() {
+        // Decode with a scratch buffer that is too small
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let mut scratch = vec![0; encoded.len() - 1];
+        let reader = FromFutures::new(Cursor::new(encoded.as_slice()));
+        let decoded = block_on(Packet::try_read_embedded_async(reader, &mut scratch));
+        assert!(
+            matches!(decoded, Err(SourceOrDecode::Decode(e)) if e.is_memory()),
+            "Unexpected result for too small scratch buffer"
+        );
+    }
+}
+
+/// Tests round-trips over framed transports
+#[test]
+#[cfg(feature = "tokio-util")]
+pub fn roundtrip_codec() {
+    use futures::{SinkExt, StreamExt};
+    use mqtt_tiny::packets::codec::MqttCodec;
+    use tokio_util::codec::{FramedRead, FramedWrite};
+
+    // Create a runtime and a duplex stream
+    let runtime = tokio::runtime::Builder::new_current_thread().build().expect("Failed to create runtime");
+    runtime.block_on(async {
+        let (client, server) = tokio::io::duplex(16);
+        let mut writer = FramedWrite::new(client, MqttCodec::<Packet>::new());
+        let mut reader = FramedRead::new(server, MqttCodec::<Packet>::new());
+
+        // Write all packets concurrently and read them back
+        let writing = async {
+            for test_vector in Good::all() {
+                writer.send(&test_vector.packet).await.expect("Failed to write packet");
+            }
+        };
+        let reading = async {
+            for test_vector in Good::all() {
+                let decoded = reader.next().await.expect("Unexpected end of stream");
+                assert_eq!(
+                    decoded.expect("Failed to decode valid packet"),
+                    test_vector.packet,
+                    "Invalid decoded packet"
+                );
+            }
+        };
+        tokio::join!(writing, reading);
+    });
+}
+
+/// Tests decoding a PUBLISH that is split across reads and the frame length limit
+#[test]
+#[cfg(feature = "tokio-util")]
+pub fn decode_codec_split() {
+    use bytes::BytesMut;
+    use mqtt_tiny::packets::codec::MqttCodec;
+    use tokio_util::codec::Decoder;
+
+    // Feed a large packet in small chunks
+    let payload = vec![0x2A; 4096];
+    let packet = Packet::Publish(Publish::new(b"test", &payload, false).expect("Failed to create packet"));
+    let encoded: Vec = (&packet).into_iter().collect();
+    let (mut codec, mut buf) = (MqttCodec::<Packet>::new(), BytesMut::new());
+    for (index, chunk) in encoded.chunks(100).enumerate() {
+        buf.extend_from_slice(chunk);
+        let decoded = codec.decode(&mut buf).expect("Failed to decode valid packet");
+        match (index + 1) * 100 >= encoded.len() {
+            true => assert_eq!(decoded.as_ref(), Some(&packet), "Invalid decoded packet"),
+            false => assert_eq!(decoded, None, "Unexpected packet from incomplete frame"),
+        }
+    }
+    assert!(buf.is_empty(), "Unexpected bytes after the packet");
+
+    // Ensure that oversized frames are rejected before the body is buffered
+    let mut codec = MqttCodec::<Packet>::new().with_max_frame_length(encoded.len() - 1);
+    let mut buf = BytesMut::from(&encoded[..5]);
+    let error = codec.decode(&mut buf).expect_err("Unexpected success when decoding oversized packet");
+    assert_eq!(error.kind(), std::io::ErrorKind::OutOfMemory, "Invalid error kind");
+}
+
+/// Tests capturing raw packet frames from concatenated packets
+#[test]
+pub fn raw_packet() {
+    use mqtt_tiny::packets;
+
+    let test_vectors = Good::all();
+    for (test_vector, next) in test_vectors.iter().zip(test_vectors.iter().cycle().skip(1)) {
+        // Concatenate two packets
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let mut source = encoded.iter().copied().chain(&next.packet);
+
+        // Capture both frames and validate the boundary
+        let raw: Vec = packets::raw_packet_from_iter(&mut source, encoded.len()).expect("Failed to capture packet");
+        assert_eq!(raw, encoded, "Invalid captured packet");
+        let raw: Vec = packets::raw_packet_from_iter(&mut source, usize::MAX).expect("Failed to capture packet");
+        assert_eq!(Packet::try_from_iter(raw).expect("Failed to decode captured packet"), next.packet);
+        assert!(source.next().is_none(), "Unexpected bytes after the last packet");
+
+        // Ensure that oversized and truncated frames are rejected
+        let raw: Result<Vec, _> = packets::raw_packet_from_iter(encoded.iter().copied(), encoded.len() - 1);
+        assert!(raw.is_err_and(|e| e.is_memory()), "Unexpected result for oversized packet");
+        let raw: Result<Vec, _> = packets::raw_packet_from_iter(encoded[..encoded.len() - 1].iter().copied(), 64);
+        assert!(raw.is_err(), "Unexpected success when capturing truncated packet");
+    }
+}
+
+/// Tests reading raw packet frames from concatenated packets
+#[test]
+#[cfg(feature = "std")]
+pub fn read_raw_packet() {
+    use mqtt_tiny::packets;
+    use std::io::ErrorKind;
+
+    let test_vectors = Good::all();
+    for (test_vector, next) in test_vectors.iter().zip(test_vectors.iter().cycle().skip(1)) {
+        // Concatenate two packets
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let mut concatenated = encoded.clone();
+        concatenated.extend(&next.packet);
+
+        // Read both frames and validate the boundary
+        let mut reader = ShortReader(&concatenated);
+        let raw: Vec = packets::read_raw_packet(&mut reader, encoded.len()).expect("Failed to read packet");
+        assert_eq!(raw, encoded, "Invalid read packet");
+        let raw: Vec = packets::read_raw_packet(&mut reader, usize::MAX).expect("Failed to read packet");
+        assert_eq!(Packet::try_from_iter(raw).expect("Failed to decode read packet"), next.packet);
+        assert!(reader.0.is_empty(), "Unexpected bytes after the last packet");
+
+        // Ensure that oversized and truncated frames are rejected
+        let raw: Result<Vec, _> = packets::read_raw_packet(&mut encoded.as_slice(), encoded.len() - 1);
+        assert_eq!(raw.map_err(|e| e.kind()), Err(ErrorKind::OutOfMemory), "Unexpected result for oversized packet");
+        let raw: Result<Vec, _> = packets::read_raw_packet(&mut &encoded[..encoded.len() - 1], 64);
+        assert_eq!(raw.map_err(|e| e.kind()), Err(ErrorKind::UnexpectedEof), "Unexpected result for truncated packet");
+    }
+}
+
+/// Tests that equal packets collapse in a hash set
+#[test]
+#[cfg(feature = "std")]
+pub fn hash() {
+    use std::collections::HashSet;
+
+    // Insert each packet twice, once as decoded copy
+    let mut set = HashSet::new();
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let decoded = Packet::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert!(set.insert(test_vector.packet), "Unexpected duplicate packet");
+        assert!(!set.insert(decoded), "Duplicate packet has not collapsed");
+    }
+    assert_eq!(set.len(), Good::all().len(), "Invalid amount of distinct packets");
+
+    // Ensure that packets with different content are distinct
+    let publish = Publish::new(b"Test", b"Olope", false).expect("Failed to create packet").with_qos(2, 0x0407, false);
+    assert!(set.insert(Packet::Publish(publish.clone())), "Unexpected duplicate packet");
+    assert!(!set.insert(Packet::Publish(publish.clone())), "Duplicate packet has not collapsed");
+    assert!(set.insert(Packet::Publish(publish.with_qos(2, 0x0408, false))), "Unexpected duplicate packet");
+}
+
+/// Tests that the shared corpus round-trips and covers all packet types
+#[test]
+#[cfg(feature = "std")]
+pub fn corpus() {
+    use mqtt_tiny::test_util;
+
+    let mut types = std::vec::Vec::new();
+    for (encoded, packet) in test_util::corpus() {
+        // Validate the round-trip and the encoded representation
+        test_util::assert_roundtrip(&packet);
+        let reencoded: Vec = (&packet).into_iter().collect();
+        assert_eq!(reencoded.deref(), encoded, "Invalid encoded packet");
+        types.push(packet.packet_type());
+    }
+
+    // Ensure that all packet types are covered
+    types.dedup();
+    assert_eq!(types.len(), Good::all().len(), "Not all packet types are covered by the corpus");
+}
+
+/// Tests that invalid packets are rejected
+#[test]
+pub fn rejects() {
+    use mqtt_tiny::test_util;
+
+    test_util::assert_rejects(b"");
+    test_util::assert_rejects(b"\xF0\x00");
+    test_util::assert_rejects(b"\x40\x03\x04\x07\x00");
+}
@@ -1,12 +1,12 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
-use mqtt_tiny::{packets::TryFromIterator, Pingresp};
+use mqtt_tiny::{packets::TryFromIterator, test_util, Packet, Pingresp};
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -18,12 +18,12 @@ pub struct Good {
     decoded: Pingresp,
 }
 impl Good {
-    /// Good encoded/decoded pairs
-    pub const fn all() -> [Self; 1] {
-        [
-            // The PINGRESP packet does not carry any configurable data
-            Self { encoded: b"\xD0\x00", decoded: Pingresp::new() },
-        ]
+    /// Good encoded/decoded pairs from the shared corpus
+    pub fn all() -> impl Iterator<Item = Self> {
+        test_util::corpus().filter_map(|(encoded, packet)| match packet {
+            Packet::Pingresp(decoded) => Some(Self { encoded, decoded }),
+            _ => None,
+        })
     }
 }
 
@@ -65,6 +65,16 @@ pub fn encode() {
     }
 }
 
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
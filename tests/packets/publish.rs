@@ -1,12 +1,12 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
-use mqtt_tiny::{packets::TryFromIterator, Publish};
+use mqtt_tiny::{error::Detail, packets::TryFromIterator, test_util, Packet, Publish, PublishRef};
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -18,29 +18,12 @@ pub struct Good {
     decoded: Publish,
 }
 impl Good {
-    /// Good encoded/decoded pairs
-    pub fn all() -> [Self; 3] {
-        [
-            // A basic packet
-            Self {
-                encoded: b"\x30\x0B\x00\x04TestOlope",
-                decoded: Publish::new(b"Test", b"Olope", false).expect("failed to create packet"),
-            },
-            // A packet with QoS
-            Self {
-                encoded: b"\x34\x0D\x00\x04Test\x04\x07Olope",
-                decoded: Publish::new(b"Test", b"Olope", false).expect("failed to create packet")
-                    // Set QoS
-                    .with_qos(2, 0x0407, false),
-            },
-            // A packet with everything enabled
-            Self {
-                encoded: b"\x3B\x0D\x00\x04Test\x04\x07Olope",
-                decoded: Publish::new(b"Test", b"Olope", true).expect("failed to create packet")
-                    // Set QoS
-                    .with_qos(1, 0x0407, true),
-            },
-        ]
+    /// Good encoded/decoded pairs from the shared corpus
+    pub fn all() -> impl Iterator<Item = Self> {
+        test_util::corpus().filter_map(|(encoded, packet)| match packet {
+            Packet::Publish(decoded) => Some(Self { encoded, decoded }),
+            _ => None,
+        })
     }
 }
 
@@ -58,6 +41,12 @@ impl BadEncoded {
             Self { encoded: b"\x40\x0B\x00\x04TestOlope" },
             // Packet with non-zero QoS but missing/truncated packet ID
             Self { encoded: b"\x34\x07\x00\x04TestO" },
+            // Packet with non-zero QoS but a packet ID outside of the packet length
+            Self { encoded: b"\x32\x06\x00\x04Test\x00\x01" },
+            // Packet with invalid QoS
+            Self { encoded: b"\x36\x0D\x00\x04Test\x04\x07Olope" },
+            // Packet with a payload that is shorter than the packet length
+            Self { encoded: b"\x30\x0B\x00\x04TestOlo" },
         ]
     }
 }
@@ -73,6 +62,34 @@ pub fn decode() {
     }
 }
 
+/// Tests that zero-copy decoding is equivalent to owned decoding
+#[test]
+pub fn decode_ref() {
+    for test_vector in Good::all() {
+        // Parse and validate the borrowed view and the consumed length
+        let (decoded, consumed) = PublishRef::parse(test_vector.encoded).expect("Failed to decode valid packet");
+        assert_eq!(consumed, test_vector.encoded.len(), "Invalid consumed length");
+
+        // Convert the view and compare it against the owned decoder
+        let decoded: Publish = decoded.to_owned().expect("Failed to copy valid packet");
+        let owned = Publish::try_from_iter(test_vector.encoded.iter().copied()).expect("Failed to decode valid packet");
+        assert_eq!(decoded, owned, "Invalid decoded packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet");
+    }
+}
+
+/// Tests decoding packets in a row into the same packet
+#[test]
+pub fn decode_into() {
+    let mut decoded = Publish::new(b"a", b"", false).expect("Failed to create packet");
+    for test_vector in Good::all().chain(Good::all()) {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        decoded.try_from_iter_into(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet");
+    }
+}
+
 /// Tests successful encoding
 #[test]
 pub fn encode() {
@@ -84,6 +101,16 @@ pub fn encode() {
     }
 }
 
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -94,3 +121,350 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
     }
 }
+
+/// Tests failing zero-copy decoding
+#[test]
+pub fn decode_ref_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let decoded = PublishRef::parse(test_vector.encoded);
+        assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
+    }
+}
+
+/// Tests creating a duplicate for retransmission
+#[test]
+pub fn as_duplicate() {
+    // Create packet and duplicate
+    let original = Publish::new(b"Test", b"Olope", true).expect("failed to create packet").with_qos(1, 0x0407, false);
+    let duplicate = original.as_duplicate().expect("failed to create duplicate");
+    assert!(duplicate.dup(), "Duplicate is not marked as duplicate");
+    assert_eq!(duplicate.qos(), original.qos(), "Invalid duplicate QoS");
+    assert_eq!(duplicate.packet_id(), original.packet_id(), "Invalid duplicate packet ID");
+
+    // Encode both packets and ensure that only the DUP bit differs
+    let original: Vec = original.into_iter().collect();
+    let duplicate: Vec = duplicate.into_iter().collect();
+    assert_eq!(duplicate[0], original[0] | 0b1000, "Invalid duplicate header");
+    assert_eq!(duplicate[1..], original[1..], "Invalid duplicate body");
+}
+
+/// Tests that packets without packet ID cannot be duplicated
+#[test]
+pub fn as_duplicate_invalid() {
+    let packet = Publish::new(b"Test", b"Olope", false).expect("failed to create packet");
+    assert!(packet.as_duplicate().is_err(), "Unexpected success when duplicating packet without packet ID");
+}
+
+/// Tests that packets with a too-long topic are rejected
+#[test]
+#[cfg(feature = "alloc")]
+pub fn new_invalid() {
+    let packet = Publish::new([0x07; 65_536], b"Olope", false);
+    assert!(packet.is_err(), "Unexpected success when creating packet with too-long topic");
+}
+
+/// Tests that packets are not created with invalid topic names
+#[test]
+pub fn new_invalid_topic() {
+    let invalid: [&[u8]; 4] = [b"", b"sport/+/player1", b"sport/#", b"a\x00b"];
+    for topic in invalid {
+        // Create and validate
+        assert!(Publish::new(topic, b"Olope", false).is_err(), "Unexpected success when creating packet");
+        let packet = Publish::new(b"Test", b"Olope", false).expect("Failed to create packet");
+        assert!(packet.with_topic(topic).is_err(), "Unexpected success when replacing topic");
+        let packet = Publish::new_streaming(topic, 0, core::iter::empty(), false);
+        assert!(packet.is_err(), "Unexpected success when creating streaming packet");
+        let packet = PublishRef::new_ref(topic, b"Olope", false);
+        assert!(packet.is_err(), "Unexpected success when creating encode-only packet");
+    }
+}
+
+/// Tests round-tripping copy-on-write packets with mixed borrowed and owned fields
+#[test]
+#[cfg(feature = "alloc")]
+pub fn cow_roundtrip() {
+    use mqtt_tiny::{anyvec::CowBytes, packets::publish};
+
+    for test_vector in Good::all() {
+        // Convert the borrowed view without copying and re-encode it
+        let (decoded, _) = PublishRef::parse(test_vector.encoded).expect("Failed to decode valid packet");
+        let cow = decoded.to_cow();
+        let encoded: Vec = cow.clone().into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+
+        // Rewrite the topic so that only the topic is owned, and round-trip the packet
+        let rewritten = cow.with_topic(b"Rewritten").expect("Failed to rewrite topic");
+        assert_eq!(rewritten.topic(), b"Rewritten", "Invalid rewritten topic");
+        assert_eq!(rewritten.payload(), test_vector.decoded.payload(), "Invalid payload");
+        let encoded: Vec = rewritten.clone().into_iter().collect();
+        let redecoded: publish::Publish<CowBytes> =
+            TryFromIterator::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(redecoded, rewritten, "Invalid decoded packet");
+    }
+}
+
+/// Tests that copy-on-write fields stay borrowed until they are modified
+#[test]
+#[cfg(feature = "alloc")]
+pub fn cow_bytes() {
+    use mqtt_tiny::anyvec::{AnyVec, CowBytes};
+
+    // Borrowed bytes stay borrowed when truncated
+    let mut bytes = CowBytes::borrowed(b"Testolope");
+    bytes.truncate(4);
+    assert!(bytes.is_borrowed(), "Truncating borrowed bytes copied the bytes");
+    assert_eq!(bytes.as_ref(), b"Test", "Invalid truncated bytes");
+
+    // Borrowed bytes become owned upon modification
+    bytes.extend(b"olope").expect("Failed to extend bytes");
+    assert!(!bytes.is_borrowed(), "Modified bytes are still borrowed");
+    assert_eq!(bytes, CowBytes::owned(b"Testolope".to_vec()), "Invalid extended bytes");
+    assert_eq!(bytes.into_iter().collect::<Vec>().deref(), b"Testolope", "Invalid iterated bytes");
+}
+
+/// Tests that encode-only packets are byte-equal to owned packets
+#[test]
+pub fn encode_new_ref() {
+    for test_vector in Good::all() {
+        // Create the encode-only packet from the owned packet's fields
+        let owned = &test_vector.decoded;
+        let mut packet =
+            PublishRef::new_ref(owned.topic(), owned.payload(), owned.retain()).expect("Failed to create packet");
+        if let Some(packet_id) = owned.packet_id() {
+            packet = packet.with_qos(owned.qos(), packet_id, owned.dup());
+        }
+
+        // Encode and validate
+        let encoded: Vec = packet.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests that encode-only packets with a too-long topic are rejected
+#[test]
+pub fn new_ref_invalid() {
+    let topic = [0x07; 65_536];
+    let packet = PublishRef::new_ref(&topic, b"Olope", false);
+    assert!(packet.is_err(), "Unexpected success when creating packet with too-long topic");
+}
+
+/// Tests that streamed payloads are byte-equal to buffered payloads
+#[test]
+pub fn encode_streaming() {
+    for test_vector in Good::all() {
+        // Create the streaming packet from the owned packet's fields
+        let owned = &test_vector.decoded;
+        let payload = owned.payload().iter().copied();
+        let mut packet = Publish::new_streaming(owned.topic(), owned.payload().len(), payload, owned.retain())
+            .expect("Failed to create packet");
+        if let Some(packet_id) = owned.packet_id() {
+            packet = packet.with_qos(owned.qos(), packet_id, owned.dup());
+        }
+        assert_eq!(packet.len(), test_vector.encoded.len(), "Invalid packet length");
+
+        // Encode and validate
+        let encoded: Vec = packet.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests that a payload iterator that yields too few bytes is padded to the announced length
+#[test]
+pub fn encode_streaming_short() {
+    let packet = Publish::new_streaming(b"Test", 5, b"Olo".iter().copied(), false).expect("Failed to create packet");
+    let len = packet.len();
+    let encoded: Vec = packet.into_iter().collect();
+    assert_eq!(encoded.deref(), b"\x30\x0B\x00\x04TestOlo\x00\x00", "Invalid encoded packet");
+    assert_eq!(encoded.len(), len, "Invalid packet length");
+}
+
+/// Tests that writing a packet with a payload iterator that yields too few bytes fails
+#[test]
+#[cfg(feature = "std")]
+pub fn write_streaming_short() {
+    use std::io::ErrorKind;
+
+    // Write a packet with a too short payload
+    let mut written = std::vec::Vec::new();
+    let packet = Publish::new_streaming(b"Test", 5, b"Olo".iter().copied(), false).expect("Failed to create packet");
+    let error = packet.write(&mut written).expect_err("Unexpected success when writing short payload");
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof, "Invalid error kind");
+    assert_eq!(written, b"\x30\x0B\x00\x04TestOlo\x00\x00", "Invalid written packet");
+
+    // Write packets with an exact and a too long payload
+    for payload in [b"Olope".as_slice(), b"OlopeOlope"] {
+        let mut written = std::vec::Vec::new();
+        let packet =
+            Publish::new_streaming(b"Test", 5, payload.iter().copied(), false).expect("Failed to create packet");
+        packet.write(&mut written).expect("Failed to write packet");
+        assert_eq!(written, b"\x30\x0B\x00\x04TestOlope", "Invalid written packet");
+    }
+}
+
+/// Tests that a payload iterator that yields too many bytes is truncated to the announced length
+#[test]
+pub fn encode_streaming_long() {
+    let payload = b"Olope".iter().copied().cycle();
+    let packet = Publish::new_streaming(b"Test", 5, payload, false).expect("Failed to create packet");
+    let encoded: Vec = packet.into_iter().collect();
+    assert_eq!(encoded.deref(), b"\x30\x0B\x00\x04TestOlope", "Invalid encoded packet");
+}
+
+/// Tests that streaming packets with an oversized announced payload length are rejected
+#[test]
+pub fn new_streaming_invalid() {
+    let packet = Publish::new_streaming(b"Test", usize::MAX, core::iter::empty(), false);
+    assert!(packet.is_err(), "Unexpected success when creating packet with oversized payload");
+}
+
+/// Tests decoding the payload into a buffer that fits exactly
+#[test]
+pub fn decode_into_buf() {
+    for test_vector in Good::all() {
+        // Decode into an exactly sized buffer
+        let mut buf = [0; 64];
+        let buf = &mut buf[..test_vector.decoded.payload().len()];
+        let encoded = test_vector.encoded.iter().copied();
+        let (header, payload) = Publish::decode_into(encoded, buf).expect("Failed to decode valid packet");
+
+        // Validate the header and payload
+        assert_eq!(header.topic(), test_vector.decoded.topic(), "Invalid topic");
+        assert_eq!(header.qos(), test_vector.decoded.qos(), "Invalid QoS");
+        assert_eq!(header.dup(), test_vector.decoded.dup(), "Invalid duplicate flag");
+        assert_eq!(header.retain(), test_vector.decoded.retain(), "Invalid retain flag");
+        assert_eq!(header.packet_id(), test_vector.decoded.packet_id(), "Invalid packet ID");
+        assert_eq!(payload, test_vector.decoded.payload(), "Invalid payload");
+    }
+}
+
+/// Tests decoding an empty payload into an empty buffer
+#[test]
+pub fn decode_into_buf_empty() {
+    let encoded = b"\x30\x06\x00\x04Test".iter().copied();
+    let (header, payload) = Publish::decode_into(encoded, &mut []).expect("Failed to decode valid packet");
+    assert_eq!(header.topic(), b"Test", "Invalid topic");
+    assert!(payload.is_empty(), "Invalid payload");
+}
+
+/// Tests that decoding the payload into a too-small buffer reports the required buffer length
+#[test]
+pub fn decode_into_buf_too_small() {
+    let encoded = b"\x30\x0B\x00\x04TestOlope".iter().copied();
+    let mut buf = [0; 4];
+    let error = Publish::decode_into(encoded, &mut buf).expect_err("Unexpected success");
+    assert!(error.is_memory(), "Invalid error variant");
+    assert_eq!(error.offset(), 8, "Invalid error offset");
+    assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 5, found: 4 }), "Invalid error detail");
+}
+
+/// Tests failing decoding into a buffer
+#[test]
+pub fn decode_into_buf_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let mut buf = [0; 64];
+        let decoded = Publish::decode_into(encoded, &mut buf);
+        assert!(decoded.is_err_and(|e| !e.is_memory()), "Unexpected result when decoding invalid packet");
+    }
+}
+
+/// Tests that headers-only decoding leaves the source positioned at the beginning of the payload
+#[test]
+pub fn decode_header() {
+    for test_vector in Good::all() {
+        // Decode the header fields from a concatenated source
+        let mut source = test_vector.encoded.iter().copied().chain(*b"Next");
+        let (header, payload_len) = Publish::decode_header(&mut source).expect("Failed to decode valid packet");
+        assert_eq!(header.topic(), test_vector.decoded.topic(), "Invalid topic");
+        assert_eq!(header.qos(), test_vector.decoded.qos(), "Invalid QoS");
+        assert_eq!(header.packet_id(), test_vector.decoded.packet_id(), "Invalid packet ID");
+        assert_eq!(payload_len, test_vector.decoded.payload().len(), "Invalid payload length");
+
+        // Validate that the source yields exactly the payload and the subsequent bytes
+        let payload: Vec = source.by_ref().take(payload_len).collect();
+        assert_eq!(payload.deref(), test_vector.decoded.payload(), "Invalid payload");
+        assert!(source.eq(*b"Next"), "Source is not positioned after the payload");
+    }
+}
+
+/// Tests failing headers-only decoding
+#[test]
+pub fn decode_header_invalid() {
+    // Packet with invalid packet type
+    let decoded = Publish::decode_header(b"\x40\x0B\x00\x04TestOlope".iter().copied());
+    assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
+
+    // Packet with non-zero QoS but a packet ID outside of the packet length
+    let decoded = Publish::decode_header(b"\x32\x06\x00\x04Test\x00\x01".iter().copied());
+    assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
+}
+
+/// Tests consuming a large payload incrementally from a reader
+#[test]
+#[cfg(feature = "std")]
+pub fn read_header_large() {
+    use std::io::{self, Cursor};
+
+    // Encode a large packet followed by a second packet
+    let payload: std::vec::Vec<u8> = (0..1024 * 1024).map(|index| index as u8).collect();
+    let packet = Publish::new(b"Test", &payload, false).expect("Failed to create packet");
+    let mut encoded: std::vec::Vec<u8> = packet.into_iter().collect();
+    encoded.extend_from_slice(b"\x30\x0B\x00\x04TestOlope");
+
+    // Read the header and copy the payload
+    let mut reader = Cursor::new(encoded);
+    let (header, mut payload_reader) = Publish::read_header(&mut reader).expect("Failed to read valid packet");
+    assert_eq!(header.topic(), b"Test", "Invalid topic");
+    assert_eq!(payload_reader.remaining(), payload.len(), "Invalid payload length");
+    let mut copied = std::vec::Vec::new();
+    io::copy(&mut payload_reader, &mut copied).expect("Failed to copy payload");
+    assert_eq!(copied, payload, "Invalid payload");
+    drop(payload_reader);
+
+    // Read the next packet
+    let (_, payload_reader) = Publish::read_header(&mut reader).expect("Failed to read valid packet");
+    assert_eq!(payload_reader.remaining(), 5, "Invalid payload length");
+}
+
+/// Tests that dropping a partially consumed payload reader skips the remaining payload
+#[test]
+#[cfg(feature = "std")]
+pub fn read_header_partial() {
+    use std::io::{Cursor, Read};
+
+    // Encode a large packet followed by a second packet
+    let packet = Publish::new(b"Test", [0x07; 64 * 1024], false).expect("Failed to create packet");
+    let mut encoded: std::vec::Vec<u8> = packet.into_iter().collect();
+    encoded.extend_from_slice(b"\x30\x0B\x00\x04TestOlope");
+
+    // Consume only a part of the payload and drop the payload reader
+    let mut reader = Cursor::new(encoded);
+    let (_, mut payload_reader) = Publish::read_header(&mut reader).expect("Failed to read valid packet");
+    let mut chunk = [0; 1000];
+    payload_reader.read_exact(&mut chunk).expect("Failed to read payload");
+    assert_eq!(payload_reader.remaining(), 64 * 1024 - 1000, "Invalid remaining payload length");
+    drop(payload_reader);
+
+    // Ensure that the next packet can be decoded
+    let (header, mut payload_reader) = Publish::read_header(&mut reader).expect("Failed to read valid packet");
+    let mut payload = std::vec::Vec::new();
+    payload_reader.read_to_end(&mut payload).expect("Failed to read payload");
+    assert_eq!(header.topic(), b"Test", "Invalid topic");
+    assert_eq!(payload, b"Olope", "Invalid payload");
+}
+
+/// Tests that a truncated payload is reported as error
+#[test]
+#[cfg(feature = "std")]
+pub fn read_header_truncated() {
+    use std::io::{Cursor, ErrorKind, Read};
+
+    let mut reader = Cursor::new(b"\x30\x0B\x00\x04TestOlo");
+    let (_, mut payload_reader) = Publish::read_header(&mut reader).expect("Failed to read valid header");
+    let mut payload = std::vec::Vec::new();
+    let error =
+        payload_reader.read_to_end(&mut payload).expect_err("Unexpected success when reading truncated payload");
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof, "Invalid error kind");
+}
@@ -0,0 +1,152 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{packets::TryFromIterator, Packet, Raw};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: Raw,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 3] {
+        [
+            // A packet of the reserved type 15
+            Self {
+                encoded: b"\xF3\x05Olope",
+                decoded: Raw::new(15, [false, false, true, true], b"Olope").expect("failed to create packet"),
+            },
+            // A packet of the reserved type 0 without body
+            Self {
+                encoded: b"\x0A\x00",
+                decoded: Raw::new(0, [true, false, true, false], b"").expect("failed to create packet"),
+            },
+            // A packet of a known type
+            Self {
+                encoded: b"\x40\x02\x04\x07",
+                decoded: Raw::new(4, [false; 4], b"\x04\x07").expect("failed to create packet"),
+            },
+        ]
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Packet with a body that is shorter than the packet length
+            Self { encoded: b"\xF0\x05Olo" },
+            // Packet with an invalid packet length
+            Self { encoded: b"\xF0\xFF\xFF\xFF\xFF\x01" },
+            // Packet with a truncated packet length
+            Self { encoded: b"\xF0" },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = Raw::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet")
+    }
+}
+
+/// Tests decoding packets in a row into the same packet
+#[test]
+pub fn decode_into() {
+    let mut decoded = Raw::new(0, [false; 4], b"").expect("Failed to create packet");
+    for test_vector in Good::all().iter().chain(Good::all().iter()) {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        decoded.try_from_iter_into(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet")
+    }
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = Raw::try_from_iter(encoded);
+        assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
+    }
+}
+
+/// Tests that unknown packet types are only accepted by the lenient decoder and round-trip byte-identically
+#[test]
+pub fn decode_lenient() {
+    // Decode the unknown packet type strictly and leniently
+    let encoded = b"\xF3\x05Olope";
+    assert!(Packet::try_from_iter(encoded.iter().copied()).is_err(), "Unexpected success when decoding unknown type");
+    let decoded = Packet::try_from_iter_lenient(encoded.iter().copied()).expect("Failed to decode unknown type");
+    let Packet::Raw(raw) = &decoded else {
+        panic!("Invalid decoded packet");
+    };
+    assert_eq!(raw.type_(), 15, "Invalid packet type");
+    assert_eq!(raw.body(), b"Olope", "Invalid packet body");
+    assert_eq!(decoded.packet_type(), None, "Invalid packet type");
+    assert_eq!(decoded.name(), "RAW", "Invalid packet name");
+
+    // Re-encode the packet
+    let reencoded: Vec = (&decoded).into_iter().collect();
+    assert_eq!(reencoded.deref(), encoded, "Invalid encoded packet");
+    let reencoded: Vec = decoded.into_iter().collect();
+    assert_eq!(reencoded.deref(), encoded, "Invalid encoded packet");
+
+    // Ensure that known packet types are still decoded as typed packets
+    let decoded = Packet::try_from_iter_lenient(*b"\x40\x02\x04\x07").expect("Failed to decode valid packet");
+    assert!(matches!(decoded, Packet::Puback(_)), "Invalid decoded packet");
+}
+
+/// Tests that invalid packets are rejected during construction
+#[test]
+#[cfg(feature = "alloc")]
+pub fn new_invalid() {
+    let packet = Raw::new(16, [false; 4], b"Olope");
+    assert!(packet.is_err(), "Unexpected success when creating packet with invalid type");
+    let packet = Raw::new(15, [false; 4], vec![0x07; 1 << 28]);
+    assert!(packet.is_err(), "Unexpected success when creating packet with too-long body");
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "proptest")]
+
+use mqtt_tiny::{
+    packets,
+    strategies::{
+        any_packet, connack_strategy, connect_strategy, publish_strategy, subscribe_strategy, unsubscribe_strategy,
+    },
+    Bytes, Connack, Connect, Packet, Publish, Subscribe, Topics, TopicsQos, Unsubscribe,
+};
+use proptest::{prop_assert, proptest};
+
+proptest! {
+    /// Tests that `decode(encode(packet)) == packet` for packets of any type
+    #[test]
+    fn roundtrip_any(packet in any_packet::<Topics, TopicsQos, Bytes>()) {
+        prop_assert!(packets::roundtrip::<Packet>(&packet).is_ok(), "Failed to round-trip packet");
+    }
+
+    /// Tests that `decode(encode(packet)) == packet` for `CONNACK` packets
+    #[test]
+    fn roundtrip_connack(packet in connack_strategy()) {
+        prop_assert!(packets::roundtrip::<Connack>(&packet).is_ok(), "Failed to round-trip packet");
+    }
+
+    /// Tests that `decode(encode(packet)) == packet` for `CONNECT` packets
+    #[test]
+    fn roundtrip_connect(packet in connect_strategy::<Bytes>()) {
+        prop_assert!(packets::roundtrip::<Connect>(&packet).is_ok(), "Failed to round-trip packet");
+    }
+
+    /// Tests that `decode(encode(packet)) == packet` for `PUBLISH` packets with large payloads
+    #[test]
+    fn roundtrip_publish(packet in publish_strategy::<Bytes>(4096)) {
+        prop_assert!(packets::roundtrip::<Publish>(&packet).is_ok(), "Failed to round-trip packet");
+    }
+
+    /// Tests that `decode(encode(packet)) == packet` for `SUBSCRIBE` packets
+    #[test]
+    fn roundtrip_subscribe(packet in subscribe_strategy::<TopicsQos, Bytes>()) {
+        prop_assert!(packets::roundtrip::<Subscribe>(&packet).is_ok(), "Failed to round-trip packet");
+    }
+
+    /// Tests that `decode(encode(packet)) == packet` for `UNSUBSCRIBE` packets
+    #[test]
+    fn roundtrip_unsubscribe(packet in unsubscribe_strategy::<Topics, Bytes>()) {
+        prop_assert!(packets::roundtrip::<Unsubscribe>(&packet).is_ok(), "Failed to round-trip packet");
+    }
+}
@@ -0,0 +1,296 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use crate::packets::packet::Good;
+use mqtt_tiny::{
+    error::Detail,
+    packets::stream::{EncodedChunks, PacketBatch},
+    Packet, PacketDecoder, PacketStream,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// Tests decoding packets that are split at every possible boundary
+#[test]
+pub fn feed_split() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        for split in 0..encoded.len() {
+            // Feed the first part
+            let (head, tail) = encoded.split_at(split);
+            let mut decoder = PacketDecoder::new();
+            let decoded = decoder.feed(head).expect("Failed to feed valid chunk");
+            assert!(decoded.is_none(), "Unexpected packet from incomplete chunk");
+
+            // Feed the second part
+            let decoded = decoder.feed(tail).expect("Failed to feed valid chunk").expect("Missing decoded packet");
+            assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+            assert_eq!(decoder.buffered(), 0, "Unexpected remaining bytes");
+        }
+    }
+}
+
+/// Tests decoding packets that are fed byte by byte
+#[test]
+pub fn feed_bytewise() {
+    let mut decoder = PacketDecoder::new();
+    for test_vector in Good::all() {
+        // Feed each byte
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        let mut decoded = None;
+        for byte in encoded.iter() {
+            assert!(decoded.is_none(), "Unexpected packet from incomplete chunk");
+            decoded = decoder.feed(&[*byte]).expect("Failed to feed valid byte");
+        }
+        assert_eq!(decoded, Some(test_vector.packet), "Invalid decoded packet");
+    }
+}
+
+/// Tests decoding multiple packets from a single chunk
+#[test]
+pub fn feed_multiple() {
+    let test_vectors = Good::all();
+    for (first, second) in test_vectors.iter().zip(test_vectors.iter().skip(1)) {
+        // Concatenate the packets
+        let mut chunk: Vec = (&first.packet).into_iter().collect();
+        chunk.extend(&second.packet);
+
+        // Decode the first and the buffered second packet
+        let mut decoder = PacketDecoder::new();
+        let decoded = decoder.feed(&chunk).expect("Failed to feed valid chunk");
+        assert_eq!(decoded.as_ref(), Some(&first.packet), "Invalid decoded packet");
+        let decoded = decoder.feed(&[]).expect("Failed to feed valid chunk");
+        assert_eq!(decoded.as_ref(), Some(&second.packet), "Invalid decoded packet");
+        assert_eq!(decoder.feed(&[]), Ok(None::<Packet>), "Unexpected packet");
+    }
+}
+
+/// Tests decoding all concatenated packets from a byte iterator
+#[test]
+pub fn stream() {
+    // Concatenate all packets
+    let test_vectors = Good::all();
+    let source = test_vectors.iter().flat_map(|test_vector| &test_vector.packet);
+
+    // Decode and validate each packet in order
+    let mut stream = PacketStream::new(source);
+    for test_vector in test_vectors.iter() {
+        let decoded = stream.next().expect("Missing decoded packet").expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+    }
+    assert!(stream.next().is_none(), "Unexpected packet after end of source");
+}
+
+/// Tests that the stream yields an error and fuses on invalid input
+#[test]
+pub fn stream_invalid() {
+    // Concatenate a valid packet and an invalid one
+    let test_vectors = Good::all();
+    let source = (&test_vectors[0].packet).into_iter().chain(*b"\xFF\x00").chain(&test_vectors[1].packet);
+
+    // Decode and validate
+    let mut stream = PacketStream::new(source);
+    let decoded = stream.next().expect("Missing decoded packet").expect("Failed to decode valid packet");
+    assert_eq!(decoded, test_vectors[0].packet, "Invalid decoded packet");
+    assert!(stream.next().expect("Missing error").is_err(), "Unexpected success when decoding invalid packet");
+    assert!(stream.next().is_none(), "Unexpected packet after error");
+}
+
+/// Tests failing decoding
+#[test]
+pub fn feed_invalid() {
+    // Feed a packet length that is too long in two chunks
+    let mut decoder = PacketDecoder::new();
+    assert_eq!(decoder.feed(b"\x30\x80\x80"), Ok(None), "Unexpected result for incomplete chunk");
+    assert!(decoder.feed(b"\x80\x80\x00").is_err(), "Unexpected success when decoding invalid packet length");
+
+    // Ensure that the decoder has been reset
+    assert_eq!(decoder.buffered(), 0, "Unexpected remaining bytes");
+    let decoded = decoder.feed(b"\xE0\x00").expect("Failed to feed valid chunk");
+    assert!(matches!(decoded, Some(Packet::Disconnect(_))), "Invalid decoded packet");
+}
+
+/// Tests that oversized packets are rejected as soon as the packet length is known
+#[test]
+pub fn feed_too_large() {
+    let mut decoder = PacketDecoder::new().with_max_packet_size(16);
+    for _ in 0..2 {
+        // Feed the header of a packet that exceeds the limit; the limit is retained after the reset
+        let error = decoder.feed(b"\x30\x26\x00\x04").expect_err("Unexpected success when decoding oversized packet");
+        assert!(error.is_memory(), "Invalid error variant");
+        assert_eq!(error.detail(), Some(Detail::Mismatch { expected: 38, found: 16 }), "Invalid error detail");
+        assert_eq!(decoder.buffered(), 0, "Unexpected remaining bytes");
+    }
+
+    // Packets within the limit are decoded
+    let decoded = decoder.feed(b"\xE0\x00").expect("Failed to feed valid chunk");
+    assert!(matches!(decoded, Some(Packet::Disconnect(_))), "Invalid decoded packet");
+}
+
+/// Tests chunked encoding with different chunk sizes
+#[test]
+pub fn fill() {
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        for chunk_size in [1, 3, 64] {
+            // Encode the packet chunk by chunk
+            let mut chunks = EncodedChunks::new(&test_vector.packet);
+            let mut reassembled = Vec::new();
+            while !chunks.is_complete() {
+                let mut buf = [0; 64];
+                let written = chunks.fill(&mut buf[..chunk_size]);
+                assert!(written > 0, "Failed to fill chunk");
+                reassembled.extend(buf[..written].iter().copied());
+            }
+
+            // Validate the encoding and that no further bytes are written
+            assert_eq!(reassembled, encoded, "Invalid encoded packet");
+            assert_eq!(chunks.fill(&mut [0; 64]), 0, "Unexpected bytes after end of packet");
+        }
+    }
+}
+
+/// Tests that a batch encodes to the concatenation of the individual packets
+#[test]
+pub fn batch() {
+    let test_vectors = Good::all();
+    for (first, second) in test_vectors.iter().zip(test_vectors.iter().skip(1)) {
+        // Concatenate the individual encodings
+        let mut concatenated: Vec = (&first.packet).into_iter().collect();
+        concatenated.extend(&second.packet);
+
+        // Batch the packets and validate
+        let mut batch = PacketBatch::<Packet, 2>::new();
+        batch.push(first.packet.clone()).expect("Failed to push packet");
+        let batch = batch.with(second.packet.clone()).expect("Failed to push packet");
+        assert_eq!(batch.len(), 2, "Invalid batch length");
+        assert!((&batch).into_iter().eq(concatenated.iter().copied()), "Invalid encoded borrowed batch");
+        assert!(batch.into_iter().eq(concatenated.iter().copied()), "Invalid encoded batch");
+    }
+
+    // Ensure that a full batch is rejected
+    let mut batch = PacketBatch::<Packet, 1>::new();
+    assert!(batch.is_empty(), "Unexpected non-empty batch");
+    batch.push(test_vectors[0].packet.clone()).expect("Failed to push packet");
+    assert!(batch.push(test_vectors[1].packet.clone()).is_err(), "Unexpected success when pushing into full batch");
+}
+
+/// Tests writing a batch with a single write
+#[test]
+#[cfg(feature = "std")]
+pub fn batch_write() {
+    use mqtt_tiny::packets::ToWriter;
+
+    // Batch all packets
+    let test_vectors = Good::all();
+    let mut batch = PacketBatch::<Packet, 14>::new();
+    let mut concatenated = Vec::new();
+    for test_vector in test_vectors.iter() {
+        batch.push(test_vector.packet.clone()).expect("Failed to push packet");
+        concatenated.extend(&test_vector.packet);
+    }
+
+    // Write and validate
+    let mut written = Vec::new();
+    batch.write(&mut written).expect("Failed to write batch");
+    assert_eq!(written, concatenated, "Invalid written batch");
+}
+
+/// A reader that alternates between blocking and yielding a single byte
+#[cfg(feature = "std")]
+struct BlockingReader<'a> {
+    /// The remaining bytes
+    bytes: &'a [u8],
+    /// Whether the next read would block or not
+    would_block: bool,
+}
+#[cfg(feature = "std")]
+impl std::io::Read for BlockingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Block on every other read
+        self.would_block = !self.would_block;
+        if self.would_block {
+            return Err(std::io::ErrorKind::WouldBlock.into());
+        }
+
+        // Yield the next byte
+        let Some((byte, rest)) = self.bytes.split_first() else {
+            return Ok(0);
+        };
+        buf[0] = *byte;
+        self.bytes = rest;
+        Ok(1)
+    }
+}
+
+/// Tests reading packets from a reader that blocks between every byte
+#[test]
+#[cfg(feature = "std")]
+pub fn partial_read() {
+    use mqtt_tiny::PartialReader;
+
+    // Concatenate all packets
+    let mut concatenated = Vec::new();
+    for test_vector in Good::all() {
+        concatenated.extend(&test_vector.packet);
+    }
+
+    // Read all packets and validate
+    let mut reader = BlockingReader { bytes: &concatenated, would_block: false };
+    let mut partial = PartialReader::new();
+    for test_vector in Good::all() {
+        let decoded = loop {
+            match partial.poll_read(&mut reader).expect("Failed to read valid packet") {
+                Some(decoded) => break decoded,
+                None => continue,
+            }
+        };
+        assert_eq!(decoded, test_vector.packet, "Invalid decoded packet");
+        assert_eq!(partial.buffered(), 0, "Unexpected buffered bytes after packet");
+    }
+
+    // Ensure that the end of the reader is reported at the packet boundary
+    let error = loop {
+        match partial.poll_read(&mut reader) {
+            Ok(None) => continue,
+            Ok(Some(_)) => panic!("Unexpected packet after the last packet"),
+            Err(e) => break e,
+        }
+    };
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof, "Invalid error kind");
+}
+
+/// Tests reading the encoded representation of packets via the reader adapter
+#[test]
+#[cfg(feature = "std")]
+pub fn reader() {
+    use mqtt_tiny::{packets::stream::PacketReader, Publish};
+    use std::io::{self, Read};
+
+    // Copy a large packet through the adapter
+    let payload = vec![0x2A; 4096];
+    let packet = Packet::Publish(Publish::new(b"test", &payload, false).expect("Failed to create packet"));
+    let encoded: Vec = (&packet).into_iter().collect();
+    let mut copied = Vec::new();
+    io::copy(&mut packet.clone().into_reader(), &mut copied).expect("Failed to copy packet");
+    assert_eq!(copied, encoded, "Invalid copied packet");
+
+    // Read all packets via tiny destination buffers
+    for test_vector in Good::all() {
+        let encoded: Vec = (&test_vector.packet).into_iter().collect();
+        for buf_len in 1..4 {
+            let mut reader = PacketReader::new(&test_vector.packet);
+            let (mut read, mut buf) = (Vec::new(), vec![0; buf_len]);
+            loop {
+                match reader.read(&mut buf).expect("Failed to read packet") {
+                    0 => break,
+                    len => read.extend_from_slice(&buf[..len]),
+                }
+            }
+            assert_eq!(read, encoded, "Invalid read packet");
+        }
+    }
+}
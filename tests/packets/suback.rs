@@ -1,12 +1,15 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
-use mqtt_tiny::{packets::TryFromIterator, Suback};
+use mqtt_tiny::{
+    packets::{suback::GrantedQos, TryFromIterator},
+    test_util, Packet, Suback,
+};
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -18,12 +21,12 @@ pub struct Good {
     decoded: Suback,
 }
 impl Good {
-    /// Good encoded/decoded pairs
-    pub const fn all() -> [Self; 1] {
-        [
-            // The SUBACK packet does not have any context specific encoding
-            Self { encoded: b"\x90\x02\x04\x07", decoded: Suback::new(0x0407) },
-        ]
+    /// Good encoded/decoded pairs from the shared corpus
+    pub fn all() -> impl Iterator<Item = Self> {
+        test_util::corpus().filter_map(|(encoded, packet)| match packet {
+            Packet::Suback(decoded) => Some(Self { encoded, decoded }),
+            _ => None,
+        })
     }
 }
 
@@ -67,6 +70,16 @@ pub fn encode() {
     }
 }
 
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -77,3 +90,31 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
     }
 }
+
+/// Tests the conversion of all return codes
+#[test]
+pub fn granted_qos() {
+    let expected = [
+        (0x00, GrantedQos::AtMostOnce, Some(0)),
+        (0x01, GrantedQos::AtLeastOnce, Some(1)),
+        (0x02, GrantedQos::ExactlyOnce, Some(2)),
+        (0x80, GrantedQos::Failure, None),
+    ];
+    for (code, granted, qos) in expected {
+        // Convert and validate
+        assert_eq!(GrantedQos::try_from(code), Ok(granted), "Invalid return code");
+        assert_eq!(u8::from(granted), code, "Invalid encoded return code");
+        assert_eq!(granted.qos(), qos, "Invalid granted QoS");
+        assert_eq!(granted.is_failure(), qos.is_none(), "Invalid failure flag");
+    }
+}
+
+/// Tests that reserved return codes are rejected
+#[test]
+pub fn granted_qos_invalid() {
+    for code in (0..=u8::MAX).filter(|code| !matches!(code, 0x00 | 0x01 | 0x02 | 0x80)) {
+        // Convert and validate
+        let granted = GrantedQos::try_from(code);
+        assert!(granted.is_err(), "Unexpected success when converting reserved return code");
+    }
+}
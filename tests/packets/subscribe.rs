@@ -1,12 +1,12 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
-use mqtt_tiny::{packets::TryFromIterator, Subscribe};
+use mqtt_tiny::{error::Detail, packets::TryFromIterator, test_util, Packet, Subscribe, SubscribeRef};
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -18,20 +18,12 @@ pub struct Good {
     decoded: Subscribe,
 }
 impl Good {
-    /// Good encoded/decoded pairs
-    pub fn all() -> [Self; 2] {
-        [
-            // Single topic subscription
-            Self {
-                encoded: b"\x82\x0E\x04\x07\x00\x09testolope\x01",
-                decoded: Subscribe::new(0x0407, [(b"testolope", 1)]).expect("failed to create packet"),
-            },
-            // Multiple topic subscription
-            Self {
-                encoded: b"\x82\x11\x04\x07\x00\x04test\x01\x00\x05olope\x02",
-                decoded: Subscribe::new(0x0407, [("test", 1), ("olope", 2)]).expect("failed to create packet"),
-            },
-        ]
+    /// Good encoded/decoded pairs from the shared corpus
+    pub fn all() -> impl Iterator<Item = Self> {
+        test_util::corpus().filter_map(|(encoded, packet)| match packet {
+            Packet::Subscribe(decoded) => Some(Self { encoded, decoded }),
+            _ => None,
+        })
     }
 }
 
@@ -49,6 +41,12 @@ impl BadEncoded {
             Self { encoded: b"\x92\x0E\x04\x07\x00\x09testolope\x01" },
             // Packet with invalid header flags
             Self { encoded: b"\x80\x0E\x04\x07\x00\x09testolope\x01" },
+            // Packet with an empty topic filter
+            Self { encoded: b"\x82\x05\x04\x07\x00\x00\x01" },
+            // Packet with a single-level wildcard that does not occupy an entire level
+            Self { encoded: b"\x82\x07\x04\x07\x00\x02+a\x01" },
+            // Packet with an invalid UTF-8 topic filter
+            Self { encoded: b"\x82\x06\x04\x07\x00\x01\xFF\x01" },
         ]
     }
 }
@@ -75,6 +73,16 @@ pub fn encode() {
     }
 }
 
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -85,3 +93,88 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
     }
 }
+
+/// Tests the offset and violated rule of an invalid topic filter
+#[test]
+pub fn decode_invalid_filter() {
+    // A packet whose second topic filter contains a multi-level wildcard that is not the last level
+    let encoded = b"\x82\x0E\x04\x07\x00\x01a\x00\x00\x05b/#/c\x01";
+    let error = Subscribe::try_from_iter(encoded.iter().copied()).expect_err("Unexpected success when decoding packet");
+    assert!(error.is_spec_violation(), "Invalid error variant");
+    assert_eq!(error.offset(), 12, "Invalid error offset");
+    assert_eq!(error.detail(), Some(Detail::Rule("MQTT-4.7.1-2")), "Invalid error detail");
+    assert!(error.context_chain().eq(["topic filters"]), "Invalid context chain");
+}
+
+/// Tests that decoding fails if the packet contains more topics than allowed
+#[test]
+pub fn decode_capped() {
+    // A packet with three topic+quality-of-service tuples
+    let encoded = b"\x82\x0E\x04\x07\x00\x01a\x00\x00\x01b\x01\x00\x01c\x02";
+    let decoded = Subscribe::try_from_iter_with_limits(encoded.iter().copied(), 3).expect("Failed to decode packet");
+    assert_eq!(decoded.topics_qos().len(), 3, "Invalid decoded packet");
+
+    // Decode with a smaller limit
+    let decoded = Subscribe::try_from_iter_with_limits(encoded.iter().copied(), 2);
+    assert!(decoded.is_err(), "Unexpected success when decoding packet with too many topics");
+}
+
+/// Tests the context chain of an error within the topic sequence
+#[test]
+pub fn decode_context() {
+    // A packet whose third topic lacks the quality-of-service byte
+    let encoded = b"\x82\x0D\x04\x07\x00\x01a\x00\x00\x01b\x01\x00\x01c";
+    let error = Subscribe::try_from_iter(encoded.iter().copied()).expect_err("Unexpected success when decoding packet");
+    assert!(error.is_truncated(), "Invalid error variant");
+    assert!(error.context_chain().eq(["requested QoS", "topic filters"]), "Invalid context chain");
+    assert_eq!(
+        error.to_string(),
+        "MQTT error: Truncated (Truncated input) at offset 15 in topic filters > requested QoS",
+        "Invalid error message"
+    );
+}
+
+/// Tests that decoding fails if the packet contains more topics than the default limit
+#[test]
+#[cfg(feature = "alloc")]
+pub fn decode_capped_default() {
+    /// Creates a packet with the given amount of single-level topics
+    fn packet(count: usize) -> Vec {
+        let topics = (0..count).map(|_| (b"a", 0));
+        Subscribe::new(7, topics).expect("Failed to create packet").into_iter().collect()
+    }
+
+    // Decode packets with the maximum amount of topics and one topic more
+    let decoded = Subscribe::try_from_iter(packet(Subscribe::MAX_TOPICS));
+    assert!(decoded.is_ok(), "Failed to decode packet with the maximum amount of topics");
+    let decoded = Subscribe::try_from_iter(packet(Subscribe::MAX_TOPICS + 1));
+    assert!(decoded.is_err(), "Unexpected success when decoding packet with too many topics");
+}
+
+/// Tests that encode-only packets are byte-equal to owned packets
+#[test]
+pub fn encode_new_ref() {
+    for test_vector in Good::all() {
+        // Borrow the owned packet's topics into a stack array
+        let owned = &test_vector.decoded;
+        let mut topics_qos = [(&[][..], 0); 8];
+        for (slot, (topic, qos)) in topics_qos.iter_mut().zip(owned.topics_qos().iter()) {
+            *slot = (topic.as_ref(), *qos);
+        }
+        let topics_qos = &topics_qos[..owned.topics_qos().len()];
+
+        // Create the encode-only packet, encode and validate
+        let packet = SubscribeRef::new_ref(owned.packet_id(), topics_qos).expect("Failed to create packet");
+        let encoded: Vec = (&packet).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests that encode-only packets with a too-long topic are rejected
+#[test]
+pub fn new_ref_invalid() {
+    let topic = [0x07; 65_536];
+    let topics_qos = [(&b"Test"[..], 0), (&topic[..], 1)];
+    let packet = SubscribeRef::new_ref(0x0407, &topics_qos);
+    assert!(packet.is_err(), "Unexpected success when creating packet with too-long topic");
+}
@@ -1,12 +1,12 @@
-#![cfg(any(feature = "std", feature = "arrayvec"))]
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
 
 use core::ops::Deref;
-use mqtt_tiny::{packets::TryFromIterator, Unsubscribe};
+use mqtt_tiny::{error::Detail, packets::TryFromIterator, test_util, Packet, Unsubscribe};
 
 // Select an appropriate vector type
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 type Vec = std::vec::Vec<u8>;
-#[cfg(all(not(feature = "std"), feature = "arrayvec"))]
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
 type Vec = arrayvec::ArrayVec<u8, 64>;
 
 /// A test vector for encoded/decoded pairs
@@ -18,20 +18,12 @@ pub struct Good {
     decoded: Unsubscribe,
 }
 impl Good {
-    /// Good encoded/decoded pairs
-    pub fn all() -> [Self; 2] {
-        [
-            // Single topic unsubscription
-            Self {
-                encoded: b"\xA2\x0D\x04\x07\x00\x09testolope",
-                decoded: Unsubscribe::new(0x0407, [b"testolope"]).expect("failed to create packet"),
-            },
-            // Multiple topic unsubscription
-            Self {
-                encoded: b"\xA2\x0F\x04\x07\x00\x04test\x00\x05olope",
-                decoded: Unsubscribe::new(0x0407, ["test", "olope"]).expect("failed to create packet"),
-            },
-        ]
+    /// Good encoded/decoded pairs from the shared corpus
+    pub fn all() -> impl Iterator<Item = Self> {
+        test_util::corpus().filter_map(|(encoded, packet)| match packet {
+            Packet::Unsubscribe(decoded) => Some(Self { encoded, decoded }),
+            _ => None,
+        })
     }
 }
 
@@ -49,6 +41,12 @@ impl BadEncoded {
             Self { encoded: b"\xB2\x0D\x04\x07\x00\x09testolope" },
             // Packet with invalid header flags
             Self { encoded: b"\xA0\x0D\x04\x07\x00\x09testolope" },
+            // Packet with an empty topic filter
+            Self { encoded: b"\xA2\x04\x04\x07\x00\x00" },
+            // Packet with a multi-level wildcard that does not occupy an entire level
+            Self { encoded: b"\xA2\x06\x04\x07\x00\x02a#" },
+            // Packet with a topic filter that contains the null character
+            Self { encoded: b"\xA2\x05\x04\x07\x00\x01\x00" },
         ]
     }
 }
@@ -75,6 +73,16 @@ pub fn encode() {
     }
 }
 
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
 /// Tests failing decoding
 #[test]
 pub fn decode_invalid() {
@@ -85,3 +93,46 @@ pub fn decode_invalid() {
         assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
     }
 }
+
+/// Tests the offset and violated rule of an invalid topic filter
+#[test]
+pub fn decode_invalid_filter() {
+    // A packet whose second topic filter contains a multi-level wildcard that is not the last level
+    let encoded = b"\xA2\x0C\x04\x07\x00\x01a\x00\x05b/#/c";
+    let error =
+        Unsubscribe::try_from_iter(encoded.iter().copied()).expect_err("Unexpected success when decoding packet");
+    assert!(error.is_spec_violation(), "Invalid error variant");
+    assert_eq!(error.offset(), 11, "Invalid error offset");
+    assert_eq!(error.detail(), Some(Detail::Rule("MQTT-4.7.1-2")), "Invalid error detail");
+    assert!(error.context_chain().eq(["topic filters"]), "Invalid context chain");
+}
+
+/// Tests that decoding fails if the packet contains more topics than allowed
+#[test]
+pub fn decode_capped() {
+    // A packet with three topics
+    let encoded = b"\xA2\x0B\x04\x07\x00\x01a\x00\x01b\x00\x01c";
+    let decoded = Unsubscribe::try_from_iter_with_limits(encoded.iter().copied(), 3).expect("Failed to decode packet");
+    assert_eq!(decoded.topics().len(), 3, "Invalid decoded packet");
+
+    // Decode with a smaller limit
+    let decoded = Unsubscribe::try_from_iter_with_limits(encoded.iter().copied(), 2);
+    assert!(decoded.is_err(), "Unexpected success when decoding packet with too many topics");
+}
+
+/// Tests that decoding fails if the packet contains more topics than the default limit
+#[test]
+#[cfg(feature = "alloc")]
+pub fn decode_capped_default() {
+    /// Creates a packet with the given amount of single-level topics
+    fn packet(count: usize) -> Vec {
+        let topics = (0..count).map(|_| b"a");
+        Unsubscribe::new(7, topics).expect("Failed to create packet").into_iter().collect()
+    }
+
+    // Decode packets with the maximum amount of topics and one topic more
+    let decoded = Unsubscribe::try_from_iter(packet(Unsubscribe::MAX_TOPICS));
+    assert!(decoded.is_ok(), "Failed to decode packet with the maximum amount of topics");
+    let decoded = Unsubscribe::try_from_iter(packet(Unsubscribe::MAX_TOPICS + 1));
+    assert!(decoded.is_err(), "Unexpected success when decoding packet with too many topics");
+}
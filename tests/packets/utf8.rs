@@ -0,0 +1,138 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::{
+    anystring::{AnyString, Utf8},
+    coding::Decoder,
+    packets::{publish::Publish, subscribe::Subscribe, unsubscribe::Unsubscribe, TryFromIterator},
+    Bytes, PublishUtf8, SubscribeUtf8, UnsubscribeUtf8,
+};
+
+// Select an appropriate sequence type
+#[cfg(feature = "alloc")]
+type Seq<T> = std::vec::Vec<T>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Seq<T> = arrayvec::ArrayVec<T, 4>;
+
+/// Round-trips string-typed PUBLISH, SUBSCRIBE and UNSUBSCRIBE packets with the given topic type
+fn roundtrip<S>()
+where
+    S: AnyString + core::fmt::Debug + PartialEq,
+{
+    // Round-trip a PUBLISH packet with a string-typed topic and a byte payload
+    let publish = Publish::<Bytes, Utf8<S>>::new("Test/Ölope", b"\x00\xFF", false).expect("Failed to create packet");
+    let decoded = Publish::<Bytes, Utf8<S>>::try_from_iter(&publish).expect("Failed to decode valid packet");
+    assert_eq!(decoded, publish, "Invalid decoded packet");
+    assert_eq!(decoded.topic_str(), "Test/Ölope", "Invalid topic");
+    assert_eq!(decoded.payload(), b"\x00\xFF", "Invalid payload");
+
+    // Round-trip a SUBSCRIBE packet with string-typed topics
+    type TopicsQos<S> = Seq<(Utf8<S>, u8)>;
+    let subscribe = Subscribe::<TopicsQos<S>, Utf8<S>>::new(0x0407, [("Test/#", 1), ("Ölope/+", 2)])
+        .expect("Failed to create packet");
+    let decoded = Subscribe::<TopicsQos<S>, Utf8<S>>::try_from_iter(&subscribe).expect("Failed to decode valid packet");
+    assert_eq!(decoded, subscribe, "Invalid decoded packet");
+    assert_eq!(decoded.topics_qos()[1].0.as_str(), "Ölope/+", "Invalid topic");
+
+    // Round-trip an UNSUBSCRIBE packet with string-typed topics
+    type Topics<S> = Seq<Utf8<S>>;
+    let unsubscribe =
+        Unsubscribe::<Topics<S>, Utf8<S>>::new(0x0407, ["Test/#", "Ölope/+"]).expect("Failed to create packet");
+    let decoded =
+        Unsubscribe::<Topics<S>, Utf8<S>>::try_from_iter(&unsubscribe).expect("Failed to decode valid packet");
+    assert_eq!(decoded, unsubscribe, "Invalid decoded packet");
+    assert_eq!(decoded.topics()[0].as_str(), "Test/#", "Invalid topic");
+}
+
+/// Decodes non-ASCII string fields into the given topic type
+fn decode_utf8<S>()
+where
+    S: AnyString + core::fmt::Debug + PartialEq,
+{
+    // Decode a string with multi-byte characters
+    let mut decoder = Decoder::new(b"\x00\x06\xC3\xA4\xC3\xB6\xC3\xBC".iter().copied());
+    let decoded: Utf8<S> = decoder.utf8().expect("Failed to decode valid string");
+    assert_eq!(decoded.as_str(), "äöü", "Invalid decoded string");
+    let mut decoder = Decoder::new(b"\x00\x03\xE2\x82\xAC".iter().copied());
+    let decoded: Option<Utf8<S>> = decoder.optional_utf8(true).expect("Failed to decode valid string");
+    assert_eq!(decoded.as_ref().map(Utf8::as_str), Some("€"), "Invalid decoded string");
+
+    // Decode invalid and truncated strings
+    let mut decoder = Decoder::new(b"\x00\x02\xC3\x28".iter().copied());
+    let error = decoder.utf8::<Utf8<S>>().expect_err("Unexpected success");
+    assert!(error.is_spec_violation(), "Invalid error variant");
+    assert_eq!(error.offset(), 3, "Invalid error offset");
+    let mut decoder = Decoder::new(b"\x00\x06\xC3\xA4\xC3".iter().copied());
+    let error = decoder.utf8::<Utf8<S>>().expect_err("Unexpected success");
+    assert!(error.is_truncated(), "Invalid error variant");
+    assert_eq!(error.offset(), 5, "Invalid error offset");
+}
+
+/// Tests decoding non-ASCII string fields into `String` topics
+#[test]
+#[cfg(feature = "alloc")]
+pub fn decode_utf8_string() {
+    decode_utf8::<std::string::String>();
+}
+
+/// Tests decoding non-ASCII string fields into `ArrayString` topics
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn decode_utf8_arraystring() {
+    decode_utf8::<arrayvec::ArrayString<64>>();
+}
+
+/// Tests round-tripping packets with `String` topics
+#[test]
+#[cfg(feature = "alloc")]
+pub fn roundtrip_string() {
+    roundtrip::<std::string::String>();
+}
+
+/// Tests round-tripping packets with `ArrayString` topics
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn roundtrip_arraystring() {
+    roundtrip::<arrayvec::ArrayString<64>>();
+}
+
+/// Tests the default string-typed packet aliases
+#[test]
+pub fn aliases() {
+    let publish = PublishUtf8::new("Test", b"Olope", false).expect("Failed to create packet");
+    let decoded = PublishUtf8::try_from_iter(&publish).expect("Failed to decode valid packet");
+    assert_eq!(decoded.topic_str(), "Test", "Invalid topic");
+
+    let subscribe = SubscribeUtf8::new(0x0407, [("Test", 1)]).expect("Failed to create packet");
+    assert_eq!(SubscribeUtf8::try_from_iter(&subscribe), Ok(subscribe), "Invalid decoded packet");
+    let unsubscribe = UnsubscribeUtf8::new(0x0407, ["Test"]).expect("Failed to create packet");
+    assert_eq!(UnsubscribeUtf8::try_from_iter(&unsubscribe), Ok(unsubscribe), "Invalid decoded packet");
+}
+
+/// Tests that invalid UTF-8 topics are rejected during decoding
+#[test]
+pub fn decode_invalid() {
+    // Topic with an invalid UTF-8 sequence
+    let decoded = PublishUtf8::try_from_iter(*b"\x30\x0B\x00\x04Te\xFFtOlope");
+    assert!(decoded.is_err(), "Unexpected success when decoding invalid topic");
+
+    // Topic that ends within a multi-byte sequence
+    let decoded = PublishUtf8::try_from_iter(*b"\x30\x0B\x00\x04Tes\xC3Olope");
+    assert!(decoded.is_err(), "Unexpected success when decoding truncated topic");
+
+    // Topic with a null character
+    let decoded = SubscribeUtf8::try_from_iter(*b"\x82\x09\x04\x07\x00\x04Te\x00t\x01");
+    assert!(decoded.is_err(), "Unexpected success when decoding topic with null character");
+}
+
+/// Tests that string-backed byte fields cannot be truncated within a character
+#[test]
+#[cfg(feature = "alloc")]
+pub fn truncate() {
+    use mqtt_tiny::anyvec::AnyVec;
+
+    let mut topic = Utf8::new(std::string::String::from("Ölope"));
+    topic.truncate(1);
+    assert_eq!(topic.as_str(), "", "Invalid truncated topic");
+    topic.extend("Test".as_bytes()).expect("Failed to extend topic");
+    assert_eq!(topic.into_iter().collect::<std::vec::Vec<u8>>(), b"Test", "Invalid iterated topic");
+}
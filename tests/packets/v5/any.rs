@@ -0,0 +1,177 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    packets::{
+        packet::{PacketType, ProtocolVersion},
+        v5::reason::ReasonCode,
+    },
+    AnyPacket, Packet, PacketV5, Puback, PubackV5, Publish, PublishV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The negotiated protocol version
+    version: ProtocolVersion,
+    /// The decoded representation
+    decoded: AnyPacket,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 7] {
+        [
+            Self {
+                encoded: b"\x32\x07\x00\x01t\x00\x07hi",
+                version: ProtocolVersion::V3_1_1,
+                decoded: AnyPacket::V3(Packet::Publish(
+                    Publish::new(b"t", b"hi", false).expect("Failed to create test vector").with_qos(1, 7, false),
+                )),
+            },
+            Self {
+                encoded: b"\x32\x08\x00\x01t\x00\x07\x00hi",
+                version: ProtocolVersion::V5,
+                decoded: AnyPacket::V5(PacketV5::Publish(
+                    PublishV5::new(b"t", b"hi", false).expect("Failed to create test vector").with_qos(1, 7, false),
+                )),
+            },
+            Self {
+                encoded: b"\x40\x02\x00\x07",
+                version: ProtocolVersion::V3_1_1,
+                decoded: AnyPacket::V3(Packet::Puback(Puback::new(7))),
+            },
+            Self {
+                encoded: b"\x40\x02\x00\x07",
+                version: ProtocolVersion::V3_1,
+                decoded: AnyPacket::V3(Packet::Puback(Puback::new(7))),
+            },
+            Self {
+                encoded: b"\x40\x02\x00\x07",
+                version: ProtocolVersion::V5,
+                decoded: AnyPacket::V5(PacketV5::Puback(PubackV5::new(7))),
+            },
+            Self {
+                encoded: b"\x40\x03\x00\x07\x10",
+                version: ProtocolVersion::V5,
+                decoded: AnyPacket::V5(PacketV5::Puback(
+                    PubackV5::new(7).with_reason_code(ReasonCode::NoMatchingSubscribers),
+                )),
+            },
+            Self {
+                encoded: b"\x30\x04\x00\x01t\x00",
+                version: ProtocolVersion::V5,
+                decoded: AnyPacket::V5(PacketV5::Publish(
+                    PublishV5::new(b"t", b"", false).expect("Failed to create test vector"),
+                )),
+            },
+        ]
+    }
+}
+
+/// A test vector for known-bad encoded packets
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The negotiated protocol version
+    version: ProtocolVersion,
+}
+impl BadEncoded {
+    /// Known-bad encoded packets
+    pub const fn all() -> &'static [Self] {
+        &[
+            // An MQTT 5 `PUBACK` packet with reason code on an MQTT 3.1.1 session
+            Self { encoded: b"\x40\x03\x00\x07\x10", version: ProtocolVersion::V3_1_1 },
+            // An MQTT 5 `PUBLISH` packet with a truncated properties block on an MQTT 5 session
+            Self { encoded: b"\x32\x05\x00\x01t\x00\x07", version: ProtocolVersion::V5 },
+            // A `PINGREQ` packet which is not modelled for MQTT 5
+            Self { encoded: b"\xC0\x00", version: ProtocolVersion::V5 },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded =
+            AnyPacket::try_from_iter_versioned(encoded, test_vector.version).expect("Failed to decode packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet");
+        assert!(decoded.is_compatible(test_vector.version), "Invalid protocol version");
+    }
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.encode_for(test_vector.version).expect("Failed to encode packet").collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let iter = test_vector.decoded.encode_ref_for(test_vector.version).expect("Failed to encode packet");
+        assert_eq!(iter.len(), test_vector.encoded.len(), "Invalid size hint");
+        let encoded: Vec = iter.collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests that packets are not encoded for sessions of another protocol version
+#[test]
+pub fn encode_mismatch() {
+    // Encode a MQTT 5 packet for MQTT 3.1.1 and MQTT 3.1 sessions
+    let packet = AnyPacket::from(PacketV5::Puback(PubackV5::new(7)));
+    assert!(packet.clone().encode_for(ProtocolVersion::V3_1_1).is_err(), "Unexpected success");
+    assert!(packet.clone().encode_for(ProtocolVersion::V3_1).is_err(), "Unexpected success");
+    assert!(packet.encode_ref_for(ProtocolVersion::V3_1_1).is_err(), "Unexpected success");
+
+    // Encode a MQTT 3.1.1 packet for a MQTT 5 session
+    let packet = AnyPacket::from(Packet::Puback(Puback::new(7)));
+    assert!(packet.clone().encode_for(ProtocolVersion::V5).is_err(), "Unexpected success");
+    assert!(packet.encode_ref_for(ProtocolVersion::V5).is_err(), "Unexpected success");
+}
+
+/// Tests the explicit conversions into the version-specific packets
+#[test]
+pub fn convert() {
+    // Convert a MQTT 3.1.1 packet
+    let packet = AnyPacket::from(Packet::Puback(Puback::new(7)));
+    assert_eq!(packet.packet_type(), Some(PacketType::Puback), "Invalid packet type");
+    assert_eq!(packet.name(), "PUBACK", "Invalid packet name");
+    let packet = packet.into_v5().expect_err("Unexpected MQTT 5 packet");
+    assert_eq!(packet.into_v3().ok(), Some(Packet::Puback(Puback::new(7))), "Invalid converted packet");
+
+    // Convert a MQTT 5 packet
+    let packet = AnyPacket::from(PacketV5::Puback(PubackV5::new(7)));
+    let packet = packet.into_v3().expect_err("Unexpected MQTT 3.1.1 packet");
+    assert_eq!(packet.into_v5().ok(), Some(PacketV5::Puback(PubackV5::new(7))), "Invalid converted packet");
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = AnyPacket::try_from_iter_versioned(encoded, test_vector.version);
+        assert!(decoded.is_err(), "Unexpected success when decoding invalid packet");
+    }
+}
@@ -0,0 +1,192 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    error::Detail,
+    packets::{
+        v5::{
+            properties::{id, Value},
+            session::MAX_PACKET_SIZE,
+        },
+        TryFromIterator,
+    },
+    ConnackV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: ConnackV5,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 4] {
+        [
+            // A successful connack without properties
+            Self { encoded: b"\x20\x03\x00\x00\x00", decoded: ConnackV5::new(false, 0x00) },
+            // A refused connack due to an unsupported protocol version
+            Self { encoded: b"\x20\x03\x00\x84\x00", decoded: ConnackV5::new(false, 0x84) },
+            // A successful connack with a present session and server properties
+            Self {
+                encoded: b"\x20\x28\x01\x00\
+                    \x25\x12\x00\x04auto\x13\x00\x1E\x21\x00\x0A\x24\x01\x25\x00\x27\x00\x01\x00\x00\x22\x00\x05\
+                    \x1F\x00\x02ok\x26\x00\x01a\x00\x01b",
+                decoded: Self::full().expect("Failed to create test vector"),
+            },
+            // A connack with an unknown property that is preserved verbatim
+            Self {
+                encoded: b"\x20\x09\x00\x00\x06\x21\x00\x0A\x7F\x01\x02",
+                decoded: Self::unknown().expect("Failed to create test vector"),
+            },
+        ]
+    }
+
+    /// Creates a packet with all server properties
+    fn full() -> Result<ConnackV5, &'static str> {
+        ConnackV5::new(true, 0x00)
+            .with_property(id::ASSIGNED_CLIENT_IDENTIFIER, Value::Utf8(b"auto"))?
+            .with_property(id::SERVER_KEEP_ALIVE, Value::U16(30))?
+            .with_property(id::RECEIVE_MAXIMUM, Value::U16(10))?
+            .with_property(id::MAXIMUM_QOS, Value::Byte(1))?
+            .with_property(id::RETAIN_AVAILABLE, Value::Byte(0))?
+            .with_property(id::MAXIMUM_PACKET_SIZE, Value::U32(65_536))?
+            .with_property(id::TOPIC_ALIAS_MAXIMUM, Value::U16(5))?
+            .with_property(id::REASON_STRING, Value::Utf8(b"ok"))?
+            .with_property(id::USER_PROPERTY, Value::Pair(b"a", b"b"))
+    }
+    /// Creates a packet with an unknown property by decoding it
+    fn unknown() -> Result<ConnackV5, &'static str> {
+        let encoded = b"\x20\x09\x00\x00\x06\x21\x00\x0A\x7F\x01\x02";
+        ConnackV5::try_from_iter(encoded.iter().copied()).map_err(|e| e.description())
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The offset of the offending byte
+    offset: usize,
+    /// The expected/found or rule detail, if any
+    detail: Option<Detail>,
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Empty packet
+            Self { encoded: b"", offset: 0, detail: None },
+            // Packet with invalid packet type
+            Self {
+                encoded: b"\x10\x03\x00\x00\x00",
+                offset: 0,
+                detail: Some(Detail::Mismatch { expected: 2, found: 1 }),
+            },
+            // Packet without properties block
+            Self { encoded: b"\x20\x02\x00\x00", offset: 4, detail: None },
+            // Packet with reserved acknowledge flags set
+            Self { encoded: b"\x20\x03\x02\x00\x00", offset: 2, detail: Some(Detail::Rule("MQTT-3.2.2-1")) },
+            // Packet with a duplicate property
+            Self { encoded: b"\x20\x09\x00\x00\x06\x21\x00\x01\x21\x00\x02", offset: 8, detail: None },
+            // Packet with an invalid UTF-8 reason string
+            Self { encoded: b"\x20\x08\x00\x00\x05\x1F\x00\x02\x00\x41", offset: 5, detail: None },
+            // Packet with trailing bytes after the properties
+            Self { encoded: b"\x20\x04\x00\x00\x00\x00", offset: 5, detail: None },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = ConnackV5::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet")
+    }
+}
+
+/// Tests the property accessors of a decoded packet
+#[test]
+pub fn decode_properties() {
+    // Decode the packet with all server properties
+    let [_, _, full, unknown] = Good::all();
+    let decoded = ConnackV5::try_from_iter(full.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the properties
+    assert!(decoded.session_present(), "Invalid session present flag");
+    assert_eq!(decoded.assigned_client_identifier(), Some(&b"auto"[..]), "Invalid assigned client identifier");
+    assert_eq!(decoded.server_keep_alive(), Some(30), "Invalid server keep alive");
+    assert_eq!(decoded.receive_maximum(), 10, "Invalid receive maximum");
+    assert_eq!(decoded.maximum_qos(), Some(1), "Invalid maximum QoS");
+    assert_eq!(decoded.retain_available(), Some(false), "Invalid retain available");
+    assert_eq!(decoded.maximum_packet_size(), 65_536, "Invalid maximum packet size");
+    assert_eq!(decoded.topic_alias_maximum(), Some(5), "Invalid topic alias maximum");
+    assert_eq!(decoded.reason_string(), Some(&b"ok"[..]), "Invalid reason string");
+    assert!(decoded.user_properties().eq([(&b"a"[..], &b"b"[..])]), "Invalid user properties");
+    assert_eq!(decoded.session_expiry_interval(), None, "Invalid session expiry interval");
+
+    // Validate the unknown property
+    let expected = [(id::RECEIVE_MAXIMUM, Value::U16(10)), (0x7F, Value::Raw(b"\x7F\x01\x02"))];
+    assert!(unknown.decoded.properties().iter().eq(expected), "Invalid properties");
+}
+
+/// Tests the spec-defined defaults of absent properties
+#[test]
+pub fn decode_properties_default() {
+    // Decode the packet without properties
+    let [minimal, ..] = Good::all();
+    let decoded = ConnackV5::try_from_iter(minimal.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the properties
+    assert_eq!(decoded.receive_maximum(), 65535, "Invalid receive maximum");
+    assert_eq!(decoded.maximum_packet_size(), MAX_PACKET_SIZE, "Invalid maximum packet size");
+    assert_eq!(decoded.session_expiry_interval(), None, "Invalid session expiry interval");
+    assert_eq!(decoded.server_keep_alive(), None, "Invalid server keep alive");
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let Err(error) = ConnackV5::try_from_iter(encoded) else {
+            panic!("Unexpected success when decoding invalid packet {:02x?}", test_vector.encoded);
+        };
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset for {:02x?}", test_vector.encoded);
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail for {:02x?}", test_vector.encoded);
+    }
+}
@@ -0,0 +1,218 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    error::Detail,
+    packets::{
+        v5::{
+            properties::{id, Value},
+            session::MAX_PACKET_SIZE,
+        },
+        TryFromIterator,
+    },
+    ConnectV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 128>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: ConnectV5,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 2] {
+        [
+            // A minimal packet without properties
+            Self {
+                encoded: b"\x10\x10\x00\x04MQTT\x05\x02\x00\x3C\x00\x00\x03abc",
+                decoded: ConnectV5::new(60, true, b"abc").expect("Failed to create test vector"),
+            },
+            // A packet with connect properties, last-will with will properties, username and password
+            Self {
+                encoded: b"\x10\x52\x00\x04MQTT\x05\xEE\x00\x3C\
+                    \x24\x11\x00\x00\x00\x78\x21\x00\x14\x27\x00\x00\x10\x00\x22\x00\x0A\x26\x00\x01k\x00\x01v\
+                    \x15\x00\x05SCRAM\x16\x00\x02\x01\x02\
+                    \x00\x03abc\
+                    \x07\x18\x00\x00\x00\x05\x01\x01\x00\x03t/w\x00\x03bye\
+                    \x00\x04user\x00\x04pass",
+                decoded: Self::full().expect("Failed to create test vector"),
+            },
+        ]
+    }
+
+    /// Creates a packet that uses all fields
+    fn full() -> Result<ConnectV5, &'static str> {
+        ConnectV5::new(60, true, b"abc")?
+            .with_property(id::SESSION_EXPIRY_INTERVAL, Value::U32(120))?
+            .with_property(id::RECEIVE_MAXIMUM, Value::U16(20))?
+            .with_property(id::MAXIMUM_PACKET_SIZE, Value::U32(4096))?
+            .with_property(id::TOPIC_ALIAS_MAXIMUM, Value::U16(10))?
+            .with_property(id::USER_PROPERTY, Value::Pair(b"k", b"v"))?
+            .with_property(id::AUTHENTICATION_METHOD, Value::Utf8(b"SCRAM"))?
+            .with_property(id::AUTHENTICATION_DATA, Value::Binary(b"\x01\x02"))?
+            .with_will(b"t/w", b"bye", 1, true)?
+            .with_will_property(id::WILL_DELAY_INTERVAL, Value::U32(5))?
+            .with_will_property(id::PAYLOAD_FORMAT_INDICATOR, Value::Byte(1))?
+            .with_username_password(b"user", b"pass")
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The offset of the offending byte
+    offset: usize,
+    /// The expected/found or rule detail, if any
+    detail: Option<Detail>,
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Packet with MQTT 3.1.1 protocol version
+            Self {
+                encoded: b"\x10\x10\x00\x04MQTT\x04\x02\x00\x3C\x00\x00\x03abc",
+                offset: 8,
+                detail: Some(Detail::Mismatch { expected: 5, found: 4 }),
+            },
+            // Packet with a properties block that exceeds the packet
+            Self { encoded: b"\x10\x10\x00\x04MQTT\x05\x02\x00\x3C\x08\x00\x03abc", offset: 18, detail: None },
+            // Packet with a truncated property
+            Self { encoded: b"\x10\x12\x00\x04MQTT\x05\x02\x00\x3C\x02\x21\x00\x00\x03abc", offset: 13, detail: None },
+            // Packet with a duplicate property
+            Self {
+                encoded: b"\x10\x16\x00\x04MQTT\x05\x02\x00\x3C\x06\x21\x00\x01\x21\x00\x02\x00\x03abc",
+                offset: 16,
+                detail: None,
+            },
+            // Packet with an invalid UTF-8 authentication method
+            Self {
+                encoded: b"\x10\x15\x00\x04MQTT\x05\x02\x00\x3C\x05\x15\x00\x02\xC3\x28\x00\x03abc",
+                offset: 13,
+                detail: None,
+            },
+            // Packet with indicated last will but missing will properties
+            Self { encoded: b"\x10\x10\x00\x04MQTT\x05\x06\x00\x3C\x00\x00\x03abc", offset: 18, detail: None },
+            // Packet with the reserved flag set
+            Self {
+                encoded: b"\x10\x10\x00\x04MQTT\x05\x03\x00\x3C\x00\x00\x03abc",
+                offset: 9,
+                detail: Some(Detail::Rule("MQTT-3.1.2-3")),
+            },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = ConnectV5::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet")
+    }
+}
+
+/// Tests the property accessors of a decoded packet
+#[test]
+pub fn decode_properties() {
+    // Decode the packet that uses all fields
+    let [_, full] = Good::all();
+    let decoded = ConnectV5::try_from_iter(full.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the properties
+    assert_eq!(decoded.session_expiry_interval(), 120, "Invalid session expiry interval");
+    assert_eq!(decoded.receive_maximum(), 20, "Invalid receive maximum");
+    assert_eq!(decoded.maximum_packet_size(), 4096, "Invalid maximum packet size");
+    assert_eq!(decoded.topic_alias_maximum(), Some(10), "Invalid topic alias maximum");
+    assert!(decoded.user_properties().eq([(&b"k"[..], &b"v"[..])]), "Invalid user properties");
+    assert_eq!(decoded.authentication_method(), Some(&b"SCRAM"[..]), "Invalid authentication method");
+    assert_eq!(decoded.authentication_data(), Some(&b"\x01\x02"[..]), "Invalid authentication data");
+
+    // Validate the will
+    let will_properties = decoded.will_properties().expect("Missing will properties");
+    assert_eq!(will_properties.get(id::WILL_DELAY_INTERVAL), Some(Value::U32(5)), "Invalid will delay interval");
+    assert_eq!(decoded.will_topic(), Some(&b"t/w"[..]), "Invalid will topic");
+    assert_eq!(decoded.will_payload(), Some(&b"bye"[..]), "Invalid will payload");
+    assert_eq!((decoded.will_qos(), decoded.will_retain()), (1, true), "Invalid will flags");
+}
+
+/// Tests the spec-defined defaults of absent properties
+#[test]
+pub fn decode_properties_default() {
+    // Decode the packet without properties
+    let [minimal, _] = Good::all();
+    let decoded = ConnectV5::try_from_iter(minimal.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the properties
+    assert_eq!(decoded.session_expiry_interval(), 0, "Invalid session expiry interval");
+    assert_eq!(decoded.receive_maximum(), 65535, "Invalid receive maximum");
+    assert_eq!(decoded.maximum_packet_size(), MAX_PACKET_SIZE, "Invalid maximum packet size");
+    assert_eq!(decoded.topic_alias_maximum(), None, "Invalid topic alias maximum");
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let Err(error) = ConnectV5::try_from_iter(encoded) else {
+            panic!("Unexpected success when decoding invalid packet {:02x?}", test_vector.encoded);
+        };
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset for {:02x?}", test_vector.encoded);
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail for {:02x?}", test_vector.encoded);
+    }
+}
+
+/// Tests that an MQTT 3.1.1 packet is reported as unsupported version
+#[test]
+pub fn decode_unsupported_version() {
+    let encoded = b"\x10\x10\x00\x04MQTT\x04\x02\x00\x3C\x00\x00\x03abc";
+    let error = ConnectV5::try_from_iter(encoded.iter().copied()).expect_err("Unexpected success");
+    assert!(error.is_unsupported_version(), "Invalid error variant");
+}
+
+/// Tests that the debug representation redacts credentials
+#[test]
+#[cfg(all(feature = "alloc", not(feature = "debug-credentials")))]
+pub fn debug_redacted() {
+    let [_, full] = Good::all();
+    let debug = format!("{:?}", full.decoded);
+    assert!(debug.contains("password: Some(<redacted, 4 bytes>)"), "Password is not redacted: {debug}");
+    assert!(debug.contains("(22, <redacted, 2 bytes>)"), "Authentication data is not redacted: {debug}");
+    assert!(!debug.contains("bye"), "Will payload is not redacted: {debug}");
+}
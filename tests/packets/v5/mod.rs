@@ -0,0 +1,14 @@
+#![cfg(feature = "v5")]
+
+pub mod any;
+pub mod connack;
+pub mod connect;
+pub mod packet;
+pub mod properties;
+pub mod puback;
+pub mod publish;
+pub mod session;
+pub mod suback;
+pub mod topic_alias;
+pub mod unsuback;
+pub mod user_properties;
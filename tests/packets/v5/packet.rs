@@ -0,0 +1,163 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    error::Decoding,
+    packets::{
+        packet::PacketType,
+        v5::{
+            properties::{id, Value},
+            reason::ReasonCode,
+        },
+        TryFromIterator,
+    },
+    ConnackV5, ConnectV5, PacketV5, PubackV5, PubcompV5, PublishV5, PubrecV5, PubrelV5, SubackV5, UnsubackV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The packet type
+    type_: PacketType,
+    /// The decoded representation
+    decoded: PacketV5,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 11] {
+        [
+            Self {
+                encoded: b"\x20\x03\x00\x00\x00",
+                type_: PacketType::Connack,
+                decoded: PacketV5::Connack(ConnackV5::new(false, 0x00)),
+            },
+            Self {
+                encoded: b"\x10\x0F\x00\x04MQTT\x05\x00\x00\x1E\x00\x00\x02id",
+                type_: PacketType::Connect,
+                decoded: PacketV5::Connect(ConnectV5::new(30, false, b"id").expect("Failed to create test vector")),
+            },
+            Self {
+                encoded: b"\x32\x08\x00\x01t\x00\x07\x00hi",
+                type_: PacketType::Publish,
+                decoded: PacketV5::Publish(
+                    PublishV5::new(b"t", b"hi", false).expect("Failed to create test vector").with_qos(1, 7, false),
+                ),
+            },
+            Self {
+                encoded: b"\x40\x02\x00\x07",
+                type_: PacketType::Puback,
+                decoded: PacketV5::Puback(PubackV5::new(7)),
+            },
+            Self {
+                encoded: b"\x40\x03\x00\x07\x10",
+                type_: PacketType::Puback,
+                decoded: PacketV5::Puback(PubackV5::new(7).with_reason_code(ReasonCode::NoMatchingSubscribers)),
+            },
+            Self {
+                encoded: b"\x50\x02\x00\x07",
+                type_: PacketType::Pubrec,
+                decoded: PacketV5::Pubrec(PubrecV5::new(7)),
+            },
+            Self {
+                encoded: b"\x60\x03\x00\x07\x92",
+                type_: PacketType::Pubrel,
+                decoded: PacketV5::Pubrel(PubrelV5::new(7).with_reason_code(ReasonCode::PacketIdentifierNotFound)),
+            },
+            Self {
+                encoded: b"\x70\x02\x00\x07",
+                type_: PacketType::Pubcomp,
+                decoded: PacketV5::Pubcomp(PubcompV5::new(7)),
+            },
+            Self {
+                encoded: b"\x70\x08\x00\x07\x00\x04\x1F\x00\x01x",
+                type_: PacketType::Pubcomp,
+                decoded: PacketV5::Pubcomp(
+                    PubcompV5::new(7)
+                        .with_property(id::REASON_STRING, Value::Utf8(b"x"))
+                        .expect("Failed to create test vector"),
+                ),
+            },
+            Self {
+                encoded: b"\x90\x05\x00\x07\x00\x01\x87",
+                type_: PacketType::Suback,
+                decoded: PacketV5::Suback(
+                    SubackV5::new(7, [ReasonCode::GrantedQos1, ReasonCode::NotAuthorized])
+                        .expect("Failed to create test vector"),
+                ),
+            },
+            Self {
+                encoded: b"\xB0\x04\x00\x07\x00\x11",
+                type_: PacketType::Unsuback,
+                decoded: PacketV5::Unsuback(
+                    UnsubackV5::new(7, [ReasonCode::NoSubscriptionExisted]).expect("Failed to create test vector"),
+                ),
+            },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = PacketV5::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet");
+        assert_eq!(decoded.packet_type(), test_vector.type_, "Invalid packet type");
+        assert_eq!(decoded.name(), test_vector.type_.name(), "Invalid packet name");
+    }
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests that the encoding iterators report their exact remaining length
+#[test]
+pub fn size_hint() {
+    for test_vector in Good::all() {
+        // Validate the iterator while consuming it
+        let mut iter = test_vector.decoded.into_iter();
+        assert_eq!(iter.len(), test_vector.encoded.len(), "Invalid size hint");
+        for remaining in (0..test_vector.encoded.len()).rev() {
+            iter.next().expect("Failed to read encoded byte");
+            assert_eq!(iter.len(), remaining, "Invalid size hint");
+        }
+    }
+}
+
+/// Tests that packet types without an MQTT 5 representation are rejected
+#[test]
+pub fn decode_unknown() {
+    // Decode a `PINGREQ` packet
+    let Err(error) = PacketV5::try_from_iter(*b"\xC0\x00") else {
+        panic!("Unexpected success when decoding unsupported packet");
+    };
+    assert_eq!(error.variant(), &Decoding::UnknownPacketType, "Invalid error variant");
+}
@@ -0,0 +1,61 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::packets::v5::properties::{id, Properties, Value};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// Tests encoding of all value types
+#[test]
+pub fn push() {
+    // Push a property of each value type
+    let mut properties = Properties::<Vec>::new();
+    properties.push(id::PAYLOAD_FORMAT_INDICATOR, Value::Byte(1)).expect("Failed to push valid property");
+    properties.push(id::TOPIC_ALIAS, Value::U16(0x0102)).expect("Failed to push valid property");
+    properties.push(id::MESSAGE_EXPIRY_INTERVAL, Value::U32(0x01020304)).expect("Failed to push valid property");
+    properties.push(id::SUBSCRIPTION_IDENTIFIER, Value::Varint(100)).expect("Failed to push valid property");
+    properties.push(id::CORRELATION_DATA, Value::Binary(b"\x00\xFF")).expect("Failed to push valid property");
+    properties.push(id::CONTENT_TYPE, Value::Utf8(b"text")).expect("Failed to push valid property");
+    properties.push(id::USER_PROPERTY, Value::Pair(b"k", b"v")).expect("Failed to push valid property");
+    properties.push(id::USER_PROPERTY, Value::Pair(b"k", b"w")).expect("Failed to push valid property");
+
+    // Validate the encoded block
+    let encoded = b"\x01\x01\x23\x01\x02\x02\x01\x02\x03\x04\x0B\x64\x09\x00\x02\x00\xFF\x03\x00\x04text\
+        \x26\x00\x01k\x00\x01v\x26\x00\x01k\x00\x01w";
+    assert_eq!(properties.as_ref(), encoded, "Invalid encoded properties");
+
+    // Validate the decoded values
+    let expected = [
+        (id::PAYLOAD_FORMAT_INDICATOR, Value::Byte(1)),
+        (id::TOPIC_ALIAS, Value::U16(0x0102)),
+        (id::MESSAGE_EXPIRY_INTERVAL, Value::U32(0x01020304)),
+        (id::SUBSCRIPTION_IDENTIFIER, Value::Varint(100)),
+        (id::CORRELATION_DATA, Value::Binary(b"\x00\xFF")),
+        (id::CONTENT_TYPE, Value::Utf8(b"text")),
+        (id::USER_PROPERTY, Value::Pair(b"k", b"v")),
+        (id::USER_PROPERTY, Value::Pair(b"k", b"w")),
+    ];
+    assert!(properties.iter().eq(expected), "Invalid decoded properties");
+    assert_eq!(properties.get(id::USER_PROPERTY), Some(Value::Pair(b"k", b"v")), "Invalid first user property");
+}
+
+/// Tests that invalid properties are rejected and leave the block untouched
+#[test]
+pub fn push_invalid() {
+    let mut properties = Properties::<Vec>::new();
+    properties.push(id::RECEIVE_MAXIMUM, Value::U16(1)).expect("Failed to push valid property");
+    let before = properties.clone();
+
+    // Push invalid properties
+    assert!(properties.push(id::RECEIVE_MAXIMUM, Value::U16(2)).is_err(), "Unexpected duplicate property");
+    assert!(properties.push(id::RECEIVE_MAXIMUM, Value::U32(2)).is_err(), "Unexpected value type mismatch");
+    assert!(properties.push(0x7F, Value::Byte(2)).is_err(), "Unexpected unknown property");
+    assert!(properties.push(0x7F, Value::Raw(b"\x7F")).is_err(), "Unexpected raw property");
+    assert!(properties.push(id::CONTENT_TYPE, Value::Utf8(b"\xC3\x28")).is_err(), "Unexpected invalid UTF-8");
+    assert!(properties.push(id::USER_PROPERTY, Value::Pair(b"k", b"\x00")).is_err(), "Unexpected null character");
+    assert!(properties.push(id::SUBSCRIPTION_IDENTIFIER, Value::Varint(1 << 28)).is_err(), "Unexpected varint");
+    assert_eq!(properties, before, "Invalid properties after failed push");
+}
@@ -0,0 +1,204 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    error::Detail,
+    packets::{
+        v5::{
+            properties::{id, Value},
+            reason::ReasonCode,
+        },
+        TryFromIterator,
+    },
+    PubackV5, PubcompV5, PubrecV5, PubrelV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: PubackV5,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 5] {
+        [
+            // A legacy-length puback without reason code
+            Self { encoded: b"\x40\x02\x00\x07", decoded: PubackV5::new(7) },
+            // A puback with a reason code but without properties
+            Self {
+                encoded: b"\x40\x03\x00\x07\x10",
+                decoded: PubackV5::new(7).with_reason_code(ReasonCode::NoMatchingSubscribers),
+            },
+            // A puback with an unassigned reason code
+            Self {
+                encoded: b"\x40\x03\x00\x07\x42",
+                decoded: PubackV5::new(7).with_reason_code(ReasonCode::Other(0x42)),
+            },
+            // A full-length puback with an error reason code and a reason string
+            Self {
+                encoded: b"\x40\x08\x00\x07\x87\x04\x1F\x00\x01x",
+                decoded: PubackV5::new(7)
+                    .with_reason_code(ReasonCode::NotAuthorized)
+                    .with_property(id::REASON_STRING, Value::Utf8(b"x"))
+                    .expect("Failed to create test vector"),
+            },
+            // A full-length puback with a success reason code and a user property
+            Self {
+                encoded: b"\x40\x0B\x00\x07\x00\x07\x26\x00\x01k\x00\x01v",
+                decoded: PubackV5::new(7)
+                    .with_property(id::USER_PROPERTY, Value::Pair(b"k", b"v"))
+                    .expect("Failed to create test vector"),
+            },
+        ]
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The offset of the offending byte
+    offset: usize,
+    /// The expected/found or rule detail, if any
+    detail: Option<Detail>,
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Empty packet
+            Self { encoded: b"", offset: 0, detail: None },
+            // Packet with invalid packet type
+            Self { encoded: b"\x50\x02\x00\x07", offset: 0, detail: Some(Detail::Mismatch { expected: 4, found: 5 }) },
+            // Packet that is too short to contain a packet ID
+            Self { encoded: b"\x40\x01\x00", offset: 1, detail: None },
+            // Packet with a duplicate reason string
+            Self { encoded: b"\x40\x0A\x00\x07\x80\x06\x1F\x00\x00\x1F\x00\x00", offset: 9, detail: None },
+            // Packet with trailing bytes after the properties
+            Self { encoded: b"\x40\x05\x00\x07\x00\x00\x00", offset: 6, detail: None },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = PubackV5::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet")
+    }
+}
+
+/// Tests the reason code and property accessors of a decoded packet
+#[test]
+pub fn decode_reason_code() {
+    // Decode the packets
+    let [legacy, short, _, full, _] = Good::all();
+    let legacy = PubackV5::try_from_iter(legacy.encoded.iter().copied()).expect("Failed to decode valid packet");
+    let short = PubackV5::try_from_iter(short.encoded.iter().copied()).expect("Failed to decode valid packet");
+    let full = PubackV5::try_from_iter(full.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the reason codes and properties
+    assert_eq!((legacy.packet_id(), legacy.reason_code()), (7, ReasonCode::Success), "Invalid reason code");
+    assert_eq!(short.reason_code(), ReasonCode::NoMatchingSubscribers, "Invalid reason code");
+    assert!(short.reason_code().is_success(), "Invalid reason code success");
+    assert_eq!(full.reason_code(), ReasonCode::NotAuthorized, "Invalid reason code");
+    assert!(!full.reason_code().is_success(), "Invalid reason code success");
+    assert_eq!(full.reason_string(), Some(&b"x"[..]), "Invalid reason string");
+    assert_eq!(full.user_properties().count(), 0, "Invalid user properties");
+}
+
+/// Tests that the reason codes convert to and from their raw representation
+#[test]
+pub fn reason_code() {
+    for code in 0..=u8::MAX {
+        // Convert back and forth
+        let reason_code = ReasonCode::from(code);
+        assert_eq!(u8::from(reason_code), code, "Invalid raw reason code");
+        if let ReasonCode::Other(_) = reason_code {
+            // Unassigned codes must not collide with assigned ones
+            assert!(matches!(code, 0x03 | 0x05..=0x0F | 0x12..=0x17 | 0x1A..=0x7F | 0xA3..), "Invalid reason code");
+        }
+    }
+}
+
+/// Tests that the non-canonical full-length form of a success acknowledgement is encoded in the shortest form
+#[test]
+pub fn encode_shortest() {
+    // Decode the packet with an explicit success code and empty properties
+    let encoded = b"\x40\x04\x00\x07\x00\x00";
+    let decoded = PubackV5::try_from_iter(encoded.iter().copied()).expect("Failed to decode valid packet");
+    assert_eq!(decoded, PubackV5::new(7), "Invalid decoded packet");
+
+    // Re-encode the packet
+    let encoded: Vec = decoded.into_iter().collect();
+    assert_eq!(encoded.deref(), b"\x40\x02\x00\x07", "Invalid encoded packet");
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests that the other acknowledgements share the encoding with their respective packet type
+#[test]
+pub fn encode_other_types() {
+    // Encode the 2-, 3- and full-length forms of the other acknowledgements
+    let pubrec: Vec = PubrecV5::new(7).into_iter().collect();
+    let pubrel: Vec = PubrelV5::new(7).with_reason_code(ReasonCode::PacketIdentifierNotFound).into_iter().collect();
+    let pubcomp = PubcompV5::new(7).with_property(id::REASON_STRING, Value::Utf8(b"x"));
+    let pubcomp: Vec = pubcomp.expect("Failed to create packet").into_iter().collect();
+    assert_eq!(pubrec.deref(), b"\x50\x02\x00\x07", "Invalid encoded packet");
+    assert_eq!(pubrel.deref(), b"\x60\x03\x00\x07\x92", "Invalid encoded packet");
+    assert_eq!(pubcomp.deref(), b"\x70\x08\x00\x07\x00\x04\x1F\x00\x01x", "Invalid encoded packet");
+
+    // Decode the packets again
+    let decoded = PubrecV5::try_from_iter(pubrec.iter().copied()).expect("Failed to decode valid packet");
+    assert_eq!(decoded, PubrecV5::new(7), "Invalid decoded packet");
+    let decoded = PubrelV5::try_from_iter(pubrel.iter().copied()).expect("Failed to decode valid packet");
+    assert_eq!(decoded.reason_code(), ReasonCode::PacketIdentifierNotFound, "Invalid decoded packet");
+    let decoded = PubcompV5::try_from_iter(pubcomp.iter().copied()).expect("Failed to decode valid packet");
+    assert_eq!(decoded.reason_string(), Some(&b"x"[..]), "Invalid decoded packet");
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let Err(error) = PubackV5::try_from_iter(encoded) else {
+            panic!("Unexpected success when decoding invalid packet {:02x?}", test_vector.encoded);
+        };
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset for {:02x?}", test_vector.encoded);
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail for {:02x?}", test_vector.encoded);
+    }
+}
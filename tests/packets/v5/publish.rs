@@ -0,0 +1,242 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    error::Detail,
+    packets::{
+        v5::properties::{id, Value},
+        TryFromIterator,
+    },
+    PublishV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: PublishV5,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 4] {
+        [
+            // A QoS 0 publish without properties
+            Self {
+                encoded: b"\x30\x08\x00\x03a/b\x00hi",
+                decoded: PublishV5::new(b"a/b", b"hi", false).expect("Failed to create test vector"),
+            },
+            // A retained QoS 1 publish with all typed properties and repeated subscription identifiers
+            Self {
+                encoded: b"\x33\x29\x00\x01t\x00\x07\
+                    \x22\x01\x01\x02\x00\x00\x00\x3C\x03\x00\x04text\x09\x00\x02\x01\x02\x08\x00\x01r\
+                    \x26\x00\x01k\x00\x01v\x0B\x05\x0B\x09x",
+                decoded: Self::full().expect("Failed to create test vector"),
+            },
+            // A duplicate QoS 2 publish with an empty topic via a topic alias
+            Self {
+                encoded: b"\x3C\x09\x00\x00\x00\x01\x03\x23\x00\x03p",
+                decoded: Self::aliased().expect("Failed to create test vector"),
+            },
+            // A publish with an unknown property that is preserved verbatim
+            Self {
+                encoded: b"\x30\x0B\x00\x01t\x06\x23\x00\x02\x7F\x01\x02z",
+                decoded: Self::unknown().expect("Failed to create test vector"),
+            },
+        ]
+    }
+
+    /// Creates a packet with all typed properties
+    fn full() -> Result<PublishV5, &'static str> {
+        PublishV5::new(b"t", b"x", true)?
+            .with_qos(1, 7, false)
+            .with_payload_format_indicator(1)?
+            .with_message_expiry_interval(60)?
+            .with_content_type(b"text")?
+            .with_correlation_data(b"\x01\x02")?
+            .with_response_topic(b"r")?
+            .with_user_property(b"k", b"v")?
+            .with_subscription_identifier(5)?
+            .with_subscription_identifier(9)
+    }
+    /// Creates a packet with an empty topic via a topic alias
+    fn aliased() -> Result<PublishV5, &'static str> {
+        Ok(PublishV5::new_aliased(3, b"p", false)?.with_qos(2, 1, true))
+    }
+    /// Creates a packet with an unknown property by decoding it
+    fn unknown() -> Result<PublishV5, &'static str> {
+        let encoded = b"\x30\x0B\x00\x01t\x06\x23\x00\x02\x7F\x01\x02z";
+        PublishV5::try_from_iter(encoded.iter().copied()).map_err(|e| e.description())
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The offset of the offending byte
+    offset: usize,
+    /// The expected/found or rule detail, if any
+    detail: Option<Detail>,
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Empty packet
+            Self { encoded: b"", offset: 0, detail: None },
+            // Packet with invalid packet type
+            Self {
+                encoded: b"\x20\x05\x00\x01t\x00z",
+                offset: 0,
+                detail: Some(Detail::Mismatch { expected: 3, found: 2 }),
+            },
+            // Packet without properties block
+            Self { encoded: b"\x30\x03\x00\x01t", offset: 5, detail: None },
+            // QoS 1 packet with a truncated packet ID
+            Self { encoded: b"\x32\x04\x00\x01t\x00", offset: 5, detail: None },
+            // Packet with a properties length that exceeds the packet length
+            Self { encoded: b"\x30\x07\x00\x01t\xFF\xFF\xFF\x7F", offset: 9, detail: None },
+            // Packet with a duplicate message expiry interval
+            Self {
+                encoded: b"\x30\x0E\x00\x01t\x0A\x02\x00\x00\x00\x01\x02\x00\x00\x00\x02",
+                offset: 11,
+                detail: None,
+            },
+            // Packet with an empty topic and no topic alias
+            Self { encoded: b"\x30\x04\x00\x00\x00z", offset: 2, detail: None },
+            // Packet with a zero topic alias
+            Self { encoded: b"\x30\x07\x00\x01t\x03\x23\x00\x00", offset: 5, detail: None },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = PublishV5::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet")
+    }
+}
+
+/// Tests the property accessors of a decoded packet
+#[test]
+pub fn decode_properties() {
+    // Decode the packets
+    let [minimal, full, aliased, unknown] = Good::all();
+    let minimal = PublishV5::try_from_iter(minimal.encoded.iter().copied()).expect("Failed to decode valid packet");
+    let full = PublishV5::try_from_iter(full.encoded.iter().copied()).expect("Failed to decode valid packet");
+    let aliased = PublishV5::try_from_iter(aliased.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the packet without properties
+    assert_eq!(minimal.topic(), b"a/b", "Invalid topic");
+    assert_eq!(minimal.payload(), b"hi", "Invalid payload");
+    assert!(minimal.properties().is_empty(), "Invalid properties");
+    assert_eq!(minimal.unknown_properties(), None, "Invalid unknown properties");
+
+    // Validate the typed properties
+    assert_eq!((full.qos(), full.packet_id(), full.retain()), (1, Some(7), true), "Invalid flags");
+    assert_eq!(full.payload_format_indicator(), Some(1), "Invalid payload format indicator");
+    assert_eq!(full.message_expiry_interval(), Some(60), "Invalid message expiry interval");
+    assert_eq!(full.content_type(), Some(&b"text"[..]), "Invalid content type");
+    assert_eq!(full.correlation_data(), Some(&b"\x01\x02"[..]), "Invalid correlation data");
+    assert_eq!(full.response_topic(), Some(&b"r"[..]), "Invalid response topic");
+    assert!(full.user_properties().eq([(&b"k"[..], &b"v"[..])]), "Invalid user properties");
+    assert!(full.subscription_identifiers().eq([5, 9]), "Invalid subscription identifiers");
+    assert_eq!(full.topic_alias(), None, "Invalid topic alias");
+
+    // Validate the aliased packet
+    assert_eq!((aliased.topic(), aliased.topic_alias()), (&b""[..], Some(3)), "Invalid topic alias");
+    assert_eq!((aliased.qos(), aliased.dup()), (2, true), "Invalid flags");
+
+    // Validate the unknown property
+    assert_eq!(unknown.decoded.topic_alias(), Some(2), "Invalid topic alias");
+    assert_eq!(unknown.decoded.unknown_properties(), Some(&b"\x7F\x01\x02"[..]), "Invalid unknown properties");
+    let expected = [(id::TOPIC_ALIAS, Value::U16(2)), (0x7F, Value::Raw(b"\x7F\x01\x02"))];
+    assert!(unknown.decoded.properties().iter().eq(expected), "Invalid properties");
+}
+
+/// Tests the builder validation
+#[test]
+pub fn build_invalid() {
+    // Empty topic without topic alias
+    let result = PublishV5::new(b"", b"", false);
+    assert_eq!(result.err(), Some("Empty topic without topic alias"), "Unexpected success for empty topic");
+
+    // Topic with wildcard
+    let result = PublishV5::new(b"t/+", b"", false);
+    assert_eq!(result.err(), Some("Wildcard within topic name"), "Unexpected success for wildcard topic");
+
+    // Zero topic alias and subscription identifier
+    let result = PublishV5::new_aliased(0, b"", false);
+    assert_eq!(result.err(), Some("Invalid topic alias"), "Unexpected success for zero topic alias");
+    let result = PublishV5::new(b"t", b"", false).and_then(|packet| packet.with_subscription_identifier(0));
+    assert_eq!(result.err(), Some("Invalid subscription identifier"), "Unexpected success for zero identifier");
+
+    // Duplicate non-repeatable property
+    let result = PublishV5::new(b"t", b"", false)
+        .and_then(|packet| packet.with_message_expiry_interval(1))
+        .and_then(|packet| packet.with_message_expiry_interval(2));
+    assert!(result.is_err(), "Unexpected success for duplicate property");
+}
+
+/// Tests that properties are rejected if the packet cannot be encoded anymore
+#[test]
+#[cfg(feature = "alloc")]
+pub fn build_too_large() {
+    // Create a packet with the maximum body length
+    let payload = vec![0x2A; mqtt_tiny::error::EncodeError::MAX_PACKET_LEN - 6];
+    let packet = PublishV5::new(b"t", &payload, false).expect("Failed to create packet");
+
+    // Append properties
+    let result = packet.clone().with_message_expiry_interval(1);
+    assert_eq!(result.err(), Some("Packet length is too large"), "Unexpected success for too large packet");
+    let result = packet.with_user_property(b"k", b"v");
+    assert_eq!(result.err(), Some("Packet length is too large"), "Unexpected success for too large packet");
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let Err(error) = PublishV5::try_from_iter(encoded) else {
+            panic!("Unexpected success when decoding invalid packet {:02x?}", test_vector.encoded);
+        };
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset for {:02x?}", test_vector.encoded);
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail for {:02x?}", test_vector.encoded);
+    }
+}
@@ -0,0 +1,62 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::{
+    packets::v5::{
+        properties::{id, Value},
+        session::{NegotiatedSession, MAX_PACKET_SIZE},
+    },
+    ConnackV5, ConnectV5,
+};
+
+/// Tests the negotiated session if no properties are present
+#[test]
+pub fn negotiate_default() {
+    let connect = ConnectV5::new(60, true, b"abc").expect("Failed to create packet");
+    let connack = ConnackV5::new(false, 0x00);
+
+    // Validate the session
+    let expected = NegotiatedSession {
+        session_present: false,
+        session_expiry_interval: 0,
+        keep_alive_secs: 60,
+        client_receive_maximum: 65535,
+        server_receive_maximum: 65535,
+        client_maximum_packet_size: MAX_PACKET_SIZE,
+        server_maximum_packet_size: MAX_PACKET_SIZE,
+    };
+    assert_eq!(NegotiatedSession::new(&connect, &connack), expected, "Invalid negotiated session");
+}
+
+/// Tests the negotiated session if all properties are present
+#[test]
+pub fn negotiate_explicit() {
+    let connect = ConnectV5::new(60, false, b"abc")
+        .and_then(|connect| connect.with_property(id::SESSION_EXPIRY_INTERVAL, Value::U32(120)))
+        .and_then(|connect| connect.with_property(id::RECEIVE_MAXIMUM, Value::U16(20)))
+        .and_then(|connect| connect.with_property(id::MAXIMUM_PACKET_SIZE, Value::U32(4096)))
+        .expect("Failed to create packet");
+    let connack = ConnackV5::new(true, 0x00)
+        .with_property(id::SESSION_EXPIRY_INTERVAL, Value::U32(30))
+        .and_then(|connack| connack.with_property(id::SERVER_KEEP_ALIVE, Value::U16(15)))
+        .and_then(|connack| connack.with_property(id::RECEIVE_MAXIMUM, Value::U16(10)))
+        .and_then(|connack| connack.with_property(id::MAXIMUM_PACKET_SIZE, Value::U32(65_536)))
+        .expect("Failed to create packet");
+
+    // Validate the session
+    let expected = NegotiatedSession {
+        session_present: true,
+        session_expiry_interval: 30,
+        keep_alive_secs: 15,
+        client_receive_maximum: 20,
+        server_receive_maximum: 10,
+        client_maximum_packet_size: 4096,
+        server_maximum_packet_size: 65_536,
+    };
+    assert_eq!(NegotiatedSession::new(&connect, &connack), expected, "Invalid negotiated session");
+
+    // The server does not override the session expiry interval and keep alive time
+    let connack = ConnackV5::new(true, 0x00);
+    let session = NegotiatedSession::new(&connect, &connack);
+    assert_eq!(session.session_expiry_interval, 120, "Invalid session expiry interval");
+    assert_eq!(session.keep_alive_secs, 60, "Invalid keep alive time");
+}
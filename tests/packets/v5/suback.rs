@@ -0,0 +1,159 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    error::Detail,
+    packets::{v5::reason::ReasonCode, TryFromIterator},
+    SubackV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: SubackV5,
+    /// Whether all filters succeeded
+    all_succeeded: bool,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 3] {
+        [
+            // A suback granting all QoS levels
+            Self {
+                encoded: b"\x90\x06\x00\x07\x00\x00\x01\x02",
+                decoded: SubackV5::new(7, [ReasonCode::Success, ReasonCode::GrantedQos1, ReasonCode::GrantedQos2])
+                    .expect("Failed to create test vector"),
+                all_succeeded: true,
+            },
+            // A suback with mixed success and failure codes and a reason string
+            Self {
+                encoded: b"\x90\x0B\x00\x07\x05\x1F\x00\x02no\x01\x87\x8F",
+                decoded: Self::mixed().expect("Failed to create test vector"),
+                all_succeeded: false,
+            },
+            // A suback with a single unassigned failure code
+            Self {
+                encoded: b"\x90\x04\x00\x07\x00\xF0",
+                decoded: SubackV5::new(7, [ReasonCode::Other(0xF0)]).expect("Failed to create test vector"),
+                all_succeeded: false,
+            },
+        ]
+    }
+
+    /// Creates a packet with mixed success and failure codes
+    fn mixed() -> Result<SubackV5, &'static str> {
+        let reason_codes = [ReasonCode::GrantedQos1, ReasonCode::NotAuthorized, ReasonCode::TopicFilterInvalid];
+        SubackV5::new(7, reason_codes)?.with_reason_string(b"no")
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The offset of the offending byte
+    offset: usize,
+    /// The expected/found or rule detail, if any
+    detail: Option<Detail>,
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Empty packet
+            Self { encoded: b"", offset: 0, detail: None },
+            // Packet with invalid packet type
+            Self {
+                encoded: b"\xB0\x04\x00\x07\x00\x00",
+                offset: 0,
+                detail: Some(Detail::Mismatch { expected: 9, found: 11 }),
+            },
+            // Packet without properties block
+            Self { encoded: b"\x90\x02\x00\x07", offset: 4, detail: None },
+            // Packet without reason codes
+            Self { encoded: b"\x90\x03\x00\x07\x00", offset: 5, detail: None },
+            // Packet with a duplicate reason string
+            Self { encoded: b"\x90\x0A\x00\x07\x06\x1F\x00\x00\x1F\x00\x00\x00", offset: 8, detail: None },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = SubackV5::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet");
+        assert_eq!(decoded.all_succeeded(), test_vector.all_succeeded, "Invalid success state");
+    }
+}
+
+/// Tests the reason code and property accessors of a decoded packet
+#[test]
+pub fn decode_reason_codes() {
+    // Decode the packet with mixed reason codes
+    let [_, mixed, _] = Good::all();
+    let decoded = SubackV5::try_from_iter(mixed.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the fields
+    let expected = [ReasonCode::GrantedQos1, ReasonCode::NotAuthorized, ReasonCode::TopicFilterInvalid];
+    assert_eq!(decoded.packet_id(), 7, "Invalid packet ID");
+    assert!(decoded.reason_codes().eq(expected), "Invalid reason codes");
+    assert_eq!(decoded.reason_codes_raw(), b"\x01\x87\x8F", "Invalid raw reason codes");
+    assert_eq!(decoded.reason_string(), Some(&b"no"[..]), "Invalid reason string");
+    assert_eq!(decoded.user_properties().count(), 0, "Invalid user properties");
+}
+
+/// Tests that a packet without reason codes cannot be created
+#[test]
+pub fn new_empty() {
+    let result = SubackV5::new(7, []);
+    assert_eq!(result.err(), Some("Missing reason codes"), "Unexpected success for empty reason codes");
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let Err(error) = SubackV5::try_from_iter(encoded) else {
+            panic!("Unexpected success when decoding invalid packet {:02x?}", test_vector.encoded);
+        };
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset for {:02x?}", test_vector.encoded);
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail for {:02x?}", test_vector.encoded);
+    }
+}
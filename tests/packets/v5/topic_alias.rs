@@ -0,0 +1,123 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use mqtt_tiny::{
+    error::ProtocolError,
+    packets::v5::{
+        properties::{id, Value},
+        topic_alias::AliasDecision,
+    },
+    PublishV5, TopicAliasMap,
+};
+
+/// Tests alias assignment and reuse for sent packets
+#[test]
+pub fn outbound() {
+    // Assign and reuse aliases
+    let mut map = TopicAliasMap::<4>::new(2);
+    assert_eq!(map.maximum(), 2, "Invalid topic alias maximum");
+    assert_eq!(map.outbound(b"a"), AliasDecision::AssignNew(1), "Invalid alias decision");
+    assert_eq!(map.outbound(b"b"), AliasDecision::AssignNew(2), "Invalid alias decision");
+    assert_eq!(map.outbound(b"a"), AliasDecision::UseAlias(1), "Invalid alias decision");
+    assert_eq!(map.outbound(b"b"), AliasDecision::UseAlias(2), "Invalid alias decision");
+
+    // Reassign aliases in order once all aliases are in use
+    assert_eq!(map.outbound(b"c"), AliasDecision::AssignNew(1), "Invalid alias decision");
+    assert_eq!(map.outbound(b"d"), AliasDecision::AssignNew(2), "Invalid alias decision");
+    assert_eq!(map.outbound(b"e"), AliasDecision::AssignNew(1), "Invalid alias decision");
+    assert_eq!(map.outbound(b"d"), AliasDecision::UseAlias(2), "Invalid alias decision");
+    assert_eq!(map.get(1), Some(b"e".as_slice()), "Invalid aliased topic");
+    assert_eq!(map.get(3), None, "Unexpected aliased topic");
+
+    // Forget all aliases
+    map.clear();
+    assert_eq!(map.get(1), None, "Unexpected aliased topic");
+    assert_eq!(map.outbound(b"d"), AliasDecision::AssignNew(1), "Invalid alias decision");
+}
+
+/// Tests that no aliases are assigned if the peer does not accept topic aliases
+#[test]
+pub fn outbound_disabled() {
+    // Topic aliases are disabled by the peer
+    let mut map = TopicAliasMap::<4>::new(0);
+    assert_eq!(map.outbound(b"a"), AliasDecision::SendFullTopic, "Invalid alias decision");
+    assert_eq!(map.outbound(b"a"), AliasDecision::SendFullTopic, "Invalid alias decision");
+
+    // The advertised maximum is capped at the capacity
+    let mut map = TopicAliasMap::<1>::new(u16::MAX);
+    assert_eq!(map.maximum(), 1, "Invalid topic alias maximum");
+    assert_eq!(map.outbound(b"a"), AliasDecision::AssignNew(1), "Invalid alias decision");
+    assert_eq!(map.outbound(b"b"), AliasDecision::AssignNew(1), "Invalid alias decision");
+}
+
+/// Tests alias establishment and resolution for received packets
+#[test]
+pub fn inbound() {
+    let mut map = TopicAliasMap::<4>::new(4);
+    assert_eq!(map.inbound(2, Some(b"a")), Ok(b"a".as_slice()), "Invalid resolved topic");
+    assert_eq!(map.inbound(2, None), Ok(b"a".as_slice()), "Invalid resolved topic");
+    assert_eq!(map.inbound(2, Some(b"")), Ok(b"a".as_slice()), "Invalid resolved topic");
+
+    // Replace the alias
+    assert_eq!(map.inbound(2, Some(b"b")), Ok(b"b".as_slice()), "Invalid resolved topic");
+    assert_eq!(map.inbound(2, None), Ok(b"b".as_slice()), "Invalid resolved topic");
+}
+
+/// Tests protocol errors for received packets
+#[test]
+pub fn inbound_invalid() {
+    let mut map = TopicAliasMap::<4>::new(2);
+    assert_eq!(map.inbound(0, Some(b"a")), Err(ProtocolError::ZeroTopicAlias), "Invalid protocol error");
+    assert_eq!(
+        map.inbound(3, Some(b"a")),
+        Err(ProtocolError::TopicAliasExceedsMaximum { alias: 3, maximum: 2 }),
+        "Invalid protocol error"
+    );
+    assert_eq!(map.inbound(1, None), Err(ProtocolError::UnknownTopicAlias { alias: 1 }), "Invalid protocol error");
+
+    // Aliases are unknown again after a reconnect
+    map.inbound(1, Some(b"a")).expect("Failed to establish topic alias");
+    map.clear();
+    assert_eq!(map.inbound(1, None), Err(ProtocolError::UnknownTopicAlias { alias: 1 }), "Invalid protocol error");
+}
+
+/// Tests applying and stripping topic aliases on `PUBLISH` packets
+#[test]
+pub fn publish() {
+    let (mut sender, mut receiver) = (TopicAliasMap::<4>::new(4), TopicAliasMap::<4>::new(4));
+    let publish = PublishV5::new(b"t", b"hi", false).expect("Failed to create packet").with_qos(1, 7, false);
+    let publish = publish.with_content_type(b"text").expect("Failed to create packet");
+
+    // The first packet assigns the alias and carries the full topic
+    let aliased = sender.apply(publish.clone()).expect("Failed to apply topic alias");
+    assert_eq!(aliased.topic(), b"t", "Invalid topic");
+    assert_eq!(aliased.topic_alias(), Some(1), "Invalid topic alias");
+    assert_eq!(aliased.content_type(), Some(b"text".as_slice()), "Invalid content type");
+    let stripped = receiver.strip(aliased).expect("Failed to strip topic alias");
+    assert_eq!(stripped, publish, "Invalid stripped packet");
+
+    // The next packet only carries the alias
+    let aliased = sender.apply(publish.clone()).expect("Failed to apply topic alias");
+    assert_eq!(aliased.topic(), b"", "Invalid topic");
+    assert_eq!(aliased.topic_alias(), Some(1), "Invalid topic alias");
+    assert_eq!(aliased.content_type(), Some(b"text".as_slice()), "Invalid content type");
+    assert_eq!(aliased.packet_id(), Some(7), "Invalid packet ID");
+    let stripped = receiver.strip(aliased).expect("Failed to strip topic alias");
+    assert_eq!(stripped, publish, "Invalid stripped packet");
+
+    // Packets with an explicit topic alias are passed through
+    let explicit = PublishV5::new_aliased(3, b"hi", false).expect("Failed to create packet");
+    assert_eq!(sender.apply(explicit.clone()), Ok(explicit), "Invalid passed-through packet");
+}
+
+/// Tests stripping topic aliases from invalid `PUBLISH` packets
+#[test]
+pub fn publish_invalid() {
+    let mut receiver = TopicAliasMap::<4>::new(2);
+    let publish = PublishV5::new_aliased(1, b"hi", false).expect("Failed to create packet");
+    assert_eq!(receiver.strip(publish), Err(ProtocolError::UnknownTopicAlias { alias: 1 }), "Invalid protocol error");
+
+    // A topic alias property with value zero
+    let publish = PublishV5::new(b"t", b"hi", false).expect("Failed to create packet");
+    let publish = publish.with_property(id::TOPIC_ALIAS, Value::U16(0)).expect("Failed to create packet");
+    assert_eq!(receiver.strip(publish), Err(ProtocolError::ZeroTopicAlias), "Invalid protocol error");
+}
@@ -0,0 +1,143 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    error::Detail,
+    packets::{v5::reason::ReasonCode, TryFromIterator},
+    UnsubackV5,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for encoded/decoded pairs
+#[derive(Debug, Clone)]
+pub struct Good {
+    /// The encoded representation
+    encoded: &'static [u8],
+    /// The decoded representation
+    decoded: UnsubackV5,
+    /// Whether all filters succeeded
+    all_succeeded: bool,
+}
+impl Good {
+    /// Good encoded/decoded pairs
+    pub fn all() -> [Self; 2] {
+        [
+            // An unsuback with a success and a no-subscription-existed code
+            Self {
+                encoded: b"\xB0\x05\x00\x07\x00\x00\x11",
+                decoded: UnsubackV5::new(7, [ReasonCode::Success, ReasonCode::NoSubscriptionExisted])
+                    .expect("Failed to create test vector"),
+                all_succeeded: true,
+            },
+            // An unsuback with mixed success and failure codes and a user property
+            Self {
+                encoded: b"\xB0\x0D\x00\x07\x07\x26\x00\x01k\x00\x01v\x00\x80\x83",
+                decoded: Self::mixed().expect("Failed to create test vector"),
+                all_succeeded: false,
+            },
+        ]
+    }
+
+    /// Creates a packet with mixed success and failure codes
+    fn mixed() -> Result<UnsubackV5, &'static str> {
+        let reason_codes = [ReasonCode::Success, ReasonCode::UnspecifiedError, ReasonCode::ImplementationSpecificError];
+        UnsubackV5::new(7, reason_codes)?.with_user_property(b"k", b"v")
+    }
+}
+
+/// A test vector for known-bad encoded encoded fields
+#[derive(Debug)]
+pub struct BadEncoded {
+    /// The invalid encoded representation
+    encoded: &'static [u8],
+    /// The offset of the offending byte
+    offset: usize,
+    /// The expected/found or rule detail, if any
+    detail: Option<Detail>,
+}
+impl BadEncoded {
+    /// Good encoded/decoded pairs
+    pub const fn all() -> &'static [Self] {
+        &[
+            // Empty packet
+            Self { encoded: b"", offset: 0, detail: None },
+            // Packet with invalid packet type
+            Self {
+                encoded: b"\x90\x04\x00\x07\x00\x00",
+                offset: 0,
+                detail: Some(Detail::Mismatch { expected: 11, found: 9 }),
+            },
+            // Packet without reason codes
+            Self { encoded: b"\xB0\x03\x00\x07\x00", offset: 5, detail: None },
+            // Packet with truncated properties
+            Self { encoded: b"\xB0\x04\x00\x07\x03\x11", offset: 6, detail: None },
+        ]
+    }
+}
+
+/// Tests successful decoding
+#[test]
+pub fn decode() {
+    for test_vector in Good::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let decoded = UnsubackV5::try_from_iter(encoded).expect("Failed to decode valid packet");
+        assert_eq!(decoded, test_vector.decoded, "Invalid decoded packet");
+        assert_eq!(decoded.all_succeeded(), test_vector.all_succeeded, "Invalid success state");
+    }
+}
+
+/// Tests the reason code and property accessors of a decoded packet
+#[test]
+pub fn decode_reason_codes() {
+    // Decode the packet with mixed reason codes
+    let [_, mixed] = Good::all();
+    let decoded = UnsubackV5::try_from_iter(mixed.encoded.iter().copied()).expect("Failed to decode valid packet");
+
+    // Validate the fields
+    let expected = [ReasonCode::Success, ReasonCode::UnspecifiedError, ReasonCode::ImplementationSpecificError];
+    assert!(decoded.reason_codes().eq(expected), "Invalid reason codes");
+    assert_eq!(decoded.reason_codes().len(), 3, "Invalid reason code count");
+    assert!(decoded.user_properties().eq([(&b"k"[..], &b"v"[..])]), "Invalid user properties");
+    assert_eq!(decoded.reason_string(), None, "Invalid reason string");
+}
+
+/// Tests successful encoding
+#[test]
+pub fn encode() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let decoded = test_vector.decoded.clone();
+        let encoded: Vec = decoded.into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests successful encoding of a borrowed packet
+#[test]
+pub fn encode_ref() {
+    for test_vector in Good::all() {
+        // Encode and validate
+        let encoded: Vec = (&test_vector.decoded).into_iter().collect();
+        assert_eq!(encoded.deref(), test_vector.encoded, "Invalid encoded packet");
+    }
+}
+
+/// Tests failing decoding
+#[test]
+pub fn decode_invalid() {
+    for test_vector in BadEncoded::all() {
+        // Decode and validate
+        let encoded = test_vector.encoded.iter().copied();
+        let Err(error) = UnsubackV5::try_from_iter(encoded) else {
+            panic!("Unexpected success when decoding invalid packet {:02x?}", test_vector.encoded);
+        };
+        assert_eq!(error.offset(), test_vector.offset, "Invalid error offset for {:02x?}", test_vector.encoded);
+        assert_eq!(error.detail(), test_vector.detail, "Invalid error detail for {:02x?}", test_vector.encoded);
+    }
+}
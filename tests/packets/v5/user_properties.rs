@@ -0,0 +1,94 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::{
+    packets::{v5::properties::Properties, TryFromIterator},
+    PubackV5, UserProperties,
+};
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// Creates a list with multiple entries and a duplicate key
+fn multi() -> Result<UserProperties, &'static str> {
+    UserProperties::new().with(b"a", b"1")?.with(b"b", b"2")?.with(b"a", b"3")
+}
+
+/// Tests an empty list
+#[test]
+pub fn empty() {
+    let user_properties = UserProperties::new();
+    assert!(user_properties.is_empty(), "Invalid empty state");
+    assert_eq!(user_properties.len(), 0, "Invalid length");
+    assert_eq!(user_properties.iter().next(), None, "Invalid pairs");
+
+    // Append the empty list to a properties block
+    let mut properties = Properties::<Vec>::new();
+    user_properties.append_to(&mut properties).expect("Failed to append user properties");
+    assert!(properties.is_empty(), "Invalid properties");
+}
+
+/// Tests a list with multiple entries and duplicate keys
+#[test]
+pub fn multi_entry() {
+    let user_properties = multi().expect("Failed to create user properties");
+
+    // Validate order and duplicates
+    let expected = [(&b"a"[..], &b"1"[..]), (b"b", b"2"), (b"a", b"3")];
+    assert_eq!(user_properties.len(), 3, "Invalid length");
+    assert!(user_properties.iter().eq(expected), "Invalid pairs");
+    assert!(user_properties.get(b"a").eq([&b"1"[..], b"3"]), "Invalid values for duplicate key");
+    assert_eq!(user_properties.get(b"c").next(), None, "Invalid values for missing key");
+}
+
+/// Tests that invalid pairs are rejected and leave the list unchanged
+#[test]
+pub fn push_invalid() {
+    let mut user_properties = multi().expect("Failed to create user properties");
+
+    // Push invalid UTF-8 and a null character
+    assert!(user_properties.push(b"\xFF", b"1").is_err(), "Unexpected success for invalid UTF-8 key");
+    assert!(user_properties.push(b"c", b"\x00").is_err(), "Unexpected success for null character in value");
+    assert_eq!(user_properties, multi().expect("Failed to create user properties"), "Invalid pairs after error");
+}
+
+/// Tests encoding through a packet and decoding the user properties again
+#[test]
+pub fn encode_decode() {
+    let user_properties = multi().expect("Failed to create user properties");
+
+    // Encode the user properties within a packet
+    let packet = PubackV5::new(7).with_user_properties(&user_properties).expect("Failed to create packet");
+    let encoded: Vec = packet.into_iter().collect();
+    let expected = b"\x40\x19\x00\x07\x00\x15\x26\x00\x01a\x00\x011\x26\x00\x01b\x00\x012\x26\x00\x01a\x00\x013";
+    assert_eq!(encoded.deref(), expected, "Invalid encoded packet");
+
+    // Decode the packet and read the user properties
+    let decoded = PubackV5::try_from_iter(encoded.iter().copied()).expect("Failed to decode valid packet");
+    let decoded = UserProperties::from_properties(decoded.properties()).expect("Failed to read user properties");
+    assert_eq!(decoded, user_properties, "Invalid decoded user properties");
+}
+
+/// Tests capacity exhaustion on fixed-capacity backends
+#[test]
+#[cfg(feature = "arrayvec")]
+pub fn capacity() {
+    type Bytes = arrayvec::ArrayVec<u8, 4>;
+    type Pairs = arrayvec::ArrayVec<(Bytes, Bytes), 2>;
+    type UserProperties = mqtt_tiny::packets::v5::user_properties::UserProperties<Pairs, Bytes>;
+
+    // Exhaust the pair and byte capacity
+    let mut user_properties = UserProperties::new().with(b"a", b"1").and_then(|this| this.with(b"b", b"2"));
+    let user_properties = user_properties.as_mut().expect("Failed to create user properties");
+    assert!(user_properties.push(b"c", b"3").is_err(), "Unexpected success for exhausted pair capacity");
+    assert!(user_properties.push(b"long key", b"3").is_err(), "Unexpected success for exhausted byte capacity");
+    assert!(user_properties.iter().eq([(&b"a"[..], &b"1"[..]), (b"b", b"2")]), "Invalid pairs after error");
+
+    // Exhaust the properties block capacity and ensure that it is left unchanged
+    let mut properties = Properties::<arrayvec::ArrayVec<u8, 8>>::new();
+    assert!(user_properties.append_to(&mut properties).is_err(), "Unexpected success for exhausted capacity");
+    assert!(properties.is_empty(), "Invalid properties after error");
+}
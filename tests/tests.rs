@@ -1,3 +1,6 @@
 //! All test cases
+pub mod client;
 pub mod coding;
 pub mod packets;
+pub mod topic;
+pub mod transport;
@@ -0,0 +1,98 @@
+#![cfg(any(feature = "alloc", feature = "arrayvec"))]
+
+use core::ops::Deref;
+use mqtt_tiny::topic::SharedFilter;
+
+// Select an appropriate vector type
+#[cfg(feature = "alloc")]
+type Vec = std::vec::Vec<u8>;
+#[cfg(all(not(feature = "alloc"), feature = "arrayvec"))]
+type Vec = arrayvec::ArrayVec<u8, 64>;
+
+/// A test vector for shared subscription filters
+#[derive(Debug)]
+pub struct Good {
+    /// The combined filter
+    combined: &'static [u8],
+    /// The share name
+    group: &'static [u8],
+    /// The inner topic filter
+    filter: &'static [u8],
+}
+impl Good {
+    /// Valid shared subscription filters
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self { combined: b"$share/group/topic", group: b"group", filter: b"topic" },
+            Self { combined: b"$share/g/a/b/c", group: b"g", filter: b"a/b/c" },
+            Self { combined: b"$share/g/+/b/#", group: b"g", filter: b"+/b/#" },
+            Self { combined: b"$share/g/#", group: b"g", filter: b"#" },
+            Self { combined: b"$share/g//", group: b"g", filter: b"/" },
+            Self { combined: b"$share/$g/$SYS/x", group: b"$g", filter: b"$SYS/x" },
+        ]
+    }
+}
+
+/// Known-bad shared subscription filters
+const BAD: &[&[u8]] = &[
+    // Not a shared subscription filter
+    b"group/topic",
+    b"$SHARE/group/topic",
+    b"$share",
+    b"share/group/topic",
+    // A missing share name
+    b"$share/",
+    b"$share//topic",
+    // A missing inner filter
+    b"$share/group",
+    b"$share/group/",
+    // Wildcards within the share name
+    b"$share/gr+oup/topic",
+    b"$share/+/topic",
+    b"$share/gr#/topic",
+    b"$share/#",
+];
+
+/// Tests successful parsing
+#[test]
+pub fn parse() {
+    for test_vector in Good::all() {
+        // Parse and validate
+        let shared = SharedFilter::parse(test_vector.combined).expect("Failed to parse shared filter");
+        assert_eq!(shared.group(), test_vector.group, "Invalid share name");
+        assert_eq!(shared.filter(), test_vector.filter, "Invalid inner filter");
+    }
+}
+
+/// Tests successful construction
+#[test]
+pub fn build() {
+    for test_vector in Good::all() {
+        // Construct and validate
+        let shared = SharedFilter::new(test_vector.group, test_vector.filter).expect("Failed to create shared filter");
+        let combined: Vec = shared.to_vec().expect("Failed to build shared filter");
+        assert_eq!(combined.deref(), test_vector.combined, "Invalid combined filter");
+    }
+}
+
+/// Tests failing parsing
+#[test]
+pub fn parse_invalid() {
+    for filter in BAD {
+        // Parse and validate
+        let shared = SharedFilter::parse(filter);
+        assert!(shared.is_none(), "Unexpected success when parsing invalid shared filter");
+    }
+}
+
+/// Tests failing construction
+#[test]
+pub fn build_invalid() {
+    let invalid: [(&[u8], &[u8]); 6] =
+        [(b"", b"topic"), (b"gr/oup", b"topic"), (b"gr+", b"topic"), (b"#", b"topic"), (b"group", b""), (b"/", b"/")];
+    for (group, filter) in invalid {
+        // Construct and validate
+        let shared = SharedFilter::new(group, filter);
+        assert!(shared.is_err(), "Unexpected success when creating invalid shared filter");
+    }
+}
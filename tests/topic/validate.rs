@@ -0,0 +1,147 @@
+use mqtt_tiny::{
+    error::TopicError,
+    topic::{self, SharedFilter},
+};
+
+/// Valid topic names
+const GOOD_NAMES: &[&[u8]] = &[
+    b"a",
+    b"/",
+    b"//",
+    b"sport/tennis/player1",
+    b"sport/tennis/player1/ranking",
+    b"/finance",
+    b"finance/",
+    b"$SYS/monitor/Clients",
+    b"with space",
+    "Ölope/⚡".as_bytes(),
+];
+
+/// Valid topic filters
+const GOOD_FILTERS: &[&[u8]] = &[
+    b"a",
+    b"/",
+    b"#",
+    b"+",
+    b"/#",
+    b"+/",
+    b"/+",
+    b"+/+",
+    b"+/#",
+    b"sport/#",
+    b"sport/tennis/#",
+    b"sport/+/player1",
+    b"sport/tennis/+",
+    b"+/tennis/#",
+    b"$SYS/#",
+    b"//+//#",
+];
+
+/// Known-bad topic names and their errors
+const BAD_NAMES: &[(&[u8], TopicError)] = &[
+    (b"", TopicError::Empty),
+    (b"+", TopicError::WildcardInName { offset: 0 }),
+    (b"#", TopicError::WildcardInName { offset: 0 }),
+    (b"sport/+/player1", TopicError::WildcardInName { offset: 6 }),
+    (b"sport/tennis/#", TopicError::WildcardInName { offset: 13 }),
+    (b"sport#", TopicError::WildcardInName { offset: 5 }),
+    (b"a\x00b", TopicError::NullCharacter { offset: 1 }),
+    (b"ab\xFF", TopicError::InvalidUtf8 { offset: 2 }),
+    (b"a/\xC3", TopicError::InvalidUtf8 { offset: 2 }),
+    (b"\xED\xA0\x80", TopicError::InvalidUtf8 { offset: 0 }),
+];
+
+/// Known-bad topic filters and their errors
+const BAD_FILTERS: &[(&[u8], TopicError)] = &[
+    (b"", TopicError::Empty),
+    (b"sport+", TopicError::InvalidSingleLevelWildcard { offset: 5 }),
+    (b"sport/+tennis", TopicError::InvalidSingleLevelWildcard { offset: 6 }),
+    (b"sport/ten+nis/#", TopicError::InvalidSingleLevelWildcard { offset: 9 }),
+    (b"++", TopicError::InvalidSingleLevelWildcard { offset: 0 }),
+    (b"sport/tennis#", TopicError::InvalidMultiLevelWildcard { offset: 12 }),
+    (b"sport/tennis/#/ranking", TopicError::InvalidMultiLevelWildcard { offset: 13 }),
+    (b"#/", TopicError::InvalidMultiLevelWildcard { offset: 0 }),
+    (b"##", TopicError::InvalidMultiLevelWildcard { offset: 0 }),
+    (b"+#", TopicError::InvalidSingleLevelWildcard { offset: 0 }),
+    (b"a/\x00/#", TopicError::NullCharacter { offset: 2 }),
+    (b"a/\xFF/#", TopicError::InvalidUtf8 { offset: 2 }),
+];
+
+/// Tests valid topic names
+#[test]
+pub fn name() {
+    for name in GOOD_NAMES {
+        // Validate the name, which is also a valid filter
+        assert_eq!(topic::validate_name(name), Ok(()), "Unexpected invalid topic name");
+        assert_eq!(topic::validate_filter(name), Ok(()), "Unexpected invalid topic filter");
+    }
+}
+
+/// Tests valid topic filters
+#[test]
+pub fn filter() {
+    for filter in GOOD_FILTERS {
+        // Validate the filter
+        assert_eq!(topic::validate_filter(filter), Ok(()), "Unexpected invalid topic filter");
+    }
+}
+
+/// Tests invalid topic names
+#[test]
+pub fn name_invalid() {
+    for (name, error) in BAD_NAMES {
+        // Validate the name
+        assert_eq!(topic::validate_name(name), Err(*error), "Invalid topic error");
+    }
+}
+
+/// Tests invalid topic filters
+#[test]
+pub fn filter_invalid() {
+    for (filter, error) in BAD_FILTERS {
+        // Validate the filter
+        assert_eq!(topic::validate_filter(filter), Err(*error), "Invalid topic error");
+    }
+}
+
+/// Tests the length limit of topic names and topic filters
+#[test]
+pub fn length() {
+    // Validate topics at and beyond the length limit
+    let topic = [b'a'; TopicError::MAX_LEN + 1];
+    let (valid, invalid) = (&topic[..TopicError::MAX_LEN], &topic[..]);
+    assert_eq!(topic::validate_name(valid), Ok(()), "Unexpected invalid topic name");
+    assert_eq!(topic::validate_filter(valid), Ok(()), "Unexpected invalid topic filter");
+    assert_eq!(topic::validate_name(invalid), Err(TopicError::TooLong), "Invalid topic error");
+    assert_eq!(topic::validate_filter(invalid), Err(TopicError::TooLong), "Invalid topic error");
+}
+
+/// Tests the violated rules and the error messages
+#[test]
+pub fn error() {
+    // Validate the rule and offset
+    let error = topic::validate_filter(b"sport/tennis/#/ranking").expect_err("Unexpected valid topic filter");
+    assert_eq!(error.rule(), "MQTT-4.7.1-2", "Invalid violated rule");
+    assert_eq!(error.offset(), 13, "Invalid offset");
+    assert_eq!(
+        error.to_string(),
+        "Invalid multi-level wildcard at offset 13 (violates MQTT-4.7.1-2)",
+        "Invalid error message"
+    );
+
+    // Validate the remaining rules
+    assert_eq!(TopicError::Empty.rule(), "MQTT-4.7.3-1", "Invalid violated rule");
+    assert_eq!(TopicError::NullCharacter { offset: 0 }.rule(), "MQTT-4.7.3-2", "Invalid violated rule");
+    assert_eq!(TopicError::WildcardInName { offset: 0 }.rule(), "MQTT-4.7.1-1", "Invalid violated rule");
+    assert_eq!(TopicError::InvalidSingleLevelWildcard { offset: 0 }.rule(), "MQTT-4.7.1-3", "Invalid violated rule");
+}
+
+/// Tests that shared subscription filters validate the inner filter
+#[test]
+pub fn shared() {
+    for (filter, _) in BAD_FILTERS {
+        // Construct and validate
+        let shared = SharedFilter::new(b"group", filter);
+        assert!(shared.is_err(), "Unexpected success when creating invalid shared filter");
+    }
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use crate::packets::packet::Good;
+use mqtt_tiny::{
+    packets::stream::PartialReader,
+    test_util::LoopbackPipe,
+    transport::{PacketSink, PacketSource},
+    Packet,
+};
+use std::io::{ErrorKind, Read, Write};
+
+/// Tests sending and receiving packets over a loopback pipe
+#[test]
+pub fn loopback() {
+    // Send all packets from one endpoint and receive them from the other
+    let (mut a, b) = LoopbackPipe::pair();
+    let mut b = b.with_chunking(1);
+    for test_vector in Good::all() {
+        PacketSink::send(&mut a, &test_vector.packet).expect("Failed to send packet");
+    }
+    drop(a);
+    for test_vector in Good::all() {
+        let packet: Option<Packet> = b.recv().expect("Failed to receive packet");
+        assert_eq!(packet.as_ref(), Some(&test_vector.packet), "Invalid received packet");
+    }
+
+    // Validate the clean end-of-file
+    let packet: Option<Packet> = b.recv().expect("Failed to receive end-of-file");
+    assert_eq!(packet, None, "Unexpected packet after end-of-file");
+}
+
+/// Tests partial reads and would-block handling over a loopback pipe
+#[test]
+pub fn partial() {
+    let (mut a, b) = LoopbackPipe::pair();
+    let mut b = b.with_chunking(2);
+    for test_vector in Good::all() {
+        // Write the packet byte-wise and poll after each byte
+        let encoded: Vec<u8> = (&test_vector.packet).into_iter().collect();
+        let mut reader: PartialReader<Packet, Vec<u8>> = PartialReader::new();
+        let (last, head) = encoded.split_last().expect("Empty encoded packet");
+        for byte in head {
+            a.write_all(&[*byte]).expect("Failed to write byte");
+            let packet = reader.poll_read(&mut b).expect("Failed to poll packet");
+            assert_eq!(packet, None, "Unexpected packet before completion");
+        }
+
+        // Complete the packet
+        a.write_all(&[*last]).expect("Failed to write byte");
+        let packet = reader.poll_read(&mut b).expect("Failed to poll packet");
+        assert_eq!(packet.as_ref(), Some(&test_vector.packet), "Invalid received packet");
+        assert_eq!(b.pending(), 0, "Unexpected pending bytes");
+    }
+}
+
+/// Tests injected errors and the end-of-file signal of a loopback pipe
+#[test]
+pub fn errors() {
+    let (mut a, mut b) = LoopbackPipe::pair();
+    let mut buf = [0; 4];
+
+    // Empty pipes would block until the peer is dropped
+    let e = b.read(&mut buf).expect_err("Unexpected success when reading from empty pipe");
+    assert_eq!(e.kind(), ErrorKind::WouldBlock, "Invalid error kind");
+
+    // Injected errors are returned once and in order
+    a.inject_write_error(ErrorKind::BrokenPipe);
+    b.inject_read_error(ErrorKind::Interrupted);
+    b.inject_read_error(ErrorKind::ConnectionReset);
+    let e = a.write(b"test").expect_err("Unexpected success when writing with injected error");
+    assert_eq!(e.kind(), ErrorKind::BrokenPipe, "Invalid error kind");
+    a.write_all(b"test").expect("Failed to write bytes");
+    let e = b.read(&mut buf).expect_err("Unexpected success when reading with injected error");
+    assert_eq!(e.kind(), ErrorKind::Interrupted, "Invalid error kind");
+    let e = b.read(&mut buf).expect_err("Unexpected success when reading with injected error");
+    assert_eq!(e.kind(), ErrorKind::ConnectionReset, "Invalid error kind");
+    assert_eq!(b.read(&mut buf).expect("Failed to read bytes"), 4, "Invalid amount of read bytes");
+    assert_eq!(&buf, b"test", "Invalid read bytes");
+
+    // Dropping the peer signals end-of-file
+    drop(a);
+    assert_eq!(b.read(&mut buf).expect("Failed to read end-of-file"), 0, "Unexpected bytes after end-of-file");
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+
+use crate::packets::packet::Good;
+use mqtt_tiny::transport::{PacketSink, PacketSource};
+use std::{collections::VecDeque, convert::Infallible};
+
+/// A mock transport that yields prepared packets and records sent packets
+#[derive(Debug)]
+pub struct MockTransport<T> {
+    /// The packets to receive
+    pub incoming: VecDeque<T>,
+    /// The sent packets
+    pub outgoing: Vec<T>,
+}
+impl<T> PacketSource<T> for MockTransport<T> {
+    type Error = Infallible;
+
+    fn recv(&mut self) -> Result<Option<T>, Self::Error> {
+        Ok(self.incoming.pop_front())
+    }
+}
+impl<T> PacketSink<T> for MockTransport<T> {
+    type Error = Infallible;
+
+    fn send(&mut self, packet: T) -> Result<(), Self::Error> {
+        self.outgoing.push(packet);
+        Ok(())
+    }
+}
+
+/// Relays all packets from the source to the sink and returns the amount of relayed packets
+fn relay<T, Source, Sink>(source: &mut Source, sink: &mut Sink) -> usize
+where
+    Source: PacketSource<T>,
+    Source::Error: std::fmt::Debug,
+    Sink: PacketSink<T>,
+    Sink::Error: std::fmt::Debug,
+{
+    let mut relayed = 0;
+    while let Some(packet) = source.recv().expect("Failed to receive packet") {
+        sink.send(packet).expect("Failed to send packet");
+        relayed += 1;
+    }
+    relayed
+}
+
+/// Tests protocol logic against the mock transport
+#[test]
+pub fn mock() {
+    // Relay all packets
+    let incoming = Good::all().iter().map(|test_vector| test_vector.packet.clone()).collect();
+    let mut source = MockTransport { incoming, outgoing: Vec::new() };
+    let mut sink = MockTransport { incoming: VecDeque::new(), outgoing: Vec::new() };
+    let relayed = relay(&mut source, &mut sink);
+
+    // Validate the relayed packets
+    assert_eq!(relayed, Good::all().len(), "Invalid amount of relayed packets");
+    for (sent, test_vector) in sink.outgoing.iter().zip(Good::all()) {
+        assert_eq!(sent, &test_vector.packet, "Invalid relayed packet");
+    }
+}
+
+/// Tests the same protocol logic over `std::io` readers/writers
+#[test]
+pub fn io() {
+    use mqtt_tiny::Packet;
+
+    // Concatenate all packets
+    let mut concatenated = Vec::new();
+    for test_vector in Good::all() {
+        concatenated.extend(&test_vector.packet);
+    }
+
+    // Relay all packets and validate
+    let mut source = concatenated.as_slice();
+    let mut sink = Vec::new();
+    let relayed = relay::<Packet, _, _>(&mut source, &mut sink);
+    assert_eq!(relayed, Good::all().len(), "Invalid amount of relayed packets");
+    assert_eq!(sink, concatenated, "Invalid relayed packets");
+}
+
+/// Tests the same protocol logic over `embedded-io` streams
+#[test]
+#[cfg(feature = "embedded-io")]
+pub fn embedded() {
+    use embedded_io_adapters::std::FromStd;
+    use mqtt_tiny::{transport::EmbeddedTransport, Packet};
+
+    // Concatenate all packets
+    let mut concatenated = Vec::new();
+    for test_vector in Good::all() {
+        concatenated.extend(&test_vector.packet);
+    }
+
+    // Relay all packets and validate
+    let (mut scratch, mut unused) = ([0; 256], [0; 0]);
+    let mut source = EmbeddedTransport::new(FromStd::new(concatenated.as_slice()), &mut scratch);
+    let mut sink = EmbeddedTransport::new(FromStd::new(Vec::new()), &mut unused);
+    let relayed = relay::<Packet, _, _>(&mut source, &mut sink);
+    assert_eq!(relayed, Good::all().len(), "Invalid amount of relayed packets");
+    assert_eq!(sink.into_inner().into_inner(), concatenated, "Invalid relayed packets");
+}